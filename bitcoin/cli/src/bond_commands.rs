@@ -1,32 +1,49 @@
-use std::str::FromStr;
+use std::{path::PathBuf, str::FromStr};
 
 use anyhow::{anyhow, bail, Context};
 use base64::{engine::general_purpose, Engine};
 use bitcoin::{
-	bip32::{ChildNumber, DerivationPath, Fingerprint},
+	bip32::{ChildNumber, DerivationPath, Fingerprint, KeySource, Xpriv},
+	consensus::encode::{deserialize_hex, serialize_hex},
 	key::Secp256k1,
-	secp256k1, Address, CompressedPublicKey, FeeRate, Network, Txid,
+	secp256k1, Address, CompressedPublicKey, FeeRate, Network, ScriptBuf, Transaction, TxOut, Txid,
 };
 use clap::{Subcommand, ValueEnum};
-use comfy_table::{modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL, ContentArrangement, Table};
+use serde::Serialize;
 use sp_runtime::{testing::H256, FixedPointNumber, FixedU128};
 
-use argon_bitcoin::{Amount, CosignScript, CosignScriptArgs, UnlockStep, UtxoUnlocker};
+use argon_bitcoin::{
+	create_cpfp_psbt, Amount, CosignScript, CosignScriptArgs, MusigSecretNonce, UnlockStep,
+	UtxoUnlocker,
+};
 use argon_client::{
 	api,
-	api::{apis, runtime_types::pallet_bond::pallet::UtxoCosignRequest, storage, tx},
-	conversion::from_api_fixed_u128,
-	MainchainClient,
+	api::{
+		apis,
+		runtime_types::pallet_bond::pallet::{
+			BitcoinUnlockCosign, OwnerPubkeyRotation, UtxoCosignRequest,
+		},
+		storage, tx,
+	},
+	types::AccountId32,
+	ArgonConfig, MainchainClient,
 };
 use argon_primitives::{
 	bitcoin::{
-		BitcoinScriptPubkey, BitcoinSignature, CompressedBitcoinPubkey, H256Le, UtxoId,
-		SATOSHIS_PER_BITCOIN,
+		BitcoinCosignScriptPubkey, BitcoinCosignScriptType, BitcoinHeight, BitcoinMusigNonce,
+		BitcoinScriptPubkey, BitcoinSignature, BitcoinXPub, CompressedBitcoinPubkey, H256Le,
+		Satoshis, UtxoId, SATOSHIS_PER_BITCOIN,
 	},
 	BlockNumber, BondId, KeystoreParams, VaultId,
 };
 
-use crate::{formatters::ArgonFormatter, helpers::get_bitcoin_network, xpriv_file::XprivFile};
+use crate::{
+	formatters::{ArgonFormatter, OutputFormat, OutputRecord},
+	helpers::get_bitcoin_network,
+	musig_nonce_file::MusigNonceFile,
+	timelock_claim::{print_claim_tx, BondScriptExport, BondScriptSource, ClaimSigner},
+	xpriv_file::XprivFile,
+};
 
 #[derive(Subcommand, Debug)]
 pub enum BondCommands {
@@ -44,6 +61,11 @@ pub enum BondCommands {
 		#[clap(short, long)]
 		owner_pubkey: String,
 
+		/// Lock the bitcoin in a taproot (P2TR) script. Cosigned unlocks will be a MuSig2 key
+		/// path spend.
+		#[clap(long)]
+		taproot: bool,
+
 		#[clap(flatten)]
 		keypair: KeystoreParams,
 	},
@@ -76,9 +98,120 @@ pub enum BondCommands {
 		#[clap(short, long, default_value = "5")]
 		fee_rate_sats_per_kb: u64,
 
+		/// Only redeem this amount of btc. The remainder will stay bonded in a new cosign script.
+		#[clap(long)]
+		redeem_btc: Option<f64>,
+
+		/// Allow the vault to cosign this unlock in a single bitcoin transaction with other
+		/// unlocks, sharing the bitcoin fee
+		#[clap(long)]
+		allow_batching: bool,
+
+		#[clap(flatten)]
+		musig_nonce_file: MusigNonceFile,
+
+		#[clap(flatten)]
+		keypair: KeystoreParams,
+	},
+	/// Transfer a bond (and the right to unlock it) to another Argon account
+	Transfer {
+		/// The bond id
+		#[clap(short, long)]
+		bond_id: BondId,
+
+		/// The Argon account to transfer the bond to
+		#[clap(short, long)]
+		to_account_id: String,
+
+		/// Move the bitcoin to a new cosign script with this owner pubkey. Without it, the new
+		/// owner will need the current owner key to unlock.
+		#[clap(long)]
+		new_owner_pubkey: Option<String>,
+
+		/// The fee rate per sats (sat/vB) to use to move the bitcoin
+		#[clap(short, long, default_value = "5")]
+		fee_rate_sats_per_kb: u64,
+
+		#[clap(flatten)]
+		musig_nonce_file: MusigNonceFile,
+
+		#[clap(flatten)]
+		keypair: KeystoreParams,
+	},
+	/// Renew a bond before it expires. The bitcoin will move to a new cosign script with new
+	/// claim heights once the current vault cosigns.
+	Renew {
+		/// The bond id
+		#[clap(short, long)]
+		bond_id: BondId,
+
+		/// The vault to renew the bond with (defaults to the current vault)
+		#[clap(short, long)]
+		vault_id: Option<VaultId>,
+
+		/// The fee rate per sats (sat/vB) to use to move the bitcoin
+		#[clap(short, long, default_value = "5")]
+		fee_rate_sats_per_kb: u64,
+
+		#[clap(flatten)]
+		musig_nonce_file: MusigNonceFile,
+
+		#[clap(flatten)]
+		keypair: KeystoreParams,
+	},
+	/// Migrate a bond off a closed vault to the vault that accepted its bonds. The bitcoin will
+	/// move to a cosign script of the new vault once the closed vault cosigns.
+	Migrate {
+		/// The bond id
+		#[clap(short, long)]
+		bond_id: BondId,
+
+		/// The fee rate per sats (sat/vB) to use to move the bitcoin
+		#[clap(short, long, default_value = "5")]
+		fee_rate_sats_per_kb: u64,
+
+		#[clap(flatten)]
+		musig_nonce_file: MusigNonceFile,
+
+		#[clap(flatten)]
+		keypair: KeystoreParams,
+	},
+	/// Ask the vault to cosign an unlock again with a higher fee. The new transaction can replace
+	/// the original one (replace-by-fee) until it's seen in a bitcoin block.
+	RequestFeeBump {
+		/// The utxo id of the cosigned unlock
+		#[clap(short, long)]
+		utxo_id: UtxoId,
+
+		/// The new fee rate per sats (sat/vB) to use
+		#[clap(short, long)]
+		fee_rate_sats_per_kb: u64,
+
+		#[clap(flatten)]
+		musig_nonce_file: MusigNonceFile,
+
 		#[clap(flatten)]
 		keypair: KeystoreParams,
 	},
+	/// Create a psbt that spends the output of an unconfirmed unlock with a higher fee so both
+	/// transactions confirm faster (child pays for parent)
+	CpfpPsbt {
+		/// The utxo id of the cosigned unlock
+		#[clap(short, long)]
+		utxo_id: UtxoId,
+
+		/// The unlock transaction that was broadcast to bitcoin (hex encoded)
+		#[clap(long)]
+		unlock_tx: String,
+
+		/// The destination to send the bitcoin to
+		#[clap(short, long)]
+		dest_pubkey: String,
+
+		/// The fee rate per sats (sat/vB) to use for both transactions together
+		#[clap(short, long)]
+		fee_rate_sats_per_kb: u64,
+	},
 	/// Create the vault side of this unlock request to submit to Argon
 	VaultCosign {
 		/// The bond id to unlock
@@ -92,7 +225,54 @@ pub enum BondCommands {
 		#[clap(long)]
 		master_xpub_hd_path: String,
 	},
-	/// Create an unlock psbt to submit to bitcoin
+	/// Create the vault side of many unlock requests to submit to Argon together
+	VaultCosignBatch {
+		/// The bond ids to unlock
+		#[clap(short, long, required = true, num_args = 1..)]
+		bond_ids: Vec<BondId>,
+
+		/// Combine the unlocks into a single bitcoin transaction paying this fee rate per sats
+		/// (sat/vB). The fee can't be more than the bond owners requested in total.
+		#[clap(short, long)]
+		fee_rate_sats_per_kb: Option<u64>,
+
+		#[clap(flatten)]
+		xpriv_file: XprivFile,
+
+		/// Provide the path of the derived master xpub uploaded to Argon
+		#[clap(long)]
+		master_xpub_hd_path: String,
+	},
+	/// Export the pending unlock of a bond as a psbt so the vault can sign it in an external
+	/// wallet
+	ExportUnlockPsbt {
+		/// The bond id being unlocked
+		#[clap(short, long)]
+		bond_id: BondId,
+
+		/// Provide the hd path of the owner to put as a hint into the psbt (if applicable)
+		#[clap(long)]
+		hd_path: Option<String>,
+
+		/// Provide the parent fingerprint of the owner to put as a hint into the psbt (if
+		/// applicable)
+		#[clap(long)]
+		parent_fingerprint: Option<String>,
+	},
+	/// Import an unlock psbt signed by the vault in an external wallet and create the cosign to
+	/// submit to Argon
+	ImportUnlockPsbt {
+		/// The bond id being unlocked
+		#[clap(short, long)]
+		bond_id: BondId,
+
+		/// The base64 encoded psbt(s) to import. Multiple psbts will be combined.
+		#[clap(short, long, required = true, num_args = 1..)]
+		psbt: Vec<String>,
+	},
+	/// Create an unlock psbt to submit to bitcoin. If the vault cosigned the unlock in a batch,
+	/// the psbt will only be signed for this bond. Combine it with the psbts of the other bond
+	/// owners in the batch to broadcast.
 	OwnerCosignPsbt {
 		/// The utxo id in Argon. NOTE: bonds are cleaned up on release, so you need this id. You
 		/// can use the `bond get` command at a previous block to look this up.
@@ -108,10 +288,14 @@ pub enum BondCommands {
 		parent_fingerprint: Option<String>,
 
 		/// Provide the private key directly to sign the psbt (this should be used for testnet
-		/// only).
+		/// only). Required for taproot bonds.
 		#[clap(long)]
 		private_key: Option<String>,
 
+		// the nonce file created by `request-unlock`, which is deleted once the nonce is used
+		#[clap(flatten)]
+		musig_nonce_file: MusigNonceFile,
+
 		/// Wait for the cosignature to be submitted if it's not found right away
 		#[clap(long)]
 		wait: bool,
@@ -154,6 +338,42 @@ pub enum BondCommands {
 		#[clap(short, long, default_value = "5")]
 		fee_rate_sats_per_kb: u64,
 	},
+	/// Export the bond data needed to rebuild its cosign script. Keep it to recover the bitcoin
+	/// without an Argon node.
+	ExportScript {
+		/// The bond id
+		#[clap(short, long)]
+		bond_id: BondId,
+
+		/// Export the bond at a specific block (defaults to the latest finalized block)
+		#[clap(short, long)]
+		at_block: Option<BlockNumber>,
+
+		/// Write the export to this file instead of printing it
+		#[clap(short, long, value_name = "PATH")]
+		out: Option<PathBuf>,
+	},
+	/// Recover the bitcoin with the owner key once the open claim height is reached. Outputs a
+	/// signed transaction to broadcast to bitcoin.
+	Recover {
+		#[clap(flatten)]
+		script: BondScriptSource,
+
+		/// The hd path of the owner pubkey in your xpriv (or hardware wallet)
+		#[clap(long)]
+		hd_path: String,
+
+		/// The destination you want to send the bitcoin to
+		#[clap(short, long)]
+		dest_pubkey: String,
+
+		/// The fee rate per sats (sat/vB) to use
+		#[clap(short, long, default_value = "5")]
+		fee_rate_sats_per_kb: u64,
+
+		#[clap(flatten)]
+		signer: ClaimSigner,
+	},
 }
 
 #[derive(Debug, Clone, ValueEnum, Default)]
@@ -164,33 +384,37 @@ pub enum BitcoinClaimer {
 }
 
 impl BondCommands {
-	pub async fn process(self, rpc_url: String) -> anyhow::Result<()> {
+	pub async fn process(self, rpc_url: String, format: OutputFormat) -> anyhow::Result<()> {
 		match self {
-			BondCommands::Apply { vault_id, keypair: _, owner_pubkey, btc } => {
+			BondCommands::Apply { vault_id, keypair: _, owner_pubkey, btc, taproot } => {
 				let client = MainchainClient::from_url(&rpc_url)
 					.await
 					.context("Failed to connect to argon node")?;
 
 				let owner_pubkey: CompressedBitcoinPubkey =
 					CompressedPublicKey::from_str(&owner_pubkey)?.into();
-				let vault = client
-					.fetch_storage(&storage().vaults().vaults_by_id(vault_id), None)
-					.await?
-					.ok_or(anyhow!("Vault not found"))?;
 				let satoshis = FixedU128::from_float(btc).saturating_mul_int(SATOSHIS_PER_BITCOIN);
-				let Some(argons_minted) =
-					client.call(apis().bitcoin_apis().market_rate(satoshis), None).await?
-				else {
-					println!("No price conversion found in blockchain for bitcoin to argon");
-					return Ok(());
-				};
-				let fee = vault.bitcoin_argons.base_fee +
-					from_api_fixed_u128(vault.bitcoin_argons.annual_percent_rate)
-						.saturating_mul_int(argons_minted);
+				let quote = client.quote_bitcoin_bond(vault_id, satoshis, None).await?;
 				println!("You're bonding {} sats in exchange for {}. Your Argon account needs {} for the bond cost",
-						 satoshis, ArgonFormatter(argons_minted), ArgonFormatter(fee));
+						 satoshis, ArgonFormatter(quote.amount), ArgonFormatter(quote.total_fee));
+				if let Some(redemption_price) = quote.redemption_price {
+					println!(
+						"At current prices, unlocking the bitcoin will cost {}",
+						ArgonFormatter(redemption_price)
+					);
+				}
 
-				let call = tx().bonds().bond_bitcoin(vault_id, satoshis, owner_pubkey.into());
+				let script_type = if taproot {
+					BitcoinCosignScriptType::P2TR
+				} else {
+					BitcoinCosignScriptType::P2WSH
+				};
+				let call = tx().bonds().bond_bitcoin(
+					vault_id,
+					satoshis,
+					owner_pubkey.into(),
+					script_type.into(),
+				);
 				let url = client.create_polkadotjs_deeplink(&call)?;
 				println!("Link to complete transaction:\n\t{}", url);
 			},
@@ -274,12 +498,10 @@ impl BondCommands {
 				};
 
 				let minted = if utxo.is_verified { bond.amount - remaining } else { 0 };
-				let utxo_ref_str = utxo_ref
-					.map(|a| {
-						let utxo_txid: Txid = H256Le(a.txid.0).into();
-						format!("{}, vout={}", utxo_txid, a.output_index)
-					})
-					.unwrap_or("-".to_string());
+				let utxo_ref = utxo_ref.map(|a| {
+					let utxo_txid: Txid = H256Le(a.txid.0).into();
+					(utxo_txid.to_string(), a.output_index)
+				});
 				let cosign_script = get_cosign_script(&utxo, Network::Bitcoin)?;
 
 				let vault_pubkey: CompressedBitcoinPubkey = utxo.vault_pubkey.into();
@@ -288,51 +510,32 @@ impl BondCommands {
 				let owner_pubkey: CompressedBitcoinPubkey = utxo.owner_pubkey.into();
 				let owner_bitcoin_pubkey: bitcoin::CompressedPublicKey = owner_pubkey.try_into()?;
 
-				let mut rows = vec![
-					vec!["Bitcoin Utxo".into(), utxo_ref_str],
-					vec!["Vault pubkey".into(), format!("{}", vault_bitcoin_pubkey)],
-					vec!["Owner pubkey".into(), format!("{}", owner_bitcoin_pubkey)],
-					vec!["Output Descriptor".into(), format!("{}", cosign_script.descriptor)],
-					vec![
-						"Minted Argons".into(),
-						format!("{} of {}", ArgonFormatter(minted), ArgonFormatter(bond.amount)),
-					],
-					vec!["Status".into(), status.into()],
-					vec![
-						format!(
-							"Redemption Price{}",
-							if unlock_request.is_some() { " (paid)" } else { "" }
-						),
-						format!("{}", ArgonFormatter(redemption_price)),
-					],
-					vec!["Expiration Bitcoin Block".into(), format!("{}", utxo.vault_claim_height)],
-					vec![
-						"Owner Reclaim Bitcoin Block".into(),
-						format!("{}", utxo.open_claim_height),
-					],
-				];
-
-				if let Some(ref unlock_request) = unlock_request {
-					rows.push(vec![
-						"Unlock Requested".into(),
-						format!("due at block {}", unlock_request.cosign_due_block),
-					])
-				}
-
-				let mut table = Table::new();
-				table
-					.load_preset(UTF8_FULL)
-					.apply_modifier(UTF8_ROUND_CORNERS)
-					.set_content_arrangement(ContentArrangement::Dynamic);
-
-				table.add_rows(rows);
-
-				println!("{table}");
+				let bond_status = BondStatus {
+					bond_id,
+					utxo_id,
+					utxo_txid: utxo_ref.as_ref().map(|(txid, _)| txid.clone()),
+					utxo_output_index: utxo_ref.as_ref().map(|(_, vout)| *vout),
+					vault_pubkey: vault_bitcoin_pubkey.to_string(),
+					owner_pubkey: owner_bitcoin_pubkey.to_string(),
+					output_descriptor: cosign_script.descriptor.to_string(),
+					minted_argons: minted,
+					bond_amount: bond.amount,
+					status: status.to_string(),
+					redemption_price,
+					redemption_paid: unlock_request.is_some(),
+					vault_claim_height: utxo.vault_claim_height,
+					open_claim_height: utxo.open_claim_height,
+					unlock_due_block: unlock_request.as_ref().map(|a| a.cosign_due_block),
+				};
+				println!("{}", format.format_record(&bond_status)?);
 			},
 			BondCommands::RequestUnlock {
 				bond_id,
 				dest_pubkey,
 				fee_rate_sats_per_kb,
+				redeem_btc,
+				allow_batching,
+				musig_nonce_file,
 				keypair: _,
 			} => {
 				let client = MainchainClient::from_url(&rpc_url)
@@ -343,7 +546,10 @@ impl BondCommands {
 				let network = get_bitcoin_network(&client, at_block).await?;
 
 				let (_, utxo, _) = get_utxo_from_bond_id(&client, bond_id, at_block).await?;
-				let redemption_price_query = apis().bitcoin_apis().redemption_rate(utxo.satoshis);
+				let redeem_satoshis = redeem_btc
+					.map(|btc| FixedU128::from_float(btc).saturating_mul_int(SATOSHIS_PER_BITCOIN));
+				let redemption_price_query =
+					apis().bitcoin_apis().redemption_rate(redeem_satoshis.unwrap_or(utxo.satoshis));
 
 				let redemption_price = client
 					.live
@@ -359,27 +565,300 @@ impl BondCommands {
 					.script_pubkey();
 				let cosign = get_cosign_script(&utxo, network)?;
 
+				let fee_rate = FeeRate::from_sat_per_vb(fee_rate_sats_per_kb)
+					.ok_or(anyhow!("Invalid fee rate"))?;
+				let mut network_fee =
+					cosign.calculate_fee(true, bitcoin_dest_pubkey.clone(), fee_rate)?;
+				if let Some(redeem_satoshis) = redeem_satoshis {
+					// the remainder output goes to a script of the same type
+					let remainder_output = TxOut {
+						value: Amount::from_sat(utxo.satoshis.saturating_sub(redeem_satoshis)),
+						script_pubkey: cosign.get_script_pubkey(),
+					};
+					network_fee += fee_rate
+						.fee_wu(remainder_output.weight())
+						.ok_or(anyhow!("Invalid fee rate"))?;
+				}
+
+				let owner_musig_nonce = new_owner_musig_nonce(&cosign, &musig_nonce_file)?;
+				let argon_bitcoin_script_pubkey: BitcoinScriptPubkey = bitcoin_dest_pubkey.into();
+				let call = tx().bonds().unlock_bitcoin_bond(
+					bond_id,
+					argon_bitcoin_script_pubkey.into(),
+					network_fee.to_sat(),
+					owner_musig_nonce.as_ref().map(|(nonce, _)| (*nonce).into()),
+					redeem_satoshis,
+					allow_batching,
+				);
+				let url = client.create_polkadotjs_deeplink(&call)?;
+
+				if format != OutputFormat::Table {
+					let quote = UnlockQuote {
+						bond_id,
+						redemption_price,
+						bitcoin_network_fee: network_fee.to_sat(),
+						owner_musig_nonce_file: owner_musig_nonce
+							.map(|(_, path)| path.display().to_string()),
+						transaction_url: url,
+					};
+					println!("{}", format.format_record(&quote)?);
+					return Ok(());
+				}
+				println!(
+					"The price to unlock this bond is: {}\nBitcoin fee: {:?}",
+					ArgonFormatter(redemption_price),
+					network_fee
+				);
+				if let Some((_, path)) = owner_musig_nonce {
+					print_owner_musig_nonce_file(&path);
+				}
+				println!("Link to create transaction:\n\t{}", url);
+			},
+			BondCommands::Transfer {
+				bond_id,
+				to_account_id,
+				new_owner_pubkey,
+				fee_rate_sats_per_kb,
+				musig_nonce_file,
+				keypair: _,
+			} => {
+				let client = MainchainClient::from_url(&rpc_url)
+					.await
+					.context("Failed to connect to argon node")?;
+				let to_account_id = AccountId32::from_str(&to_account_id)
+					.map_err(|e| anyhow!("Unable to parse the argon account id: {e:?}"))?;
+
+				let owner_pubkey_rotation = if let Some(new_owner_pubkey) = new_owner_pubkey {
+					let new_owner_pubkey: CompressedBitcoinPubkey =
+						CompressedPublicKey::from_str(&new_owner_pubkey)?.into();
+					let at_block = Some(client.latest_finalized_block_hash().await?.hash());
+					let network = get_bitcoin_network(&client, at_block).await?;
+					let (_, utxo, _) = get_utxo_from_bond_id(&client, bond_id, at_block).await?;
+					let cosign = get_cosign_script(&utxo, network)?;
+					let network_fee = cosign.calculate_fee(
+						true,
+						cosign.get_script_pubkey(),
+						FeeRate::from_sat_per_vb(fee_rate_sats_per_kb)
+							.ok_or(anyhow!("Invalid fee rate"))?,
+					)?;
+					println!(
+						"The bitcoin will move to a new cosign script once the vault cosigns.\nBitcoin fee: {:?}",
+						network_fee
+					);
+					Some(OwnerPubkeyRotation {
+						owner_pubkey: new_owner_pubkey.into(),
+						bitcoin_network_fee: network_fee.to_sat(),
+						owner_musig_nonce: create_owner_musig_nonce(&cosign, &musig_nonce_file)?
+							.map(Into::into),
+					})
+				} else {
+					None
+				};
+
+				let call = tx().bonds().transfer_bitcoin_bond(
+					bond_id,
+					to_account_id,
+					owner_pubkey_rotation,
+				);
+				let url = client.create_polkadotjs_deeplink(&call)?;
+				println!("Link to create transaction:\n\t{}", url);
+			},
+			BondCommands::Renew {
+				bond_id,
+				vault_id,
+				fee_rate_sats_per_kb,
+				musig_nonce_file,
+				keypair: _,
+			} => {
+				let client = MainchainClient::from_url(&rpc_url)
+					.await
+					.context("Failed to connect to argon node")?;
+				let at_block = Some(client.latest_finalized_block_hash().await?.hash());
+				let network = get_bitcoin_network(&client, at_block).await?;
+				let (_, utxo, bond) = get_utxo_from_bond_id(&client, bond_id, at_block).await?;
+				let vault_id = vault_id.unwrap_or(bond.vault_id);
+				let cosign = get_cosign_script(&utxo, network)?;
 				let network_fee = cosign.calculate_fee(
 					true,
-					bitcoin_dest_pubkey.clone(),
+					cosign.get_script_pubkey(),
 					FeeRate::from_sat_per_vb(fee_rate_sats_per_kb)
 						.ok_or(anyhow!("Invalid fee rate"))?,
 				)?;
+				println!(
+					"The bitcoin will move to a new cosign script for vault {} once the current vault cosigns.\nBitcoin fee: {:?}",
+					vault_id, network_fee
+				);
 
+				let call = tx().bonds().renew_bitcoin_bond(
+					bond_id,
+					vault_id,
+					network_fee.to_sat(),
+					create_owner_musig_nonce(&cosign, &musig_nonce_file)?.map(Into::into),
+				);
+				let url = client.create_polkadotjs_deeplink(&call)?;
+				println!("Link to create transaction:\n\t{}", url);
+			},
+			BondCommands::Migrate {
+				bond_id,
+				fee_rate_sats_per_kb,
+				musig_nonce_file,
+				keypair: _,
+			} => {
+				let client = MainchainClient::from_url(&rpc_url)
+					.await
+					.context("Failed to connect to argon node")?;
+				let at_block = Some(client.latest_finalized_block_hash().await?.hash());
+				let network = get_bitcoin_network(&client, at_block).await?;
+				let (_, utxo, bond) = get_utxo_from_bond_id(&client, bond_id, at_block).await?;
+				let migration = client
+					.fetch_storage(
+						&storage().vaults().bond_migrations_by_vault_id(bond.vault_id),
+						at_block,
+					)
+					.await?
+					.filter(|a| a.is_accepted)
+					.ok_or(anyhow!(
+						"No other vault has accepted the bonds of vault {}",
+						bond.vault_id
+					))?;
+				let cosign = get_cosign_script(&utxo, network)?;
+				let network_fee = cosign.calculate_fee(
+					true,
+					cosign.get_script_pubkey(),
+					FeeRate::from_sat_per_vb(fee_rate_sats_per_kb)
+						.ok_or(anyhow!("Invalid fee rate"))?,
+				)?;
 				println!(
-					"The price to unlock this bond is: {}\nBitcoin fee: {:?}",
-					ArgonFormatter(redemption_price),
-					network_fee
+					"The bitcoin will move to a new cosign script for vault {} once vault {} cosigns.\nBitcoin fee: {:?}",
+					migration.to_vault_id, bond.vault_id, network_fee
 				);
-				let argon_bitcoin_script_pubkey: BitcoinScriptPubkey = bitcoin_dest_pubkey.into();
-				let call = tx().bonds().unlock_bitcoin_bond(
+
+				let call = tx().bonds().migrate_bitcoin_bond(
 					bond_id,
-					argon_bitcoin_script_pubkey.into(),
 					network_fee.to_sat(),
+					create_owner_musig_nonce(&cosign, &musig_nonce_file)?.map(Into::into),
 				);
 				let url = client.create_polkadotjs_deeplink(&call)?;
 				println!("Link to create transaction:\n\t{}", url);
 			},
+			BondCommands::RequestFeeBump {
+				utxo_id,
+				fee_rate_sats_per_kb,
+				musig_nonce_file,
+				keypair: _,
+			} => {
+				let client = MainchainClient::from_url(&rpc_url)
+					.await
+					.context("Failed to connect to argon node")?;
+				let latest_block = client.latest_finalized_block_hash().await?;
+				let at_block = Some(latest_block.hash());
+				let network = get_bitcoin_network(&client, at_block).await?;
+
+				let (utxo, request) = client
+					.fetch_storage(
+						&storage().bonds().cosigned_unlocks_by_utxo_id(utxo_id),
+						at_block,
+					)
+					.await?
+					.ok_or(anyhow!(
+						"No cosigned unlock found for this utxo. It might already be on the bitcoin network."
+					))?;
+				let to_script_pubkey: BitcoinScriptPubkey = request
+					.to_script_pubkey
+					.try_into()
+					.map_err(|_| anyhow!("Unable to decode the destination pubkey"))?;
+				let cosign = get_cosign_script(&utxo, network)?;
+				let fee_rate = FeeRate::from_sat_per_vb(fee_rate_sats_per_kb)
+					.ok_or(anyhow!("Invalid fee rate"))?;
+				let mut network_fee =
+					cosign.calculate_fee(true, to_script_pubkey.into(), fee_rate)?;
+				if let Some((_, remainder)) = request.remainder_utxo {
+					let remainder_output = TxOut {
+						value: Amount::from_sat(remainder.satoshis),
+						script_pubkey: cosign.get_script_pubkey(),
+					};
+					network_fee += fee_rate
+						.fee_wu(remainder_output.weight())
+						.ok_or(anyhow!("Invalid fee rate"))?;
+				}
+				if network_fee.to_sat() <= request.bitcoin_network_fee {
+					bail!(
+						"The new bitcoin fee ({}) must be higher than the current fee ({} sats)",
+						network_fee,
+						request.bitcoin_network_fee
+					);
+				}
+
+				println!(
+					"Bitcoin fee: {:?} (was {} sats)",
+					network_fee, request.bitcoin_network_fee
+				);
+				let owner_musig_nonce =
+					create_owner_musig_nonce(&cosign, &musig_nonce_file)?.map(Into::into);
+				let call = tx().bonds().request_bitcoin_unlock_fee_bump(
+					utxo_id,
+					network_fee.to_sat(),
+					owner_musig_nonce,
+				);
+				let url = client.create_polkadotjs_deeplink(&call)?;
+				println!("Link to create transaction:\n\t{}", url);
+			},
+			BondCommands::CpfpPsbt { utxo_id, unlock_tx, dest_pubkey, fee_rate_sats_per_kb } => {
+				let client = MainchainClient::from_url(&rpc_url)
+					.await
+					.context("Failed to connect to argon node")?;
+				let latest_block = client.latest_finalized_block_hash().await?;
+				let at_block = Some(latest_block.hash());
+				let network = get_bitcoin_network(&client, at_block).await?;
+
+				let (utxo, request) = client
+					.fetch_storage(
+						&storage().bonds().cosigned_unlocks_by_utxo_id(utxo_id),
+						at_block,
+					)
+					.await?
+					.ok_or(anyhow!(
+						"No cosigned unlock found for this utxo. It might already be on the bitcoin network."
+					))?;
+				let unlock_tx: Transaction = deserialize_hex(unlock_tx.trim())
+					.map_err(|e| anyhow!("Unable to decode the unlock transaction: {e:?}"))?;
+				if unlock_tx.input.len() != 1 {
+					bail!("Only an unlock of a single utxo can be sped up with this command");
+				}
+				let to_script_pubkey: BitcoinScriptPubkey = request
+					.to_script_pubkey
+					.try_into()
+					.map_err(|_| anyhow!("Unable to decode the destination pubkey"))?;
+				let to_script_pubkey: ScriptBuf = to_script_pubkey.into();
+				let output_index = unlock_tx
+					.output
+					.iter()
+					.position(|a| a.script_pubkey == to_script_pubkey)
+					.ok_or(anyhow!("The transaction doesn't pay to the unlock destination"))?;
+				let outputs = unlock_tx.output.iter().map(|a| a.value).sum::<Amount>();
+				let unlock_fee = Amount::from_sat(utxo.satoshis)
+					.checked_sub(outputs)
+					.ok_or(anyhow!("The transaction doesn't spend this utxo"))?;
+
+				let dest_script_pubkey = Address::from_str(&dest_pubkey)
+					.map_err(|e| anyhow!("Unable to parse bitcoin destination pubkey: {e:?}"))?
+					.require_network(network)?
+					.script_pubkey();
+				let (psbt, fee) = create_cpfp_psbt(
+					&unlock_tx,
+					unlock_fee,
+					output_index as u32,
+					dest_script_pubkey,
+					FeeRate::from_sat_per_vb(fee_rate_sats_per_kb)
+						.ok_or(anyhow!("Invalid fee rate"))?,
+				)?;
+
+				println!(
+					"This transaction pays a fee of {:?}. Sign it with the key of the unlock destination and broadcast it after the unlock:\n\n{}",
+					fee,
+					general_purpose::STANDARD.encode(&psbt.serialize()[..])
+				);
+			},
 			BondCommands::VaultCosign { bond_id, xpriv_file, master_xpub_hd_path } => {
 				let client = MainchainClient::from_url(&rpc_url)
 					.await
@@ -392,6 +871,7 @@ impl BondCommands {
 				)?;
 
 				let (utxo_id, utxo, _) = get_utxo_from_bond_id(&client, bond_id, at_block).await?;
+				ensure_single_key_vault(&utxo)?;
 				let mut unlocker = load_unlocker(&client, utxo_id, &utxo, at_block).await?;
 				let owner_pubkey: CompressedPublicKey =
 					unlocker.cosign_script.script_args.owner_pubkey.try_into()?;
@@ -401,6 +881,30 @@ impl BondCommands {
 				let hd_path =
 					DerivationPath::from(vec![ChildNumber::from(utxo.vault_xpub_sources.1)]);
 
+				if unlocker.cosign_script.script_type == BitcoinCosignScriptType::P2TR {
+					let vault_pubkey: CompressedBitcoinPubkey = utxo.vault_pubkey.clone().into();
+					let secret_nonce = MusigSecretNonce::new(rand::random(), vault_pubkey, None)?;
+					let vault_musig_nonce = secret_nonce.public_nonce();
+					unlocker.add_musig_nonce(vault_pubkey.try_into()?, vault_musig_nonce);
+					let (signature, _) = unlocker.sign_musig_derived(
+						child_xpriv,
+						(fingerprint, hd_path),
+						&secret_nonce,
+					)?;
+					println!(
+						"Your xpriv was used to create a MuSig2 partial signature for this unlock"
+					);
+
+					let unlock_fulfill = tx().bonds().cosign_bitcoin_unlock(
+						bond_id,
+						signature.into(),
+						Some(vault_musig_nonce.into()),
+					);
+					let url = client.create_polkadotjs_deeplink(&unlock_fulfill)?;
+					println!("Link to create transaction:\n\t{}", url);
+					return Ok(());
+				}
+
 				unlocker.psbt.inputs[0]
 					.bip32_derivation
 					.insert(compressed.0, (fingerprint, hd_path));
@@ -425,7 +929,188 @@ impl BondCommands {
 				.try_into()
 				.map_err(|_| anyhow!("Unable to translate signature to bytes"))?;
 
-				let unlock_fulfill = tx().bonds().cosign_bitcoin_unlock(bond_id, signature.into());
+				let unlock_fulfill =
+					tx().bonds().cosign_bitcoin_unlock(bond_id, signature.into(), None);
+				let url = client.create_polkadotjs_deeplink(&unlock_fulfill)?;
+				println!("Link to create transaction:\n\t{}", url);
+			},
+			BondCommands::VaultCosignBatch {
+				bond_ids,
+				fee_rate_sats_per_kb,
+				xpriv_file,
+				master_xpub_hd_path,
+			} => {
+				let client = MainchainClient::from_url(&rpc_url)
+					.await
+					.context("Failed to connect to argon node")?;
+				let latest_block = client.latest_finalized_block_hash().await?;
+				let at_block = Some(latest_block.hash());
+				let child_xpriv = xpriv_file.read()?.derive_priv(
+					&Secp256k1::new(),
+					&DerivationPath::from_str(&master_xpub_hd_path)?,
+				)?;
+
+				let mut utxos = Vec::with_capacity(bond_ids.len());
+				let mut unlockers = Vec::with_capacity(bond_ids.len());
+				for bond_id in &bond_ids {
+					let (utxo_id, utxo, _) =
+						get_utxo_from_bond_id(&client, *bond_id, at_block).await?;
+					ensure_single_key_vault(&utxo)?;
+					unlockers.push(load_unlocker(&client, utxo_id, &utxo, at_block).await?);
+					utxos.push(utxo);
+				}
+
+				let mut batch_bitcoin_network_fee = None;
+				if let Some(fee_rate_sats_per_kb) = fee_rate_sats_per_kb {
+					let fee = UtxoUnlocker::calculate_batch_fee(
+						&unlockers,
+						FeeRate::from_sat_per_vb(fee_rate_sats_per_kb)
+							.ok_or(anyhow!("Invalid fee rate"))?,
+					)?;
+					unlockers = UtxoUnlocker::batch(unlockers, fee)?;
+					println!(
+						"The unlocks will be combined into a single bitcoin transaction with a fee of {} sats",
+						fee.to_sat()
+					);
+					batch_bitcoin_network_fee = Some(fee.to_sat());
+				}
+
+				let mut cosigns = Vec::with_capacity(bond_ids.len());
+				for ((bond_id, utxo), mut unlocker) in
+					bond_ids.into_iter().zip(utxos.iter()).zip(unlockers)
+				{
+					let (signature, vault_musig_nonce) =
+						sign_vault_unlock(&mut unlocker, utxo, child_xpriv)?;
+					cosigns.push(BitcoinUnlockCosign {
+						bond_id,
+						signature: Some(signature.into()),
+						vault_musig_nonce: vault_musig_nonce.map(Into::into),
+						cosigner_signatures: vec![].into(),
+					});
+				}
+				println!("Your xpriv was used to cosign {} unlocks", cosigns.len());
+
+				let unlock_fulfill =
+					tx().bonds().cosign_bitcoin_unlocks(cosigns.into(), batch_bitcoin_network_fee);
+				let url = client.create_polkadotjs_deeplink(&unlock_fulfill)?;
+				println!("Link to create transaction:\n\t{}", url);
+			},
+			BondCommands::ExportUnlockPsbt { bond_id, hd_path, parent_fingerprint } => {
+				let client = MainchainClient::from_url(&rpc_url)
+					.await
+					.context("Failed to connect to argon node")?;
+				let latest_block = client.latest_finalized_block_hash().await?;
+				let at_block = Some(latest_block.hash());
+
+				let (utxo_id, utxo, bond) =
+					get_utxo_from_bond_id(&client, bond_id, at_block).await?;
+				let mut unlocker = load_unlocker(&client, utxo_id, &utxo, at_block).await?;
+				if unlocker.cosign_script.script_type == BitcoinCosignScriptType::P2TR {
+					bail!("Taproot bonds are cosigned with MuSig2, which can't be exported to a psbt. Use `vault-cosign` instead.");
+				}
+				add_cosigner_key_sources(&client, &mut unlocker, bond.vault_id, &utxo, at_block)
+					.await?;
+				let vault_fingerprint = Fingerprint::from(utxo.vault_xpub_sources.0);
+				let vault_hd_path =
+					DerivationPath::from(vec![ChildNumber::from(utxo.vault_xpub_sources.1)]);
+				let vault_pubkey: CompressedBitcoinPubkey = utxo.vault_pubkey.into();
+				let vault_pubkey: CompressedPublicKey = vault_pubkey.try_into()?;
+				add_key_source(&mut unlocker, vault_pubkey, (vault_fingerprint, vault_hd_path));
+				if let (Some(hd_path), Some(parent_fingerprint)) = (hd_path, parent_fingerprint) {
+					let owner_pubkey: CompressedPublicKey =
+						unlocker.cosign_script.script_args.owner_pubkey.try_into()?;
+					let fingerprint = Fingerprint::from_str(&parent_fingerprint)?;
+					let hd_path = DerivationPath::from_str(&hd_path)?;
+					add_key_source(&mut unlocker, owner_pubkey, (fingerprint, hd_path));
+				}
+
+				println!(
+					"Sign this psbt with the vault key (and the cosigner keys of a multisig vault), then use `import-unlock-psbt` to cosign on Argon:\n\n{}",
+					general_purpose::STANDARD.encode(unlocker.to_psbt())
+				);
+			},
+			BondCommands::ImportUnlockPsbt { bond_id, psbt } => {
+				let client = MainchainClient::from_url(&rpc_url)
+					.await
+					.context("Failed to connect to argon node")?;
+				let latest_block = client.latest_finalized_block_hash().await?;
+				let at_block = Some(latest_block.hash());
+
+				let (utxo_id, utxo, _) = get_utxo_from_bond_id(&client, bond_id, at_block).await?;
+				let mut unlocker = load_unlocker(&client, utxo_id, &utxo, at_block).await?;
+				if unlocker.cosign_script.script_type == BitcoinCosignScriptType::P2TR {
+					bail!("Taproot bonds are cosigned with MuSig2, which can't be imported from a psbt. Use `vault-cosign` instead.");
+				}
+				for psbt in psbt {
+					let psbt = general_purpose::STANDARD
+						.decode(psbt.trim())
+						.map_err(|e| anyhow!("Unable to decode the psbt: {e:?}"))?;
+					unlocker.combine_psbt(&psbt).map_err(|e| {
+						anyhow!("This psbt doesn't match the pending unlock of this bond -> {e:?}")
+					})?;
+				}
+
+				let vault_bitcoin_pubkey: CompressedBitcoinPubkey = utxo.vault_pubkey.into();
+				let Some(vault_multisig) =
+					unlocker.cosign_script.script_args.vault_multisig.clone()
+				else {
+					let vault_pubkey: CompressedPublicKey = vault_bitcoin_pubkey.try_into()?;
+					let signature = *unlocker.psbt.inputs[unlocker.input_index]
+						.partial_sigs
+						.get(&vault_pubkey.into())
+						.ok_or(anyhow!("The psbt isn't signed by the vault"))?;
+					let signature: BitcoinSignature = signature
+						.try_into()
+						.map_err(|_| anyhow!("Unable to translate signature to bytes"))?;
+					let vault_signature_valid = unlocker
+						.verify_signature_raw(vault_bitcoin_pubkey, &signature)
+						.map_err(|e| anyhow!("Unable to verify the vault signature -> {e:?}"))?;
+					if !vault_signature_valid {
+						bail!("The vault signature in the psbt is invalid");
+					}
+					let unlock_fulfill =
+						tx().bonds().cosign_bitcoin_unlock(bond_id, signature.into(), None);
+					let url = client.create_polkadotjs_deeplink(&unlock_fulfill)?;
+					println!("Link to create transaction:\n\t{}", url);
+					return Ok(());
+				};
+
+				// any of the multisig pubkeys can sign, including the vault pubkey
+				let mut cosigner_signatures: Vec<(
+					api::runtime_types::argon_primitives::bitcoin::CompressedBitcoinPubkey,
+					api::runtime_types::argon_primitives::bitcoin::BitcoinSignature,
+				)> = vec![];
+				for cosigner_pubkey in
+					core::iter::once(vault_bitcoin_pubkey).chain(vault_multisig.cosign_pubkeys)
+				{
+					let pubkey: CompressedPublicKey = cosigner_pubkey.try_into()?;
+					let Some(signature) =
+						unlocker.psbt.inputs[unlocker.input_index].partial_sigs.get(&pubkey.into())
+					else {
+						continue;
+					};
+					let signature: BitcoinSignature = (*signature)
+						.try_into()
+						.map_err(|_| anyhow!("Unable to translate signature to bytes"))?;
+					if !unlocker
+						.verify_signature_raw(cosigner_pubkey, &signature)
+						.map_err(|e| anyhow!("Unable to verify a cosigner signature -> {e:?}"))?
+					{
+						bail!("The signature of vault pubkey {pubkey} in the psbt is invalid");
+					}
+					cosigner_signatures.push((cosigner_pubkey.into(), signature.into()));
+				}
+				if cosigner_signatures.len() < vault_multisig.threshold as usize {
+					bail!(
+						"The psbt has {} of the {} vault signatures needed",
+						cosigner_signatures.len(),
+						vault_multisig.threshold
+					);
+				}
+
+				let unlock_fulfill = tx()
+					.bonds()
+					.cosign_bitcoin_unlock_multisig(bond_id, cosigner_signatures.into());
 				let url = client.create_polkadotjs_deeplink(&unlock_fulfill)?;
 				println!("Link to create transaction:\n\t{}", url);
 			},
@@ -434,6 +1119,7 @@ impl BondCommands {
 				parent_fingerprint,
 				hd_path,
 				private_key,
+				musig_nonce_file,
 				wait,
 			} => {
 				let private_key = if let Some(private_key) = private_key {
@@ -449,14 +1135,28 @@ impl BondCommands {
 				let at_block = Some(latest_block.hash());
 
 				let mut signature: Option<BitcoinSignature> = None;
+				let mut vault_musig_nonce: Option<BitcoinMusigNonce> = None;
+				let mut cosigner_signatures = vec![];
 				let mut active_height: Option<H256> = None;
-				if let Some(release_height) = client
+				let mut batch: Option<(Vec<UtxoId>, Satoshis)> = None;
+				// a fee bump replaces the signatures, but keeps the original release height
+				let fee_bump_height = client
 					.fetch_storage(
-						&storage().bonds().utxos_cosign_release_height_by_id(utxo_id),
+						&storage().bonds().utxos_fee_bump_cosign_height_by_id(utxo_id),
 						at_block,
 					)
-					.await?
-				{
+					.await?;
+				let release_height = match fee_bump_height {
+					Some(height) => Some(height),
+					None =>
+						client
+							.fetch_storage(
+								&storage().bonds().utxos_cosign_release_height_by_id(utxo_id),
+								at_block,
+							)
+							.await?,
+				};
+				if let Some(release_height) = release_height {
 					let release_block = client
 						.block_at_height(release_height)
 						.await?
@@ -464,14 +1164,20 @@ impl BondCommands {
 					let release_events =
 						client.live.blocks().at(release_block).await?.events().await?;
 					let release_event = release_events
-                        .find_first::<api::bonds::events::BitcoinUtxoCosigned>()?.ok_or(anyhow!("No corresponding event found for the cosign release height in the blockchain."))?;
-
-					signature = Some(
-						release_event
-							.signature
-							.try_into()
-							.map_err(|_| anyhow!("Unable to translate bitcoin signature"))?,
-					);
+						.find::<api::bonds::events::BitcoinUtxoCosigned>()
+						.collect::<Result<Vec<_>, _>>()?
+						.into_iter()
+						.find(|a| a.utxo_id == utxo_id)
+						.ok_or(anyhow!("No corresponding event found for the cosign release height in the blockchain."))?;
+					batch = find_unlock_batch(&release_events, utxo_id)?;
+
+					signature = release_event
+						.signature
+						.map(|a| a.try_into())
+						.transpose()
+						.map_err(|_| anyhow!("Unable to translate bitcoin signature"))?;
+					vault_musig_nonce = release_event.vault_musig_nonce.map(Into::into);
+					cosigner_signatures = release_event.cosigner_signatures.0;
 
 					active_height = client.block_at_height(release_height - 1).await?;
 				} else {
@@ -489,19 +1195,23 @@ impl BondCommands {
 						while let Some(block) = finalized_sub.next().await {
 							print!(".");
 							let block = block?;
-							let utxo_unlock = block
-								.events()
-								.await?
-								.find_first::<api::bonds::events::BitcoinUtxoCosigned>(
-							)?;
+							let events = block.events().await?;
+							let utxo_unlock = events
+								.find::<api::bonds::events::BitcoinUtxoCosigned>()
+								.collect::<Result<Vec<_>, _>>()?
+								.into_iter()
+								.find(|a| a.utxo_id == utxo_id);
 							if let Some(utxo_unlock) = utxo_unlock {
 								if utxo_unlock.bond_id == pending_unlock.bond_id {
-									signature = Some(
-										utxo_unlock
-											.signature
-											.try_into()
-											.map_err(|_| anyhow!("Unable to decode signature"))?,
-									);
+									batch = find_unlock_batch(&events, utxo_id)?;
+									signature = utxo_unlock
+										.signature
+										.map(|a| a.try_into())
+										.transpose()
+										.map_err(|_| anyhow!("Unable to decode signature"))?;
+									vault_musig_nonce =
+										utxo_unlock.vault_musig_nonce.map(Into::into);
+									cosigner_signatures = utxo_unlock.cosigner_signatures.0;
 									active_height = Some(block.hash());
 									break;
 								}
@@ -516,31 +1226,87 @@ impl BondCommands {
 					}
 				};
 
-				let (active_height, signature) = match (active_height, signature) {
-					(Some(a), Some(b)) => (Some(a), b),
-					_ => bail!("No signature found"),
-				};
-
-				let utxo = client
-					.fetch_storage(&storage().bonds().utxos_by_id(utxo_id), active_height)
-					.await?
-					.ok_or(anyhow::anyhow!("No utxo found for bond"))?;
+				if active_height.is_none() {
+					bail!("No signature found");
+				}
 
-				let mut unlocker = load_unlocker(&client, utxo_id, &utxo, active_height).await?;
-				unlocker.add_signature(
-					unlocker
+				let utxo = get_unlocking_utxo(&client, utxo_id, active_height).await?;
+
+				let mut unlocker = if let Some((batch_utxo_ids, bitcoin_network_fee)) = &batch {
+					let mut unlockers = Vec::with_capacity(batch_utxo_ids.len());
+					for batch_utxo_id in batch_utxo_ids {
+						let batch_utxo =
+							get_unlocking_utxo(&client, *batch_utxo_id, active_height).await?;
+						unlockers.push(
+							load_unlocker(&client, *batch_utxo_id, &batch_utxo, active_height)
+								.await?,
+						);
+					}
+					let input_index = batch_utxo_ids
+						.iter()
+						.position(|a| *a == utxo_id)
+						.ok_or(anyhow!("This utxo isn't part of the batch"))?;
+					UtxoUnlocker::batch(unlockers, Amount::from_sat(*bitcoin_network_fee))?
+						.swap_remove(input_index)
+				} else {
+					load_unlocker(&client, utxo_id, &utxo, active_height).await?
+				};
+				let input_index = unlocker.input_index;
+				if unlocker.cosign_script.script_type == BitcoinCosignScriptType::P2TR {
+					let private_key = private_key
+						.ok_or(anyhow!("A private key is required to cosign a taproot bond"))?;
+					let vault_musig_nonce =
+						vault_musig_nonce.ok_or(anyhow!("No vault MuSig2 nonce found"))?;
+					// the nonce file is removed before signing so the nonce can never sign twice
+					let secret_nonce = musig_nonce_file.take()?;
+					let vault_pubkey = unlocker
 						.cosign_script
 						.script_args
 						.bitcoin_vault_pubkey()
-						.map_err(|e| anyhow!("Could not convert the vault pubkey {:?}", e))?,
-					signature.try_into()?,
-				);
+						.map_err(|e| anyhow!("Could not convert the vault pubkey {:?}", e))?;
+					let signature = signature.ok_or(anyhow!("No vault signature found"))?;
+					unlocker.add_musig_nonce(vault_pubkey, vault_musig_nonce);
+					unlocker.add_musig_partial_signature(vault_pubkey, &signature)?;
+					unlocker.sign_musig(private_key, &secret_nonce)?;
+					if batch.is_some() {
+						unlocker.aggregate_musig_signature()?;
+						println!(
+							"Your bond is signed in this batch psbt. Combine it with the psbts of the other bonds in the batch to broadcast:\n\n{}",
+							general_purpose::STANDARD.encode(&unlocker.psbt.serialize()[..])
+						);
+						return Ok(());
+					}
+					let tx = unlocker.extract_tx()?;
+					println!(
+						"Broadcast this transaction to unlock your bitcoin:\n\n{}",
+						serialize_hex(&tx)
+					);
+					return Ok(());
+				}
+				// a multisig vault might not have signed with the vault pubkey
+				if let Some(signature) = signature {
+					unlocker.add_signature(
+						unlocker
+							.cosign_script
+							.script_args
+							.bitcoin_vault_pubkey()
+							.map_err(|e| anyhow!("Could not convert the vault pubkey {:?}", e))?,
+						signature.try_into()?,
+					);
+				}
+				for (pubkey, signature) in cosigner_signatures {
+					let pubkey: CompressedBitcoinPubkey = pubkey.into();
+					let signature: BitcoinSignature = signature
+						.try_into()
+						.map_err(|_| anyhow!("Unable to translate a cosigner signature"))?;
+					unlocker.add_signature(pubkey.try_into()?, signature.try_into()?);
+				}
 				let vault_fingerprint = Fingerprint::from(utxo.vault_xpub_sources.0);
 				let vault_hd_path =
 					DerivationPath::from(vec![ChildNumber::from(utxo.vault_xpub_sources.1)]);
 				let vault_pubkey: CompressedBitcoinPubkey = utxo.vault_pubkey.into();
 				let vault_pubkey: CompressedPublicKey = vault_pubkey.try_into()?;
-				unlocker.psbt.inputs[0]
+				unlocker.psbt.inputs[input_index]
 					.bip32_derivation
 					.insert(vault_pubkey.0, (vault_fingerprint, vault_hd_path));
 				if let (Some(hd_path), Some(parent_fingerprint)) = (hd_path, parent_fingerprint) {
@@ -556,7 +1322,7 @@ impl BondCommands {
 						"Adding owner pubkey to psbt bip32 derivation: {:?}, {:?}",
 						owner_pubkey, keysource
 					);
-					unlocker.psbt.inputs[0].bip32_derivation.insert(
+					unlocker.psbt.inputs[input_index].bip32_derivation.insert(
 						secp256k1::PublicKey::from_slice(&owner_pubkey.to_bytes()[..])?,
 						keysource,
 					);
@@ -631,18 +1397,14 @@ impl BondCommands {
 					DerivationPath::from(vec![ChildNumber::from(utxo.vault_xpub_sources.2)]);
 				let vault_pubkey: CompressedBitcoinPubkey = utxo.vault_claim_pubkey.into();
 				let vault_pubkey: CompressedPublicKey = vault_pubkey.try_into()?;
-				unlocker.psbt.inputs[0]
-					.bip32_derivation
-					.insert(vault_pubkey.0, (vault_fingerprint, vault_hd_path));
+				add_key_source(&mut unlocker, vault_pubkey, (vault_fingerprint, vault_hd_path));
 
 				if let (Some(hd_path), Some(parent_fingerprint)) = (hd_path, parent_fingerprint) {
 					let owner_pubkey: CompressedPublicKey =
 						unlocker.cosign_script.script_args.owner_pubkey.try_into()?;
 					let fingerprint = Fingerprint::from_str(&parent_fingerprint)?;
 					let hd_path = DerivationPath::from_str(&hd_path)?;
-					unlocker.psbt.inputs[0]
-						.bip32_derivation
-						.insert(owner_pubkey.0, (fingerprint, hd_path));
+					add_key_source(&mut unlocker, owner_pubkey, (fingerprint, hd_path));
 				}
 				let mut psbt = unlocker.psbt;
 				if matches!(claimer, BitcoinClaimer::Vault) {
@@ -666,12 +1428,41 @@ impl BondCommands {
 					general_purpose::STANDARD.encode(&psbt.serialize()[..])
 				);
 			},
+			BondCommands::ExportScript { bond_id, at_block, out } => {
+				let client = MainchainClient::from_url(&rpc_url)
+					.await
+					.context("Failed to connect to argon node")?;
+				let export = BondScriptExport::from_chain(&client, bond_id, at_block).await?;
+				if let Some(out) = out {
+					let path = export.write(&out)?;
+					println!("Bond script exported to {}", path.display());
+				} else {
+					println!("{}", serde_json::to_string_pretty(&export)?);
+				}
+			},
+			BondCommands::Recover {
+				script,
+				hd_path,
+				dest_pubkey,
+				fee_rate_sats_per_kb,
+				signer,
+			} => {
+				let bond = script.load(&rpc_url).await?;
+				let tx = signer.create_claim_tx(
+					&bond,
+					UnlockStep::OwnerClaim,
+					DerivationPath::from_str(&hd_path)?,
+					&dest_pubkey,
+					fee_rate_sats_per_kb,
+				)?;
+				print_claim_tx(&tx);
+			},
 		}
 		Ok(())
 	}
 }
 
-async fn load_unlocker(
+pub async fn load_unlocker(
 	client: &MainchainClient,
 	utxo_id: UtxoId,
 	utxo: &api::runtime_types::pallet_bond::pallet::UtxoState,
@@ -691,7 +1482,7 @@ async fn load_unlocker(
 		.to_script_pubkey
 		.try_into()
 		.map_err(|_| anyhow!("Unable to decode the destination pubkey"))?;
-	let unlocker = UtxoUnlocker::from_script(
+	let mut unlocker = UtxoUnlocker::from_script(
 		get_cosign_script(utxo, network)?,
 		utxo.satoshis,
 		txid,
@@ -700,14 +1491,165 @@ async fn load_unlocker(
 		Amount::from_sat(unlock_info.bitcoin_network_fee),
 		pay_scriptpub.into(),
 	)?;
+	if let Some((_, remainder)) = unlock_info.remainder_utxo {
+		let remainder_script_pubkey: BitcoinCosignScriptPubkey =
+			remainder.utxo_script_pubkey.into();
+		unlocker.add_remainder_output(remainder_script_pubkey.into(), remainder.satoshis)?;
+	}
+	if let Some(owner_musig_nonce) = unlock_info.owner_musig_nonce {
+		let owner_pubkey = unlocker
+			.cosign_script
+			.script_args
+			.bitcoin_owner_pubkey()
+			.map_err(|e| anyhow!("Could not convert owner pubkey {:?}", e))?;
+		unlocker.add_musig_nonce(owner_pubkey, owner_musig_nonce.into());
+	}
 	Ok(unlocker)
 }
 
-async fn find_unlock_request(
+/// Signs an unlock with the vault xpriv. Taproot unlocks are signed with a MuSig2 partial
+/// signature, so the vault's public nonce is returned with it.
+pub fn sign_vault_unlock(
+	unlocker: &mut UtxoUnlocker,
+	utxo: &api::runtime_types::pallet_bond::pallet::UtxoState,
+	child_xpriv: Xpriv,
+) -> anyhow::Result<(BitcoinSignature, Option<BitcoinMusigNonce>)> {
+	let fingerprint = Fingerprint::from(utxo.vault_xpub_sources.0);
+	let hd_path = DerivationPath::from(vec![ChildNumber::from(utxo.vault_xpub_sources.1)]);
+	if unlocker.cosign_script.script_type == BitcoinCosignScriptType::P2TR {
+		let vault_pubkey: CompressedBitcoinPubkey = utxo.vault_pubkey.clone().into();
+		let secret_nonce = MusigSecretNonce::new(rand::random(), vault_pubkey, None)?;
+		let vault_musig_nonce = secret_nonce.public_nonce();
+		unlocker.add_musig_nonce(vault_pubkey.try_into()?, vault_musig_nonce);
+		let (signature, _) =
+			unlocker.sign_musig_derived(child_xpriv, (fingerprint, hd_path), &secret_nonce)?;
+		return Ok((signature, Some(vault_musig_nonce)));
+	}
+	let (signature, _) = unlocker.sign_derived(child_xpriv, (fingerprint, hd_path))?;
+	let signature: BitcoinSignature = signature
+		.try_into()
+		.map_err(|_| anyhow!("Unable to translate signature to bytes"))?;
+	Ok((signature, None))
+}
+
+/// The vault cosign commands sign with a single xpriv, so can't cosign for a multisig vault
+fn ensure_single_key_vault(
+	utxo: &api::runtime_types::pallet_bond::pallet::UtxoState,
+) -> anyhow::Result<()> {
+	if utxo.vault_multisig.is_some() {
+		bail!("This is a multisig vault. Sign with each vault key using `export-unlock-psbt`, then cosign with `import-unlock-psbt`.");
+	}
+	Ok(())
+}
+
+/// Adds the key sources of a multisig vault's cosigner pubkeys to the psbt, so each cosigner
+/// wallet can find its key
+async fn add_cosigner_key_sources(
+	client: &MainchainClient,
+	unlocker: &mut UtxoUnlocker,
+	vault_id: VaultId,
+	utxo: &api::runtime_types::pallet_bond::pallet::UtxoState,
+	at_block: Option<H256>,
+) -> anyhow::Result<()> {
+	let Some(vault_multisig) = unlocker.cosign_script.script_args.vault_multisig.clone() else {
+		return Ok(());
+	};
+	let Some(cosigners) = client
+		.fetch_storage(&storage().vaults().vault_multisig_by_id(vault_id), at_block)
+		.await?
+	else {
+		return Ok(());
+	};
+	let child_number = utxo.vault_xpub_sources.1;
+	for cosigner_xpub in cosigners.cosigner_xpubs.0 {
+		let cosigner_xpub: BitcoinXPub = cosigner_xpub.into();
+		let Ok(child) = cosigner_xpub.derive_pubkey(child_number) else {
+			continue;
+		};
+		// the vault could have replaced its cosigners since this utxo was created
+		if !vault_multisig.cosign_pubkeys.contains(&child.public_key) {
+			continue;
+		}
+		let pubkey: CompressedPublicKey = child.public_key.try_into()?;
+		let key_source = (
+			Fingerprint::from(child.parent_fingerprint),
+			DerivationPath::from(vec![ChildNumber::from(child_number)]),
+		);
+		add_key_source(unlocker, pubkey, key_source);
+	}
+	Ok(())
+}
+
+/// Adds a hint to the psbt for the key source of a pubkey so a wallet can sign with it
+fn add_key_source(unlocker: &mut UtxoUnlocker, pubkey: CompressedPublicKey, key_source: KeySource) {
+	let input = &mut unlocker.psbt.inputs[unlocker.input_index];
+	match unlocker.cosign_script.script_type {
+		BitcoinCosignScriptType::P2WSH => {
+			input.bip32_derivation.insert(pubkey.0, key_source);
+		},
+		BitcoinCosignScriptType::P2TR => {
+			let (x_only_pubkey, _) = pubkey.0.x_only_public_key();
+			if let Some((_, source)) = input.tap_key_origins.get_mut(&x_only_pubkey) {
+				*source = key_source;
+			}
+		},
+	}
+}
+
+/// Creates a MuSig2 nonce for the owner if the bitcoin is locked in a taproot script. The secret
+/// nonce is stored in the nonce file for the owner to keep until they cosign.
+fn create_owner_musig_nonce(
+	cosign: &CosignScript,
+	musig_nonce_file: &MusigNonceFile,
+) -> anyhow::Result<Option<BitcoinMusigNonce>> {
+	let Some((public_nonce, path)) = new_owner_musig_nonce(cosign, musig_nonce_file)? else {
+		return Ok(None);
+	};
+	print_owner_musig_nonce_file(&path);
+	Ok(Some(public_nonce))
+}
+
+/// Creates a MuSig2 secret nonce for the owner if the bitcoin is locked in a taproot script and
+/// stores it in the nonce file. Returns the public nonce and the path of the file.
+fn new_owner_musig_nonce(
+	cosign: &CosignScript,
+	musig_nonce_file: &MusigNonceFile,
+) -> anyhow::Result<Option<(BitcoinMusigNonce, PathBuf)>> {
+	if cosign.script_type != BitcoinCosignScriptType::P2TR {
+		return Ok(None);
+	}
+	let secret_nonce =
+		MusigSecretNonce::new(rand::random(), cosign.script_args.owner_pubkey, None)?;
+	let path = musig_nonce_file.write(&secret_nonce)?;
+	Ok(Some((secret_nonce.public_nonce(), path)))
+}
+
+fn print_owner_musig_nonce_file(path: &std::path::Path) {
+	println!(
+		"This bond uses a taproot script. Your MuSig2 secret nonce was saved to {}. Keep it to cosign with the vault. It's deleted once it's used.",
+		path.display()
+	);
+}
+
+/// Finds the utxos (and the shared fee) of a batch unlock that includes the given utxo
+fn find_unlock_batch(
+	events: &subxt::events::Events<ArgonConfig>,
+	utxo_id: UtxoId,
+) -> anyhow::Result<Option<(Vec<UtxoId>, Satoshis)>> {
+	for event in events.find::<api::bonds::events::BitcoinUtxosBatchCosigned>() {
+		let event = event?;
+		if event.utxo_ids.0.contains(&utxo_id) {
+			return Ok(Some((event.utxo_ids.0, event.bitcoin_network_fee)));
+		}
+	}
+	Ok(None)
+}
+
+pub async fn find_unlock_request(
 	client: &MainchainClient,
 	at_block: Option<H256>,
 	utxo_id: UtxoId,
-) -> anyhow::Result<Option<UtxoCosignRequest<u128>>> {
+) -> anyhow::Result<Option<UtxoCosignRequest<AccountId32, u128>>> {
 	let unlock_request = client
 		.fetch_storage(&storage().bonds().utxos_pending_unlock_by_utxo_id(), at_block)
 		.await?
@@ -722,7 +1664,7 @@ async fn find_unlock_request(
 		});
 	Ok(unlock_request)
 }
-async fn get_utxo_from_bond_id(
+pub async fn get_utxo_from_bond_id(
 	client: &MainchainClient,
 	bond_id: BondId,
 	at_block: Option<H256>,
@@ -736,15 +1678,39 @@ async fn get_utxo_from_bond_id(
 		.fetch_storage(&query, at_block)
 		.await?
 		.ok_or(anyhow!("No finalized bond found"))?;
-	let Some(utxo_id) = bond.utxo_id else {
+	let Some(bond_utxo_id) = bond.utxo_id else {
 		bail!("This isn't a bitcoin bond");
 	};
+	// a pending fee bump can be for a utxo the bond has since moved on from
+	let utxo_id = client
+		.fetch_storage(&storage().bonds().utxos_pending_unlock_by_utxo_id(), at_block)
+		.await?
+		.map(|a| a.0)
+		.unwrap_or_default()
+		.into_iter()
+		.find_map(|(utxo_id, unlock)| (unlock.bond_id == bond_id).then_some(utxo_id))
+		.unwrap_or(bond_utxo_id);
 
-	let utxo = client
-		.fetch_storage(&storage().bonds().utxos_by_id(utxo_id), at_block)
+	let utxo = get_unlocking_utxo(client, utxo_id, at_block).await?;
+	Ok((utxo_id, utxo, bond))
+}
+
+/// Loads a bonded utxo, or one that was already cosigned for unlock (eg, to bump its fee)
+pub async fn get_unlocking_utxo(
+	client: &MainchainClient,
+	utxo_id: UtxoId,
+	at_block: Option<H256>,
+) -> anyhow::Result<api::runtime_types::pallet_bond::pallet::UtxoState> {
+	if let Some(utxo) =
+		client.fetch_storage(&storage().bonds().utxos_by_id(utxo_id), at_block).await?
+	{
+		return Ok(utxo);
+	}
+	let (utxo, _) = client
+		.fetch_storage(&storage().bonds().cosigned_unlocks_by_utxo_id(utxo_id), at_block)
 		.await?
 		.ok_or(anyhow!("No utxo found for bond"))?;
-	Ok((utxo_id, utxo, bond))
+	Ok(utxo)
 }
 
 fn get_cosign_script(
@@ -758,7 +1724,102 @@ fn get_cosign_script(
 		vault_claim_height: utxo.vault_claim_height,
 		open_claim_height: utxo.open_claim_height,
 		created_at_height: utxo.created_at_height,
+		vault_multisig: utxo.vault_multisig.clone().map(Into::into),
 	};
+	let script_pubkey: BitcoinCosignScriptPubkey = utxo.utxo_script_pubkey.clone().into();
+
+	Ok(CosignScript::with_script_type(script_args, script_pubkey.script_type(), network)?)
+}
+
+/// The status of a bond and its bitcoin utxo
+#[derive(Serialize)]
+struct BondStatus {
+	bond_id: BondId,
+	utxo_id: UtxoId,
+	utxo_txid: Option<String>,
+	utxo_output_index: Option<u32>,
+	vault_pubkey: String,
+	owner_pubkey: String,
+	output_descriptor: String,
+	minted_argons: u128,
+	bond_amount: u128,
+	status: String,
+	redemption_price: u128,
+	/// The redemption price was paid with an unlock request
+	redemption_paid: bool,
+	vault_claim_height: BitcoinHeight,
+	open_claim_height: BitcoinHeight,
+	/// The bitcoin block the vault must cosign a requested unlock by
+	unlock_due_block: Option<BitcoinHeight>,
+}
 
-	Ok(CosignScript::new(script_args, network)?)
+impl OutputRecord for BondStatus {
+	const COLUMNS: &'static [(&'static str, &'static str)] = &[
+		("bond_id", "Bond Id"),
+		("utxo_id", "Utxo Id"),
+		("utxo_txid", "Bitcoin Utxo"),
+		("utxo_output_index", "Bitcoin Utxo Output"),
+		("vault_pubkey", "Vault pubkey"),
+		("owner_pubkey", "Owner pubkey"),
+		("output_descriptor", "Output Descriptor"),
+		("minted_argons", "Minted Argons"),
+		("bond_amount", "Bond Amount"),
+		("status", "Status"),
+		("redemption_price", "Redemption Price"),
+		("redemption_paid", "Redemption Paid"),
+		("vault_claim_height", "Expiration Bitcoin Block"),
+		("open_claim_height", "Owner Reclaim Bitcoin Block"),
+		("unlock_due_block", "Unlock Due Bitcoin Block"),
+	];
+
+	fn table_row(&self) -> Vec<String> {
+		vec![
+			self.bond_id.to_string(),
+			self.utxo_id.to_string(),
+			self.utxo_txid.clone().unwrap_or("-".to_string()),
+			self.utxo_output_index.map(|a| a.to_string()).unwrap_or_default(),
+			self.vault_pubkey.clone(),
+			self.owner_pubkey.clone(),
+			self.output_descriptor.clone(),
+			ArgonFormatter(self.minted_argons).to_string(),
+			ArgonFormatter(self.bond_amount).to_string(),
+			self.status.clone(),
+			ArgonFormatter(self.redemption_price).to_string(),
+			if self.redemption_paid { "Yes".to_string() } else { "No".to_string() },
+			self.vault_claim_height.to_string(),
+			self.open_claim_height.to_string(),
+			self.unlock_due_block.map(|a| a.to_string()).unwrap_or_default(),
+		]
+	}
+}
+
+/// The fees to unlock a bond, and the transaction to request it
+#[derive(Serialize)]
+struct UnlockQuote {
+	bond_id: BondId,
+	redemption_price: u128,
+	bitcoin_network_fee: Satoshis,
+	/// The file with the secret nonce the owner needs to cosign a taproot unlock with the vault
+	owner_musig_nonce_file: Option<String>,
+	transaction_url: String,
+}
+
+impl OutputRecord for UnlockQuote {
+	const COLUMNS: &'static [(&'static str, &'static str)] = &[
+		("bond_id", "Bond Id"),
+		("redemption_price", "Redemption Price"),
+		("bitcoin_network_fee", "Bitcoin Fee (sats)"),
+		("owner_musig_nonce_file", "Owner MuSig2 Nonce File"),
+		("transaction_url", "Transaction Link"),
+	];
+
+	fn table_row(&self) -> Vec<String> {
+		vec![
+			self.bond_id.to_string(),
+			ArgonFormatter(self.redemption_price).to_string(),
+			self.bitcoin_network_fee.to_string(),
+			self.owner_musig_nonce_file.clone().unwrap_or_default(),
+			self.transaction_url.clone(),
+		]
+	}
 }
@@ -1,6 +1,114 @@
 use argon_primitives::{argon_utils::format_argons, bitcoin::SATOSHIS_PER_BITCOIN};
+use clap::ValueEnum;
+use comfy_table::{modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL, ContentArrangement, Table};
+use serde::Serialize;
 use std::fmt;
 
+/// How the output of a command is printed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+	/// Human readable tables
+	#[default]
+	Table,
+	/// Json with a stable schema for each output. Amounts are in base units (microgons, sats).
+	Json,
+	/// Comma separated values with a header row of the json field names
+	Csv,
+}
+
+/// A record of a command output that can be printed in any [`OutputFormat`]
+pub trait OutputRecord: Serialize {
+	/// The json field name and the table header of each column, in order
+	const COLUMNS: &'static [(&'static str, &'static str)];
+
+	/// The human readable value of each column, in the order of [`Self::COLUMNS`]
+	fn table_row(&self) -> Vec<String>;
+}
+
+impl OutputFormat {
+	/// Formats a list of records, one per table row
+	pub fn format_list<T: OutputRecord>(&self, records: &[T]) -> anyhow::Result<String> {
+		match self {
+			OutputFormat::Table => {
+				let mut table = new_table();
+				table.set_header(T::COLUMNS.iter().map(|(_, header)| *header));
+				for record in records {
+					table.add_row(record.table_row());
+				}
+				Ok(table.to_string())
+			},
+			OutputFormat::Json => Ok(serde_json::to_string_pretty(records)?),
+			OutputFormat::Csv => to_csv(records),
+		}
+	}
+
+	/// Formats a single record. Tables show a row per column, skipping empty values.
+	pub fn format_record<T: OutputRecord>(&self, record: &T) -> anyhow::Result<String> {
+		match self {
+			OutputFormat::Table => {
+				let mut table = new_table();
+				for ((_, header), value) in T::COLUMNS.iter().zip(record.table_row()) {
+					if !value.is_empty() {
+						table.add_row(vec![header.to_string(), value]);
+					}
+				}
+				Ok(table.to_string())
+			},
+			OutputFormat::Json => Ok(serde_json::to_string_pretty(record)?),
+			OutputFormat::Csv => to_csv(std::slice::from_ref(record)),
+		}
+	}
+}
+
+/// A single value output (eg, a conversion)
+#[derive(Serialize)]
+pub struct OutputValue {
+	pub value: String,
+}
+
+impl OutputRecord for OutputValue {
+	const COLUMNS: &'static [(&'static str, &'static str)] = &[("value", "Value")];
+
+	fn table_row(&self) -> Vec<String> {
+		vec![self.value.clone()]
+	}
+}
+
+fn new_table() -> Table {
+	let mut table = Table::new();
+	table
+		.load_preset(UTF8_FULL)
+		.apply_modifier(UTF8_ROUND_CORNERS)
+		.set_content_arrangement(ContentArrangement::Dynamic);
+	table
+}
+
+fn to_csv<T: OutputRecord>(records: &[T]) -> anyhow::Result<String> {
+	let fields = T::COLUMNS.iter().map(|(field, _)| *field).collect::<Vec<_>>();
+	let mut lines = vec![fields.join(",")];
+	for record in records {
+		let value = serde_json::to_value(record)?;
+		let row = fields
+			.iter()
+			.map(|field| match value.get(field) {
+				None | Some(serde_json::Value::Null) => String::new(),
+				Some(serde_json::Value::String(s)) => csv_escape(s),
+				Some(other) => csv_escape(&other.to_string()),
+			})
+			.collect::<Vec<_>>();
+		lines.push(row.join(","));
+	}
+	Ok(lines.join("\n"))
+}
+
+fn csv_escape(value: &str) -> String {
+	if value.contains([',', '"', '\n', '\r']) {
+		format!("\"{}\"", value.replace('"', "\"\""))
+	} else {
+		value.to_string()
+	}
+}
+
 pub fn parse_number(s: &str) -> Result<f32, String> {
 	// Remove commas from the string
 	let cleaned: String = s.chars().filter(|&c| c.is_ascii_digit() || c == '.').collect();
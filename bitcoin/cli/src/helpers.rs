@@ -17,6 +17,42 @@ pub fn read_bitcoin_xpub(xpub: &str) -> Result<OpaqueBitcoinXpub, String> {
 	Ok(OpaqueBitcoinXpub(raw_bytes))
 }
 
+/// Reads the threshold and xpubs of a multisig output descriptor (eg,
+/// `wsh(multi(2,xpub1,xpub2,xpub3))`). Keys can include their origin (`[fingerprint/path]xpub`),
+/// but must derive directly from the xpub (`xpub` or `xpub/*`), which is how Argon derives bond
+/// pubkeys.
+pub fn read_multisig_descriptor(descriptor: &str) -> Result<(u8, Vec<String>), String> {
+	// drop the checksum
+	let descriptor = descriptor.split('#').next().unwrap_or_default().trim();
+	let inner = descriptor
+		.strip_prefix("wsh(multi(")
+		.and_then(|a| a.strip_suffix("))"))
+		.ok_or("Only wsh(multi(...)) descriptors are supported")?;
+	let mut parts = inner.split(',');
+	let threshold = parts
+		.next()
+		.unwrap_or_default()
+		.trim()
+		.parse::<u8>()
+		.map_err(|_| "Invalid multisig threshold")?;
+	let xpubs = parts
+		.map(|key| {
+			let key = key.trim();
+			let key = key.split_once(']').map(|(_, key)| key).unwrap_or(key);
+			let key = key.strip_suffix("/*").unwrap_or(key);
+			if key.contains('/') {
+				return Err(format!("Keys must derive directly from the xpub ({key})"));
+			}
+			read_bitcoin_xpub(key)?;
+			Ok(key.to_string())
+		})
+		.collect::<Result<Vec<_>, String>>()?;
+	if xpubs.len() < 2 || threshold == 0 || threshold as usize > xpubs.len() {
+		return Err(format!("A {threshold} of {} multisig isn't supported", xpubs.len()));
+	}
+	Ok((threshold, xpubs))
+}
+
 /// Translate a percent out of 100 to a fixed 128-bit number
 pub fn read_percent_to_fixed_128(percent: f32) -> FixedU128 {
 	FixedU128::from_float(percent as f64).div(FixedU128::from_u32(100))
@@ -1,6 +1,6 @@
 use crate::{
 	bond_commands::BondCommands,
-	formatters::{parse_number, Pct64},
+	formatters::{parse_number, OutputFormat, OutputValue, Pct64},
 	helpers::{read_bitcoin_xpub, read_percent_to_fixed_128},
 	vault_commands::VaultCommands,
 	xpriv_commands::XPrivCommands,
@@ -13,6 +13,9 @@ use std::{env, str::FromStr};
 mod bond_commands;
 mod formatters;
 mod helpers;
+mod musig_nonce_file;
+mod timelock_claim;
+mod vault_autopilot;
 mod vault_commands;
 mod vault_create;
 mod xpriv_commands;
@@ -26,6 +29,11 @@ struct Cli {
 	#[clap(short, long, env, global = true, default_value = "ws://127.0.0.1:9944")]
 	trusted_rpc_url: String,
 
+	/// How to print the output of vault, bond and utils commands. Json and csv use a stable
+	/// schema with amounts in base units (microgons and sats).
+	#[clap(long, global = true, value_enum, default_value_t = OutputFormat::Table)]
+	format: OutputFormat,
+
 	#[command(subcommand)]
 	command: Commands,
 }
@@ -66,9 +74,9 @@ async fn main() -> anyhow::Result<()> {
 	let rpc_url = cli.trusted_rpc_url.clone();
 
 	match cli.command {
-		Commands::Vault { subcommand } => subcommand.process(rpc_url).await?,
-		Commands::Bond { subcommand } => subcommand.process(rpc_url).await?,
-		Commands::Utils { subcommand } => subcommand.process(rpc_url).await?,
+		Commands::Vault { subcommand } => subcommand.process(rpc_url, cli.format).await?,
+		Commands::Bond { subcommand } => subcommand.process(rpc_url, cli.format).await?,
+		Commands::Utils { subcommand } => subcommand.process(rpc_url, cli.format).await?,
 		Commands::XPriv { subcommand } => subcommand.process(rpc_url).await?,
 	};
 
@@ -91,23 +99,27 @@ struct OneArg {
 	arg: String,
 }
 impl UtilCommands {
-	pub async fn process(self, _rpc_url: String) -> anyhow::Result<()> {
-		match self {
+	pub async fn process(self, _rpc_url: String, format: OutputFormat) -> anyhow::Result<()> {
+		let value = match self {
 			UtilCommands::ToFixed(OneArg { arg }) => {
 				let fixed = parse_number(&arg).map_err(|e| anyhow!(e))?;
 				let fixed = read_percent_to_fixed_128(fixed);
-				println!("{}", fixed.into_inner());
+				fixed.into_inner().to_string()
 			},
 			UtilCommands::FromFixed(fixed) => {
 				let fixed = FixedU128::from_str(&fixed.arg).map_err(|e| anyhow!(e))?;
 				let percent = fixed.mul(FixedU128::from_u32(100)).to_float();
-
-				println!("{}", Pct64(percent));
+				Pct64(percent).to_string()
 			},
 			UtilCommands::EncodeXPub(xpub) => {
 				let xpub = read_bitcoin_xpub(&xpub.arg).map_err(|e| anyhow!(e))?;
-				println!("0x{}", hex::encode(xpub.0));
+				format!("0x{}", hex::encode(xpub.0))
 			},
+		};
+		if format == OutputFormat::Table {
+			println!("{value}");
+		} else {
+			println!("{}", format.format_record(&OutputValue { value })?);
 		}
 		Ok(())
 	}
@@ -0,0 +1,67 @@
+use crate::xpriv_file::expand_path;
+use anyhow::anyhow;
+use argon_bitcoin::MusigSecretNonce;
+use clap::Args;
+use std::{
+	fs,
+	fs::OpenOptions,
+	io::{ErrorKind, Write},
+	path::PathBuf,
+};
+
+/// A local file that keeps the owner's MuSig2 secret nonce between requesting a taproot unlock and
+/// cosigning it. The nonce is deleted as soon as it's used, so it can never sign twice.
+#[derive(Debug, Clone, Args)]
+pub struct MusigNonceFile {
+	/// The file that stores the MuSig2 secret nonce of a taproot bond (required for taproot
+	/// bonds)
+	#[arg(long, value_name = "PATH")]
+	pub musig_nonce_file: Option<PathBuf>,
+}
+
+impl MusigNonceFile {
+	fn path(&self) -> anyhow::Result<PathBuf> {
+		let path = self
+			.musig_nonce_file
+			.as_ref()
+			.ok_or(anyhow!("A --musig-nonce-file is required for a taproot bond"))?;
+		Ok(expand_path(path))
+	}
+
+	/// Stores a new secret nonce. An existing file is never overwritten since it might hold a
+	/// nonce that is still needed to cosign.
+	pub fn write(&self, secret_nonce: &MusigSecretNonce) -> anyhow::Result<PathBuf> {
+		let path = self.path()?;
+		if let Some(parent) = path.parent() {
+			if !parent.as_os_str().is_empty() && !parent.exists() {
+				fs::create_dir_all(parent)?;
+			}
+		}
+
+		let mut options = OpenOptions::new();
+		options.write(true).create_new(true);
+		#[cfg(unix)]
+		{
+			use std::os::unix::fs::OpenOptionsExt;
+			options.mode(0o600);
+		}
+		let mut file = options.open(&path).map_err(|e| match e.kind() {
+			ErrorKind::AlreadyExists => anyhow!(
+				"The MuSig2 nonce file {path:?} already exists. Use a new file for every request"
+			),
+			_ => anyhow!("Unable to create the MuSig2 nonce file {path:?}: {e}"),
+		})?;
+		file.write_all(hex::encode(secret_nonce.to_bytes()).as_bytes())?;
+		Ok(path)
+	}
+
+	/// Reads the secret nonce and deletes the file so the nonce can't be re-used
+	pub fn take(&self) -> anyhow::Result<MusigSecretNonce> {
+		let path = self.path()?;
+		let contents = fs::read_to_string(&path).map_err(|e| {
+			anyhow!("Unable to read the MuSig2 nonce file {path:?}. It might already be used: {e}")
+		})?;
+		fs::remove_file(&path)?;
+		Ok(MusigSecretNonce::from_bytes(&hex::decode(contents.trim())?)?)
+	}
+}
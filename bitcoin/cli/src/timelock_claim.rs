@@ -0,0 +1,332 @@
+use std::{fs, path::PathBuf, str::FromStr};
+
+use anyhow::{anyhow, bail, ensure, Context};
+use bitcoin::{
+	bip32::{ChildNumber, DerivationPath, Fingerprint},
+	consensus::encode::serialize_hex,
+	key::Secp256k1,
+	Address, CompressedPublicKey, FeeRate, Network, ScriptBuf, Transaction, Txid,
+};
+use clap::Args;
+use serde::{Deserialize, Serialize};
+use sp_runtime::{testing::H256, BoundedVec};
+
+use argon_bitcoin::{CosignScript, CosignScriptArgs, UnlockStep, UtxoUnlocker};
+use argon_client::{api::storage, MainchainClient};
+use argon_primitives::{
+	bitcoin::{
+		BitcoinCosignScriptPubkey, BitcoinHeight, CompressedBitcoinPubkey, H256Le, Satoshis,
+		UtxoId, VaultMultisigPubkeys,
+	},
+	BlockNumber, BondId,
+};
+
+use crate::{
+	bond_commands::get_utxo_from_bond_id,
+	helpers::get_bitcoin_network,
+	xpriv_file::{expand_path, XprivFile},
+};
+
+/// Where to load the cosign script of a bond from
+#[derive(Debug, Clone, Args)]
+pub struct BondScriptSource {
+	/// The bond id that holds the bitcoin utxo in Argon
+	#[clap(short, long, required_unless_present = "bond_file", conflicts_with = "bond_file")]
+	pub bond_id: Option<BondId>,
+
+	/// Load the bond at this Argon block number. Bonds are cleaned up on release, so use a block
+	/// where the bond still existed (defaults to the latest finalized block).
+	#[clap(short, long, requires = "bond_id")]
+	pub at_block: Option<BlockNumber>,
+
+	/// A file created by `bond export-script`. The claim can then be created without an Argon
+	/// node.
+	#[clap(long, value_name = "PATH")]
+	pub bond_file: Option<PathBuf>,
+}
+
+impl BondScriptSource {
+	pub async fn load(&self, rpc_url: &str) -> anyhow::Result<BondScriptExport> {
+		if let Some(path) = &self.bond_file {
+			return BondScriptExport::read(path);
+		}
+		let bond_id = self.bond_id.ok_or(anyhow!("A bond id or a bond file is required"))?;
+		let client = MainchainClient::from_url(rpc_url)
+			.await
+			.context("Failed to connect to argon node")?;
+		BondScriptExport::from_chain(&client, bond_id, self.at_block).await
+	}
+}
+
+/// The bond data needed to rebuild a cosign script and claim its utxo once a timelock expires
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BondScriptExport {
+	pub bond_id: BondId,
+	pub utxo_id: UtxoId,
+	pub network: String,
+	pub txid: String,
+	pub output_index: u32,
+	pub satoshis: Satoshis,
+	/// The hex encoded script pubkey of the utxo
+	pub script_pubkey: String,
+	pub vault_pubkey: String,
+	pub vault_claim_pubkey: String,
+	pub owner_pubkey: String,
+	pub vault_claim_height: BitcoinHeight,
+	pub open_claim_height: BitcoinHeight,
+	pub created_at_height: BitcoinHeight,
+	/// The fingerprint of the vault xpub the vault pubkeys were derived from
+	pub vault_xpub_fingerprint: String,
+	pub vault_xpub_child: u32,
+	pub vault_claim_xpub_child: u32,
+	/// The other vault pubkeys if the vault is a multisig
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub vault_multisig: Option<BondScriptMultisig>,
+}
+
+/// The multisig side of a vault in a [`BondScriptExport`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BondScriptMultisig {
+	pub threshold: u8,
+	/// Hex encoded pubkeys that cosign with the vault pubkey
+	pub cosign_pubkeys: Vec<String>,
+	/// Hex encoded pubkeys that claim with the vault claim pubkey
+	pub claim_pubkeys: Vec<String>,
+}
+
+impl BondScriptExport {
+	pub async fn from_chain(
+		client: &MainchainClient,
+		bond_id: BondId,
+		at_block: Option<BlockNumber>,
+	) -> anyhow::Result<Self> {
+		let at_block: H256 = match at_block {
+			Some(block_number) => client
+				.fetch_storage(&storage().system().block_hash(block_number), None)
+				.await?
+				.ok_or(anyhow!("No block found for the given block number"))?
+				.into(),
+			None => client.latest_finalized_block_hash().await?.hash(),
+		};
+		let at_block = Some(at_block);
+
+		let (utxo_id, utxo, _) = get_utxo_from_bond_id(client, bond_id, at_block).await?;
+		let utxo_ref = client
+			.fetch_storage(&storage().bitcoin_utxos().utxo_id_to_ref(utxo_id), at_block)
+			.await?
+			.ok_or(anyhow!("No utxo ref found for bond"))?;
+		let network = get_bitcoin_network(client, at_block).await?;
+
+		let txid: Txid = H256Le(utxo_ref.txid.0).into();
+		let script_pubkey: BitcoinCosignScriptPubkey = utxo.utxo_script_pubkey.into();
+		let encode_pubkey = |pubkey: CompressedBitcoinPubkey| hex::encode(pubkey.0);
+		let vault_multisig = utxo.vault_multisig.map(|multisig| {
+			let multisig: VaultMultisigPubkeys = multisig.into();
+			BondScriptMultisig {
+				threshold: multisig.threshold,
+				cosign_pubkeys: multisig.cosign_pubkeys.into_iter().map(encode_pubkey).collect(),
+				claim_pubkeys: multisig.claim_pubkeys.into_iter().map(encode_pubkey).collect(),
+			}
+		});
+
+		Ok(Self {
+			bond_id,
+			utxo_id,
+			network: network.to_string(),
+			txid: txid.to_string(),
+			output_index: utxo_ref.output_index,
+			satoshis: utxo.satoshis,
+			script_pubkey: hex::encode(ScriptBuf::from(script_pubkey).as_bytes()),
+			vault_pubkey: encode_pubkey(utxo.vault_pubkey.into()),
+			vault_claim_pubkey: encode_pubkey(utxo.vault_claim_pubkey.into()),
+			owner_pubkey: encode_pubkey(utxo.owner_pubkey.into()),
+			vault_claim_height: utxo.vault_claim_height,
+			open_claim_height: utxo.open_claim_height,
+			created_at_height: utxo.created_at_height,
+			vault_xpub_fingerprint: Fingerprint::from(utxo.vault_xpub_sources.0).to_string(),
+			vault_xpub_child: utxo.vault_xpub_sources.1,
+			vault_claim_xpub_child: utxo.vault_xpub_sources.2,
+			vault_multisig,
+		})
+	}
+
+	pub fn read(path: &PathBuf) -> anyhow::Result<Self> {
+		let contents = fs::read_to_string(expand_path(path))
+			.with_context(|| format!("Unable to read the bond file {}", path.display()))?;
+		serde_json::from_str(&contents).context("Invalid bond file")
+	}
+
+	pub fn write(&self, path: &PathBuf) -> anyhow::Result<PathBuf> {
+		let path = expand_path(path);
+		if path.is_file() {
+			bail!("File already exists");
+		}
+		fs::write(&path, serde_json::to_string_pretty(self)?)?;
+		Ok(path)
+	}
+
+	pub fn network(&self) -> anyhow::Result<Network> {
+		Network::from_str(&self.network).map_err(|e| anyhow!("Invalid bitcoin network: {e}"))
+	}
+
+	/// Rebuilds the cosign script and checks it still matches the script pubkey of the utxo
+	pub fn cosign_script(&self) -> anyhow::Result<CosignScript> {
+		let decode_pubkey = |pubkey: &str| -> anyhow::Result<CompressedBitcoinPubkey> {
+			Ok(CompressedPublicKey::from_str(pubkey)
+				.map_err(|e| anyhow!("Invalid pubkey {pubkey}: {e}"))?
+				.into())
+		};
+		let script_pubkey = ScriptBuf::from_bytes(hex::decode(&self.script_pubkey)?);
+		let cosign_script_pubkey: BitcoinCosignScriptPubkey = script_pubkey
+			.clone()
+			.try_into()
+			.map_err(|_| anyhow!("The script pubkey isn't a cosign script"))?;
+		let vault_multisig = match &self.vault_multisig {
+			Some(multisig) => {
+				let decode_pubkeys = |pubkeys: &Vec<String>| -> anyhow::Result<_> {
+					let pubkeys = pubkeys
+						.iter()
+						.map(|a| decode_pubkey(a))
+						.collect::<anyhow::Result<Vec<_>>>()?;
+					BoundedVec::try_from(pubkeys).map_err(|_| anyhow!("Too many vault pubkeys"))
+				};
+				Some(VaultMultisigPubkeys {
+					threshold: multisig.threshold,
+					cosign_pubkeys: decode_pubkeys(&multisig.cosign_pubkeys)?,
+					claim_pubkeys: decode_pubkeys(&multisig.claim_pubkeys)?,
+				})
+			},
+			None => None,
+		};
+		let script_args = CosignScriptArgs {
+			vault_pubkey: decode_pubkey(&self.vault_pubkey)?,
+			vault_claim_pubkey: decode_pubkey(&self.vault_claim_pubkey)?,
+			owner_pubkey: decode_pubkey(&self.owner_pubkey)?,
+			vault_claim_height: self.vault_claim_height,
+			open_claim_height: self.open_claim_height,
+			created_at_height: self.created_at_height,
+			vault_multisig,
+		};
+		let cosign_script = CosignScript::with_script_type(
+			script_args,
+			cosign_script_pubkey.script_type(),
+			self.network()?,
+		)?;
+		ensure!(
+			cosign_script.get_script_pubkey() == script_pubkey,
+			"The bond data doesn't recreate the script pubkey of the utxo"
+		);
+		Ok(cosign_script)
+	}
+
+	/// The hd path of the vault claim pubkey under the master xpub the vault uploaded to Argon
+	pub fn vault_claim_hd_path(&self, master_xpub_hd_path: &str) -> anyhow::Result<DerivationPath> {
+		Ok(DerivationPath::from_str(master_xpub_hd_path)?
+			.extend([ChildNumber::from(self.vault_claim_xpub_child)]))
+	}
+}
+
+/// Signs a timelocked claim with an xpriv file or a hardware wallet
+#[derive(Debug, Clone, Args)]
+pub struct ClaimSigner {
+	#[clap(flatten)]
+	pub xpriv_file: XprivFile,
+
+	/// Sign with a hardware wallet (using HWI) instead of an xpriv file. Only P2WSH bonds can be
+	/// claimed this way.
+	#[cfg(feature = "hwi")]
+	#[clap(long)]
+	pub hwi: bool,
+}
+
+impl ClaimSigner {
+	/// Builds the claim transaction for the given unlock step, with a lock time of the claim
+	/// height, and signs it with the key at `hd_path`
+	pub fn create_claim_tx(
+		&self,
+		bond: &BondScriptExport,
+		unlock_step: UnlockStep,
+		hd_path: DerivationPath,
+		dest_pubkey: &str,
+		fee_rate_sats_per_kb: u64,
+	) -> anyhow::Result<Transaction> {
+		let network = bond.network()?;
+		let cosign_script = bond.cosign_script()?;
+		let expected_pubkey = match unlock_step {
+			UnlockStep::OwnerClaim => cosign_script.script_args.owner_pubkey,
+			UnlockStep::VaultClaim => {
+				ensure!(
+					bond.vault_multisig.is_none(),
+					"A multisig vault claim needs a signature from each of its cosigner keys, which this command can't collect"
+				);
+				cosign_script.script_args.vault_claim_pubkey
+			},
+			_ => bail!("Only the timelocked claims can be signed"),
+		};
+		let fee_rate =
+			FeeRate::from_sat_per_vb(fee_rate_sats_per_kb).ok_or(anyhow!("Invalid fee rate"))?;
+		let pay_scriptpub = Address::from_str(dest_pubkey)
+			.map_err(|e| anyhow!("Unable to parse bitcoin destination pubkey: {e:?}"))?
+			.require_network(network)?
+			.script_pubkey();
+		let fee = cosign_script.calculate_fee(false, pay_scriptpub.clone(), fee_rate)?;
+
+		let mut unlocker = UtxoUnlocker::from_script(
+			cosign_script,
+			bond.satoshis,
+			Txid::from_str(&bond.txid)?,
+			bond.output_index,
+			unlock_step,
+			fee,
+			pay_scriptpub,
+		)?;
+
+		#[cfg(feature = "hwi")]
+		if self.hwi {
+			return sign_with_hwi(unlocker, hd_path, network);
+		}
+
+		let secp = Secp256k1::new();
+		let master_xpriv = self.xpriv_file.read()?;
+		let child_xpriv = master_xpriv.derive_priv(&secp, &hd_path)?;
+		let pubkey = CompressedPublicKey::from_private_key(&secp, &child_xpriv.to_priv())?;
+		ensure!(
+			CompressedBitcoinPubkey::from(pubkey) == expected_pubkey,
+			"The xpriv doesn't derive the claim pubkey of this bond at {hd_path}"
+		);
+		let tx = unlocker
+			.sign_claim_derived(master_xpriv, (master_xpriv.fingerprint(&secp), hd_path))?;
+		Ok(tx)
+	}
+}
+
+#[cfg(feature = "hwi")]
+fn sign_with_hwi(
+	mut unlocker: UtxoUnlocker,
+	hd_path: DerivationPath,
+	network: Network,
+) -> anyhow::Result<Transaction> {
+	ensure!(
+		unlocker.cosign_script.script_type ==
+			argon_primitives::bitcoin::BitcoinCosignScriptType::P2WSH,
+		"Hardware wallets can only sign claims of P2WSH bonds"
+	);
+	let device = hwi::HWIClient::enumerate()
+		.map_err(|e| anyhow!("Error enumerating devices: {:?}", e))?
+		.into_iter()
+		.flatten()
+		.next()
+		.ok_or(anyhow!("No hardware wallet found"))?;
+	let key_source = (device.fingerprint, hd_path);
+	unlocker.sign_hwi(key_source, Some(device), network)?;
+	Ok(unlocker.extract_tx()?)
+}
+
+/// Prints the raw claim transaction and the bitcoin height it can be broadcast at
+pub fn print_claim_tx(tx: &Transaction) {
+	println!(
+		"Broadcast this transaction once bitcoin reaches block {} (eg, with `bitcoin-cli sendrawtransaction`):\n\n{}",
+		tx.lock_time,
+		serialize_hex(tx)
+	);
+}
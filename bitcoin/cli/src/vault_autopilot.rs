@@ -0,0 +1,376 @@
+use std::{collections::BTreeMap, process::Command, str::FromStr, time::Duration};
+
+use anyhow::{anyhow, bail};
+use bitcoin::{
+	bip32::{ChildNumber, DerivationPath, Xpriv},
+	key::Secp256k1,
+	CompressedPublicKey,
+};
+use clap::Args;
+use sp_runtime::{key_types::ACCOUNT, testing::H256, AccountId32};
+use tokio::time::sleep;
+use tracing::{error, info, warn};
+
+use argon_client::{
+	api,
+	api::{runtime_types::pallet_bond::pallet::UtxoCosignRequest, storage, tx},
+	signer::{KeystoreSigner, Signer},
+	MainchainClient, ReconnectingClient,
+};
+use argon_primitives::{
+	bitcoin::{BitcoinHeight, CompressedBitcoinPubkey, UtxoId},
+	BlockNumber, BondId, CryptoType, KeystoreParams, VaultId,
+};
+
+use crate::{
+	bond_commands::{find_unlock_request, get_unlocking_utxo, load_unlocker, sign_vault_unlock},
+	xpriv_file::XprivFile,
+};
+
+/// The environment variable the alert message is passed to the alert command in
+const ALERT_ENV: &str = "ARGON_AUTOPILOT_ALERT";
+/// How many finalized blocks to wait for a submitted cosign before submitting it again
+const RESUBMIT_AFTER_BLOCKS: BlockNumber = 10;
+/// The most finalized blocks to wait between failed attempts
+const MAX_RETRY_DELAY_BLOCKS: BlockNumber = 10;
+
+#[derive(Debug, Clone, Args)]
+pub struct VaultAutopilot {
+	/// The vault id to cosign unlocks for
+	#[clap(short, long)]
+	vault_id: VaultId,
+
+	#[clap(flatten)]
+	xpriv_file: XprivFile,
+
+	/// Provide the path of the derived master xpub uploaded to Argon
+	#[clap(long)]
+	master_xpub_hd_path: String,
+
+	/// The secret uri of the account that submits the cosigns. This can be the vault operator or
+	/// an account assigned the cosigner role of the vault. If the value is a file, the file
+	/// content is used. If not given, you will be prompted for it.
+	#[clap(long, env)]
+	operator_suri: Option<String>,
+
+	/// The crypto type of the vault operator account
+	#[clap(long, env, default_value_t = CryptoType::Sr25519)]
+	operator_crypto: CryptoType,
+
+	/// Don't cosign unlocks that pay a bitcoin network fee above this many sats. They will be
+	/// sent as alerts to handle by hand.
+	#[clap(long)]
+	max_bitcoin_fee_sats: Option<u64>,
+
+	/// Send an alert if an unlock isn't cosigned this many bitcoin blocks before it's due
+	#[clap(long, default_value = "6")]
+	alert_blocks_before_deadline: BitcoinHeight,
+
+	/// Send an alert after this many failed attempts to submit a cosign
+	#[clap(long, default_value = "3")]
+	alert_after_failures: u32,
+
+	/// A shell command to run for each alert (eg, to notify you). The message is passed in the
+	/// `ARGON_AUTOPILOT_ALERT` environment variable.
+	#[clap(long, env)]
+	alert_command: Option<String>,
+}
+
+/// An unlock request of the vault that hasn't been cosigned in a finalized block yet
+struct PendingCosign {
+	bond_id: BondId,
+	attempts: u32,
+	next_attempt_block: BlockNumber,
+	/// The request can't be cosigned by the autopilot, so the operator was alerted to handle it
+	needs_attention: bool,
+	deadline_alerted: bool,
+}
+
+impl PendingCosign {
+	fn new(bond_id: BondId) -> Self {
+		Self {
+			bond_id,
+			attempts: 0,
+			next_attempt_block: 0,
+			needs_attention: false,
+			deadline_alerted: false,
+		}
+	}
+}
+
+enum CosignError {
+	/// The request can't be cosigned with the local xpriv, so needs the operator's attention
+	Rejected(String),
+	/// The cosign failed to submit, but can be retried
+	Failed(anyhow::Error),
+}
+
+impl From<anyhow::Error> for CosignError {
+	fn from(e: anyhow::Error) -> Self {
+		CosignError::Failed(e)
+	}
+}
+
+impl VaultAutopilot {
+	/// Follows finalized blocks and cosigns the unlock requests of the vault until stopped
+	pub async fn run(self, rpc_url: String) -> anyhow::Result<()> {
+		let child_xpriv = self.xpriv_file.read()?.derive_priv(
+			&Secp256k1::new(),
+			&DerivationPath::from_str(&self.master_xpub_hd_path)?,
+		)?;
+		let signer = self.operator_signer()?;
+
+		let mut client = ReconnectingClient::new(vec![rpc_url]);
+		let mainchain_client = client.get().await?;
+		let vault = mainchain_client
+			.fetch_storage(&storage().vaults().vaults_by_id(self.vault_id), None)
+			.await?
+			.ok_or(anyhow!("No vault found with id {}", self.vault_id))?;
+		let account_id = mainchain_client.api_account(&signer.account_id());
+		let is_cosigner = vault.operator_account_id == account_id ||
+			mainchain_client
+				.fetch_storage(&storage().vaults().vault_roles_by_id(self.vault_id), None)
+				.await?
+				.is_some_and(|roles| roles.cosigners.0.contains(&account_id));
+		if !is_cosigner {
+			bail!(
+				"The operator account is not the operator or a cosigner of vault {}",
+				self.vault_id
+			);
+		}
+		info!(vault_id = self.vault_id, operator = ?signer.account_id(), "Vault autopilot started");
+
+		let mut pending = BTreeMap::new();
+		loop {
+			if let Err(e) =
+				self.follow_blocks(&mut client, &signer, child_xpriv, &mut pending).await
+			{
+				warn!(?e, "Stopped following argon blocks. Reconnecting...");
+				sleep(Duration::from_secs(5)).await;
+			}
+		}
+	}
+
+	fn operator_signer(&self) -> anyhow::Result<KeystoreSigner> {
+		let keystore_params = KeystoreParams {
+			keystore_path: None,
+			password_interactive: false,
+			password: None,
+			password_filename: None,
+		};
+		let (keystore, address) = keystore_params.open_with_account(
+			self.operator_suri.as_ref(),
+			self.operator_crypto.clone(),
+			ACCOUNT,
+			true,
+		)?;
+		let account_id = AccountId32::from_str(&address)
+			.map_err(|e| anyhow!("Invalid operator address {address} -> {e}"))?;
+		Ok(KeystoreSigner::new(keystore, account_id, self.operator_crypto.clone()))
+	}
+
+	async fn follow_blocks(
+		&self,
+		client: &mut ReconnectingClient,
+		signer: &KeystoreSigner,
+		child_xpriv: Xpriv,
+		pending: &mut BTreeMap<UtxoId, PendingCosign>,
+	) -> anyhow::Result<()> {
+		let client = client.get().await?;
+		let mut blocks = client.live.blocks().subscribe_finalized().await?;
+
+		// requests made while the autopilot wasn't following blocks are only found in storage
+		let at_block = client.latest_finalized_block_hash().await?.hash();
+		let requests = client
+			.fetch_storage(&storage().bonds().utxos_pending_unlock_by_utxo_id(), Some(at_block))
+			.await?
+			.map(|a| a.0)
+			.unwrap_or_default();
+		for (utxo_id, request) in requests {
+			if request.vault_id == self.vault_id {
+				pending.entry(utxo_id).or_insert_with(|| PendingCosign::new(request.bond_id));
+			}
+		}
+
+		while let Some(block) = blocks.next().await {
+			let block = block?;
+			let events = block.events().await?;
+			for event in events.find::<api::bonds::events::BitcoinUtxoCosignRequested>() {
+				let event = event?;
+				if event.vault_id == self.vault_id {
+					info!(bond_id = event.bond_id, utxo_id = event.utxo_id, "Unlock requested");
+					pending.insert(event.utxo_id, PendingCosign::new(event.bond_id));
+				}
+			}
+			for event in events.find::<api::bonds::events::BitcoinUtxoCosigned>() {
+				let event = event?;
+				if event.vault_id == self.vault_id && pending.remove(&event.utxo_id).is_some() {
+					info!(bond_id = event.bond_id, utxo_id = event.utxo_id, "Unlock cosigned");
+				}
+			}
+			self.cosign_pending(
+				&client,
+				signer,
+				child_xpriv,
+				block.number(),
+				block.hash(),
+				pending,
+			)
+			.await?;
+		}
+		bail!("The finalized block subscription ended")
+	}
+
+	async fn cosign_pending(
+		&self,
+		client: &MainchainClient,
+		signer: &KeystoreSigner,
+		child_xpriv: Xpriv,
+		block_number: BlockNumber,
+		block_hash: H256,
+		pending: &mut BTreeMap<UtxoId, PendingCosign>,
+	) -> anyhow::Result<()> {
+		if pending.is_empty() {
+			return Ok(());
+		}
+		let bitcoin_tip = client
+			.fetch_storage(
+				&storage().bitcoin_utxos().confirmed_bitcoin_block_tip(),
+				Some(block_hash),
+			)
+			.await?
+			.map(|a| a.block_height)
+			.unwrap_or_default();
+
+		let utxo_ids = pending.keys().copied().collect::<Vec<_>>();
+		for utxo_id in utxo_ids {
+			let Some(request) = find_unlock_request(client, Some(block_hash), utxo_id).await?
+			else {
+				// cosigned (or canceled) outside of the autopilot
+				pending.remove(&utxo_id);
+				continue;
+			};
+			let Some(entry) = pending.get_mut(&utxo_id) else {
+				continue;
+			};
+
+			let blocks_left = request.cosign_due_block.saturating_sub(bitcoin_tip);
+			if !entry.deadline_alerted && blocks_left <= self.alert_blocks_before_deadline {
+				entry.deadline_alerted = true;
+				self.alert(format!(
+					"The unlock of bond {} (utxo {utxo_id}) isn't cosigned and is due in {blocks_left} bitcoin blocks. Vault funds will be burned if it's not cosigned by bitcoin block {}.",
+					entry.bond_id, request.cosign_due_block
+				));
+			}
+			if entry.needs_attention || entry.next_attempt_block > block_number {
+				continue;
+			}
+
+			match self.cosign(client, signer, child_xpriv, utxo_id, &request, block_hash).await {
+				Ok(()) => {
+					info!(bond_id = entry.bond_id, utxo_id, "Submitted unlock cosign");
+					entry.attempts = 0;
+					entry.next_attempt_block = block_number + RESUBMIT_AFTER_BLOCKS;
+				},
+				Err(CosignError::Rejected(reason)) => {
+					entry.needs_attention = true;
+					self.alert(format!(
+						"The unlock of bond {} (utxo {utxo_id}) must be cosigned by hand: {reason}",
+						entry.bond_id
+					));
+				},
+				Err(CosignError::Failed(e)) => {
+					entry.attempts += 1;
+					entry.next_attempt_block = block_number +
+						2u32.saturating_pow(entry.attempts).min(MAX_RETRY_DELAY_BLOCKS);
+					warn!(
+						bond_id = entry.bond_id,
+						utxo_id,
+						attempts = entry.attempts,
+						?e,
+						"Failed to cosign unlock"
+					);
+					if entry.attempts == self.alert_after_failures {
+						self.alert(format!(
+							"Failed to cosign the unlock of bond {} (utxo {utxo_id}) {} times. Last error: {e:?}",
+							entry.bond_id, entry.attempts
+						));
+					}
+				},
+			}
+		}
+		Ok(())
+	}
+
+	async fn cosign(
+		&self,
+		client: &MainchainClient,
+		signer: &KeystoreSigner,
+		child_xpriv: Xpriv,
+		utxo_id: UtxoId,
+		request: &UtxoCosignRequest<argon_client::types::AccountId32, u128>,
+		at_block: H256,
+	) -> Result<(), CosignError> {
+		let utxo = get_unlocking_utxo(client, utxo_id, Some(at_block)).await?;
+		self.validate_request(request, &utxo, child_xpriv)
+			.map_err(CosignError::Rejected)?;
+
+		let mut unlocker = load_unlocker(client, utxo_id, &utxo, Some(at_block)).await?;
+		let (signature, vault_musig_nonce) = sign_vault_unlock(&mut unlocker, &utxo, child_xpriv)?;
+		let call = tx().bonds().cosign_bitcoin_unlock(
+			request.bond_id,
+			signature.into(),
+			vault_musig_nonce.map(Into::into),
+		);
+		let params = client.params_with_best_nonce(&signer.account_id()).await?.build();
+		client.submit_tx(&call, signer, Some(params), false).await?;
+		Ok(())
+	}
+
+	/// Checks that an unlock request can be cosigned with the local xpriv
+	fn validate_request(
+		&self,
+		request: &UtxoCosignRequest<argon_client::types::AccountId32, u128>,
+		utxo: &api::runtime_types::pallet_bond::pallet::UtxoState,
+		child_xpriv: Xpriv,
+	) -> Result<(), String> {
+		if utxo.vault_multisig.is_some() {
+			return Err("the bitcoin is locked with the vault multisig keys".to_string());
+		}
+		if let Some(max_fee) = self.max_bitcoin_fee_sats {
+			if request.bitcoin_network_fee > max_fee {
+				return Err(format!(
+					"the bitcoin network fee of {} sats is more than the max of {max_fee} sats",
+					request.bitcoin_network_fee
+				));
+			}
+		}
+
+		let secp = Secp256k1::new();
+		let hd_path = DerivationPath::from(vec![ChildNumber::from(utxo.vault_xpub_sources.1)]);
+		let vault_xpriv = child_xpriv
+			.derive_priv(&secp, &hd_path)
+			.map_err(|e| format!("unable to derive the vault key -> {e}"))?;
+		let vault_pubkey: CompressedBitcoinPubkey = utxo.vault_pubkey.clone().into();
+		let vault_pubkey: CompressedPublicKey =
+			vault_pubkey.try_into().map_err(|_| "invalid vault pubkey".to_string())?;
+		if CompressedPublicKey(vault_xpriv.private_key.public_key(&secp)) != vault_pubkey {
+			return Err("the local xpriv doesn't match the vault pubkey of the bitcoin".to_string());
+		}
+		Ok(())
+	}
+
+	fn alert(&self, message: String) {
+		error!("{message}");
+		let Some(command) = self.alert_command.clone() else {
+			return;
+		};
+		tokio::task::spawn_blocking(move || {
+			match Command::new("sh").arg("-c").arg(&command).env(ALERT_ENV, &message).status() {
+				Ok(status) if status.success() => {},
+				Ok(status) => warn!(?status, "The alert command failed"),
+				Err(e) => warn!(?e, "Unable to run the alert command"),
+			}
+		});
+	}
+}
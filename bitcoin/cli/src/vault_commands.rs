@@ -1,20 +1,27 @@
-use anyhow::Context;
+use anyhow::{bail, Context};
 use clap::Subcommand;
-use comfy_table::{modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL, ContentArrangement, Table};
+use serde::Serialize;
 use sp_runtime::{FixedPointNumber, FixedU128};
 use subxt::dynamic::Value;
 
+use argon_bitcoin::UnlockStep;
 use argon_client::{
 	api::{
 		apis, bonds::events::bond_created::VaultId, storage, tx,
 		vaults::storage::types::vaults_by_id::VaultsById,
 	},
-	conversion::from_api_fixed_u128,
 	MainchainClient,
 };
-use argon_primitives::{bitcoin::SATOSHIS_PER_BITCOIN, KeystoreParams};
+use argon_primitives::{
+	bitcoin::{BitcoinHeight, Satoshis, UtxoId, SATOSHIS_PER_BITCOIN},
+	BondId, KeystoreParams,
+};
 
-use crate::{formatters::ArgonFormatter, vault_create};
+use crate::{
+	formatters::{ArgonFormatter, OutputFormat, OutputRecord},
+	timelock_claim::{print_claim_tx, BondScriptSource, ClaimSigner},
+	vault_autopilot, vault_create,
+};
 
 #[derive(Subcommand, Debug)]
 pub enum VaultCommands {
@@ -39,9 +46,36 @@ pub enum VaultCommands {
 		#[clap(flatten)]
 		keypair: KeystoreParams,
 	},
+	/// Claim the bitcoin of a bond with the vault claim key once the vault claim height is
+	/// reached. Outputs a signed transaction to broadcast to bitcoin.
+	Claim {
+		#[clap(flatten)]
+		script: BondScriptSource,
+
+		/// The hd path of the master xpub uploaded to Argon
+		#[clap(long)]
+		master_xpub_hd_path: String,
+
+		/// The destination you want to send the bitcoin to
+		#[clap(short, long)]
+		dest_pubkey: String,
+
+		/// The fee rate per sats (sat/vB) to use
+		#[clap(short, long, default_value = "5")]
+		fee_rate_sats_per_kb: u64,
+
+		#[clap(flatten)]
+		signer: ClaimSigner,
+	},
+	/// Keep running to cosign the unlock requests of a vault as they come in. Requests that
+	/// can't be cosigned automatically, or are getting close to their deadline, are alerted.
+	Autopilot {
+		#[clap(flatten)]
+		autopilot: vault_autopilot::VaultAutopilot,
+	},
 }
 impl VaultCommands {
-	pub async fn process(self, rpc_url: String) -> anyhow::Result<()> {
+	pub async fn process(self, rpc_url: String, format: OutputFormat) -> anyhow::Result<()> {
 		match self {
 			VaultCommands::List { btc } => {
 				let client = MainchainClient::from_url(&rpc_url)
@@ -59,57 +93,51 @@ impl VaultCommands {
 					.call(apis().bitcoin_apis().market_rate(satoshis))
 					.await?
 				else {
+					if format != OutputFormat::Table {
+						bail!("No price conversion found in blockchain for bitcoin to argon");
+					}
 					println!("No price conversion found in blockchain for bitcoin to argon");
 					return Ok(());
 				};
 
-				println!("Showing for: {:#?} btc", btc);
-				println!("Current mint value: {} argons", ArgonFormatter(argons_needed));
-
 				// NOTE: the typegen doesn't work, so revert to dynamic storage
 				let keys: Vec<Value> = vec![];
 				let query = subxt::dynamic::storage("Vaults", "VaultsById", keys);
 				let mut vaults = client.live.storage().at_latest().await?.iter(query).await?;
 
-				let mut table = Table::new();
-				table
-					.load_preset(UTF8_FULL)
-					.apply_modifier(UTF8_ROUND_CORNERS)
-					.set_content_arrangement(ContentArrangement::Dynamic)
-					.set_header(vec![
-						"Id",
-						"Available argons",
-						"Bonded argons",
-						"Securitization",
-						"Fee",
-					]);
-
+				let mut listings = vec![];
 				while let Some(Ok(kv)) = vaults.next().await {
 					let vault: VaultsById = kv.value.as_type()?;
 					let Some(vault_id) = kv.keys[0].as_u128().map(|a| a as VaultId) else {
 						continue;
 					};
-					let bitcoin_argons_available =
-						vault.bitcoin_argons.allocated - vault.bitcoin_argons.bonded;
-					if bitcoin_argons_available >= argons_needed {
-						let fee = vault.bitcoin_argons.base_fee +
-							from_api_fixed_u128(vault.bitcoin_argons.annual_percent_rate)
-								.saturating_mul_int(argons_needed);
-
-						table.add_row(vec![
-							vault_id.to_string(),
-							ArgonFormatter(bitcoin_argons_available).to_string(),
-							ArgonFormatter(vault.bitcoin_argons.bonded).to_string(),
-							ArgonFormatter(vault.securitized_argons).to_string(),
-							ArgonFormatter(fee).to_string(),
-						]);
-					}
+					// vaults that can't take the bond don't get a quote
+					let Ok(quote) =
+						client.quote_bitcoin_bond(vault_id, satoshis, Some(best_block)).await
+					else {
+						continue;
+					};
+
+					listings.push(VaultListing {
+						vault_id,
+						available_argons: vault.bitcoin_argons.allocated -
+							vault.bitcoin_argons.bonded,
+						bonded_argons: vault.bitcoin_argons.bonded,
+						securitized_argons: vault.securitized_argons,
+						bond_fee: quote.total_fee,
+					});
 				}
 
-				if table.is_empty() {
+				if format != OutputFormat::Table {
+					println!("{}", format.format_list(&listings)?);
+					return Ok(());
+				}
+				println!("Showing for: {:#?} btc", btc);
+				println!("Current mint value: {} argons", ArgonFormatter(argons_needed));
+				if listings.is_empty() {
 					println!("No vaults found that can support {} btc", btc);
 				} else {
-					println!("{table}");
+					println!("{}", format.format_list(&listings)?);
 				}
 			},
 			VaultCommands::Create { config, keypair } => {
@@ -131,39 +159,109 @@ impl VaultCommands {
 					.await
 					.context("Failed to connect to argon node")?;
 				let call = storage().bonds().utxos_pending_unlock_by_utxo_id();
-				let Some(pending) = client.fetch_storage(&call, None).await? else {
-					println!("No pending unlock requests found");
-					return Ok(());
-				};
+				let pending =
+					client.fetch_storage(&call, None).await?.map(|a| a.0).unwrap_or_default();
 				let current_block = client.live.blocks().at_latest().await?.number();
-				let mut table = Table::new();
-				table
-					.load_preset(UTF8_FULL)
-					.apply_modifier(UTF8_ROUND_CORNERS)
-					.set_content_arrangement(ContentArrangement::Dynamic)
-					.set_header(vec![
-						"Bond Id",
-						"Utxo Id",
-						"Cosign Due Block",
-						"Type",
-						"Redemption Price",
-					]);
-				for (utxo_id, pending) in pending.0.iter() {
-					if pending.vault_id != vault_id {
-						continue;
-					}
-					table.add_row(vec![
-						pending.bond_id.to_string(),
-						utxo_id.to_string(),
-						pending.cosign_due_block.to_string(),
-						"Cosign Request".to_string(),
-						ArgonFormatter(pending.redemption_price).to_string(),
-					]);
-				}
+				let unlocks = pending
+					.into_iter()
+					.filter(|(_, pending)| pending.vault_id == vault_id)
+					.map(|(utxo_id, pending)| PendingUnlock {
+						bond_id: pending.bond_id,
+						utxo_id,
+						cosign_due_block: pending.cosign_due_block,
+						redemption_price: pending.redemption_price,
+						bitcoin_network_fee: pending.bitcoin_network_fee,
+					})
+					.collect::<Vec<_>>();
 
-				println!("Pending as of block #{:?}\n\nNOTE: does not include eligible for reclaim by vault.\n\n{table}", current_block);
+				if format != OutputFormat::Table {
+					println!("{}", format.format_list(&unlocks)?);
+				} else if unlocks.is_empty() {
+					println!("No pending unlock requests found");
+				} else {
+					println!("Pending as of block #{:?}\n\nNOTE: does not include eligible for reclaim by vault.\n\n{}", current_block, format.format_list(&unlocks)?);
+				}
+			},
+			VaultCommands::Claim {
+				script,
+				master_xpub_hd_path,
+				dest_pubkey,
+				fee_rate_sats_per_kb,
+				signer,
+			} => {
+				let bond = script.load(&rpc_url).await?;
+				let tx = signer.create_claim_tx(
+					&bond,
+					UnlockStep::VaultClaim,
+					bond.vault_claim_hd_path(&master_xpub_hd_path)?,
+					&dest_pubkey,
+					fee_rate_sats_per_kb,
+				)?;
+				print_claim_tx(&tx);
 			},
+			VaultCommands::Autopilot { autopilot } => autopilot.run(rpc_url).await?,
 		}
 		Ok(())
 	}
 }
+
+/// A vault that can support the bond of the requested amount of btc
+#[derive(Serialize)]
+struct VaultListing {
+	vault_id: VaultId,
+	available_argons: u128,
+	bonded_argons: u128,
+	securitized_argons: u128,
+	/// The fee to bond the requested amount of btc
+	bond_fee: u128,
+}
+
+impl OutputRecord for VaultListing {
+	const COLUMNS: &'static [(&'static str, &'static str)] = &[
+		("vault_id", "Id"),
+		("available_argons", "Available argons"),
+		("bonded_argons", "Bonded argons"),
+		("securitized_argons", "Securitization"),
+		("bond_fee", "Fee"),
+	];
+
+	fn table_row(&self) -> Vec<String> {
+		vec![
+			self.vault_id.to_string(),
+			ArgonFormatter(self.available_argons).to_string(),
+			ArgonFormatter(self.bonded_argons).to_string(),
+			ArgonFormatter(self.securitized_argons).to_string(),
+			ArgonFormatter(self.bond_fee).to_string(),
+		]
+	}
+}
+
+/// An unlock request waiting for the vault to cosign
+#[derive(Serialize)]
+struct PendingUnlock {
+	bond_id: BondId,
+	utxo_id: UtxoId,
+	cosign_due_block: BitcoinHeight,
+	redemption_price: u128,
+	bitcoin_network_fee: Satoshis,
+}
+
+impl OutputRecord for PendingUnlock {
+	const COLUMNS: &'static [(&'static str, &'static str)] = &[
+		("bond_id", "Bond Id"),
+		("utxo_id", "Utxo Id"),
+		("cosign_due_block", "Cosign Due Block"),
+		("redemption_price", "Redemption Price"),
+		("bitcoin_network_fee", "Bitcoin Fee (sats)"),
+	];
+
+	fn table_row(&self) -> Vec<String> {
+		vec![
+			self.bond_id.to_string(),
+			self.utxo_id.to_string(),
+			self.cosign_due_block.to_string(),
+			ArgonFormatter(self.redemption_price).to_string(),
+			self.bitcoin_network_fee.to_string(),
+		]
+	}
+}
@@ -9,7 +9,7 @@ use inquire::{
 
 use crate::{
 	formatters::{parse_number, Argons, Pct},
-	helpers::{read_bitcoin_xpub, read_percent_to_fixed_128},
+	helpers::{read_bitcoin_xpub, read_multisig_descriptor, read_percent_to_fixed_128},
 };
 use argon_bitcoin_cli_macros::ReadDocs;
 use argon_client::{api, api::runtime_types, conversion::to_api_fixed_u128};
@@ -24,6 +24,11 @@ pub struct VaultConfig {
 	/// incrementing index used for each bond.
 	#[clap(long)]
 	bitcoin_xpub: Option<String>,
+	/// A multisig output descriptor to use for the vault side of each bitcoin cosign script (eg,
+	/// `wsh(multi(2,xpub1,xpub2,xpub3))`). The `bitcoin_xpub` must be one of its xpubs (the first
+	/// is used if not provided). The other xpubs will be cosigners.
+	#[clap(long)]
+	bitcoin_multisig: Option<String>,
 
 	/// The base fee in argons. Up to 6 decimal points
 	#[clap(long, value_parser=parse_number)]
@@ -75,6 +80,10 @@ fn label(field: &str) -> &str {
 impl VaultConfig {
 	pub async fn complete_prompt(&mut self, has_keypair: bool) -> bool {
 		self.sanitize_bad_values();
+		if let Err(e) = self.read_multisig() {
+			println!("Invalid bitcoin multisig: {e}");
+			return false;
+		}
 
 		if self.next_incomplete_field().is_none() {
 			return true;
@@ -89,21 +98,53 @@ impl VaultConfig {
 		}
 	}
 
-	pub fn as_call_data(&self) -> api::vaults::calls::types::create::VaultConfig {
+	/// Reads the cosigner xpubs of the `bitcoin_multisig` descriptor (using the first xpub as the
+	/// vault xpub if none was provided)
+	fn read_multisig(&mut self) -> Result<Option<(u8, Vec<String>)>, String> {
+		let Some(descriptor) = &self.bitcoin_multisig else {
+			return Ok(None);
+		};
+		let (threshold, mut xpubs) = read_multisig_descriptor(descriptor)?;
+		let bitcoin_xpub = self.bitcoin_xpub.get_or_insert_with(|| xpubs[0].clone());
+		let index = xpubs
+			.iter()
+			.position(|a| a == bitcoin_xpub)
+			.ok_or("The bitcoin xpub isn't in the multisig descriptor")?;
+		xpubs.remove(index);
+		Ok(Some((threshold, xpubs)))
+	}
+
+	pub fn as_call_data(&mut self) -> api::vaults::calls::types::create::VaultConfig {
+		let bitcoin_multisig =
+			self.read_multisig()
+				.expect("Invalid multisig")
+				.map(|(threshold, cosigner_xpubs)| {
+					runtime_types::argon_primitives::bitcoin::BitcoinVaultMultisig {
+						threshold,
+						cosigner_xpubs: cosigner_xpubs
+							.iter()
+							.map(|a| read_bitcoin_xpub(a).expect("Invalid xpub").0.into())
+							.collect::<Vec<_>>()
+							.into(),
+					}
+				});
 		let opaque_xpub = read_bitcoin_xpub(&self.bitcoin_xpub.clone().unwrap_or_default())
 			.expect("Invalid xpub");
 
 		api::vaults::calls::types::create::VaultConfig {
 			bitcoin_xpubkey: opaque_xpub.0.into(),
+			bitcoin_multisig,
 			terms: runtime_types::argon_primitives::bond::VaultTerms::<u128> {
 				bitcoin_base_fee: (self.bitcoin_base_fee.unwrap_or(0.0) * 1_000_000.0) as u128,
 				bitcoin_annual_percent_rate: to_api_fixed_u128(read_percent_to_fixed_128(
 					self.bitcoin_apr.unwrap_or(0.0),
 				)),
+				bitcoin_pricing_curve: None,
 				mining_base_fee: (self.mining_base_fee.unwrap_or(0.0) * 1_000_000.0) as u128,
 				mining_annual_percent_rate: to_api_fixed_u128(read_percent_to_fixed_128(
 					self.mining_apr.unwrap_or(0.0),
 				)),
+				mining_pricing_curve: None,
 				mining_reward_sharing_percent_take: to_api_fixed_u128(read_percent_to_fixed_128(
 					self.mining_reward_sharing_percent_take.unwrap_or(0.0),
 				)),
@@ -1,5 +1,8 @@
-use crate::{helpers::get_bitcoin_network, xpriv_file::XprivFile};
-use anyhow::anyhow;
+use crate::{
+	helpers::get_bitcoin_network,
+	xpriv_file::{XprivData, XprivFile},
+};
+use anyhow::{anyhow, bail};
 use argon_client::MainchainClient;
 use bip39::Mnemonic;
 use bitcoin::{
@@ -8,6 +11,7 @@ use bitcoin::{
 	key::Secp256k1,
 };
 use clap::{Parser, Subcommand};
+use inquire::{Password, Text};
 use std::str::FromStr;
 
 /// Create, secure, and manage your Bitcoin Master XPriv Key
@@ -21,6 +25,33 @@ pub(crate) enum XPrivCommands {
 		/// Optionally import your own mnemonic to generate the Master XPriv
 		#[clap(long)]
 		mnemonic: Option<String>,
+
+		/// The HD Path you will derive the master XPub for Argon from. It's stored in the file so
+		/// it can be recovered with the key.
+		#[clap(long)]
+		hd_path: Option<String>,
+	},
+	/// Restore a Bitcoin Master XPriv Key from a BIP-39 mnemonic into a Local File
+	ImportMnemonic {
+		#[clap(flatten)]
+		xpriv_file: XprivFile,
+
+		/// The mnemonic words. If not given, you will be prompted for them.
+		#[clap(long)]
+		mnemonic: Option<String>,
+
+		/// Prompt for the BIP-39 passphrase used with the mnemonic
+		#[clap(long)]
+		mnemonic_passphrase: bool,
+
+		/// The HD Path you derived the master XPub for Argon from
+		#[clap(long)]
+		hd_path: Option<String>,
+	},
+	/// Show the BIP-39 mnemonic of your Master XPriv to back it up on paper
+	ExportMnemonic {
+		#[clap(flatten)]
+		xpriv_file: XprivFile,
 	},
 	#[clap(name = "derive-xpub")]
 	/// Derive an XPub from your Master XPriv
@@ -28,9 +59,10 @@ pub(crate) enum XPrivCommands {
 		#[clap(flatten)]
 		xpriv_file: XprivFile,
 
-		/// HD Path to derive the XPub from (NOTE: must be hardened to submit to Argon)
+		/// HD Path to derive the XPub from (NOTE: must be hardened to submit to Argon). Defaults
+		/// to the HD Path stored in the XPriv file.
 		#[clap(long)]
-		hd_path: String,
+		hd_path: Option<String>,
 	},
 }
 
@@ -41,27 +73,71 @@ struct OneArg {
 impl XPrivCommands {
 	pub async fn process(self, rpc_url: String) -> anyhow::Result<()> {
 		match self {
-			XPrivCommands::Master { xpriv_file, mnemonic } => {
-				let client = MainchainClient::from_url(&rpc_url).await?;
-				let network = get_bitcoin_network(&client, None).await?;
-				let mnemonic = if let Some(x) = mnemonic {
-					Mnemonic::from_str(&x).map_err(|e| anyhow!(e))?
+			XPrivCommands::Master { xpriv_file, mnemonic, hd_path } => {
+				let (mnemonic, is_new) = if let Some(x) = mnemonic {
+					(Mnemonic::from_str(&x).map_err(|e| anyhow!(e))?, false)
 				} else {
-					Mnemonic::from_entropy(&[0; 32]).map_err(|e| anyhow!(e))?
+					(
+						Mnemonic::from_entropy(&rand::random::<[u8; 32]>())
+							.map_err(|e| anyhow!(e))?,
+						true,
+					)
 				};
-				let seed = mnemonic.to_seed("");
-
-				let xpriv = Xpriv::new_master(network, &seed).map_err(|e| anyhow!(e))?;
-
-				let path = xpriv_file.write(&xpriv)?;
+				let path = write_master(&rpc_url, &xpriv_file, mnemonic, None, hd_path).await?;
 
 				println!("Your Master XPriv has been saved to {}", path.display());
+				if is_new {
+					println!("Back up your mnemonic on paper with `xpriv export-mnemonic`");
+				}
+			},
+			XPrivCommands::ImportMnemonic {
+				xpriv_file,
+				mnemonic,
+				mnemonic_passphrase,
+				hd_path,
+			} => {
+				let mnemonic = match mnemonic {
+					Some(x) => x,
+					None => Text::new("Mnemonic words:").prompt()?,
+				};
+				let mnemonic = Mnemonic::parse_normalized(mnemonic.trim())
+					.map_err(|e| anyhow!("Invalid mnemonic -> {e}"))?;
+				let passphrase = if mnemonic_passphrase {
+					Some(Password::new("BIP-39 passphrase:").prompt()?)
+				} else {
+					None
+				};
+				let path =
+					write_master(&rpc_url, &xpriv_file, mnemonic, passphrase, hd_path).await?;
+
+				println!("Your Master XPriv has been restored to {}", path.display());
+			},
+			XPrivCommands::ExportMnemonic { xpriv_file } => {
+				let data = xpriv_file.read_data()?;
+				let Some(mnemonic) = data.mnemonic else {
+					bail!("This XPriv file doesn't contain a mnemonic (it might be from an older version of the cli)");
+				};
+				println!("Write down these words in order and keep them somewhere safe:\n");
+				for (i, word) in mnemonic.words().enumerate() {
+					println!("{:>2}. {}", i + 1, word);
+				}
+				if data.has_mnemonic_passphrase {
+					println!("\nThis mnemonic was imported with a BIP-39 passphrase. You need the passphrase to restore your XPriv.");
+				}
+				if let Some(hd_path) = data.derivation_path {
+					println!("\nThe HD Path of your master XPub is {hd_path}");
+				}
 			},
 			XPrivCommands::DeriveXPub { xpriv_file, hd_path } => {
-				let xpriv = xpriv_file.read()?;
-				let hd_path = bip32::DerivationPath::from_str(&hd_path).map_err(|e| anyhow!(e))?;
+				let data = xpriv_file.read_data()?;
+				let hd_path = match hd_path {
+					Some(x) => bip32::DerivationPath::from_str(&x).map_err(|e| anyhow!(e))?,
+					None => data.derivation_path.ok_or(anyhow!(
+						"No HD Path is stored in the XPriv file. Provide one with --hd-path"
+					))?,
+				};
 
-				let child = xpriv.derive_priv(&Secp256k1::new(), &hd_path)?;
+				let child = data.xpriv.derive_priv(&Secp256k1::new(), &hd_path)?;
 
 				let child_xpub = Xpub::from_priv(&Secp256k1::new(), &child);
 				println!("0x{}", hex::encode(child_xpub.encode()));
@@ -70,3 +146,29 @@ impl XPrivCommands {
 		Ok(())
 	}
 }
+
+/// Creates the master xpriv of a mnemonic (for the bitcoin network of Argon) and writes it to the
+/// xpriv file
+async fn write_master(
+	rpc_url: &str,
+	xpriv_file: &XprivFile,
+	mnemonic: Mnemonic,
+	passphrase: Option<String>,
+	hd_path: Option<String>,
+) -> anyhow::Result<std::path::PathBuf> {
+	let derivation_path = hd_path
+		.map(|a| bip32::DerivationPath::from_str(&a))
+		.transpose()
+		.map_err(|e| anyhow!(e))?;
+	let client = MainchainClient::from_url(rpc_url).await?;
+	let network = get_bitcoin_network(&client, None).await?;
+	let seed = mnemonic.to_seed(passphrase.as_deref().unwrap_or_default());
+
+	let xpriv = Xpriv::new_master(network, &seed).map_err(|e| anyhow!(e))?;
+	xpriv_file.write(&XprivData {
+		xpriv,
+		mnemonic: Some(mnemonic),
+		has_mnemonic_passphrase: passphrase.is_some_and(|a| !a.is_empty()),
+		derivation_path,
+	})
+}
@@ -1,16 +1,24 @@
 use age::{secrecy::SecretString, Decryptor, Encryptor};
 use anyhow::{anyhow, bail};
 use argon_primitives::KeystoreParams;
-use bitcoin::bip32::Xpriv;
+use base64::{engine::general_purpose, Engine};
+use bip39::Mnemonic;
+use bitcoin::bip32::{DerivationPath, Xpriv};
 use clap::Args;
 use directories::BaseDirs;
+use serde::{Deserialize, Serialize};
 use std::{
 	fs,
 	fs::File,
-	io::{BufReader, BufWriter, Read, Write},
+	io::{Read, Write},
 	path::{Path, PathBuf},
+	str::FromStr,
 };
 
+/// The version of the xpriv file format. Files from before the format was versioned only contain
+/// the encoded xpriv (optionally encrypted with age), and can still be read.
+const XPRIV_FILE_VERSION: u8 = 1;
+
 /// A local file that can store an encrypted (or plaintext) xpriv.
 #[derive(Debug, Clone, Args)]
 pub struct XprivFile {
@@ -51,6 +59,70 @@ pub struct XprivFile {
 	pub password_yubikey: Option<bool>,
 }
 
+/// The secrets and metadata stored in an xpriv file
+#[derive(Debug, Clone)]
+pub struct XprivData {
+	pub xpriv: Xpriv,
+	/// The BIP-39 mnemonic the xpriv was created from, so it can be backed up on paper
+	pub mnemonic: Option<Mnemonic>,
+	/// The mnemonic was used with a BIP-39 passphrase. The passphrase isn't stored, so must be
+	/// backed up with the mnemonic.
+	pub has_mnemonic_passphrase: bool,
+	/// The hd path of the master xpub derived for Argon
+	pub derivation_path: Option<DerivationPath>,
+}
+
+/// The contents of a versioned xpriv file. The payload is an [`XprivPayload`] encrypted with age
+/// if the file has a password. Age derives the key with scrypt and encrypts with
+/// ChaCha20-Poly1305, so a modified payload will fail to decrypt.
+#[derive(Serialize, Deserialize)]
+struct XprivFileEnvelope {
+	version: u8,
+	encrypted: bool,
+	/// The base64 encoded payload
+	payload: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct XprivPayload {
+	xpriv: String,
+	mnemonic: Option<String>,
+	#[serde(default)]
+	has_mnemonic_passphrase: bool,
+	derivation_path: Option<String>,
+}
+
+impl From<&XprivData> for XprivPayload {
+	fn from(data: &XprivData) -> Self {
+		Self {
+			xpriv: data.xpriv.to_string(),
+			mnemonic: data.mnemonic.as_ref().map(|a| a.to_string()),
+			has_mnemonic_passphrase: data.has_mnemonic_passphrase,
+			derivation_path: data.derivation_path.as_ref().map(|a| a.to_string()),
+		}
+	}
+}
+
+impl TryFrom<XprivPayload> for XprivData {
+	type Error = anyhow::Error;
+
+	fn try_from(payload: XprivPayload) -> Result<Self, Self::Error> {
+		Ok(Self {
+			xpriv: Xpriv::from_str(&payload.xpriv).map_err(|_| anyhow!("Invalid Xpriv"))?,
+			mnemonic: payload
+				.mnemonic
+				.map(|a| Mnemonic::parse_normalized(&a))
+				.transpose()
+				.map_err(|e| anyhow!("Invalid mnemonic -> {e}"))?,
+			has_mnemonic_passphrase: payload.has_mnemonic_passphrase,
+			derivation_path: payload
+				.derivation_path
+				.map(|a| DerivationPath::from_str(&a))
+				.transpose()?,
+		})
+	}
+}
+
 impl XprivFile {
 	fn read_password(&self) -> anyhow::Result<Option<SecretString>> {
 		let xpriv_params = KeystoreParams {
@@ -69,7 +141,7 @@ impl XprivFile {
 		xpriv_params.read_password()
 	}
 
-	pub fn write(&self, xpriv: &Xpriv) -> anyhow::Result<PathBuf> {
+	pub fn write(&self, data: &XprivData) -> anyhow::Result<PathBuf> {
 		let password = self.read_password()?;
 
 		let path = self.xpriv_path.clone().ok_or(anyhow!("No key path provided"))?;
@@ -78,6 +150,17 @@ impl XprivFile {
 			bail!("File already exists");
 		}
 
+		let payload = serde_json::to_vec(&XprivPayload::from(data))?;
+		let (encrypted, payload) = match password {
+			Some(password) => (true, encrypt(&payload, password)?),
+			None => (false, payload),
+		};
+		let envelope = XprivFileEnvelope {
+			version: XPRIV_FILE_VERSION,
+			encrypted,
+			payload: general_purpose::STANDARD.encode(payload),
+		};
+
 		if let Some(parent) = path.parent() {
 			if !parent.exists() {
 				fs::create_dir_all(parent)?;
@@ -94,42 +177,68 @@ impl XprivFile {
 				.map_err(|e| anyhow!("Failed to set permissions: {}", e))?;
 		}
 
-		if let Some(password) = password {
-			let encryptor = Encryptor::with_user_passphrase(password);
-
-			let mut writer = encryptor.wrap_output(BufWriter::new(output_file))?;
-
-			writer.write_all(xpriv.encode().as_ref())?;
-			writer.finish()?;
-		} else {
-			output_file.write_all(xpriv.encode().as_ref())?;
-		}
+		output_file.write_all(&serde_json::to_vec_pretty(&envelope)?)?;
 
 		Ok(path)
 	}
 
 	pub fn read(&self) -> anyhow::Result<Xpriv> {
+		Ok(self.read_data()?.xpriv)
+	}
+
+	pub fn read_data(&self) -> anyhow::Result<XprivData> {
 		let password = self.read_password()?;
 		let input_path = self.xpriv_path.clone().ok_or(anyhow!("No key path provided"))?;
 		let input_path = expand_path(&input_path);
-		let mut input_file = File::open(input_path)?;
-
-		let mut bytes = Vec::new();
-		if let Some(password) = password {
-			let decryptor = Decryptor::new(BufReader::new(input_file))?;
-			if let Decryptor::Passphrase(decryptor) = decryptor {
-				decryptor.decrypt(&password, None)?.read_to_end(&mut bytes)?;
-			} else {
-				bail!("Decryption failed");
-			}
-		} else {
-			input_file.read_to_end(&mut bytes)?;
-		}
+		let bytes = fs::read(input_path)?;
 
-		Xpriv::decode(bytes.as_ref()).map_err(|_| anyhow!("Invalid Xpriv"))
+		let Ok(envelope) = serde_json::from_slice::<XprivFileEnvelope>(&bytes) else {
+			return read_unversioned(&bytes, password);
+		};
+		if envelope.version != XPRIV_FILE_VERSION {
+			bail!("Unsupported xpriv file version {}", envelope.version);
+		}
+		let payload = general_purpose::STANDARD.decode(envelope.payload)?;
+		let payload = if envelope.encrypted {
+			let Some(password) = password else {
+				bail!("This xpriv file is encrypted. Provide the password to read it");
+			};
+			decrypt(&payload, &password)?
+		} else {
+			payload
+		};
+		serde_json::from_slice::<XprivPayload>(&payload)?.try_into()
 	}
 }
 
+/// Reads a file from before the format was versioned, which only contains the encoded xpriv
+fn read_unversioned(bytes: &[u8], password: Option<SecretString>) -> anyhow::Result<XprivData> {
+	let bytes = match password {
+		Some(password) => decrypt(bytes, &password)?,
+		None => bytes.to_vec(),
+	};
+	let xpriv = Xpriv::decode(bytes.as_ref()).map_err(|_| anyhow!("Invalid Xpriv"))?;
+	Ok(XprivData { xpriv, mnemonic: None, has_mnemonic_passphrase: false, derivation_path: None })
+}
+
+fn encrypt(bytes: &[u8], password: SecretString) -> anyhow::Result<Vec<u8>> {
+	let encryptor = Encryptor::with_user_passphrase(password);
+	let mut encrypted = Vec::new();
+	let mut writer = encryptor.wrap_output(&mut encrypted)?;
+	writer.write_all(bytes)?;
+	writer.finish()?;
+	Ok(encrypted)
+}
+
+fn decrypt(bytes: &[u8], password: &SecretString) -> anyhow::Result<Vec<u8>> {
+	let Decryptor::Passphrase(decryptor) = Decryptor::new(bytes)? else {
+		bail!("Decryption failed");
+	};
+	let mut decrypted = Vec::new();
+	decryptor.decrypt(password, None)?.read_to_end(&mut decrypted)?;
+	Ok(decrypted)
+}
+
 pub fn secret_string_from_str(s: &str) -> Result<SecretString, String> {
 	std::str::FromStr::from_str(s).map_err(|_| "Could not get SecretString".to_string())
 }
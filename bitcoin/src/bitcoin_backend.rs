@@ -0,0 +1,725 @@
+use std::collections::BTreeSet;
+
+use anyhow::{anyhow, ensure};
+use bitcoin::{
+	bip158, block::Header, consensus::serialize, constants::genesis_block, hashes::Hash, Block,
+	BlockHash, Network, ScriptBuf, Transaction, Txid,
+};
+use bitcoincore_rpc::{Auth, Client, RpcApi};
+use electrum_client::ElectrumApi;
+
+use argon_primitives::bitcoin::{BitcoinHeight, BitcoinNetwork, H256Le};
+
+use crate::create_merkle_branch;
+
+/// The details of a block header needed to link blocks together
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlockHeaderInfo {
+	pub block_height: BitcoinHeight,
+	pub previous_block_hash: Option<BlockHash>,
+}
+
+/// A source of bitcoin blocks used to track the status of bonded utxos
+pub trait BitcoinBackend: Send + Sync {
+	fn get_network(&self) -> anyhow::Result<BitcoinNetwork>;
+
+	/// Looks up the header of a block. Some backends can only look up headers by height, so the
+	/// expected height of the block is provided.
+	fn get_block_header(
+		&self,
+		block_hash: &BlockHash,
+		block_height: BitcoinHeight,
+	) -> anyhow::Result<BlockHeaderInfo>;
+
+	/// A compact filter of the block, or `None` if this backend can't provide one. Blocks without
+	/// a filter are always checked for tracked utxos.
+	fn get_block_filter(&self, block_hash: &BlockHash) -> anyhow::Result<Option<Vec<u8>>>;
+
+	/// The transactions of a block that could create or spend outputs of the given scripts
+	fn get_block_transactions(
+		&self,
+		block_hash: &BlockHash,
+		block_height: BitcoinHeight,
+		scripts: &[ScriptBuf],
+	) -> anyhow::Result<Vec<Transaction>>;
+
+	/// The header of the best chain block at the given height
+	fn get_header_by_height(&self, block_height: BitcoinHeight) -> anyhow::Result<Header>;
+
+	/// The index of a transaction in a block and the merkle branch proving it is included
+	fn get_merkle_branch(
+		&self,
+		block_hash: &BlockHash,
+		block_height: BitcoinHeight,
+		txid: &Txid,
+	) -> anyhow::Result<(u32, Vec<H256Le>)>;
+}
+
+/// Creates a compact filter for a block when a backend doesn't serve BIP-158 filters. Instead of
+/// the scripts spent by the block (which would need every previous output to be looked up), it
+/// contains the spent outpoints.
+pub fn create_block_filter(block: &Block) -> anyhow::Result<Vec<u8>> {
+	let mut filter = Vec::new();
+	let mut writer = bip158::BlockFilterWriter::new(&mut filter, block);
+	writer.add_output_scripts();
+	for tx in block.txdata.iter().filter(|tx| !tx.is_coinbase()) {
+		for input in &tx.input {
+			writer.add_element(&serialize(&input.previous_output));
+		}
+	}
+	writer.finish()?;
+	Ok(filter)
+}
+
+fn merkle_branch_from_block(block: &Block, txid: &Txid) -> anyhow::Result<(u32, Vec<H256Le>)> {
+	let txids = block.txdata.iter().map(|tx| tx.compute_txid()).collect::<Vec<_>>();
+	let tx_index = txids.iter().position(|a| a == txid).ok_or(anyhow!(
+		"Transaction {} not found in block {}",
+		txid,
+		block.block_hash()
+	))?;
+	Ok((tx_index as u32, create_merkle_branch(&txids, tx_index)))
+}
+
+fn network_from_genesis_hash(block_hash: BlockHash) -> anyhow::Result<BitcoinNetwork> {
+	[Network::Bitcoin, Network::Testnet, Network::Signet, Network::Regtest]
+		.into_iter()
+		.find(|network| genesis_block(*network).block_hash() == block_hash)
+		.map(Into::into)
+		.ok_or(anyhow!("Unknown bitcoin genesis block {}", block_hash))
+}
+
+fn previous_block_hash(header: &bitcoin::block::Header) -> Option<BlockHash> {
+	if header.prev_blockhash == BlockHash::all_zeros() {
+		return None;
+	}
+	Some(header.prev_blockhash)
+}
+
+/// A bitcoin full node with BIP-158 block filters enabled (`-blockfilterindex`)
+pub struct BitcoindBackend {
+	client: Client,
+}
+
+impl BitcoindBackend {
+	pub fn new(rpc_url: &str, auth: Option<(String, String)>) -> anyhow::Result<Self> {
+		let auth = if let Some((username, password)) = auth {
+			Auth::UserPass(username, password)
+		} else {
+			Auth::None
+		};
+		Ok(Self { client: Client::new(rpc_url, auth)? })
+	}
+}
+
+impl BitcoinBackend for BitcoindBackend {
+	fn get_network(&self) -> anyhow::Result<BitcoinNetwork> {
+		Ok(self.client.get_blockchain_info()?.chain.into())
+	}
+
+	fn get_block_header(
+		&self,
+		block_hash: &BlockHash,
+		_block_height: BitcoinHeight,
+	) -> anyhow::Result<BlockHeaderInfo> {
+		let header = self.client.get_block_header_info(block_hash)?;
+		Ok(BlockHeaderInfo {
+			block_height: header.height as BitcoinHeight,
+			previous_block_hash: header.previous_block_hash,
+		})
+	}
+
+	fn get_block_filter(&self, block_hash: &BlockHash) -> anyhow::Result<Option<Vec<u8>>> {
+		Ok(Some(self.client.get_block_filter(block_hash)?.filter))
+	}
+
+	fn get_block_transactions(
+		&self,
+		block_hash: &BlockHash,
+		_block_height: BitcoinHeight,
+		_scripts: &[ScriptBuf],
+	) -> anyhow::Result<Vec<Transaction>> {
+		Ok(self.client.get_block(block_hash)?.txdata)
+	}
+
+	fn get_header_by_height(&self, block_height: BitcoinHeight) -> anyhow::Result<Header> {
+		let block_hash = self.client.get_block_hash(block_height)?;
+		Ok(self.client.get_block_header(&block_hash)?)
+	}
+
+	fn get_merkle_branch(
+		&self,
+		block_hash: &BlockHash,
+		_block_height: BitcoinHeight,
+		txid: &Txid,
+	) -> anyhow::Result<(u32, Vec<H256Le>)> {
+		merkle_branch_from_block(&self.client.get_block(block_hash)?, txid)
+	}
+}
+
+/// An Esplora REST api (eg, a self-hosted electrs or mempool.space instance). Esplora doesn't
+/// serve block filters, so each block is downloaded once to create one.
+pub struct EsploraBackend {
+	client: esplora_client::BlockingClient,
+}
+
+impl EsploraBackend {
+	pub fn new(url: &str) -> anyhow::Result<Self> {
+		Ok(Self {
+			client: esplora_client::Builder::new(url.trim_end_matches('/')).build_blocking(),
+		})
+	}
+
+	fn get_block(&self, block_hash: &BlockHash) -> anyhow::Result<Block> {
+		self.client
+			.get_block_by_hash(block_hash)?
+			.ok_or(anyhow!("Block {} not found", block_hash))
+	}
+}
+
+impl BitcoinBackend for EsploraBackend {
+	fn get_network(&self) -> anyhow::Result<BitcoinNetwork> {
+		network_from_genesis_hash(self.client.get_block_hash(0)?)
+	}
+
+	fn get_block_header(
+		&self,
+		block_hash: &BlockHash,
+		_block_height: BitcoinHeight,
+	) -> anyhow::Result<BlockHeaderInfo> {
+		let header = self.client.get_header_by_hash(block_hash)?;
+		let status = self.client.get_block_status(block_hash)?;
+		let block_height =
+			status.height.ok_or(anyhow!("Block {} is not in the best chain", block_hash))?;
+		Ok(BlockHeaderInfo {
+			block_height: block_height as BitcoinHeight,
+			previous_block_hash: previous_block_hash(&header),
+		})
+	}
+
+	fn get_block_filter(&self, block_hash: &BlockHash) -> anyhow::Result<Option<Vec<u8>>> {
+		Ok(Some(create_block_filter(&self.get_block(block_hash)?)?))
+	}
+
+	fn get_block_transactions(
+		&self,
+		block_hash: &BlockHash,
+		_block_height: BitcoinHeight,
+		_scripts: &[ScriptBuf],
+	) -> anyhow::Result<Vec<Transaction>> {
+		Ok(self.get_block(block_hash)?.txdata)
+	}
+
+	fn get_header_by_height(&self, block_height: BitcoinHeight) -> anyhow::Result<Header> {
+		let block_hash = self.client.get_block_hash(block_height as u32)?;
+		Ok(self.client.get_header_by_hash(&block_hash)?)
+	}
+
+	fn get_merkle_branch(
+		&self,
+		block_hash: &BlockHash,
+		_block_height: BitcoinHeight,
+		txid: &Txid,
+	) -> anyhow::Result<(u32, Vec<H256Le>)> {
+		merkle_branch_from_block(&self.get_block(block_hash)?, txid)
+	}
+}
+
+/// An Electrum server (eg, `ssl://electrum.blockstream.info:50002`). Electrum can't serve blocks,
+/// so the transactions are found through the history of each tracked script.
+pub struct ElectrumBackend {
+	client: electrum_client::Client,
+}
+
+impl ElectrumBackend {
+	pub fn new(url: &str) -> anyhow::Result<Self> {
+		Ok(Self { client: electrum_client::Client::new(url)? })
+	}
+}
+
+impl BitcoinBackend for ElectrumBackend {
+	fn get_network(&self) -> anyhow::Result<BitcoinNetwork> {
+		network_from_genesis_hash(self.client.block_header(0)?.block_hash())
+	}
+
+	fn get_block_header(
+		&self,
+		block_hash: &BlockHash,
+		block_height: BitcoinHeight,
+	) -> anyhow::Result<BlockHeaderInfo> {
+		let header = self.client.block_header(block_height as usize)?;
+		ensure!(
+			header.block_hash() == *block_hash,
+			"Block {} is not at height {} of the electrum server's chain",
+			block_hash,
+			block_height
+		);
+		Ok(BlockHeaderInfo { block_height, previous_block_hash: previous_block_hash(&header) })
+	}
+
+	fn get_block_filter(&self, _block_hash: &BlockHash) -> anyhow::Result<Option<Vec<u8>>> {
+		Ok(None)
+	}
+
+	fn get_block_transactions(
+		&self,
+		_block_hash: &BlockHash,
+		block_height: BitcoinHeight,
+		scripts: &[ScriptBuf],
+	) -> anyhow::Result<Vec<Transaction>> {
+		if scripts.is_empty() {
+			return Ok(vec![]);
+		}
+		let histories =
+			self.client.batch_script_get_history(scripts.iter().map(|a| a.as_script()))?;
+		let txids = histories
+			.into_iter()
+			.flatten()
+			.filter(|a| a.height > 0 && a.height as BitcoinHeight == block_height)
+			.map(|a| a.tx_hash)
+			.collect::<BTreeSet<_>>();
+		if txids.is_empty() {
+			return Ok(vec![]);
+		}
+		Ok(self.client.batch_transaction_get(txids.iter())?)
+	}
+
+	fn get_header_by_height(&self, block_height: BitcoinHeight) -> anyhow::Result<Header> {
+		Ok(self.client.block_header(block_height as usize)?)
+	}
+
+	fn get_merkle_branch(
+		&self,
+		block_hash: &BlockHash,
+		block_height: BitcoinHeight,
+		txid: &Txid,
+	) -> anyhow::Result<(u32, Vec<H256Le>)> {
+		let merkle = self.client.transaction_get_merkle(txid, block_height as usize)?;
+		ensure!(
+			merkle.block_height as BitcoinHeight == block_height,
+			"Transaction {} is not in block {}",
+			txid,
+			block_hash
+		);
+		// electrum serves the branch in display (reversed) byte order
+		let branch = merkle
+			.merkle
+			.into_iter()
+			.map(|mut hash| {
+				hash.reverse();
+				H256Le(hash)
+			})
+			.collect();
+		Ok((merkle.pos as u32, branch))
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use std::{
+		io::{BufRead, BufReader, Write},
+		net::TcpListener,
+		sync::Arc,
+		thread,
+	};
+
+	use bitcoin::{
+		absolute::LockTime,
+		block::{Header, Version},
+		consensus::encode::serialize_hex,
+		hashes::{sha256, Hash},
+		hex::DisplayHex,
+		transaction, Amount, Block, OutPoint, ScriptBuf, Sequence, Transaction, TxIn, TxMerkleNode,
+		TxOut, Witness,
+	};
+	use serde_json::{json, Value};
+	use sp_core::H256;
+
+	use argon_primitives::bitcoin::{
+		BitcoinBlock, BitcoinCosignScriptPubkey, BitcoinNetwork, BitcoinSyncStatus, H256Le,
+		UtxoRef, UtxoValue,
+	};
+
+	use super::*;
+	use crate::{create_header_checkpoint, verify_utxo_inclusion, UtxoSpendFilter};
+
+	struct MockChain {
+		blocks: Vec<Block>,
+		verified_script: ScriptBuf,
+		spent_script: ScriptBuf,
+		verified_outpoint: OutPoint,
+		spent_outpoint: OutPoint,
+	}
+
+	fn create_tx(
+		height: u8,
+		inputs: Vec<OutPoint>,
+		outputs: Vec<(ScriptBuf, Amount)>,
+	) -> Transaction {
+		let input = if inputs.is_empty() {
+			vec![TxIn {
+				previous_output: OutPoint::null(),
+				script_sig: ScriptBuf::from_bytes(vec![1, height]),
+				..Default::default()
+			}]
+		} else {
+			inputs
+				.into_iter()
+				.map(|previous_output| TxIn {
+					previous_output,
+					script_sig: ScriptBuf::new(),
+					sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+					witness: Witness::new(),
+				})
+				.collect()
+		};
+		Transaction {
+			version: transaction::Version::TWO,
+			lock_time: LockTime::ZERO,
+			input,
+			output: outputs
+				.into_iter()
+				.map(|(script_pubkey, value)| TxOut { script_pubkey, value })
+				.collect(),
+		}
+	}
+
+	fn next_block(prev: &Block, txdata: Vec<Transaction>) -> Block {
+		let mut block = Block {
+			header: Header {
+				version: Version::TWO,
+				prev_blockhash: prev.block_hash(),
+				merkle_root: TxMerkleNode::all_zeros(),
+				time: prev.header.time + 600,
+				bits: prev.header.bits,
+				nonce: 0,
+			},
+			txdata,
+		};
+		block.header.merkle_root = block.compute_merkle_root().expect("has transactions");
+		while block.header.validate_pow(block.header.target()).is_err() {
+			block.header.nonce += 1;
+		}
+		block
+	}
+
+	fn script(byte: u8) -> (BitcoinCosignScriptPubkey, ScriptBuf) {
+		let script_pubkey = BitcoinCosignScriptPubkey::P2WSH { wscript_hash: H256([byte; 32]) };
+		(script_pubkey, script_pubkey.into())
+	}
+
+	/// A regtest chain where block 1 funds two scripts and block 2 spends one of them
+	fn create_chain() -> MockChain {
+		let miner = script(0).1;
+		let genesis = genesis_block(Network::Regtest);
+		let funding = create_tx(
+			1,
+			vec![OutPoint { txid: genesis.txdata[0].compute_txid(), vout: 0 }],
+			vec![(script(1).1, Amount::ONE_BTC), (script(2).1, Amount::from_sat(50_000))],
+		);
+		let verified_outpoint = OutPoint { txid: funding.compute_txid(), vout: 0 };
+		let spent_outpoint = OutPoint { txid: funding.compute_txid(), vout: 1 };
+		let block1 = next_block(
+			&genesis,
+			vec![create_tx(1, vec![], vec![(miner.clone(), Amount::ONE_BTC)]), funding],
+		);
+		let spend =
+			create_tx(2, vec![spent_outpoint], vec![(miner.clone(), Amount::from_sat(40_000))]);
+		let block2 = next_block(
+			&block1,
+			vec![create_tx(2, vec![], vec![(miner.clone(), Amount::ONE_BTC)]), spend],
+		);
+		let block3 =
+			next_block(&block2, vec![create_tx(3, vec![], vec![(miner, Amount::ONE_BTC)])]);
+		MockChain {
+			blocks: vec![genesis, block1, block2, block3],
+			verified_script: script(1).1,
+			spent_script: script(2).1,
+			verified_outpoint,
+			spent_outpoint,
+		}
+	}
+
+	fn find_block<'a>(chain: &'a MockChain, hash: &str) -> Option<(usize, &'a Block)> {
+		chain
+			.blocks
+			.iter()
+			.enumerate()
+			.find(|(_, b)| b.block_hash().to_string() == hash)
+	}
+
+	fn start_esplora(chain: Arc<MockChain>) -> String {
+		let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+		let url = format!("http://{}", listener.local_addr().unwrap());
+		thread::spawn(move || {
+			for stream in listener.incoming() {
+				let Ok(mut stream) = stream else { continue };
+				let mut reader = BufReader::new(stream.try_clone().unwrap());
+				let mut request_line = String::new();
+				reader.read_line(&mut request_line).unwrap();
+				loop {
+					let mut line = String::new();
+					if reader.read_line(&mut line).unwrap() == 0 || line == "\r\n" {
+						break;
+					}
+				}
+				let path = request_line.split_whitespace().nth(1).unwrap_or_default();
+				let parts = path.trim_start_matches('/').split('/').collect::<Vec<_>>();
+				let body: Option<Vec<u8>> = match parts.as_slice() {
+					["block-height", height] => height
+						.parse::<usize>()
+						.ok()
+						.and_then(|h| chain.blocks.get(h))
+						.map(|b| b.block_hash().to_string().into_bytes()),
+					["block", hash, "header"] =>
+						find_block(&chain, hash).map(|(_, b)| serialize_hex(&b.header).into_bytes()),
+					["block", hash, "status"] => find_block(&chain, hash).map(|(height, _)| {
+						json!({ "in_best_chain": true, "height": height, "next_best": null })
+							.to_string()
+							.into_bytes()
+					}),
+					["block", hash, "raw"] => find_block(&chain, hash).map(|(_, b)| serialize(b)),
+					_ => None,
+				};
+				let (status, body) = match body {
+					Some(body) => ("200 OK", body),
+					None => ("404 Not Found", b"not found".to_vec()),
+				};
+				let _ = stream.write_all(
+					format!(
+						"HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+						body.len()
+					)
+					.as_bytes(),
+				);
+				let _ = stream.write_all(&body);
+			}
+		});
+		url
+	}
+
+	fn find_tx<'a>(chain: &'a MockChain, txid: &str) -> Option<&'a Transaction> {
+		chain
+			.blocks
+			.iter()
+			.flat_map(|b| b.txdata.iter())
+			.find(|tx| tx.compute_txid().to_string() == txid)
+	}
+
+	fn electrum_script_hash(script: &ScriptBuf) -> String {
+		let mut hash = sha256::Hash::hash(script.as_bytes()).to_byte_array();
+		hash.reverse();
+		hash.to_lower_hex_string()
+	}
+
+	fn electrum_response(chain: &MockChain, request: &Value) -> Value {
+		let params = request["params"].as_array().cloned().unwrap_or_default();
+		let result = match request["method"].as_str().unwrap_or_default() {
+			"blockchain.block.header" => {
+				let height = params[0].as_u64().unwrap() as usize;
+				json!(serialize_hex(&chain.blocks[height].header))
+			},
+			"blockchain.scripthash.get_history" => {
+				let script_hash = params[0].as_str().unwrap();
+				let matches = |script: &ScriptBuf| electrum_script_hash(script) == script_hash;
+				let mut history = vec![];
+				for (height, block) in chain.blocks.iter().enumerate() {
+					for tx in &block.txdata {
+						let funds = tx.output.iter().any(|o| matches(&o.script_pubkey));
+						let spends = tx.input.iter().any(|i| {
+							find_tx(chain, &i.previous_output.txid.to_string())
+								.and_then(|prev| prev.output.get(i.previous_output.vout as usize))
+								.is_some_and(|o| matches(&o.script_pubkey))
+						});
+						if funds || spends {
+							history.push(
+								json!({ "height": height, "tx_hash": tx.compute_txid().to_string() }),
+							);
+						}
+					}
+				}
+				json!(history)
+			},
+			"blockchain.transaction.get_merkle" => {
+				let txid = params[0].as_str().unwrap();
+				let height = params[1].as_u64().unwrap() as usize;
+				let block = &chain.blocks[height];
+				let txid = find_tx(chain, txid).unwrap().compute_txid();
+				let (pos, branch) = merkle_branch_from_block(block, &txid).unwrap();
+				let merkle = branch
+					.into_iter()
+					.map(|mut hash| {
+						hash.0.reverse();
+						hash.0.to_lower_hex_string()
+					})
+					.collect::<Vec<_>>();
+				json!({ "block_height": height, "pos": pos, "merkle": merkle })
+			},
+			"blockchain.transaction.get" => {
+				let tx = find_tx(chain, params[0].as_str().unwrap()).unwrap();
+				json!(serialize_hex(tx))
+			},
+			_ => Value::Null,
+		};
+		json!({ "jsonrpc": "2.0", "id": request["id"], "result": result })
+	}
+
+	fn start_electrum(chain: Arc<MockChain>) -> String {
+		let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+		let url = format!("tcp://{}", listener.local_addr().unwrap());
+		thread::spawn(move || {
+			for stream in listener.incoming() {
+				let Ok(mut stream) = stream else { continue };
+				let chain = chain.clone();
+				thread::spawn(move || {
+					let reader = BufReader::new(stream.try_clone().unwrap());
+					for line in reader.lines() {
+						let Ok(line) = line else { break };
+						let Ok(request) = serde_json::from_str::<Value>(&line) else { continue };
+						let response = match request {
+							Value::Array(requests) => json!(requests
+								.iter()
+								.map(|r| electrum_response(&chain, r))
+								.collect::<Vec<_>>()),
+							request => electrum_response(&chain, &request),
+						};
+						if stream.write_all(format!("{}\n", response).as_bytes()).is_err() {
+							break;
+						}
+					}
+				});
+			}
+		});
+		url
+	}
+
+	fn check_sync(chain: &MockChain, backend: Box<dyn BitcoinBackend>) {
+		let filter = UtxoSpendFilter::with_backend(backend);
+		assert_eq!(filter.get_network().unwrap(), BitcoinNetwork::Regtest);
+
+		let confirmed = &chain.blocks[3];
+		let sync_status = BitcoinSyncStatus {
+			confirmed_block: BitcoinBlock {
+				block_hash: confirmed.block_hash().into(),
+				block_height: 3,
+			},
+			synched_block: None,
+			oldest_allowed_block_height: 1,
+		};
+		filter.sync_to_block(&sync_status).unwrap();
+		let filters = filter.get_stored_filters();
+		assert_eq!(filters.iter().map(|a| a.block_height).collect::<Vec<_>>(), vec![1, 2, 3]);
+		assert_eq!(filters[0].block_hash, H256Le::from(chain.blocks[1].block_hash()));
+
+		let (verified_script, _) = script(1);
+		let (spent_script, _) = script(2);
+		let result = filter
+			.refresh_utxo_status(vec![
+				(
+					None,
+					UtxoValue {
+						utxo_id: 1,
+						script_pubkey: verified_script,
+						satoshis: Amount::ONE_BTC.to_sat(),
+						submitted_at_height: 1,
+						watch_for_spent_until_height: 100,
+					},
+				),
+				(
+					Some(chain.spent_outpoint.into()),
+					UtxoValue {
+						utxo_id: 2,
+						script_pubkey: spent_script,
+						satoshis: 50_000,
+						submitted_at_height: 1,
+						watch_for_spent_until_height: 100,
+					},
+				),
+			])
+			.unwrap();
+		assert_eq!(result.sync_to_block.block_height, 3);
+		assert_eq!(result.verified.get(&1), Some(&UtxoRef::from(chain.verified_outpoint)));
+		assert_eq!(result.spent.get(&2), Some(&2));
+		assert_eq!(result.spent.len(), 1);
+
+		// the verified utxo is proven against the block header
+		let (_, entry) =
+			create_header_checkpoint(BitcoinNetwork::Regtest, 1, &(&chain.blocks[1].header).into())
+				.unwrap();
+		let proof = result.inclusion_proofs.get(&1).unwrap();
+		assert_eq!(proof.block_hash, H256Le::from(chain.blocks[1].block_hash()));
+		assert_eq!(
+			verify_utxo_inclusion(&entry, proof, &chain.verified_outpoint.into(), verified_script)
+				.unwrap(),
+			Amount::ONE_BTC.to_sat()
+		);
+
+		// headers extend the runtime chain up to the confirmed block
+		let runtime_hashes = |hashes: Vec<BlockHash>| {
+			move |height: BitcoinHeight| -> anyhow::Result<Option<H256Le>> {
+				Ok(hashes.get(height as usize).map(|a| (*a).into()))
+			}
+		};
+		let best_chain = chain.blocks.iter().map(|b| b.block_hash()).collect::<Vec<_>>();
+		let headers = filter
+			.get_headers_to_submit(
+				&BitcoinBlock::new(1, chain.blocks[1].block_hash().into()),
+				runtime_hashes(best_chain[..2].to_vec()),
+				3,
+				10,
+			)
+			.unwrap();
+		assert_eq!(
+			headers,
+			vec![(&chain.blocks[2].header).into(), (&chain.blocks[3].header).into()]
+		);
+		// a runtime on another fork is sent the headers from the common block
+		let fork = vec![best_chain[0], BlockHash::all_zeros()];
+		let headers = filter
+			.get_headers_to_submit(
+				&BitcoinBlock::new(1, BlockHash::all_zeros().into()),
+				runtime_hashes(fork),
+				3,
+				2,
+			)
+			.unwrap();
+		assert_eq!(
+			headers,
+			vec![(&chain.blocks[1].header).into(), (&chain.blocks[2].header).into()]
+		);
+	}
+
+	#[test]
+	fn created_filters_match_outputs_and_spent_outpoints() {
+		let chain = create_chain();
+		let block = &chain.blocks[2];
+		let filter = bip158::BlockFilter::new(&create_block_filter(block).unwrap());
+		let outpoint = serialize(&chain.spent_outpoint);
+		assert!(filter
+			.match_any(&block.block_hash(), [outpoint.as_slice()].into_iter())
+			.unwrap());
+		assert!(!filter
+			.match_any(&block.block_hash(), [chain.verified_script.as_bytes()].into_iter())
+			.unwrap());
+		assert!(!filter
+			.match_any(&block.block_hash(), [chain.spent_script.as_bytes()].into_iter())
+			.unwrap());
+	}
+
+	#[test]
+	fn can_track_utxos_with_esplora() {
+		let chain = Arc::new(create_chain());
+		let url = start_esplora(chain.clone());
+		let backend = EsploraBackend::new(&url).unwrap();
+		check_sync(&chain, Box::new(backend));
+	}
+
+	#[test]
+	fn can_track_utxos_with_electrum() {
+		let chain = Arc::new(create_chain());
+		let url = start_electrum(chain.clone());
+		let backend = ElectrumBackend::new(&url).unwrap();
+		assert_eq!(backend.get_block_filter(&chain.blocks[1].block_hash()).unwrap(), None);
+		check_sync(&chain, Box::new(backend));
+	}
+}
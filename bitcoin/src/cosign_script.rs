@@ -1,9 +1,16 @@
-use alloc::{format, string::ToString, vec};
+use alloc::{
+	format,
+	string::{String, ToString},
+	vec,
+	vec::Vec,
+};
 use core::str::FromStr;
 
 pub use bitcoin::Amount;
 use bitcoin::{
 	absolute::LockTime,
+	hashes::Hash,
+	taproot::TapTweakHash,
 	transaction::{predict_weight, InputWeightPrediction},
 	Address, FeeRate, Network, PublicKey, ScriptBuf,
 };
@@ -15,9 +22,15 @@ use miniscript::{
 	Descriptor, FromStrKey, MiniscriptKey, Segwitv0,
 };
 
-use argon_primitives::bitcoin::{BitcoinError, BitcoinHeight, CompressedBitcoinPubkey};
+use argon_primitives::{
+	bitcoin::{
+		BitcoinCosignScriptType, BitcoinError, BitcoinHeight, CompressedBitcoinPubkey,
+		VaultMultisigPubkeys,
+	},
+	ensure,
+};
 
-use crate::errors::Error;
+use crate::{errors::Error, musig::MusigKeyAgg};
 
 #[derive(Copy, Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
 pub enum UnlockStep {
@@ -35,6 +48,8 @@ pub struct CosignScriptArgs {
 	pub vault_claim_height: BitcoinHeight,
 	pub open_claim_height: BitcoinHeight,
 	pub created_at_height: BitcoinHeight,
+	/// The other vault pubkeys of a multisig vault. Only supported for P2WSH scripts.
+	pub vault_multisig: Option<VaultMultisigPubkeys>,
 }
 
 impl CosignScriptArgs {
@@ -59,12 +74,65 @@ impl CosignScriptArgs {
 		LockTime::from_height(self.open_claim_height as u32)
 			.map_err(|_| BitcoinError::InvalidLockTime)
 	}
+
+	/// The number of vault signatures needed to cosign or claim
+	pub fn vault_threshold(&self) -> usize {
+		self.vault_multisig.as_ref().map(|a| a.threshold as usize).unwrap_or(1)
+	}
+
+	/// The vault pubkeys that can cosign an unlock
+	pub fn vault_cosign_pubkeys(&self) -> Vec<CompressedBitcoinPubkey> {
+		let mut pubkeys = vec![self.vault_pubkey];
+		if let Some(multisig) = &self.vault_multisig {
+			pubkeys.extend(multisig.cosign_pubkeys.iter().copied());
+		}
+		pubkeys
+	}
+
+	/// The vault pubkeys that can claim the bitcoin after the vault claim height
+	pub fn vault_claim_pubkeys(&self) -> Vec<CompressedBitcoinPubkey> {
+		let mut pubkeys = vec![self.vault_claim_pubkey];
+		if let Some(multisig) = &self.vault_multisig {
+			pubkeys.extend(multisig.claim_pubkeys.iter().copied());
+		}
+		pubkeys
+	}
+
+	/// The vault side of a policy (or P2WSH descriptor) for the given vault pubkeys. A single
+	/// vault pubkey is a `pk`. A multisig vault needs `threshold` of the pubkeys, which is a
+	/// `thresh` of `pk`s in a policy and a `multi` in a descriptor.
+	fn vault_keys_fragment(
+		&self,
+		pubkeys: &[CompressedBitcoinPubkey],
+		is_policy: bool,
+	) -> Result<String, BitcoinError> {
+		let pubkeys = pubkeys
+			.iter()
+			.map(|a| (*a).try_into().map_err(|_| BitcoinError::InvalidPubkey))
+			.collect::<Result<Vec<PublicKey>, _>>()?;
+		if self.vault_multisig.is_none() {
+			let pubkey = pubkeys.first().ok_or(BitcoinError::InvalidPubkey)?;
+			return Ok(format!("pk({pubkey})"));
+		}
+		let threshold = self.vault_threshold();
+		ensure!(threshold > 0 && threshold <= pubkeys.len(), BitcoinError::InvalidPolicy);
+		let fragment = if is_policy {
+			let keys = pubkeys.iter().map(|a| format!("pk({a})")).collect::<Vec<_>>();
+			format!("thresh({threshold},{})", keys.join(","))
+		} else {
+			let keys = pubkeys.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+			format!("multi({threshold},{})", keys.join(","))
+		};
+		Ok(fragment)
+	}
 }
 
 #[derive(Clone, Eq, Debug, PartialEq)]
 pub struct CosignScript {
 	pub script_args: CosignScriptArgs,
+	pub script_type: BitcoinCosignScriptType,
 	pub policy: Policy<PublicKey>,
+	/// The witness script for a P2WSH script, or the output script pubkey for a P2TR script
 	pub script: ScriptBuf,
 	pub address: Address,
 	pub descriptor: Descriptor<PublicKey>,
@@ -72,12 +140,43 @@ pub struct CosignScript {
 
 impl CosignScript {
 	pub fn new(cosign_script_args: CosignScriptArgs, network: Network) -> Result<Self, Error> {
+		Self::with_script_type(cosign_script_args, BitcoinCosignScriptType::P2WSH, network)
+	}
+
+	pub fn with_script_type(
+		cosign_script_args: CosignScriptArgs,
+		script_type: BitcoinCosignScriptType,
+		network: Network,
+	) -> Result<Self, Error> {
+		// a multisig vault can't join the MuSig2 key path of a taproot script
+		ensure!(
+			script_type == BitcoinCosignScriptType::P2WSH ||
+				cosign_script_args.vault_multisig.is_none(),
+			Error::UnsupportedScriptType
+		);
 		let policy =
 			Self::create_policy(&cosign_script_args).map_err(Error::TimelockScriptError)?;
-		let descriptor = Self::build_descriptor(&cosign_script_args, &policy)?;
-		let script = descriptor.script_code().map_err(|_| BitcoinError::InvalidPolicy)?;
+		let (descriptor, script) = match script_type {
+			BitcoinCosignScriptType::P2WSH => {
+				let descriptor = Self::build_descriptor(&cosign_script_args, &policy)?;
+				let script = descriptor.script_code().map_err(|_| BitcoinError::InvalidPolicy)?;
+				(descriptor, script)
+			},
+			BitcoinCosignScriptType::P2TR => {
+				let descriptor = Self::get_taproot_descriptor(&cosign_script_args)?;
+				let script = descriptor.script_pubkey();
+				(descriptor, script)
+			},
+		};
 		let address = descriptor.address(network).map_err(|_| Error::AddressError)?;
-		Ok(Self { script_args: cosign_script_args, policy, script, address, descriptor })
+		Ok(Self {
+			script_args: cosign_script_args,
+			script_type,
+			policy,
+			script,
+			address,
+			descriptor,
+		})
 	}
 
 	pub fn get_script_address(&self) -> Address {
@@ -85,7 +184,7 @@ impl CosignScript {
 	}
 
 	pub fn get_script_pubkey(&self) -> ScriptBuf {
-		self.script.clone().to_p2wsh()
+		self.descriptor.script_pubkey()
 	}
 
 	pub fn set_registered_height(&mut self, height: BitcoinHeight) {
@@ -106,16 +205,7 @@ impl CosignScript {
 		to_script_pubkey: ScriptBuf,
 		fee_rate: FeeRate,
 	) -> Result<Amount, Error> {
-		const MAX_SIGNATURE_SIZE: usize = 73;
-		const COMPRESSED_PUBKEY_SIZE: usize = 33;
-
-		let mut witness_element_lengths =
-			vec![MAX_SIGNATURE_SIZE, COMPRESSED_PUBKEY_SIZE, self.script.len()];
-
-		if is_cosign {
-			witness_element_lengths.push(MAX_SIGNATURE_SIZE);
-			witness_element_lengths.push(COMPRESSED_PUBKEY_SIZE);
-		}
+		let witness_element_lengths = self.witness_element_lengths(is_cosign)?;
 		let weight = predict_weight(
 			vec![InputWeightPrediction::from_slice(0, witness_element_lengths.as_slice())],
 			vec![to_script_pubkey.len()],
@@ -124,17 +214,64 @@ impl CosignScript {
 		Ok(fee)
 	}
 
+	/// The maximum sizes of the witness elements needed to spend this script
+	pub(crate) fn witness_element_lengths(&self, is_cosign: bool) -> Result<Vec<usize>, Error> {
+		const MAX_SIGNATURE_SIZE: usize = 73;
+		const COMPRESSED_PUBKEY_SIZE: usize = 33;
+		// schnorr signature with a non-default sighash type
+		const SCHNORR_SIGNATURE_SIZE: usize = 65;
+		const CONTROL_BLOCK_BASE_SIZE: usize = 33;
+
+		let witness_element_lengths = match self.script_type {
+			BitcoinCosignScriptType::P2WSH => {
+				let mut lengths =
+					vec![MAX_SIGNATURE_SIZE, COMPRESSED_PUBKEY_SIZE, self.script.len()];
+				if is_cosign {
+					lengths.push(MAX_SIGNATURE_SIZE);
+					lengths.push(COMPRESSED_PUBKEY_SIZE);
+				}
+				// a multisig vault signs with more keys, plus the empty CHECKMULTISIG element
+				if self.script_args.vault_multisig.is_some() {
+					lengths.push(0);
+					for _ in 1..self.script_args.vault_threshold() {
+						lengths.push(MAX_SIGNATURE_SIZE);
+					}
+				}
+				lengths
+			},
+			// a cosign is a key path spend with a single aggregate signature
+			BitcoinCosignScriptType::P2TR if is_cosign => vec![SCHNORR_SIGNATURE_SIZE],
+			BitcoinCosignScriptType::P2TR => {
+				let Descriptor::Tr(ref tr) = self.descriptor else {
+					return Err(Error::UnsupportedScriptType);
+				};
+				let (max_depth, max_script_size) =
+					tr.iter_scripts().fold((0usize, 0usize), |(depth, size), (d, ms)| {
+						(depth.max(d as usize), size.max(ms.script_size()))
+					});
+				vec![
+					SCHNORR_SIGNATURE_SIZE,
+					max_script_size,
+					CONTROL_BLOCK_BASE_SIZE + 32 * max_depth,
+				]
+			},
+		};
+		Ok(witness_element_lengths)
+	}
+
 	#[rustfmt::skip]
 	pub fn create_policy(
 		cosign_script_args: &CosignScriptArgs) -> Result<Policy<PublicKey>, BitcoinError> {
-		let vault_pubkey = cosign_script_args.bitcoin_vault_pubkey()?;
+		let vault_keys = cosign_script_args
+			.vault_keys_fragment(&cosign_script_args.vault_cosign_pubkeys(), true)?;
 		let owner_pubkey: PublicKey = cosign_script_args.bitcoin_owner_pubkey()?;
-		let vault_claim_pubkey: PublicKey = cosign_script_args.bitcoin_vault_claim_pubkey()?;
+		let vault_claim_keys = cosign_script_args
+			.vault_keys_fragment(&cosign_script_args.vault_claim_pubkeys(), true)?;
 		let open_claim_height = cosign_script_args.open_claim_locktime()?;
 		let vault_claim_height = cosign_script_args.vault_claim_locktime()?;
 		let policy_str = format!(r#"or(
-			thresh(2, pk({vault_pubkey}), pk({owner_pubkey}), after({open_claim_height})),
-			and(pk({vault_claim_pubkey}), after({vault_claim_height}))
+			thresh(2, {vault_keys}, pk({owner_pubkey}), after({open_claim_height})),
+			and({vault_claim_keys}, after({vault_claim_height}))
 		)"#);
 		// strip whitespace
 		let policy_str = policy_str.split_whitespace().collect::<alloc::string::String>();
@@ -147,7 +284,68 @@ impl CosignScript {
 	pub fn create_descriptor<Pk: MiniscriptKey + FromStrKey>(
 		&self,
 	) -> Result<Descriptor<Pk>, BitcoinError> {
-		Self::get_descriptor::<Pk>(&self.script_args)
+		match self.script_type {
+			BitcoinCosignScriptType::P2WSH => Self::get_descriptor::<Pk>(&self.script_args),
+			BitcoinCosignScriptType::P2TR => Self::get_taproot_descriptor::<Pk>(&self.script_args),
+		}
+	}
+
+	/// The MuSig2 aggregate of the vault and owner pubkeys, tweaked with the taproot script tree.
+	/// This is the key that must sign a cooperative (key path) unlock of a P2TR script.
+	pub fn musig_key_agg(&self) -> Result<MusigKeyAgg, Error> {
+		ensure!(self.script_type == BitcoinCosignScriptType::P2TR, Error::UnsupportedScriptType);
+		let Descriptor::Tr(ref tr) = self.descriptor else {
+			return Err(Error::UnsupportedScriptType);
+		};
+		let mut key_agg =
+			MusigKeyAgg::new(&[self.script_args.vault_pubkey, self.script_args.owner_pubkey])?;
+		let spend_info = tr.spend_info();
+		let tweak =
+			TapTweakHash::from_key_and_tweak(spend_info.internal_key(), spend_info.merkle_root());
+		key_agg.apply_x_only_tweak(tweak.to_byte_array())?;
+		Ok(key_agg)
+	}
+
+	/// The untweaked MuSig2 aggregate of the vault and owner pubkeys, encoded with an even y
+	/// coordinate so it can be used as a taproot internal key
+	pub fn musig_internal_key(
+		cosign_script_args: &CosignScriptArgs,
+	) -> Result<PublicKey, BitcoinError> {
+		let key_agg =
+			MusigKeyAgg::new(&[cosign_script_args.vault_pubkey, cosign_script_args.owner_pubkey])
+				.map_err(|_| BitcoinError::InvalidPubkey)?;
+		let mut internal_key = [2u8; 33];
+		internal_key[1..].copy_from_slice(&key_agg.x_only_key());
+		PublicKey::from_slice(&internal_key).map_err(|_| BitcoinError::InvalidPubkey)
+	}
+
+	/// Creates a taproot descriptor that does the following:
+	/// - The key path is a MuSig2 aggregate of the `vault_pubkey` and `owner_pubkey`
+	/// - After `vault_claim_height`, the `vault_claim_pubkey` can claim the funds
+	/// - After `open_claim_height`, the `owner_pubkey` or the `vault_pubkey` can claim the funds
+	pub fn get_taproot_descriptor<Pk: MiniscriptKey + FromStrKey>(
+		cosign_script_args: &CosignScriptArgs,
+	) -> Result<Descriptor<Pk>, BitcoinError> {
+		const TAPROOT_DESCRIPTOR: &str = "tr({musig_pubkey},{and_v(v:pk({vault_claim_pubkey}),after({vault_claim_height})),{and_v(v:pk({owner_pubkey}),after({open_claim_height})),and_v(v:pk({vault_pubkey}),after({open_claim_height}))}})";
+		ensure!(cosign_script_args.vault_multisig.is_none(), BitcoinError::InvalidPolicy);
+		let musig_pubkey = Self::musig_internal_key(cosign_script_args)?;
+		let vault_pubkey = cosign_script_args.bitcoin_vault_pubkey()?;
+		let owner_pubkey = cosign_script_args.bitcoin_owner_pubkey()?;
+		let vault_claim_pubkey = cosign_script_args.bitcoin_vault_claim_pubkey()?;
+		let vault_claim_height = cosign_script_args.vault_claim_locktime()?;
+		let open_claim_height = cosign_script_args.open_claim_locktime()?;
+
+		let descriptor_str = TAPROOT_DESCRIPTOR
+			.replace("{musig_pubkey}", &musig_pubkey.to_string())
+			.replace("{vault_pubkey}", &vault_pubkey.to_string())
+			.replace("{owner_pubkey}", &owner_pubkey.to_string())
+			.replace("{vault_claim_pubkey}", &vault_claim_pubkey.to_string())
+			.replace("{vault_claim_height}", &vault_claim_height.to_string())
+			.replace("{open_claim_height}", &open_claim_height.to_string());
+
+		let descriptor = miniscript::Descriptor::<Pk>::from_str(&descriptor_str)
+			.map_err(|_| BitcoinError::InvalidPolicy)?;
+		Ok(descriptor)
 	}
 
 	/// Creates a miniscript policy that does the following:
@@ -155,20 +353,24 @@ impl CosignScript {
 	/// - Between `vault_claim_height` and `open_claim_height`, only the `vault_claim_pubkey` can
 	///   claim the funds
 	/// - After `open_claim_height`, the `owner_pubkey` can claim the funds
+	///
+	/// For a multisig vault, each vault `pk` is a `multi` of the vault's pubkeys.
 	pub fn get_descriptor<Pk: MiniscriptKey + FromStrKey>(
 		cosign_script_args: &CosignScriptArgs,
 	) -> Result<Descriptor<Pk>, BitcoinError> {
-		const COMPILED_DESCRIPTOR: &str = "wsh(andor(pk({vault_claim_pubkey}),after({vault_claim_height}),thresh(2,pk({vault_pubkey}),s:pk({owner_pubkey}),snl:after({open_claim_height}))))";
-		let vault_pubkey = cosign_script_args.bitcoin_vault_pubkey()?;
+		const COMPILED_DESCRIPTOR: &str = "wsh(andor({vault_claim_keys},after({vault_claim_height}),thresh(2,{vault_keys},s:pk({owner_pubkey}),snl:after({open_claim_height}))))";
+		let vault_keys = cosign_script_args
+			.vault_keys_fragment(&cosign_script_args.vault_cosign_pubkeys(), false)?;
 		let owner_pubkey = cosign_script_args.bitcoin_owner_pubkey()?;
-		let vault_claim_pubkey = cosign_script_args.bitcoin_vault_claim_pubkey()?;
+		let vault_claim_keys = cosign_script_args
+			.vault_keys_fragment(&cosign_script_args.vault_claim_pubkeys(), false)?;
 		let vault_claim_height = cosign_script_args.vault_claim_locktime()?;
 		let open_claim_height = cosign_script_args.open_claim_locktime()?;
 
 		let descriptor_str = COMPILED_DESCRIPTOR
-			.replace("{vault_pubkey}", &vault_pubkey.to_string())
+			.replace("{vault_keys}", &vault_keys)
 			.replace("{owner_pubkey}", &owner_pubkey.to_string())
-			.replace("{vault_claim_pubkey}", &vault_claim_pubkey.to_string())
+			.replace("{vault_claim_keys}", &vault_claim_keys)
 			.replace("{vault_claim_height}", &vault_claim_height.to_string())
 			.replace("{open_claim_height}", &open_claim_height.to_string());
 
@@ -207,6 +409,7 @@ mod test {
 	};
 	use bitcoincore_rpc::{jsonrpc::base64, RawTx, RpcApi};
 	use bitcoind::BitcoinD;
+	use sp_runtime::BoundedVec;
 
 	use argon_primitives::bitcoin::{
 		BitcoinBlock, BitcoinCosignScriptPubkey, BitcoinHeight, BitcoinScriptPubkey,
@@ -214,7 +417,7 @@ mod test {
 	};
 	use argon_testing::*;
 
-	use crate::{UnlockStep, UtxoSpendFilter, UtxoUnlocker};
+	use crate::{MusigSecretNonce, UnlockStep, UtxoSpendFilter, UtxoUnlocker};
 
 	use super::*;
 
@@ -264,6 +467,7 @@ mod test {
 			vault_claim_height,
 			open_claim_height,
 			created_at_height: block_height,
+			vault_multisig: None,
 		};
 		let cosign_script = CosignScript::new(script_args, network).expect("script");
 		let script = cosign_script.script.to_bytes();
@@ -292,6 +496,7 @@ mod test {
 			vault_claim_height,
 			open_claim_height,
 			created_at_height: register_height,
+			vault_multisig: None,
 		};
 		let cosign_script = CosignScript::new(script_args, network).unwrap();
 
@@ -437,6 +642,7 @@ mod test {
 			vault_claim_height,
 			open_claim_height,
 			created_at_height: register_height,
+			vault_multisig: None,
 		};
 		let mut cosign_script = CosignScript::new(script_args, network).unwrap();
 
@@ -556,6 +762,7 @@ mod test {
 			vault_claim_height,
 			open_claim_height,
 			created_at_height: register_height,
+			vault_multisig: None,
 		};
 		let mut cosign_script = CosignScript::new(script_args, network).unwrap();
 
@@ -684,6 +891,7 @@ mod test {
 				vault_claim_height,
 				open_claim_height,
 				created_at_height: block_height,
+				vault_multisig: None,
 			};
 			let cosign_script = CosignScript::new(script_args, network).expect("script address");
 			cosign_script.get_script_address()
@@ -735,6 +943,7 @@ mod test {
 			vault_claim_height,
 			open_claim_height,
 			created_at_height: register_height,
+			vault_multisig: None,
 		};
 		let user_cosign_script = CosignScript::new(script_args, network).unwrap();
 		let fee = user_cosign_script
@@ -750,6 +959,7 @@ mod test {
 				vault_claim_height,
 				open_claim_height,
 				created_at_height: register_height,
+				vault_multisig: None,
 			};
 			let mut unlocker = UtxoUnlocker::new(
 				script_args,
@@ -817,6 +1027,528 @@ mod test {
 		drop(bitcoind);
 	}
 
+	#[test]
+	fn vault_and_owner_can_cosign_with_psbts() {
+		let (bitcoind, _tracker, block_address, network) = start_bitcoind();
+		let secp = Secp256k1::new();
+		let owner_keypair = PrivateKey::generate(network);
+		let owner_compressed_pubkey = owner_keypair.public_key(&secp);
+		let amount: Satoshis = Amount::ONE_BTC.to_sat();
+
+		let (vault_master_xpriv, vault_fingerprint) = create_xpriv(network);
+		let (vault_compressed_pubkey, vault_hd_path) =
+			derive(&vault_master_xpriv, "m/48'/0'/0'/0/1");
+		let vault_claim_pubkey = derive(&vault_master_xpriv, "m/48'/0'/0'/1/0").0;
+		let block_height = bitcoind.client.get_block_count().unwrap();
+		let script_args = CosignScriptArgs {
+			vault_pubkey: vault_compressed_pubkey.into(),
+			vault_claim_pubkey: vault_claim_pubkey.into(),
+			owner_pubkey: owner_compressed_pubkey.into(),
+			vault_claim_height: block_height + 10,
+			open_claim_height: block_height + 20,
+			created_at_height: block_height,
+			vault_multisig: None,
+		};
+		let cosign_script = CosignScript::new(script_args.clone(), network).expect("script");
+		let (txid, vout, _) = fund_script_address(
+			&bitcoind,
+			&cosign_script.get_script_address(),
+			amount,
+			&block_address,
+		);
+
+		let out_script_pubkey = owner_compressed_pubkey.p2wpkh_script_code().unwrap();
+		let feerate = FeeRate::from_sat_per_vb(15).expect("cant translate fee");
+		let fee = cosign_script.calculate_fee(true, out_script_pubkey.clone(), feerate).unwrap();
+		let mut owner_unlocker = UtxoUnlocker::from_script(
+			cosign_script.clone(),
+			amount,
+			txid,
+			vout,
+			UnlockStep::OwnerCosign,
+			fee,
+			out_script_pubkey,
+		)
+		.expect("unlocker");
+		let unsigned_psbt = owner_unlocker.to_psbt();
+
+		// can't use the psbt for a different script
+		let other_script = CosignScript::new(
+			CosignScriptArgs { created_at_height: block_height + 1, ..script_args },
+			network,
+		)
+		.expect("script");
+		assert!(matches!(
+			UtxoUnlocker::from_psbt(other_script, UnlockStep::VaultCosign, &unsigned_psbt),
+			Err(Error::PsbtInputNotFound)
+		));
+		// can't claim with a cosign psbt
+		assert!(UtxoUnlocker::from_psbt(
+			cosign_script.clone(),
+			UnlockStep::VaultClaim,
+			&unsigned_psbt
+		)
+		.is_err());
+
+		// the vault signs the psbt in an external wallet
+		let vault_psbt = {
+			let mut vault_unlocker =
+				UtxoUnlocker::from_psbt(cosign_script, UnlockStep::VaultCosign, &unsigned_psbt)
+					.expect("import");
+			assert_eq!(vault_unlocker.psbt, owner_unlocker.psbt);
+			vault_unlocker
+				.sign_derived(vault_master_xpriv, (vault_fingerprint, vault_hd_path))
+				.expect("sign");
+			vault_unlocker.to_psbt()
+		};
+
+		owner_unlocker.combine_psbt(&vault_psbt).expect("combine");
+		assert!(owner_unlocker.psbt.inputs[0]
+			.partial_sigs
+			.contains_key(&vault_compressed_pubkey.into()));
+		owner_unlocker.sign(owner_keypair).expect("sign");
+		let tx = owner_unlocker.extract_tx().expect("tx");
+		let acceptance = bitcoind.client.test_mempool_accept(&[tx.raw_hex()]).expect("checked");
+		assert!(acceptance[0].allowed);
+		drop(bitcoind);
+	}
+
+	#[test]
+	fn vault_and_owners_can_cosign_a_batch() {
+		let (bitcoind, _tracker, block_address, network) = start_bitcoind();
+		let secp = Secp256k1::new();
+		let amount: Satoshis = Amount::ONE_BTC.to_sat();
+
+		let (vault_master_xpriv, vault_fingerprint) = create_xpriv(network);
+		let vault_claim_pubkey = derive(&vault_master_xpriv, "m/48'/0'/0'/1/0").0;
+		let block_height = bitcoind.client.get_block_count().unwrap();
+		let feerate = FeeRate::from_sat_per_vb(15).expect("cant translate fee");
+
+		let mut owners = vec![];
+		let mut unlockers = vec![];
+		let mut unlock_fees = Amount::ZERO;
+		for i in 1..=2 {
+			let owner_keypair = PrivateKey::generate(network);
+			let owner_compressed_pubkey = owner_keypair.public_key(&secp);
+			let (vault_compressed_pubkey, vault_hd_path) =
+				derive(&vault_master_xpriv, &format!("m/48'/0'/0'/0/{i}"));
+			let script_args = CosignScriptArgs {
+				vault_pubkey: vault_compressed_pubkey.into(),
+				vault_claim_pubkey: vault_claim_pubkey.into(),
+				owner_pubkey: owner_compressed_pubkey.into(),
+				vault_claim_height: block_height + 10,
+				open_claim_height: block_height + 20,
+				created_at_height: block_height,
+				vault_multisig: None,
+			};
+			let cosign_script = CosignScript::new(script_args, network).expect("script");
+			let (txid, vout, _) = fund_script_address(
+				&bitcoind,
+				&cosign_script.get_script_address(),
+				amount,
+				&block_address,
+			);
+
+			let out_script_pubkey = owner_compressed_pubkey.p2wpkh_script_code().unwrap();
+			let fee =
+				cosign_script.calculate_fee(true, out_script_pubkey.clone(), feerate).unwrap();
+			unlock_fees += fee;
+			unlockers.push(
+				UtxoUnlocker::from_script(
+					cosign_script,
+					amount,
+					txid,
+					vout,
+					UnlockStep::VaultCosign,
+					fee,
+					out_script_pubkey,
+				)
+				.expect("unlocker"),
+			);
+			owners.push((owner_keypair, vault_hd_path));
+		}
+
+		// the batch can't pay more than the unlocks requested
+		assert!(UtxoUnlocker::batch(unlockers.clone(), unlock_fees + Amount::ONE_SAT).is_err());
+		let batch_fee = unlock_fees * 3 / 4;
+		let mut unlockers = UtxoUnlocker::batch(unlockers, batch_fee).expect("batch");
+		let tx = &unlockers[0].psbt.unsigned_tx;
+		assert_eq!(tx.input.len(), 2);
+		assert_eq!(tx.output.len(), 2);
+		assert_eq!(
+			tx.output.iter().map(|a| a.value).sum::<Amount>(),
+			Amount::from_sat(amount * 2) - batch_fee
+		);
+
+		for (unlocker, (owner_keypair, vault_hd_path)) in unlockers.iter_mut().zip(owners) {
+			let (vault_signature, vault_pubkey) = unlocker
+				.sign_derived(vault_master_xpriv, (vault_fingerprint, vault_hd_path))
+				.expect("sign");
+			let vault_signature_api: BitcoinSignature = vault_signature.try_into().unwrap();
+			assert!(unlocker
+				.verify_signature_raw(vault_pubkey.into(), &vault_signature_api)
+				.expect("verify"));
+			unlocker.sign(owner_keypair).expect("sign");
+		}
+
+		let tx = UtxoUnlocker::extract_batch_tx(&mut unlockers).expect("tx");
+		let acceptance = bitcoind.client.test_mempool_accept(&[tx.raw_hex()]).expect("checked");
+		println!("{:?}", acceptance[0]);
+		assert!(acceptance[0].allowed);
+		drop(bitcoind);
+	}
+
+	#[test]
+	fn vault_and_owner_can_cosign_taproot() {
+		let (bitcoind, tracker, block_address, network) = start_bitcoind();
+		let secp = Secp256k1::new();
+		let owner_keypair = PrivateKey::generate(network);
+		let owner_compressed_pubkey = owner_keypair.public_key(&secp);
+		let owner_pubkey: CompressedBitcoinPubkey = owner_compressed_pubkey.into();
+		let amount: Satoshis = Amount::ONE_BTC.to_sat() * 5;
+
+		let (vault_master_xpriv, vault_fingerprint) = create_xpriv(network);
+		let (vault_compressed_pubkey, vault_hd_path) =
+			derive(&vault_master_xpriv, "m/86'/0'/0'/0/1");
+		let vault_claim_pubkey = derive(&vault_master_xpriv, "m/86'/0'/0'/1/0").0;
+		let vault_pubkey: CompressedBitcoinPubkey = vault_compressed_pubkey.into();
+
+		let block_height = bitcoind.client.get_block_count().unwrap();
+		let script_args = CosignScriptArgs {
+			vault_pubkey,
+			vault_claim_pubkey: vault_claim_pubkey.into(),
+			owner_pubkey,
+			vault_claim_height: block_height + 10,
+			open_claim_height: block_height + 20,
+			created_at_height: block_height,
+			vault_multisig: None,
+		};
+		let mut cosign_script =
+			CosignScript::with_script_type(script_args, BitcoinCosignScriptType::P2TR, network)
+				.expect("script");
+		let script_address = cosign_script.get_script_address();
+		let utxo_script_pubkey: BitcoinCosignScriptPubkey =
+			script_address.clone().try_into().expect("can convert address to script");
+		assert!(matches!(utxo_script_pubkey, BitcoinCosignScriptPubkey::P2TR { .. }));
+		assert_eq!(
+			cosign_script.musig_key_agg().unwrap().x_only_key(),
+			cosign_script.get_script_pubkey().as_bytes()[2..]
+		);
+
+		let (txid, vout, _) =
+			fund_script_address(&bitcoind, &script_address, amount, &block_address);
+		let register_height = bitcoind.client.get_block_count().unwrap();
+		cosign_script.set_registered_height(register_height);
+
+		let out_script_pubkey = owner_compressed_pubkey.p2wpkh_script_code().unwrap();
+		let feerate = FeeRate::from_sat_per_vb(15).expect("cant translate fee");
+		let fee = cosign_script.calculate_fee(true, out_script_pubkey.clone(), feerate).unwrap();
+		let claim_fee =
+			cosign_script.calculate_fee(false, out_script_pubkey.clone(), feerate).unwrap();
+		assert!(fee < claim_fee);
+
+		let unlocker = || {
+			UtxoUnlocker::from_script(
+				cosign_script.clone(),
+				amount,
+				txid,
+				vout,
+				UnlockStep::VaultCosign,
+				fee,
+				out_script_pubkey.clone(),
+			)
+			.expect("unlocker")
+		};
+
+		// 1. owner publishes a nonce with the unlock request
+		let owner_nonce = MusigSecretNonce::new(rand::random(), owner_pubkey, None).unwrap();
+
+		// 2. vault creates a nonce and partial signature
+		let vault_nonce = MusigSecretNonce::new(rand::random(), vault_pubkey, None).unwrap();
+		let vault_signature = {
+			let mut unlocker = unlocker();
+			unlocker.add_musig_nonce(owner_compressed_pubkey, owner_nonce.public_nonce());
+			unlocker.add_musig_nonce(vault_compressed_pubkey.into(), vault_nonce.public_nonce());
+			let (signature, _) = unlocker
+				.sign_musig_derived(
+					vault_master_xpriv,
+					(vault_fingerprint, vault_hd_path),
+					&vault_nonce,
+				)
+				.expect("sign");
+			assert!(unlocker.verify_signature_raw(vault_pubkey, &signature).unwrap());
+			assert!(!unlocker.verify_signature_raw(owner_pubkey, &signature).unwrap());
+			signature
+		};
+
+		// 3. owner adds their partial signature and broadcasts
+		let tx = {
+			let mut unlocker = unlocker();
+			unlocker.add_musig_nonce(owner_compressed_pubkey, owner_nonce.public_nonce());
+			unlocker.add_musig_nonce(vault_compressed_pubkey.into(), vault_nonce.public_nonce());
+			unlocker
+				.add_musig_partial_signature(vault_compressed_pubkey.into(), &vault_signature)
+				.unwrap();
+			unlocker.sign_musig(owner_keypair, &owner_nonce).expect("sign");
+			unlocker.extract_tx().expect("tx")
+		};
+		// key path spends only have a single witness element
+		assert_eq!(tx.input[0].witness.len(), 1);
+
+		let acceptance = bitcoind.client.test_mempool_accept(&[tx.raw_hex()]).expect("checked");
+		println!("{:?}", acceptance[0]);
+		assert!(acceptance[0].allowed);
+
+		check_spent(
+			&tx.raw_hex(),
+			&tracker,
+			&bitcoind,
+			UtxoRef { txid: txid.into(), output_index: vout },
+			UtxoValue {
+				utxo_id: 1,
+				satoshis: amount,
+				script_pubkey: utxo_script_pubkey,
+				submitted_at_height: register_height,
+				watch_for_spent_until_height: block_height + 20,
+			},
+			&block_address,
+		);
+		drop(bitcoind);
+	}
+
+	#[test]
+	fn owner_can_claim_the_taproot_script_leaf() {
+		let (bitcoind, _tracker, block_address, network) = start_bitcoind();
+		let secp = Secp256k1::new();
+		let owner_keypair = PrivateKey::generate(network);
+		let owner_compressed_pubkey = owner_keypair.public_key(&secp);
+		let amount: Satoshis = Amount::ONE_BTC.to_sat();
+
+		let (master_xpriv, _) = create_xpriv(network);
+		let (vault_compressed_pubkey, _) = derive(&master_xpriv, "m/0'/0/1");
+		let vault_claim_pubkey = derive(&master_xpriv, "m/0'/1/0").0;
+
+		let block_height = bitcoind.client.get_block_count().unwrap();
+		let open_claim_height = block_height + 6;
+		let script_args = CosignScriptArgs {
+			vault_pubkey: vault_compressed_pubkey.into(),
+			vault_claim_pubkey: vault_claim_pubkey.into(),
+			owner_pubkey: owner_compressed_pubkey.into(),
+			vault_claim_height: block_height + 3,
+			open_claim_height,
+			created_at_height: block_height,
+			vault_multisig: None,
+		};
+		let cosign_script =
+			CosignScript::with_script_type(script_args, BitcoinCosignScriptType::P2TR, network)
+				.expect("script");
+
+		let (txid, vout, _) = fund_script_address(
+			&bitcoind,
+			&cosign_script.get_script_address(),
+			amount,
+			&block_address,
+		);
+
+		let out_script_pubkey = owner_compressed_pubkey.p2wpkh_script_code().unwrap();
+		let feerate = FeeRate::from_sat_per_vb(15).expect("cant translate fee");
+		let fee = cosign_script.calculate_fee(false, out_script_pubkey.clone(), feerate).unwrap();
+
+		let mut unlocker = UtxoUnlocker::from_script(
+			cosign_script.clone(),
+			amount,
+			txid,
+			vout,
+			UnlockStep::OwnerClaim,
+			fee,
+			out_script_pubkey,
+		)
+		.expect("unlocker");
+		// ecdsa signatures can't spend a taproot script
+		assert!(unlocker.clone().sign(owner_keypair).is_err());
+		unlocker.sign_tapscript(owner_keypair).expect("sign");
+		let tx = unlocker.extract_tx().expect("tx");
+
+		let current_height = bitcoind.client.get_block_count().unwrap();
+		add_blocks(&bitcoind, open_claim_height.saturating_sub(current_height), &block_address);
+		let acceptance = bitcoind.client.test_mempool_accept(&[tx.raw_hex()]).expect("checked");
+		println!("{:?}", acceptance[0]);
+		assert!(acceptance[0].allowed);
+		drop(bitcoind);
+	}
+
+	#[test]
+	fn vault_can_sign_a_taproot_claim_with_a_derived_key() {
+		let (bitcoind, _tracker, block_address, network) = start_bitcoind();
+		let secp = Secp256k1::new();
+		let owner_compressed_pubkey = PrivateKey::generate(network).public_key(&secp);
+		let amount: Satoshis = Amount::ONE_BTC.to_sat();
+
+		let (master_xpriv, fingerprint) = create_xpriv(network);
+		let (vault_compressed_pubkey, _) = derive(&master_xpriv, "m/0'/0/1");
+		let (vault_claim_pubkey, vault_claim_hd_path) = derive(&master_xpriv, "m/0'/1/0");
+
+		let block_height = bitcoind.client.get_block_count().unwrap();
+		let vault_claim_height = block_height + 5;
+		let script_args = CosignScriptArgs {
+			vault_pubkey: vault_compressed_pubkey.into(),
+			vault_claim_pubkey: vault_claim_pubkey.into(),
+			owner_pubkey: owner_compressed_pubkey.into(),
+			vault_claim_height,
+			open_claim_height: block_height + 10,
+			created_at_height: block_height,
+			vault_multisig: None,
+		};
+		let cosign_script =
+			CosignScript::with_script_type(script_args, BitcoinCosignScriptType::P2TR, network)
+				.expect("script");
+
+		let (txid, vout, _) = fund_script_address(
+			&bitcoind,
+			&cosign_script.get_script_address(),
+			amount,
+			&block_address,
+		);
+
+		let out_script_pubkey = vault_claim_pubkey.p2wpkh_script_code();
+		let feerate = FeeRate::from_sat_per_vb(15).expect("cant translate fee");
+		let fee = cosign_script.calculate_fee(false, out_script_pubkey.clone(), feerate).unwrap();
+		let key_source = (fingerprint, vault_claim_hd_path);
+
+		// a cosigned unlock can't be signed as a claim
+		let mut unlocker = UtxoUnlocker::from_script(
+			cosign_script.clone(),
+			amount,
+			txid,
+			vout,
+			UnlockStep::VaultCosign,
+			fee,
+			out_script_pubkey.clone(),
+		)
+		.expect("unlocker");
+		assert!(matches!(
+			unlocker.sign_claim_derived(master_xpriv, key_source.clone()),
+			Err(Error::NotAClaimStep)
+		));
+
+		let mut unlocker = UtxoUnlocker::from_script(
+			cosign_script.clone(),
+			amount,
+			txid,
+			vout,
+			UnlockStep::VaultClaim,
+			fee,
+			out_script_pubkey,
+		)
+		.expect("unlocker");
+		let tx = unlocker.sign_claim_derived(master_xpriv, key_source).expect("claim");
+		assert_eq!(tx.lock_time, LockTime::from_consensus(vault_claim_height as u32));
+
+		let current_height = bitcoind.client.get_block_count().unwrap();
+		assert!(current_height < vault_claim_height);
+		let acceptance = bitcoind.client.test_mempool_accept(&[tx.raw_hex()]).expect("checked");
+		assert!(!acceptance[0].allowed);
+
+		add_blocks(&bitcoind, vault_claim_height - current_height, &block_address);
+		let acceptance = bitcoind.client.test_mempool_accept(&[tx.raw_hex()]).expect("checked");
+		println!("{:?}", acceptance[0]);
+		assert!(acceptance[0].allowed);
+		drop(bitcoind);
+	}
+
+	#[test]
+	fn multisig_vault_and_owner_can_cosign() {
+		let (bitcoind, _tracker, block_address, network) = start_bitcoind();
+		let secp = Secp256k1::new();
+		let owner_keypair = PrivateKey::generate(network);
+		let owner_compressed_pubkey = owner_keypair.public_key(&secp);
+		let amount: Satoshis = Amount::ONE_BTC.to_sat();
+
+		// a 2-of-3 vault
+		let vault_xprivs = (0..3).map(|_| create_xpriv(network)).collect::<Vec<_>>();
+		let vault_keys = vault_xprivs
+			.iter()
+			.map(|(xpriv, _)| derive(xpriv, "m/48'/0'/0'/0/1"))
+			.collect::<Vec<_>>();
+		let vault_claim_pubkeys = vault_xprivs
+			.iter()
+			.map(|(xpriv, _)| derive(xpriv, "m/48'/0'/0'/0/2").0.into())
+			.collect::<Vec<CompressedBitcoinPubkey>>();
+
+		let block_height = bitcoind.client.get_block_count().unwrap();
+		let script_args = CosignScriptArgs {
+			vault_pubkey: vault_keys[0].0.into(),
+			vault_claim_pubkey: vault_claim_pubkeys[0],
+			owner_pubkey: owner_compressed_pubkey.into(),
+			vault_claim_height: block_height + 10,
+			open_claim_height: block_height + 20,
+			created_at_height: block_height,
+			vault_multisig: Some(VaultMultisigPubkeys {
+				threshold: 2,
+				cosign_pubkeys: BoundedVec::truncate_from(
+					vault_keys[1..].iter().map(|(pubkey, _)| (*pubkey).into()).collect::<Vec<_>>(),
+				),
+				claim_pubkeys: BoundedVec::truncate_from(vault_claim_pubkeys[1..].to_vec()),
+			}),
+		};
+		assert!(CosignScript::with_script_type(
+			script_args.clone(),
+			BitcoinCosignScriptType::P2TR,
+			network
+		)
+		.is_err());
+		let cosign_script = CosignScript::new(script_args, network).expect("script");
+		assert!(cosign_script.descriptor.to_string().contains("multi(2,"));
+
+		let (txid, vout, _) = fund_script_address(
+			&bitcoind,
+			&cosign_script.get_script_address(),
+			amount,
+			&block_address,
+		);
+
+		let out_script_pubkey = owner_compressed_pubkey.p2wpkh_script_code().unwrap();
+		let feerate = FeeRate::from_sat_per_vb(15).expect("cant translate fee");
+		let fee = cosign_script.calculate_fee(true, out_script_pubkey.clone(), feerate).unwrap();
+		let new_unlocker = |unlock_step| {
+			UtxoUnlocker::from_script(
+				cosign_script.clone(),
+				amount,
+				txid,
+				vout,
+				unlock_step,
+				fee,
+				out_script_pubkey.clone(),
+			)
+			.expect("unlocker")
+		};
+
+		// the vault signs with its first and third keys
+		let mut vault_unlocker = new_unlocker(UnlockStep::VaultCosign);
+		let vault_signatures = [0, 2]
+			.into_iter()
+			.map(|i| {
+				let (xpriv, fingerprint) = vault_xprivs[i];
+				vault_unlocker
+					.sign_derived(xpriv, (fingerprint, vault_keys[i].1.clone()))
+					.expect("sign")
+			})
+			.collect::<Vec<_>>();
+
+		// one vault signature isn't enough
+		let mut unlocker = new_unlocker(UnlockStep::OwnerCosign);
+		unlocker.add_signature(vault_signatures[0].1, vault_signatures[0].0);
+		unlocker.sign(owner_keypair).expect("sign");
+		assert!(unlocker.clone().extract_tx().is_err());
+
+		unlocker.add_signature(vault_signatures[1].1, vault_signatures[1].0);
+		let tx = unlocker.extract_tx().expect("tx");
+		let acceptance = bitcoind.client.test_mempool_accept(&[tx.raw_hex()]).expect("checked");
+		println!("{:?}", acceptance[0]);
+		assert!(acceptance[0].allowed);
+		drop(bitcoind);
+	}
+
 	fn check_spent(
 		tx_hex: &str,
 		tracker: &UtxoSpendFilter,
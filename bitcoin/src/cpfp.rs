@@ -0,0 +1,129 @@
+use alloc::vec;
+
+use argon_primitives::ensure;
+use bitcoin::{
+	absolute::LockTime,
+	psbt::Input,
+	transaction::{predict_weight, InputWeightPrediction, Version},
+	Amount, FeeRate, OutPoint, Psbt, ScriptBuf, Sequence, Transaction, TxIn, TxOut,
+};
+
+use crate::errors::Error;
+
+/// Creates a psbt for a transaction that spends an output of an unconfirmed unlock transaction
+/// with enough fee to bring both transactions up to the given fee rate (child pays for parent).
+///
+/// The spent output must pay to a P2WPKH or P2TR (key path) script so the size of the signature
+/// is known. Returns the psbt and the fee paid by the child.
+pub fn create_cpfp_psbt(
+	parent_tx: &Transaction,
+	parent_fee: Amount,
+	output_index: u32,
+	to_script_pubkey: ScriptBuf,
+	fee_rate: FeeRate,
+) -> Result<(Psbt, Amount), Error> {
+	let spent_output = parent_tx
+		.output
+		.get(output_index as usize)
+		.ok_or(Error::CpfpOutputNotFound)?
+		.clone();
+	let input_weight = if spent_output.script_pubkey.is_p2wpkh() {
+		InputWeightPrediction::P2WPKH_MAX
+	} else if spent_output.script_pubkey.is_p2tr() {
+		InputWeightPrediction::P2TR_KEY_DEFAULT_SIGHASH
+	} else {
+		return Err(Error::UnsupportedScriptType);
+	};
+
+	let child_weight = predict_weight([input_weight], [to_script_pubkey.len()]);
+	let package_fee =
+		fee_rate.fee_wu(parent_tx.weight() + child_weight).ok_or(Error::FeeOverflow)?;
+	let child_fee = package_fee.checked_sub(parent_fee).ok_or(Error::FeeRateNotIncreased)?;
+	// the child needs to pay at least for itself
+	ensure!(
+		child_fee >= fee_rate.fee_wu(child_weight).ok_or(Error::FeeOverflow)?,
+		Error::FeeRateNotIncreased
+	);
+	let value = spent_output.value.checked_sub(child_fee).ok_or(Error::FeeOverflow)?;
+	ensure!(value >= to_script_pubkey.minimal_non_dust(), Error::FeeOverflow);
+
+	let unsigned_tx = Transaction {
+		version: Version::TWO,
+		lock_time: LockTime::ZERO,
+		input: vec![TxIn {
+			previous_output: OutPoint { txid: parent_tx.compute_txid(), vout: output_index },
+			sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+			..TxIn::default()
+		}],
+		output: vec![TxOut { value, script_pubkey: to_script_pubkey }],
+	};
+	let mut psbt = Psbt::from_unsigned_tx(unsigned_tx).map_err(Error::PsbtError)?;
+	psbt.inputs[0] = Input { witness_utxo: Some(spent_output), ..Input::default() };
+	Ok((psbt, child_fee))
+}
+
+#[cfg(test)]
+mod test {
+	use bitcoin::{hashes::Hash, key::Secp256k1, Network, PrivateKey, Txid, WPubkeyHash, Witness};
+
+	use super::*;
+
+	fn parent_tx(script_pubkey: ScriptBuf) -> Transaction {
+		Transaction {
+			version: Version::TWO,
+			lock_time: LockTime::ZERO,
+			input: vec![TxIn {
+				previous_output: OutPoint { txid: Txid::all_zeros(), vout: 0 },
+				sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+				witness: Witness::from_slice(&[[0u8; 72].to_vec(), [0u8; 33].to_vec()]),
+				..TxIn::default()
+			}],
+			output: vec![TxOut { value: Amount::from_sat(100_000), script_pubkey }],
+		}
+	}
+
+	#[test]
+	fn it_pays_for_the_parent() {
+		let secp = Secp256k1::new();
+		let pubkey = PrivateKey::generate(Network::Regtest).public_key(&secp);
+		let owner_script = ScriptBuf::new_p2wpkh(&pubkey.wpubkey_hash().unwrap());
+		let parent = parent_tx(owner_script.clone());
+		let fee_rate = FeeRate::from_sat_per_vb(20).unwrap();
+		let parent_fee = Amount::from_sat(200);
+
+		let (psbt, child_fee) =
+			create_cpfp_psbt(&parent, parent_fee, 0, owner_script.clone(), fee_rate).expect("cpfp");
+		let child = &psbt.unsigned_tx;
+		assert_eq!(child.input[0].previous_output.txid, parent.compute_txid());
+		assert_eq!(child.output[0].value, Amount::from_sat(100_000) - child_fee);
+		assert_eq!(psbt.inputs[0].witness_utxo, Some(parent.output[0].clone()));
+
+		let child_weight =
+			predict_weight([InputWeightPrediction::P2WPKH_MAX], [owner_script.len()]);
+		assert_eq!(
+			child_fee + parent_fee,
+			fee_rate.fee_wu(parent.weight() + child_weight).unwrap()
+		);
+
+		// nothing to do if the parent already pays the fee rate
+		assert!(matches!(
+			create_cpfp_psbt(&parent, Amount::from_sat(50_000), 0, owner_script.clone(), fee_rate),
+			Err(Error::FeeRateNotIncreased)
+		));
+		assert!(matches!(
+			create_cpfp_psbt(&parent, parent_fee, 1, owner_script, fee_rate),
+			Err(Error::CpfpOutputNotFound)
+		));
+		let unknown_script = parent_tx(ScriptBuf::new_p2pkh(&pubkey.pubkey_hash()));
+		assert!(matches!(
+			create_cpfp_psbt(
+				&unknown_script,
+				parent_fee,
+				0,
+				ScriptBuf::new_p2wpkh(&WPubkeyHash::all_zeros()),
+				fee_rate
+			),
+			Err(Error::UnsupportedScriptType)
+		));
+	}
+}
@@ -12,6 +12,26 @@ pub enum Error {
 	#[error("The fees overflowed.")]
 	FeeOverflow,
 
+	/// No unlocks were provided for a batch
+	#[error("No unlocks were provided for the batch.")]
+	BatchEmpty,
+
+	/// An unlock is already part of a batch
+	#[error("An unlock is already part of a batch.")]
+	BatchAlreadyCreated,
+
+	/// The unlocks are not inputs of the same batch
+	#[error("The unlocks are not inputs of the same batch.")]
+	BatchInputMismatch,
+
+	/// The output to spend is not in the transaction
+	#[error("The output to spend is not in the transaction.")]
+	CpfpOutputNotFound,
+
+	/// A fee bump must raise the fee rate
+	#[error("The fee rate is not higher than the transaction already pays.")]
+	FeeRateNotIncreased,
+
 	/// Insufficient fees
 	#[error("Insufficient fees.")]
 	FeeTooLow,
@@ -56,6 +76,10 @@ pub enum Error {
 	#[error("Partially Signed Bitcoin Transaction Error {0:?}")]
 	PsbtError(psbt::Error),
 
+	/// The psbt doesn't spend the cosign script
+	#[error("The psbt has no input spending this cosign script")]
+	PsbtInputNotFound,
+
 	/// Psbt Finalize Error
 	#[error("Psbt Finalize Error")]
 	PsbtFinalizeError,
@@ -67,6 +91,66 @@ pub enum Error {
 	/// Invalid Compressed Pubkey Bytes
 	#[error("Invalid Compressed Pubkey Bytes")]
 	InvalidCompressPubkeyBytes,
+
+	/// Invalid MuSig2 Nonce
+	#[error("Invalid MuSig2 Nonce")]
+	InvalidMusigNonce,
+
+	/// A MuSig2 nonce is missing for one of the cosigners
+	#[error("A MuSig2 nonce is missing for one of the cosigners")]
+	MusigNonceMissing,
+
+	/// Could not aggregate the MuSig2 keys
+	#[error("Could not aggregate the MuSig2 keys")]
+	MusigKeyAggError,
+
+	/// Could not create a MuSig2 partial signature
+	#[error("Could not create a MuSig2 partial signature")]
+	MusigSignError,
+
+	/// Could not decode a bitcoin block header
+	#[error("Could not decode a bitcoin block header")]
+	InvalidBitcoinHeader,
+
+	/// Header checkpoints must start a difficulty adjustment period
+	#[error("Header checkpoints must start a difficulty adjustment period")]
+	InvalidBitcoinHeaderCheckpoint,
+
+	/// The block hash doesn't meet the header's target
+	#[error("The block hash doesn't meet the header's target")]
+	InvalidBitcoinProofOfWork,
+
+	/// The header doesn't extend the previous block
+	#[error("The header doesn't extend the previous block")]
+	BitcoinHeaderNotConnected,
+
+	/// The header difficulty doesn't match the difficulty adjustment rules
+	#[error("The header difficulty doesn't match the difficulty adjustment rules")]
+	UnexpectedBitcoinDifficulty,
+
+	/// The header time isn't after the median time of the previous blocks
+	#[error("The header time isn't after the median time of the previous blocks")]
+	BitcoinHeaderTooOld,
+
+	/// The header time is too far in the future
+	#[error("The header time is more than 2 hours in the future")]
+	BitcoinHeaderTooNew,
+
+	/// Headers of this network can't be verified from proof of work alone
+	#[error("Bitcoin headers of this network can't be verified")]
+	UnsupportedBitcoinNetwork,
+
+	/// The transaction is not proven to be in the block
+	#[error("The transaction is not proven to be in the block")]
+	InvalidInclusionProof,
+
+	/// The operation is not supported for this cosign script type
+	#[error("The operation is not supported for this cosign script type")]
+	UnsupportedScriptType,
+
+	/// The unlock isn't one of the timelocked claims
+	#[error("The unlock step is not a timelocked claim")]
+	NotAClaimStep,
 }
 
 impl From<BitcoinError> for Error {
@@ -2,15 +2,31 @@
 extern crate alloc;
 extern crate core;
 
+#[cfg(feature = "std")]
+pub use bitcoin_backend::{
+	create_block_filter, BitcoinBackend, BitcoindBackend, BlockHeaderInfo, ElectrumBackend,
+	EsploraBackend,
+};
 pub use cosign_script::{Amount, CosignScript, CosignScriptArgs, UnlockStep};
+pub use cpfp::create_cpfp_psbt;
 pub use errors::Error;
+pub use musig::{MusigKeyAgg, MusigSecretNonce, MusigSession};
+pub use spv::{
+	create_header_checkpoint, create_merkle_branch, decode_header, genesis_header, supports_spv,
+	verify_next_header, verify_tx_inclusion, verify_utxo_inclusion,
+};
 #[cfg(feature = "std")]
 pub use utxo_spend_filter::{BlockFilter, UtxoSpendFilter};
 pub use utxo_unlocker::UtxoUnlocker;
 
 mod cosign_script;
+mod cpfp;
+mod musig;
+mod spv;
 mod utxo_unlocker;
 
+#[cfg(feature = "std")]
+mod bitcoin_backend;
 mod errors;
 #[cfg(feature = "std")]
 mod utxo_spend_filter;
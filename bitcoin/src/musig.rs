@@ -0,0 +1,307 @@
+//! Two-party MuSig2 (BIP-327) used to cooperatively sign the key path of a taproot cosign script.
+//! The signing math comes from the `musig2` crate. This module only adapts it to the encodings
+//! stored on chain, and is no-std friendly so that the runtime can verify partial signatures
+//! submitted by a vault.
+use alloc::vec::Vec;
+use core::fmt;
+
+use musig2::{
+	secp::{MaybeScalar, Point, Scalar},
+	AggNonce, KeyAggContext, LiftedSignature, PartialSignature, PubNonce, SecNonce,
+};
+
+use argon_primitives::{
+	bitcoin::{BitcoinMusigNonce, CompressedBitcoinPubkey},
+	ensure,
+};
+
+use crate::errors::Error;
+
+/// The aggregate key of a set of signers, including any x-only tweaks applied to it (eg, a
+/// taproot tweak)
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MusigKeyAgg {
+	/// Sorted compressed pubkeys of the signers
+	pub pubkeys: Vec<[u8; 33]>,
+	context: KeyAggContext,
+}
+
+impl MusigKeyAgg {
+	/// Aggregates the keys in sorted order (BIP-327 `KeySort`), so the order of the keys doesn't
+	/// change the aggregate key
+	pub fn new(pubkeys: &[CompressedBitcoinPubkey]) -> Result<Self, Error> {
+		let mut pubkeys = pubkeys.iter().map(|a| a.0).collect::<Vec<_>>();
+		pubkeys.sort();
+		Self::from_ordered_keys(pubkeys)
+	}
+
+	fn from_ordered_keys(pubkeys: Vec<[u8; 33]>) -> Result<Self, Error> {
+		ensure!(!pubkeys.is_empty(), Error::MusigKeyAggError);
+		let points = pubkeys
+			.iter()
+			.map(|a| Point::from_slice(a).map_err(|_| Error::InvalidCompressPubkeyBytes))
+			.collect::<Result<Vec<_>, _>>()?;
+		let context = KeyAggContext::new(points).map_err(|_| Error::MusigKeyAggError)?;
+		Ok(Self { pubkeys, context })
+	}
+
+	/// Applies an x-only tweak (eg, a BIP-341 taproot tweak) to the aggregate key
+	pub fn apply_x_only_tweak(&mut self, tweak: [u8; 32]) -> Result<(), Error> {
+		let tweak = Scalar::from_slice(&tweak).map_err(|_| Error::MusigKeyAggError)?;
+		self.context = self
+			.context
+			.clone()
+			.with_xonly_tweak(tweak)
+			.map_err(|_| Error::MusigKeyAggError)?;
+		Ok(())
+	}
+
+	/// The x-only aggregate key
+	pub fn x_only_key(&self) -> [u8; 32] {
+		self.aggregate_key().serialize_xonly()
+	}
+
+	/// The aggregate key with its parity
+	pub fn compressed_key(&self) -> [u8; 33] {
+		self.aggregate_key().serialize()
+	}
+
+	fn aggregate_key(&self) -> Point {
+		self.context.aggregated_pubkey::<Point>()
+	}
+}
+
+/// A secret MuSig2 nonce. This must never be re-used across signing sessions.
+#[derive(Clone)]
+pub struct MusigSecretNonce {
+	nonce: SecNonce,
+	pub pubkey: [u8; 33],
+}
+
+impl MusigSecretNonce {
+	/// The BIP-327 `secnonce` encoding (k1, k2 and the signer pubkey)
+	pub const ENCODED_LEN: usize = 97;
+
+	/// Generates a new nonce. The randomness MUST be fresh for every signing session.
+	pub fn new(
+		rand: [u8; 32],
+		pubkey: CompressedBitcoinPubkey,
+		key_agg: Option<&MusigKeyAgg>,
+	) -> Result<Self, Error> {
+		let point = Point::from_slice(&pubkey.0).map_err(|_| Error::InvalidCompressPubkeyBytes)?;
+		let mut builder = SecNonce::build(rand).with_pubkey(point);
+		if let Some(key_agg) = key_agg {
+			builder = builder.with_aggregated_pubkey(key_agg.aggregate_key());
+		}
+		Ok(Self { nonce: builder.build(), pubkey: pubkey.0 })
+	}
+
+	pub fn public_nonce(&self) -> BitcoinMusigNonce {
+		BitcoinMusigNonce(self.nonce.public_nonce().to_bytes())
+	}
+
+	pub fn to_bytes(&self) -> [u8; Self::ENCODED_LEN] {
+		let mut bytes = [0u8; Self::ENCODED_LEN];
+		bytes[..64].copy_from_slice(&self.nonce.to_bytes());
+		bytes[64..].copy_from_slice(&self.pubkey);
+		bytes
+	}
+
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+		ensure!(bytes.len() == Self::ENCODED_LEN, Error::InvalidMusigNonce);
+		let nonce = SecNonce::from_bytes(&bytes[..64]).map_err(|_| Error::InvalidMusigNonce)?;
+		Point::from_slice(&bytes[64..]).map_err(|_| Error::InvalidMusigNonce)?;
+		let mut pubkey = [0u8; 33];
+		pubkey.copy_from_slice(&bytes[64..]);
+		Ok(Self { nonce, pubkey })
+	}
+}
+
+impl PartialEq for MusigSecretNonce {
+	fn eq(&self, other: &Self) -> bool {
+		self.to_bytes() == other.to_bytes()
+	}
+}
+
+impl Eq for MusigSecretNonce {}
+
+impl fmt::Debug for MusigSecretNonce {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("MusigSecretNonce")
+			.field("pubkey", &self.pubkey)
+			.finish_non_exhaustive()
+	}
+}
+
+/// A signing session over a single message with a fixed set of public nonces
+#[derive(Clone, Debug)]
+pub struct MusigSession {
+	pub key_agg: MusigKeyAgg,
+	aggregate_nonce: AggNonce,
+	msg: [u8; 32],
+}
+
+impl MusigSession {
+	pub fn new(
+		key_agg: MusigKeyAgg,
+		public_nonces: &[BitcoinMusigNonce],
+		msg: &[u8; 32],
+	) -> Result<Self, Error> {
+		let public_nonces =
+			public_nonces.iter().map(decode_public_nonce).collect::<Result<Vec<_>, _>>()?;
+		let aggregate_nonce = AggNonce::sum(&public_nonces);
+		Ok(Self { key_agg, aggregate_nonce, msg: *msg })
+	}
+
+	/// Creates a 32 byte partial signature with the given secret nonce and secret key
+	pub fn partial_sign(
+		&self,
+		secret_nonce: &MusigSecretNonce,
+		secret_key: &[u8; 32],
+	) -> Result<[u8; 32], Error> {
+		let secret_key = Scalar::from_slice(secret_key).map_err(|_| Error::MusigSignError)?;
+		ensure!(
+			secret_key.base_point_mul().serialize() == secret_nonce.pubkey,
+			Error::MusigSignError
+		);
+		let partial_signature: PartialSignature = musig2::sign_partial(
+			&self.key_agg.context,
+			secret_key,
+			secret_nonce.nonce.clone(),
+			&self.aggregate_nonce,
+			self.msg,
+		)
+		.map_err(|_| Error::MusigSignError)?;
+		Ok(partial_signature.serialize())
+	}
+
+	/// Verifies a partial signature created by the given pubkey with the given public nonce
+	pub fn partial_verify(
+		&self,
+		partial_signature: &[u8],
+		public_nonce: &BitcoinMusigNonce,
+		pubkey: &CompressedBitcoinPubkey,
+	) -> Result<bool, Error> {
+		let partial_signature =
+			MaybeScalar::from_slice(partial_signature).map_err(|_| Error::InvalidSignatureBytes)?;
+		let public_nonce = decode_public_nonce(public_nonce)?;
+		let pubkey = Point::from_slice(&pubkey.0).map_err(|_| Error::InvalidCompressPubkeyBytes)?;
+		Ok(musig2::verify_partial(
+			&self.key_agg.context,
+			partial_signature,
+			&self.aggregate_nonce,
+			pubkey,
+			&public_nonce,
+			self.msg,
+		)
+		.is_ok())
+	}
+
+	/// Aggregates partial signatures into a 64 byte BIP-340 schnorr signature
+	pub fn aggregate(&self, partial_signatures: &[[u8; 32]]) -> Result<[u8; 64], Error> {
+		let partial_signatures = partial_signatures
+			.iter()
+			.map(|a| MaybeScalar::from_slice(a).map_err(|_| Error::InvalidSignatureBytes))
+			.collect::<Result<Vec<_>, _>>()?;
+		let signature: LiftedSignature = musig2::aggregate_partial_signatures(
+			&self.key_agg.context,
+			&self.aggregate_nonce,
+			partial_signatures,
+			self.msg,
+		)
+		.map_err(|_| Error::MusigSignError)?;
+		Ok(signature.serialize())
+	}
+}
+
+fn decode_public_nonce(nonce: &BitcoinMusigNonce) -> Result<PubNonce, Error> {
+	PubNonce::from_bytes(&nonce.0).map_err(|_| Error::InvalidMusigNonce)
+}
+
+#[cfg(test)]
+mod test {
+	use bitcoin::{
+		secp256k1::{schnorr, Message, Secp256k1, XOnlyPublicKey},
+		PrivateKey,
+	};
+
+	use super::*;
+
+	fn decode<const N: usize>(hex: &str) -> [u8; N] {
+		hex::decode(hex).expect("hex").try_into().expect("length")
+	}
+
+	#[test]
+	fn matches_the_bip327_key_agg_vectors() {
+		let keys: [[u8; 33]; 3] = [
+			decode("02F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9"),
+			decode("03DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659"),
+			decode("023590A94E768F8E1815C2F24B4D80A8E3149316C3518CE7B7AD338368D038CA66"),
+		];
+		let vectors: [(&[usize], &str); 4] = [
+			(&[0, 1, 2], "90539EEDE565F5D054F32CC0C220126889ED1E5D193BAF15AEF344FE59D4610C"),
+			(&[2, 1, 0], "6204DE8B083426DC6EAF9502D27024D53FC826BF7D2012148A0575435DF54B2B"),
+			(&[0, 0, 0], "B436E3BAD62B8CD409969A224731C193D051162D8C5AE8B109306127DA3AA935"),
+			(&[0, 0, 1, 1], "69BC22BFA5D106306E48A20679DE1D7389386124D07571D0D872686028C26A3E"),
+		];
+		for (indices, expected) in vectors {
+			let pubkeys = indices.iter().map(|i| keys[*i]).collect::<Vec<_>>();
+			let key_agg = MusigKeyAgg::from_ordered_keys(pubkeys).expect("key agg");
+			assert_eq!(key_agg.x_only_key(), decode::<32>(expected), "keys {indices:?}");
+		}
+
+		// the public api sorts the keys
+		let sorted = [keys[2], keys[0], keys[1]];
+		let pubkeys = keys.iter().map(|a| CompressedBitcoinPubkey(*a)).collect::<Vec<_>>();
+		assert_eq!(
+			MusigKeyAgg::new(&pubkeys).unwrap(),
+			MusigKeyAgg::from_ordered_keys(sorted.to_vec()).unwrap()
+		);
+	}
+
+	#[test]
+	fn two_signers_can_create_an_aggregate_signature() {
+		let secp = Secp256k1::new();
+		let vault = PrivateKey::generate(bitcoin::Network::Regtest);
+		let owner = PrivateKey::generate(bitcoin::Network::Regtest);
+		let vault_pubkey: CompressedBitcoinPubkey = vault.public_key(&secp).into();
+		let owner_pubkey: CompressedBitcoinPubkey = owner.public_key(&secp).into();
+
+		let mut key_agg = MusigKeyAgg::new(&[vault_pubkey, owner_pubkey]).unwrap();
+		// order of the keys doesn't matter
+		assert_eq!(
+			key_agg.x_only_key(),
+			MusigKeyAgg::new(&[owner_pubkey, vault_pubkey]).unwrap().x_only_key()
+		);
+		key_agg.apply_x_only_tweak([7u8; 32]).unwrap();
+
+		let msg = [3u8; 32];
+		let vault_nonce =
+			MusigSecretNonce::new(rand::random(), vault_pubkey, Some(&key_agg)).unwrap();
+		let owner_nonce = MusigSecretNonce::new(rand::random(), owner_pubkey, None).unwrap();
+		assert_eq!(
+			MusigSecretNonce::from_bytes(&owner_nonce.to_bytes()).unwrap(),
+			owner_nonce.clone()
+		);
+		let nonces = [vault_nonce.public_nonce(), owner_nonce.public_nonce()];
+
+		let session = MusigSession::new(key_agg.clone(), &nonces, &msg).unwrap();
+		let vault_sig = session.partial_sign(&vault_nonce, &vault.inner.secret_bytes()).unwrap();
+		let owner_sig = session.partial_sign(&owner_nonce, &owner.inner.secret_bytes()).unwrap();
+		assert!(
+			session.partial_sign(&owner_nonce, &vault.inner.secret_bytes()).is_err(),
+			"the nonce belongs to another key"
+		);
+
+		assert!(session.partial_verify(&vault_sig, &nonces[0], &vault_pubkey).unwrap());
+		assert!(!session.partial_verify(&vault_sig, &nonces[1], &owner_pubkey).unwrap());
+		assert!(session.partial_verify(&owner_sig, &nonces[1], &owner_pubkey).unwrap());
+
+		let signature = session.aggregate(&[vault_sig, owner_sig]).unwrap();
+		let signature = schnorr::Signature::from_slice(&signature).unwrap();
+		let xonly = XOnlyPublicKey::from_slice(&key_agg.x_only_key()).unwrap();
+		assert!(secp.verify_schnorr(&signature, &Message::from_digest(msg), &xonly).is_ok());
+
+		assert!(session.aggregate(&[vault_sig, vault_sig]).is_err());
+	}
+}
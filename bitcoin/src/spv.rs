@@ -0,0 +1,482 @@
+use alloc::{vec, vec::Vec};
+
+use bitcoin::{
+	block::Header,
+	consensus::deserialize,
+	constants::genesis_block,
+	hashes::{sha256d, Hash},
+	params::Params,
+	CompactTarget, Network, Target, Transaction, Txid,
+};
+use sp_core::U256;
+
+use argon_primitives::bitcoin::{
+	BitcoinBlockHash, BitcoinCosignScriptPubkey, BitcoinHeaderEntry, BitcoinHeight, BitcoinNetwork,
+	BitcoinTxInclusionProof, H256Le, OpaqueBitcoinHeader, Satoshis, UtxoRef,
+};
+
+use crate::errors::Error;
+
+/// Testnet allows a minimum difficulty block if no block has been found for twice the target
+/// spacing
+const MIN_DIFFICULTY_DELAY_MULTIPLIER: u64 = 2;
+
+/// The number of previous block times a new block must be later than the median of
+const MEDIAN_TIME_SPAN: usize = 11;
+
+/// How far a block time can be ahead of the current time (in seconds)
+const MAX_FUTURE_BLOCK_TIME: u64 = 2 * 60 * 60;
+
+/// If the header chain of the network can be verified. Signet blocks are authorized by a signature
+/// in the coinbase transaction (BIP-325), so their low difficulty headers prove nothing on their
+/// own.
+pub fn supports_spv(network: BitcoinNetwork) -> bool {
+	network != BitcoinNetwork::Signet
+}
+
+pub fn decode_header(header: &OpaqueBitcoinHeader) -> Result<Header, Error> {
+	deserialize::<Header>(&header.0[..]).map_err(|_| Error::InvalidBitcoinHeader)
+}
+
+/// The header of the first block of the network
+pub fn genesis_header(network: BitcoinNetwork) -> OpaqueBitcoinHeader {
+	(&genesis_block(Network::from(network)).header).into()
+}
+
+fn chain_work(header: &Header) -> U256 {
+	U256::from_big_endian(&header.work().to_be_bytes())
+}
+
+/// Creates the first entry of a header chain. Difficulty adjustments can only be checked if the
+/// checkpoint is the first block of an adjustment period.
+pub fn create_header_checkpoint(
+	network: BitcoinNetwork,
+	block_height: BitcoinHeight,
+	header: &OpaqueBitcoinHeader,
+) -> Result<(BitcoinBlockHash, BitcoinHeaderEntry), Error> {
+	if !supports_spv(network.clone()) {
+		return Err(Error::UnsupportedBitcoinNetwork);
+	}
+	let params = Params::new(Network::from(network));
+	let interval = params.difficulty_adjustment_interval();
+	if block_height % interval != 0 && !params.no_pow_retargeting {
+		return Err(Error::InvalidBitcoinHeaderCheckpoint);
+	}
+	let header = decode_header(header)?;
+	let block_hash = header
+		.validate_pow(header.target())
+		.map_err(|_| Error::InvalidBitcoinProofOfWork)?;
+	Ok((
+		block_hash.into(),
+		BitcoinHeaderEntry {
+			block_height,
+			previous_block_hash: header.prev_blockhash.into(),
+			merkle_root: H256Le(header.merkle_root.to_byte_array()),
+			bits: header.bits.to_consensus(),
+			time: header.time,
+			epoch_start_time: header.time,
+			epoch_bits: header.bits.to_consensus(),
+			chain_work: chain_work(&header),
+			recent_times: vec![header.time],
+		},
+	))
+}
+
+/// Verifies that a header extends the given block with valid proof of work, difficulty and time.
+/// The time must be after the median of the previous 11 blocks (only checked once 11 blocks are
+/// known past the checkpoint), and no more than 2 hours after `now` (a unix time in seconds).
+pub fn verify_next_header(
+	network: BitcoinNetwork,
+	previous_hash: &BitcoinBlockHash,
+	previous: &BitcoinHeaderEntry,
+	header: &OpaqueBitcoinHeader,
+	now: u64,
+) -> Result<(BitcoinBlockHash, BitcoinHeaderEntry), Error> {
+	if !supports_spv(network.clone()) {
+		return Err(Error::UnsupportedBitcoinNetwork);
+	}
+	let params = Params::new(Network::from(network));
+	let header = decode_header(header)?;
+	if H256Le::from(header.prev_blockhash) != *previous_hash {
+		return Err(Error::BitcoinHeaderNotConnected);
+	}
+	// checked before the difficulty since testnet lowers it for blocks far enough apart
+	if previous.recent_times.len() >= MEDIAN_TIME_SPAN &&
+		header.time <= median_time(&previous.recent_times)
+	{
+		return Err(Error::BitcoinHeaderTooOld);
+	}
+	if header.time as u64 > now.saturating_add(MAX_FUTURE_BLOCK_TIME) {
+		return Err(Error::BitcoinHeaderTooNew);
+	}
+	let block_height = previous.block_height + 1;
+	let is_new_epoch = block_height % params.difficulty_adjustment_interval() == 0;
+
+	let expected_bits = if is_new_epoch {
+		if params.no_pow_retargeting {
+			CompactTarget::from_consensus(previous.bits)
+		} else {
+			let timespan = previous.time.saturating_sub(previous.epoch_start_time);
+			CompactTarget::from_next_work_required(
+				CompactTarget::from_consensus(previous.bits),
+				timespan as u64,
+				&params,
+			)
+		}
+	} else if params.allow_min_difficulty_blocks {
+		let min_difficulty_after =
+			previous.time as u64 + params.pow_target_spacing * MIN_DIFFICULTY_DELAY_MULTIPLIER;
+		if header.time as u64 > min_difficulty_after {
+			params.max_attainable_target.to_compact_lossy()
+		} else {
+			CompactTarget::from_consensus(previous.epoch_bits)
+		}
+	} else {
+		CompactTarget::from_consensus(previous.bits)
+	};
+	if header.bits != expected_bits {
+		return Err(Error::UnexpectedBitcoinDifficulty);
+	}
+	let block_hash = header
+		.validate_pow(Target::from_compact(expected_bits))
+		.map_err(|_| Error::InvalidBitcoinProofOfWork)?;
+
+	let (epoch_start_time, epoch_bits) = if is_new_epoch {
+		(header.time, header.bits.to_consensus())
+	} else {
+		(previous.epoch_start_time, previous.epoch_bits)
+	};
+	let mut recent_times = previous.recent_times.clone();
+	recent_times.push(header.time);
+	if recent_times.len() > MEDIAN_TIME_SPAN {
+		recent_times.remove(0);
+	}
+
+	Ok((
+		block_hash.into(),
+		BitcoinHeaderEntry {
+			block_height,
+			previous_block_hash: previous_hash.clone(),
+			merkle_root: H256Le(header.merkle_root.to_byte_array()),
+			bits: header.bits.to_consensus(),
+			time: header.time,
+			epoch_start_time,
+			epoch_bits,
+			chain_work: previous.chain_work.saturating_add(chain_work(&header)),
+			recent_times,
+		},
+	))
+}
+
+fn median_time(times: &[u32]) -> u32 {
+	let mut sorted = times.to_vec();
+	sorted.sort_unstable();
+	sorted[sorted.len() / 2]
+}
+
+/// Creates the merkle branch proving a transaction is part of a block
+pub fn create_merkle_branch(txids: &[Txid], tx_index: usize) -> Vec<H256Le> {
+	let mut level = txids.iter().map(|a| a.to_byte_array()).collect::<Vec<_>>();
+	let mut index = tx_index;
+	let mut branch = Vec::new();
+	while level.len() > 1 {
+		if level.len() % 2 == 1 {
+			level.push(*level.last().expect("level is not empty"));
+		}
+		branch.push(H256Le(level[index ^ 1]));
+		level = level.chunks(2).map(|pair| hash_nodes(&pair[0], &pair[1])).collect();
+		index /= 2;
+	}
+	branch
+}
+
+fn hash_nodes(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+	let mut engine = sha256d::Hash::engine();
+	bitcoin::hashes::HashEngine::input(&mut engine, left);
+	bitcoin::hashes::HashEngine::input(&mut engine, right);
+	sha256d::Hash::from_engine(engine).to_byte_array()
+}
+
+/// Verifies that the transaction in the proof is part of the block with the given header and
+/// returns it
+pub fn verify_tx_inclusion(
+	header: &BitcoinHeaderEntry,
+	proof: &BitcoinTxInclusionProof,
+) -> Result<Transaction, Error> {
+	// a 64 byte transaction could be disguised as an inner node of the merkle tree
+	if proof.transaction.len() == 64 || proof.merkle_branch.len() >= 32 {
+		return Err(Error::InvalidInclusionProof);
+	}
+	if proof.tx_index as u64 >> proof.merkle_branch.len() != 0 {
+		return Err(Error::InvalidInclusionProof);
+	}
+	let tx =
+		deserialize::<Transaction>(&proof.transaction).map_err(|_| Error::InvalidInclusionProof)?;
+
+	let mut node = tx.compute_txid().to_byte_array();
+	let mut index = proof.tx_index;
+	for sibling in &proof.merkle_branch {
+		node = if index & 1 == 1 {
+			hash_nodes(&sibling.0, &node)
+		} else {
+			hash_nodes(&node, &sibling.0)
+		};
+		index >>= 1;
+	}
+	if node != header.merkle_root.0 {
+		return Err(Error::InvalidInclusionProof);
+	}
+	Ok(tx)
+}
+
+/// Verifies that a utxo is created by a transaction in the block of the given header and returns
+/// the satoshis it holds
+pub fn verify_utxo_inclusion(
+	header: &BitcoinHeaderEntry,
+	proof: &BitcoinTxInclusionProof,
+	utxo_ref: &UtxoRef,
+	script_pubkey: BitcoinCosignScriptPubkey,
+) -> Result<Satoshis, Error> {
+	let tx = verify_tx_inclusion(header, proof)?;
+	if H256Le::from(tx.compute_txid()) != utxo_ref.txid {
+		return Err(Error::InvalidInclusionProof);
+	}
+	let output = tx
+		.output
+		.get(utxo_ref.output_index as usize)
+		.ok_or(Error::InvalidInclusionProof)?;
+	if output.script_pubkey != bitcoin::ScriptBuf::from(script_pubkey) {
+		return Err(Error::InvalidInclusionProof);
+	}
+	Ok(output.value.to_sat())
+}
+
+#[cfg(test)]
+mod test {
+	use bitcoin::{
+		absolute::LockTime, block::Version, consensus::serialize, transaction, Amount, Block,
+		OutPoint, ScriptBuf, Sequence, TxIn, TxMerkleNode, TxOut, Witness,
+	};
+	use sp_core::H256;
+
+	use super::*;
+
+	fn create_tx(seed: u8, script_pubkey: ScriptBuf) -> Transaction {
+		Transaction {
+			version: transaction::Version::TWO,
+			lock_time: LockTime::ZERO,
+			input: vec![TxIn {
+				previous_output: OutPoint { txid: Txid::from_byte_array([seed; 32]), vout: 0 },
+				script_sig: ScriptBuf::new(),
+				sequence: Sequence::MAX,
+				witness: Witness::new(),
+			}],
+			output: vec![TxOut { value: Amount::from_sat(50_000), script_pubkey }],
+		}
+	}
+
+	fn mine(prev: &Header, txdata: Vec<Transaction>, time: u32, bits: CompactTarget) -> Block {
+		let mut block = Block {
+			header: Header {
+				version: Version::TWO,
+				prev_blockhash: prev.block_hash(),
+				merkle_root: TxMerkleNode::all_zeros(),
+				time,
+				bits,
+				nonce: 0,
+			},
+			txdata,
+		};
+		block.header.merkle_root = block.compute_merkle_root().expect("has transactions");
+		while block.header.validate_pow(block.header.target()).is_err() {
+			block.header.nonce += 1;
+		}
+		block
+	}
+
+	#[test]
+	fn it_verifies_a_header_chain() {
+		let genesis = genesis_block(Network::Regtest).header;
+		let now = genesis.time as u64 + 60;
+		let (genesis_hash, genesis_entry) =
+			create_header_checkpoint(BitcoinNetwork::Regtest, 0, &(&genesis).into()).unwrap();
+		assert_eq!(genesis_hash, genesis.block_hash().into());
+
+		let block1 =
+			mine(&genesis, vec![create_tx(1, ScriptBuf::new())], genesis.time + 1, genesis.bits);
+		let (hash1, entry1) = verify_next_header(
+			BitcoinNetwork::Regtest,
+			&genesis_hash,
+			&genesis_entry,
+			&(&block1.header).into(),
+			now,
+		)
+		.unwrap();
+		assert_eq!(entry1.block_height, 1);
+		assert!(entry1.chain_work > genesis_entry.chain_work);
+
+		// must connect to the previous block
+		assert!(matches!(
+			verify_next_header(
+				BitcoinNetwork::Regtest,
+				&hash1,
+				&entry1,
+				&(&block1.header).into(),
+				now
+			),
+			Err(Error::BitcoinHeaderNotConnected)
+		));
+
+		// must have the expected difficulty
+		let mut wrong_bits = mine(
+			&block1.header,
+			vec![create_tx(2, ScriptBuf::new())],
+			block1.header.time + 1,
+			CompactTarget::from_consensus(0x207ffff0),
+		);
+		assert!(matches!(
+			verify_next_header(
+				BitcoinNetwork::Regtest,
+				&hash1,
+				&entry1,
+				&(&wrong_bits.header).into(),
+				now
+			),
+			Err(Error::UnexpectedBitcoinDifficulty)
+		));
+
+		// must have valid proof of work
+		wrong_bits.header.bits = genesis.bits;
+		while wrong_bits.header.validate_pow(wrong_bits.header.target()).is_ok() {
+			wrong_bits.header.nonce += 1;
+		}
+		assert!(matches!(
+			verify_next_header(
+				BitcoinNetwork::Regtest,
+				&hash1,
+				&entry1,
+				&(&wrong_bits.header).into(),
+				now
+			),
+			Err(Error::InvalidBitcoinProofOfWork)
+		));
+
+		// checkpoints must start a difficulty period outside of regtest
+		assert!(matches!(
+			create_header_checkpoint(BitcoinNetwork::Bitcoin, 1, &(&genesis).into()),
+			Err(Error::InvalidBitcoinHeaderCheckpoint)
+		));
+
+		// signet blocks are signed rather than mined
+		assert!(matches!(
+			create_header_checkpoint(BitcoinNetwork::Signet, 0, &(&genesis).into()),
+			Err(Error::UnsupportedBitcoinNetwork)
+		));
+	}
+
+	#[test]
+	fn it_verifies_header_times() {
+		let genesis = genesis_block(Network::Regtest).header;
+		let mut now = genesis.time as u64;
+		let (mut hash, mut entry) =
+			create_header_checkpoint(BitcoinNetwork::Regtest, 0, &(&genesis).into()).unwrap();
+		let mut header = genesis;
+
+		// can't be more than 2 hours ahead
+		let too_new = mine(
+			&header,
+			vec![create_tx(0, ScriptBuf::new())],
+			(now + MAX_FUTURE_BLOCK_TIME + 1) as u32,
+			genesis.bits,
+		);
+		assert!(matches!(
+			verify_next_header(
+				BitcoinNetwork::Regtest,
+				&hash,
+				&entry,
+				&(&too_new.header).into(),
+				now
+			),
+			Err(Error::BitcoinHeaderTooNew)
+		));
+
+		// times can go backwards until the median of 11 blocks is known
+		for i in 1..=11u8 {
+			let time = if i % 2 == 0 { header.time - 1 } else { header.time + 600 };
+			let block = mine(&header, vec![create_tx(i, ScriptBuf::new())], time, genesis.bits);
+			now = now.max(time as u64);
+			(hash, entry) = verify_next_header(
+				BitcoinNetwork::Regtest,
+				&hash,
+				&entry,
+				&(&block.header).into(),
+				now,
+			)
+			.unwrap();
+			header = block.header;
+		}
+		assert_eq!(entry.recent_times.len(), MEDIAN_TIME_SPAN);
+		let median = median_time(&entry.recent_times);
+
+		let too_old = mine(&header, vec![create_tx(12, ScriptBuf::new())], median, genesis.bits);
+		assert!(matches!(
+			verify_next_header(
+				BitcoinNetwork::Regtest,
+				&hash,
+				&entry,
+				&(&too_old.header).into(),
+				now
+			),
+			Err(Error::BitcoinHeaderTooOld)
+		));
+		let next = mine(&header, vec![create_tx(12, ScriptBuf::new())], median + 1, genesis.bits);
+		let (_, next_entry) =
+			verify_next_header(BitcoinNetwork::Regtest, &hash, &entry, &(&next.header).into(), now)
+				.unwrap();
+		assert_eq!(next_entry.recent_times.len(), MEDIAN_TIME_SPAN);
+		assert_eq!(next_entry.recent_times.last(), Some(&(median + 1)));
+	}
+
+	#[test]
+	fn it_verifies_utxo_inclusion() {
+		let genesis = genesis_block(Network::Regtest).header;
+		let script_pubkey = BitcoinCosignScriptPubkey::P2WSH { wscript_hash: H256([1; 32]) };
+		let txdata = (0..5)
+			.map(|i| create_tx(i, if i == 3 { script_pubkey.into() } else { ScriptBuf::new() }))
+			.collect::<Vec<_>>();
+		let block = mine(&genesis, txdata.clone(), genesis.time + 1, genesis.bits);
+		let (_, entry) =
+			create_header_checkpoint(BitcoinNetwork::Regtest, 1, &(&block.header).into()).unwrap();
+
+		let txids = txdata.iter().map(|a| a.compute_txid()).collect::<Vec<_>>();
+		let proof = BitcoinTxInclusionProof {
+			block_hash: block.block_hash().into(),
+			transaction: serialize(&txdata[3]),
+			tx_index: 3,
+			merkle_branch: create_merkle_branch(&txids, 3),
+		};
+		let utxo_ref = UtxoRef { txid: txids[3].into(), output_index: 0 };
+		assert_eq!(
+			verify_utxo_inclusion(&entry, &proof, &utxo_ref, script_pubkey).unwrap(),
+			50_000
+		);
+
+		let other_script = BitcoinCosignScriptPubkey::P2WSH { wscript_hash: H256([2; 32]) };
+		assert!(matches!(
+			verify_utxo_inclusion(&entry, &proof, &utxo_ref, other_script),
+			Err(Error::InvalidInclusionProof)
+		));
+		let mut wrong_index = proof.clone();
+		wrong_index.tx_index = 2;
+		assert!(matches!(
+			verify_utxo_inclusion(&entry, &wrong_index, &utxo_ref, script_pubkey),
+			Err(Error::InvalidInclusionProof)
+		));
+		let mut wrong_tx = proof.clone();
+		wrong_tx.transaction = serialize(&txdata[2]);
+		assert!(matches!(
+			verify_tx_inclusion(&entry, &wrong_tx),
+			Err(Error::InvalidInclusionProof)
+		));
+	}
+}
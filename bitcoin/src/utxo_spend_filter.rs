@@ -3,20 +3,22 @@
 use std::{collections::BTreeMap, sync::Arc};
 
 use anyhow::bail;
-use bitcoin::{bip158, hashes::Hash};
-use bitcoincore_rpc::{Auth, Client, RpcApi};
+use bitcoin::{bip158, consensus::serialize, hashes::Hash, OutPoint, ScriptBuf, Witness};
 use codec::{Decode, Encode};
 use parking_lot::Mutex;
 use sp_runtime::RuntimeDebug;
 
 use argon_primitives::{
 	bitcoin::{
-		BitcoinBlock, BitcoinHeight, BitcoinNetwork, BitcoinRejectedReason, BitcoinSyncStatus,
-		H256Le, UtxoRef, UtxoValue,
+		BitcoinBlock, BitcoinBlockHash, BitcoinHeight, BitcoinNetwork, BitcoinRejectedReason,
+		BitcoinSyncStatus, BitcoinTxInclusionProof, H256Le, OpaqueBitcoinHeader, UtxoRef,
+		UtxoValue,
 	},
 	inherents::BitcoinUtxoSync,
 };
 
+use crate::bitcoin_backend::{BitcoinBackend, BitcoindBackend};
+
 #[derive(Clone, Decode, Encode, PartialEq, Eq, RuntimeDebug)]
 pub struct BlockFilter {
 	pub block_hash: H256Le,
@@ -36,20 +38,18 @@ impl BlockFilter {
 }
 
 pub struct UtxoSpendFilter {
-	client: Client,
+	backend: Box<dyn BitcoinBackend>,
 	synched_filters: Arc<Mutex<Vec<BlockFilter>>>,
 }
 
 impl UtxoSpendFilter {
 	pub fn new(rpc_url: String, auth: Option<(String, String)>) -> anyhow::Result<Self> {
-		let auth = if let Some((username, password)) = auth {
-			Auth::UserPass(username, password)
-		} else {
-			Auth::None
-		};
-		let client = Client::new(&rpc_url, auth)?;
+		let backend = BitcoindBackend::new(&rpc_url, auth)?;
+		Ok(Self::with_backend(Box::new(backend)))
+	}
 
-		Ok(Self { client, synched_filters: Default::default() })
+	pub fn with_backend(backend: Box<dyn BitcoinBackend>) -> Self {
+		Self { backend, synched_filters: Default::default() }
 	}
 
 	pub fn get_stored_filters(&self) -> Vec<BlockFilter> {
@@ -57,22 +57,27 @@ impl UtxoSpendFilter {
 	}
 
 	pub fn get_network(&self) -> anyhow::Result<BitcoinNetwork> {
-		Ok(self.client.get_blockchain_info()?.chain.into())
+		self.backend.get_network()
 	}
 
 	pub fn load_filters(&self, filters: Vec<BlockFilter>) {
 		*self.synched_filters.lock() = filters;
 	}
 
-	fn get_header_and_filter(&self, block_hash: &H256Le) -> anyhow::Result<BlockFilter> {
+	fn get_header_and_filter(
+		&self,
+		block_hash: &H256Le,
+		block_height: BitcoinHeight,
+	) -> anyhow::Result<BlockFilter> {
 		let hash = bitcoin::BlockHash::from_slice(&block_hash.0)?;
-		let header = self.client.get_block_header_info(&hash)?;
-		let filter = self.client.get_block_filter(&hash)?;
+		let header = self.backend.get_block_header(&hash, block_height)?;
+		// backends without block filters store an empty filter, which always gets checked
+		let filter = self.backend.get_block_filter(&hash)?.unwrap_or_default();
 		Ok(BlockFilter {
-			block_height: header.height as u64,
+			block_height: header.block_height,
 			block_hash: block_hash.clone(),
 			previous_block_hash: header.previous_block_hash.map(Into::into),
-			filter: filter.filter,
+			filter,
 		})
 	}
 
@@ -80,7 +85,10 @@ impl UtxoSpendFilter {
 		let mut stored_filters = self.synched_filters.lock();
 		let latest_block_hash = &sync_status.confirmed_block.block_hash;
 		if stored_filters.last().map(|a| a.block_hash.clone()) != Some(latest_block_hash.clone()) {
-			let entry = self.get_header_and_filter(latest_block_hash)?;
+			let entry = self.get_header_and_filter(
+				latest_block_hash,
+				sync_status.confirmed_block.block_height,
+			)?;
 			stored_filters.push(entry);
 		}
 
@@ -98,7 +106,8 @@ impl UtxoSpendFilter {
 				break;
 			};
 			if let Some(prev_hash) = &first.previous_block_hash {
-				let entry = self.get_header_and_filter(prev_hash)?;
+				let entry =
+					self.get_header_and_filter(prev_hash, first.block_height.saturating_sub(1))?;
 				stored_filters.insert(0, entry);
 			} else {
 				break;
@@ -112,20 +121,23 @@ impl UtxoSpendFilter {
 		&self,
 		tracked_utxos: Vec<(Option<UtxoRef>, UtxoValue)>,
 	) -> anyhow::Result<BitcoinUtxoSync> {
-		let mut scripts: Vec<Vec<u8>> = vec![];
+		let mut scripts: Vec<ScriptBuf> = vec![];
+		let mut query: Vec<Vec<u8>> = vec![];
 		let mut utxos_by_ref = BTreeMap::new();
 		let mut pending_confirmation_by_script = BTreeMap::new();
 
 		for (utxo_ref, lookup) in tracked_utxos {
-			scripts.push(lookup.script_pubkey.to_script_bytes());
+			scripts.push(lookup.script_pubkey.clone().into());
+			query.push(lookup.script_pubkey.to_script_bytes());
 			if let Some(utxo_ref) = utxo_ref {
+				// filters created from blocks match spends by outpoint instead of spent script
+				query.push(serialize(&OutPoint::from(utxo_ref.clone())));
 				utxos_by_ref.insert(utxo_ref, lookup.clone());
 			} else {
 				pending_confirmation_by_script
 					.insert(lookup.script_pubkey.to_script_bytes(), lookup);
 			}
 		}
-		let scripts = scripts.into_iter();
 
 		let stored_filters = self.synched_filters.lock();
 		let Some(latest) = stored_filters.last() else {
@@ -136,17 +148,21 @@ impl UtxoSpendFilter {
 			verified: BTreeMap::new(),
 			invalid: BTreeMap::new(),
 			spent: BTreeMap::new(),
+			headers: vec![],
+			inclusion_proofs: BTreeMap::new(),
 		};
 
 		for filter in &*stored_filters {
 			let block_hash = bitcoin::BlockHash::from_slice(&filter.block_hash.0)?;
-			if !filter.to_filter().match_any(&block_hash, scripts.clone())? {
+			if !filter.filter.is_empty() &&
+				!filter.to_filter().match_any(&block_hash, query.iter().map(Vec::as_slice))?
+			{
 				continue;
 			}
 
-			let block = self.client.get_block(&block_hash)?;
 			let height = filter.block_height;
-			for tx in block.txdata {
+			let txs = self.backend.get_block_transactions(&block_hash, height, &scripts)?;
+			for tx in txs {
 				for input in &tx.input {
 					let utxo_ref = input.previous_output.into();
 					// If we're tracking the UTXO, it has been spent
@@ -168,10 +184,27 @@ impl UtxoSpendFilter {
 					if output.value.to_sat() != pending.satoshis {
 						result.invalid.insert(utxo_id, BitcoinRejectedReason::SatoshisMismatch);
 					} else {
-						let tx_id = tx.compute_txid().into();
-						result
-							.verified
-							.insert(utxo_id, UtxoRef { txid: tx_id, output_index: idx as u32 });
+						let txid = tx.compute_txid();
+						let (tx_index, merkle_branch) =
+							self.backend.get_merkle_branch(&block_hash, height, &txid)?;
+						// the txid commits to the transaction without witness data
+						let mut transaction = tx.clone();
+						for input in transaction.input.iter_mut() {
+							input.witness = Witness::new();
+						}
+						result.inclusion_proofs.insert(
+							utxo_id,
+							BitcoinTxInclusionProof {
+								block_hash: filter.block_hash.clone(),
+								transaction: serialize(&transaction),
+								tx_index,
+								merkle_branch,
+							},
+						);
+						result.verified.insert(
+							utxo_id,
+							UtxoRef { txid: txid.into(), output_index: idx as u32 },
+						);
 					};
 				}
 			}
@@ -180,6 +213,39 @@ impl UtxoSpendFilter {
 		Ok(result)
 	}
 
+	/// The headers needed to extend the runtime's bitcoin header chain up to the confirmed block.
+	/// If the runtime followed a different fork, this walks back until both chains agree on a
+	/// block.
+	pub fn get_headers_to_submit(
+		&self,
+		header_tip: &BitcoinBlock,
+		runtime_header_hash: impl Fn(BitcoinHeight) -> anyhow::Result<Option<BitcoinBlockHash>>,
+		confirmed_height: BitcoinHeight,
+		max_headers: u32,
+	) -> anyhow::Result<Vec<OpaqueBitcoinHeader>> {
+		let mut fork_height = header_tip.block_height.min(confirmed_height);
+		loop {
+			let Some(runtime_hash) = runtime_header_hash(fork_height)? else {
+				bail!("The runtime header chain has no block in common with the bitcoin backend")
+			};
+			let header = self.backend.get_header_by_height(fork_height)?;
+			if H256Le::from(header.block_hash()) == runtime_hash {
+				break;
+			}
+			if fork_height == 0 {
+				bail!("The runtime header chain has no block in common with the bitcoin backend")
+			}
+			fork_height -= 1;
+		}
+
+		let end_height = confirmed_height.min(fork_height + max_headers as BitcoinHeight);
+		let mut headers = vec![];
+		for block_height in (fork_height + 1)..=end_height {
+			headers.push((&self.backend.get_header_by_height(block_height)?).into());
+		}
+		Ok(headers)
+	}
+
 	fn prune_filters(oldest_allowed_block_height: BitcoinHeight, filters: &mut Vec<BlockFilter>) {
 		let mut drain_to = 0;
 		// make sure the blocks link together with prev_hash
@@ -1,19 +1,25 @@
-use alloc::vec;
+use alloc::{collections::BTreeMap, vec, vec::Vec};
 
 use argon_primitives::{
-	bitcoin::{BitcoinError, BitcoinSignature, CompressedBitcoinPubkey, Satoshis},
+	bitcoin::{
+		BitcoinCosignScriptType, BitcoinError, BitcoinMusigNonce, BitcoinSignature,
+		CompressedBitcoinPubkey, Satoshis,
+	},
 	ensure,
 };
 use bitcoin::{
 	absolute::LockTime,
 	bip32::{KeySource, Xpriv},
 	ecdsa::Signature,
-	key::Secp256k1,
-	psbt::Input,
-	sighash::SighashCache,
-	transaction::Version,
-	Amount, EcdsaSighashType, Network, OutPoint, PrivateKey, Psbt, PublicKey, ScriptBuf, Sequence,
-	Transaction, TxIn, TxOut, Witness,
+	hashes::Hash,
+	key::{Keypair, Secp256k1},
+	psbt::{Input, Output, SignError},
+	secp256k1::{schnorr, Message},
+	sighash::{Prevouts, SighashCache},
+	taproot,
+	transaction::{predict_weight, InputWeightPrediction, Version},
+	Amount, EcdsaSighashType, FeeRate, Network, OutPoint, PrivateKey, Psbt, PublicKey, ScriptBuf,
+	Sequence, TapSighashType, Transaction, TxIn, TxOut, Witness,
 };
 use k256::ecdsa::signature::Verifier;
 use miniscript::psbt::PsbtExt;
@@ -21,6 +27,7 @@ use miniscript::psbt::PsbtExt;
 use crate::{
 	cosign_script::{CosignScript, CosignScriptArgs, UnlockStep},
 	errors::Error,
+	musig::{MusigSecretNonce, MusigSession},
 };
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -28,6 +35,12 @@ pub struct UtxoUnlocker {
 	pub cosign_script: CosignScript,
 	pub unlock_step: UnlockStep,
 	pub psbt: Psbt,
+	/// The psbt input spending this utxo. Only a batched unlock has more than one input.
+	pub input_index: usize,
+	/// MuSig2 public nonces of the cosigners of a P2TR key path unlock
+	pub musig_nonces: BTreeMap<PublicKey, BitcoinMusigNonce>,
+	/// MuSig2 partial signatures of the cosigners of a P2TR key path unlock
+	pub musig_partial_sigs: BTreeMap<PublicKey, [u8; 32]>,
 }
 
 impl UtxoUnlocker {
@@ -49,7 +62,8 @@ impl UtxoUnlocker {
 				.map_err(|_| BitcoinError::InvalidLockTime)?,
 			input: vec![TxIn {
 				previous_output: out_point,
-				sequence: Sequence::ENABLE_LOCKTIME_NO_RBF,
+				// signal replace-by-fee so a cosigned unlock can be replaced with a higher fee
+				sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
 				..TxIn::default()
 			}],
 			output: vec![TxOut {
@@ -60,22 +74,37 @@ impl UtxoUnlocker {
 
 		let mut psbt = Psbt::from_unsigned_tx(unsigned_tx).map_err(Error::PsbtError)?;
 
-		psbt.inputs[0] = Input {
+		let mut input = Input {
 			witness_utxo: Some(TxOut {
 				value: out_amount,
 				script_pubkey: cosign_script.get_script_pubkey(),
 			}),
-			witness_script: Some(cosign_script.script.clone()),
-			sighash_type: Some(EcdsaSighashType::AllPlusAnyoneCanPay.into()),
 			..Input::default()
 		};
+		match cosign_script.script_type {
+			BitcoinCosignScriptType::P2WSH => {
+				input.witness_script = Some(cosign_script.script.clone());
+				input.sighash_type = Some(EcdsaSighashType::AllPlusAnyoneCanPay.into());
+			},
+			BitcoinCosignScriptType::P2TR => {
+				input.sighash_type = Some(TapSighashType::AllPlusAnyoneCanPay.into());
+			},
+		}
+		psbt.inputs[0] = input;
 		let descriptor = cosign_script.create_descriptor()?;
 		psbt.update_input_with_descriptor(0, &descriptor).map_err(|_| {
 			log::error!("Error updating PSBT with descriptor: {:#?}", descriptor);
 			Error::PsbtFinalizeError
 		})?;
 
-		Ok(Self { cosign_script, unlock_step, psbt })
+		Ok(Self {
+			cosign_script,
+			unlock_step,
+			psbt,
+			input_index: 0,
+			musig_nonces: BTreeMap::new(),
+			musig_partial_sigs: BTreeMap::new(),
+		})
 	}
 
 	#[allow(clippy::too_many_arguments)]
@@ -100,21 +129,278 @@ impl UtxoUnlocker {
 		)
 	}
 
+	/// Recreates an unlock from a BIP-174 psbt (eg, one exported with [`UtxoUnlocker::to_psbt`]
+	/// and signed in an external wallet). The psbt must have an input spending the cosign script
+	/// with a lock time the unlock step allows.
+	pub fn from_psbt(
+		cosign_script: CosignScript,
+		unlock_step: UnlockStep,
+		psbt: &[u8],
+	) -> Result<Self, Error> {
+		let psbt = Psbt::deserialize(psbt).map_err(Error::PsbtError)?;
+		let script_pubkey = cosign_script.get_script_pubkey();
+		let input_index = psbt
+			.inputs
+			.iter()
+			.position(|input| {
+				input.witness_utxo.as_ref().is_some_and(|a| a.script_pubkey == script_pubkey)
+			})
+			.ok_or(Error::PsbtInputNotFound)?;
+		let lock_time = psbt.unsigned_tx.lock_time;
+		ensure!(
+			lock_time.is_block_height() &&
+				lock_time.to_consensus_u32() >= cosign_script.unlock_height(unlock_step),
+			BitcoinError::InvalidLockTime
+		);
+
+		Ok(Self {
+			cosign_script,
+			unlock_step,
+			psbt,
+			input_index,
+			musig_nonces: BTreeMap::new(),
+			musig_partial_sigs: BTreeMap::new(),
+		})
+	}
+
+	/// Exports the unlock as a serialized BIP-174 psbt so it can be signed by an external wallet.
+	/// MuSig2 nonces and partial signatures are not included.
+	pub fn to_psbt(&self) -> Vec<u8> {
+		self.psbt.serialize()
+	}
+
+	/// Merges the signatures of a psbt signed elsewhere into this unlock. The psbt must be for
+	/// the same unsigned transaction.
+	pub fn combine_psbt(&mut self, psbt: &[u8]) -> Result<(), Error> {
+		let psbt = Psbt::deserialize(psbt).map_err(Error::PsbtError)?;
+		self.psbt.combine(psbt).map_err(Error::PsbtError)
+	}
+
+	/// Combines the unlocks of several utxos into one transaction. Each unlock keeps its own input
+	/// and outputs (in the given order), and the fee is shared across the unlock outputs in
+	/// proportion to the fee each unlock was created with. The shared fee cannot exceed the
+	/// combined fees of the unlocks.
+	///
+	/// Signatures use `SIGHASH_ALL | SIGHASH_ANYONECANPAY`, so each cosigner only signs their own
+	/// input, but commits to every output of the batch. Returns an unlocker for each input of the
+	/// combined transaction. This must be called before any signatures are created.
+	pub fn batch(unlockers: Vec<UtxoUnlocker>, fee: Amount) -> Result<Vec<UtxoUnlocker>, Error> {
+		ensure!(!unlockers.is_empty(), Error::BatchEmpty);
+		let mut unlock_fees = Vec::with_capacity(unlockers.len());
+		for unlocker in &unlockers {
+			ensure!(unlocker.psbt.inputs.len() == 1, Error::BatchAlreadyCreated);
+			let spent = unlocker.psbt.inputs[0]
+				.witness_utxo
+				.as_ref()
+				.ok_or(Error::SignError(SignError::MissingSpendUtxo))?
+				.value;
+			let outputs = unlocker
+				.psbt
+				.unsigned_tx
+				.output
+				.iter()
+				.try_fold(Amount::ZERO, |sum, a| sum.checked_add(a.value))
+				.ok_or(Error::FeeOverflow)?;
+			unlock_fees.push(spent.checked_sub(outputs).ok_or(Error::FeeOverflow)?);
+		}
+		let total_unlock_fees = unlock_fees
+			.iter()
+			.try_fold(Amount::ZERO, |sum, a| sum.checked_add(*a))
+			.ok_or(Error::FeeOverflow)?;
+		ensure!(fee <= total_unlock_fees, Error::FeeOverflow);
+
+		let mut unsigned_tx = Transaction {
+			version: Version::TWO,
+			lock_time: LockTime::ZERO,
+			input: vec![],
+			output: vec![],
+		};
+		let mut inputs = vec![];
+		let mut outputs = vec![];
+		let mut fee_remaining = fee;
+		let last_index = unlockers.len() - 1;
+		for (i, unlocker) in unlockers.iter().enumerate() {
+			let unlock_fee = unlock_fees[i];
+			// the last unlock picks up any rounding
+			let fee_share = if i == last_index {
+				fee_remaining
+			} else {
+				let share = (fee.to_sat() as u128)
+					.saturating_mul(unlock_fee.to_sat() as u128)
+					.checked_div(total_unlock_fees.to_sat() as u128)
+					.unwrap_or_default();
+				Amount::from_sat(share as u64)
+			};
+			fee_remaining = fee_remaining.checked_sub(fee_share).ok_or(Error::FeeOverflow)?;
+
+			let tx = &unlocker.psbt.unsigned_tx;
+			if tx.lock_time.to_consensus_u32() > unsigned_tx.lock_time.to_consensus_u32() {
+				unsigned_tx.lock_time = tx.lock_time;
+			}
+			unsigned_tx.input.extend(tx.input.iter().cloned());
+			for (j, output) in tx.output.iter().enumerate() {
+				let mut output = output.clone();
+				// the unlock output is given back the fee not used by the batch
+				if j == 0 {
+					output.value = output
+						.value
+						.checked_add(unlock_fee.checked_sub(fee_share).ok_or(Error::FeeOverflow)?)
+						.ok_or(Error::FeeOverflow)?;
+				}
+				unsigned_tx.output.push(output);
+			}
+			inputs.extend(unlocker.psbt.inputs.iter().cloned());
+			outputs.extend(unlocker.psbt.outputs.iter().cloned());
+		}
+
+		let mut psbt = Psbt::from_unsigned_tx(unsigned_tx).map_err(Error::PsbtError)?;
+		psbt.inputs = inputs;
+		psbt.outputs = outputs;
+
+		Ok(unlockers
+			.into_iter()
+			.enumerate()
+			.map(|(input_index, unlocker)| UtxoUnlocker {
+				psbt: psbt.clone(),
+				input_index,
+				..unlocker
+			})
+			.collect())
+	}
+
+	/// Calculates the fee for a cosigned batch of unlocks (see [`UtxoUnlocker::batch`]) at the
+	/// given fee rate
+	pub fn calculate_batch_fee(
+		unlockers: &[UtxoUnlocker],
+		fee_rate: FeeRate,
+	) -> Result<Amount, Error> {
+		let mut inputs = Vec::with_capacity(unlockers.len());
+		let mut output_script_lengths = vec![];
+		for unlocker in unlockers {
+			let witness_element_lengths = unlocker.cosign_script.witness_element_lengths(true)?;
+			inputs.push(InputWeightPrediction::from_slice(0, witness_element_lengths.as_slice()));
+			output_script_lengths
+				.extend(unlocker.psbt.unsigned_tx.output.iter().map(|a| a.script_pubkey.len()));
+		}
+		let weight = predict_weight(inputs, output_script_lengths);
+		fee_rate.fee_wu(weight).ok_or(Error::FeeTooLow)
+	}
+
+	/// Sends part of the utxo back to a cosign script (eg, the remainder of a partial redemption).
+	/// The satoshis are taken from the unlock output, which continues to pay the fee. This must be
+	/// called before any signatures are created.
+	pub fn add_remainder_output(
+		&mut self,
+		script_pubkey: ScriptBuf,
+		satoshis: Satoshis,
+	) -> Result<(), Error> {
+		let remainder = Amount::from_sat(satoshis);
+		let unlock_output = &mut self.psbt.unsigned_tx.output[0];
+		unlock_output.value =
+			unlock_output.value.checked_sub(remainder).ok_or(Error::FeeOverflow)?;
+		ensure!(unlock_output.value > Amount::ZERO, Error::FeeOverflow);
+		self.psbt.unsigned_tx.output.push(TxOut { value: remainder, script_pubkey });
+		self.psbt.outputs.push(Output::default());
+		Ok(())
+	}
+
 	pub fn add_signature(&mut self, pubkey: PublicKey, signature: Signature) {
-		self.psbt.inputs[0].partial_sigs.insert(pubkey, signature);
+		self.psbt.inputs[self.input_index].partial_sigs.insert(pubkey, signature);
+	}
+
+	pub fn add_musig_nonce(&mut self, pubkey: PublicKey, nonce: BitcoinMusigNonce) {
+		self.musig_nonces.insert(pubkey, nonce);
+	}
+
+	pub fn add_musig_partial_signature(
+		&mut self,
+		pubkey: PublicKey,
+		signature: &BitcoinSignature,
+	) -> Result<(), Error> {
+		let partial_sig: [u8; 32] =
+			signature.0.as_slice().try_into().map_err(|_| Error::InvalidSignatureBytes)?;
+		self.musig_partial_sigs.insert(pubkey, partial_sig);
+		Ok(())
 	}
 
-	/// No std friendly version of verifying a signature
+	/// The BIP-341 sighash signed by the MuSig2 aggregate key in a P2TR key path unlock
+	pub fn musig_sighash(&self) -> Result<[u8; 32], Error> {
+		let psbt = &self.psbt;
+		let spent_utxo = psbt.inputs[self.input_index]
+			.witness_utxo
+			.as_ref()
+			.ok_or(Error::SignError(SignError::MissingSpendUtxo))?;
+		let mut cache = SighashCache::new(&psbt.unsigned_tx);
+		let sighash = cache
+			.taproot_key_spend_signature_hash(
+				self.input_index,
+				&Prevouts::One(self.input_index, spent_utxo),
+				TapSighashType::AllPlusAnyoneCanPay,
+			)
+			.map_err(|e| Error::SignError(SignError::TaprootError(e)))?;
+		Ok(sighash.to_byte_array())
+	}
+
+	/// Creates a MuSig2 session from the vault and owner nonces. Both must have been added.
+	pub fn musig_session(&self) -> Result<MusigSession, Error> {
+		let vault_pubkey = self.cosign_script.script_args.bitcoin_vault_pubkey()?;
+		let owner_pubkey = self.cosign_script.script_args.bitcoin_owner_pubkey()?;
+		let vault_nonce = self.musig_nonces.get(&vault_pubkey).ok_or(Error::MusigNonceMissing)?;
+		let owner_nonce = self.musig_nonces.get(&owner_pubkey).ok_or(Error::MusigNonceMissing)?;
+		MusigSession::new(
+			self.cosign_script.musig_key_agg()?,
+			&[*vault_nonce, *owner_nonce],
+			&self.musig_sighash()?,
+		)
+	}
+
+	/// Creates a MuSig2 partial signature for a P2TR key path unlock. The nonces of both
+	/// cosigners must have been added.
+	pub fn sign_musig(
+		&mut self,
+		privkey: PrivateKey,
+		secret_nonce: &MusigSecretNonce,
+	) -> Result<(BitcoinSignature, PublicKey), Error> {
+		let pubkey = privkey.public_key(&Secp256k1::new());
+		let session = self.musig_session()?;
+		let partial_sig = session.partial_sign(secret_nonce, &privkey.inner.secret_bytes())?;
+		self.musig_partial_sigs.insert(pubkey, partial_sig);
+		let signature = BitcoinSignature::try_from(partial_sig.to_vec())
+			.map_err(|_| Error::SignatureExpected)?;
+		Ok((signature, pubkey))
+	}
+
+	pub fn sign_musig_derived(
+		&mut self,
+		master_xpriv: Xpriv,
+		key_source: KeySource,
+		secret_nonce: &MusigSecretNonce,
+	) -> Result<(BitcoinSignature, PublicKey), Error> {
+		let child_xpriv = master_xpriv
+			.derive_priv(&Secp256k1::new(), &key_source.1)
+			.map_err(Error::Bip32Error)?;
+		self.sign_musig(child_xpriv.to_priv(), secret_nonce)
+	}
+
+	/// No std friendly version of verifying a signature. For a P2TR script, this verifies a
+	/// MuSig2 partial signature.
 	pub fn verify_signature_raw(
 		&self,
 		pubkey: CompressedBitcoinPubkey,
 		signature_der_bytes: &BitcoinSignature,
 	) -> Result<bool, Error> {
+		if self.cosign_script.script_type == BitcoinCosignScriptType::P2TR {
+			let bitcoin_pubkey: PublicKey =
+				pubkey.try_into().map_err(|_| Error::InvalidCompressPubkeyBytes)?;
+			let nonce = self.musig_nonces.get(&bitcoin_pubkey).ok_or(Error::MusigNonceMissing)?;
+			let Ok(session) = self.musig_session() else { return Ok(false) };
+			return session.partial_verify(&signature_der_bytes.0, nonce, &pubkey);
+		}
 		let psbt = &self.psbt;
 		let mut cache = SighashCache::new(&psbt.unsigned_tx);
 
 		// Get the sighash message
-		let (msg, _) = match psbt.sighash_ecdsa(0, &mut cache) {
+		let (msg, _) = match psbt.sighash_ecdsa(self.input_index, &mut cache) {
 			Ok(result) => result,
 			Err(_) => return Ok(false),
 		};
@@ -132,14 +418,19 @@ impl UtxoUnlocker {
 	}
 
 	pub fn sign(&mut self, privkey: PrivateKey) -> Result<(Signature, PublicKey), Error> {
+		ensure!(
+			self.cosign_script.script_type == BitcoinCosignScriptType::P2WSH,
+			Error::UnsupportedScriptType
+		);
 		let psbt = &mut self.psbt;
 		let mut cache = SighashCache::new(&psbt.unsigned_tx);
-		let (msg, ecdsa_type) = psbt.sighash_ecdsa(0, &mut cache).map_err(Error::SignError)?;
+		let (msg, ecdsa_type) =
+			psbt.sighash_ecdsa(self.input_index, &mut cache).map_err(Error::SignError)?;
 		let secp = Secp256k1::new();
 		let sig = secp.sign_ecdsa(&msg, &privkey.inner);
 		let signature = Signature { signature: sig, sighash_type: ecdsa_type };
 		let pubkey = privkey.public_key(&secp);
-		psbt.inputs[0].partial_sigs.insert(pubkey, signature);
+		psbt.inputs[self.input_index].partial_sigs.insert(pubkey, signature);
 		Ok((signature, pubkey))
 	}
 
@@ -148,6 +439,10 @@ impl UtxoUnlocker {
 		master_xpriv: Xpriv,
 		key_source: KeySource,
 	) -> Result<(Signature, PublicKey), Error> {
+		ensure!(
+			self.cosign_script.script_type == BitcoinCosignScriptType::P2WSH,
+			Error::UnsupportedScriptType
+		);
 		let psbt = &mut self.psbt;
 		let secp = Secp256k1::new();
 		let child_xpriv =
@@ -155,15 +450,20 @@ impl UtxoUnlocker {
 		let child_priv = child_xpriv.to_priv();
 		let pubkey = child_priv.public_key(&secp);
 
-		psbt.inputs[0].bip32_derivation.insert(pubkey.inner, key_source);
+		psbt.inputs[self.input_index].bip32_derivation.insert(pubkey.inner, key_source);
 
 		match psbt.sign(&master_xpriv, &secp) {
-			Ok(_) => ensure!(!psbt.inputs[0].partial_sigs.is_empty(), Error::SignatureExpected),
+			Ok(_) => ensure!(
+				!psbt.inputs[self.input_index].partial_sigs.is_empty(),
+				Error::SignatureExpected
+			),
 			Err((_, errs)) => return Err(Error::SigningErrors(errs)),
 		};
 
-		let Some((_, signature)) =
-			psbt.inputs[0].partial_sigs.iter().find(|(k, _)| k.inner == pubkey.inner)
+		let Some((_, signature)) = psbt.inputs[self.input_index]
+			.partial_sigs
+			.iter()
+			.find(|(k, _)| k.inner == pubkey.inner)
 		else {
 			return Err(Error::DerivedKeySignError);
 		};
@@ -171,10 +471,128 @@ impl UtxoUnlocker {
 		Ok((*signature, pubkey))
 	}
 
+	/// Signs every tapscript leaf of a P2TR script that the given key can spend (eg, a timelocked
+	/// claim).
+	pub fn sign_tapscript(
+		&mut self,
+		privkey: PrivateKey,
+	) -> Result<(taproot::Signature, PublicKey), Error> {
+		ensure!(
+			self.cosign_script.script_type == BitcoinCosignScriptType::P2TR,
+			Error::UnsupportedScriptType
+		);
+		let secp = Secp256k1::new();
+		let keypair = Keypair::from_secret_key(&secp, &privkey.inner);
+		let (x_only_pubkey, _) = keypair.x_only_public_key();
+
+		let psbt = &self.psbt;
+		let spent_utxo = psbt.inputs[self.input_index]
+			.witness_utxo
+			.as_ref()
+			.ok_or(Error::SignError(SignError::MissingSpendUtxo))?;
+		let leaf_hashes = psbt.inputs[self.input_index]
+			.tap_key_origins
+			.get(&x_only_pubkey)
+			.map(|(leaf_hashes, _)| leaf_hashes.clone())
+			.unwrap_or_default();
+		ensure!(!leaf_hashes.is_empty(), Error::SignatureExpected);
+
+		let mut cache = SighashCache::new(&psbt.unsigned_tx);
+		let mut signatures = Vec::with_capacity(leaf_hashes.len());
+		for leaf_hash in leaf_hashes {
+			let sighash = cache
+				.taproot_script_spend_signature_hash(
+					self.input_index,
+					&Prevouts::One(self.input_index, spent_utxo),
+					leaf_hash,
+					TapSighashType::AllPlusAnyoneCanPay,
+				)
+				.map_err(|e| Error::SignError(SignError::TaprootError(e)))?;
+			let signature = secp
+				.sign_schnorr_no_aux_rand(&Message::from_digest(sighash.to_byte_array()), &keypair);
+			let signature =
+				taproot::Signature { signature, sighash_type: TapSighashType::AllPlusAnyoneCanPay };
+			signatures.push((leaf_hash, signature));
+		}
+
+		let mut result = None;
+		for (leaf_hash, signature) in signatures {
+			self.psbt.inputs[self.input_index]
+				.tap_script_sigs
+				.insert((x_only_pubkey, leaf_hash), signature);
+			result = Some(signature);
+		}
+		let signature = result.ok_or(Error::SignatureExpected)?;
+		Ok((signature, privkey.public_key(&secp)))
+	}
+
+	pub fn sign_tapscript_derived(
+		&mut self,
+		master_xpriv: Xpriv,
+		key_source: KeySource,
+	) -> Result<(taproot::Signature, PublicKey), Error> {
+		let child_xpriv = master_xpriv
+			.derive_priv(&Secp256k1::new(), &key_source.1)
+			.map_err(Error::Bip32Error)?;
+		self.sign_tapscript(child_xpriv.to_priv())
+	}
+
+	/// Signs a timelocked claim ([`UnlockStep::VaultClaim`] or [`UnlockStep::OwnerClaim`]) with a
+	/// derived key and extracts the final transaction. The transaction can only be broadcast once
+	/// bitcoin reaches the claim height set as its lock time.
+	pub fn sign_claim_derived(
+		&mut self,
+		master_xpriv: Xpriv,
+		key_source: KeySource,
+	) -> Result<Transaction, Error> {
+		ensure!(
+			matches!(self.unlock_step, UnlockStep::VaultClaim | UnlockStep::OwnerClaim),
+			Error::NotAClaimStep
+		);
+		match self.cosign_script.script_type {
+			BitcoinCosignScriptType::P2WSH => {
+				self.sign_derived(master_xpriv, key_source)?;
+			},
+			BitcoinCosignScriptType::P2TR => {
+				self.sign_tapscript_derived(master_xpriv, key_source)?;
+			},
+		}
+		self.extract_tx()
+	}
+
+	/// Aggregates the vault and owner MuSig2 partial signatures into the key path signature
+	pub fn aggregate_musig_signature(&mut self) -> Result<(), Error> {
+		let session = self.musig_session()?;
+		let vault_pubkey = self.cosign_script.script_args.bitcoin_vault_pubkey()?;
+		let owner_pubkey = self.cosign_script.script_args.bitcoin_owner_pubkey()?;
+		let vault_sig =
+			self.musig_partial_sigs.get(&vault_pubkey).ok_or(Error::SignatureExpected)?;
+		let owner_sig =
+			self.musig_partial_sigs.get(&owner_pubkey).ok_or(Error::SignatureExpected)?;
+
+		let signature = session.aggregate(&[*vault_sig, *owner_sig])?;
+		let signature =
+			schnorr::Signature::from_slice(&signature).map_err(|_| Error::InvalidSignatureBytes)?;
+		self.psbt.inputs[self.input_index].tap_key_sig = Some(taproot::Signature {
+			signature,
+			sighash_type: TapSighashType::AllPlusAnyoneCanPay,
+		});
+		Ok(())
+	}
+
 	pub fn create_witness(&mut self) -> Result<(), Error> {
+		ensure!(
+			self.cosign_script.script_type == BitcoinCosignScriptType::P2WSH,
+			Error::UnsupportedScriptType
+		);
+		// multisig vault witnesses are assembled by the psbt finalizer
+		ensure!(
+			self.cosign_script.script_args.vault_multisig.is_none(),
+			Error::UnsupportedScriptType
+		);
 		let mut witness = Witness::new();
 		let psbt = &mut self.psbt;
-		let partial_sigs = &psbt.inputs[0].partial_sigs;
+		let partial_sigs = &psbt.inputs[self.input_index].partial_sigs;
 		let owner_pubkey = self.cosign_script.script_args.bitcoin_owner_pubkey()?;
 
 		let vault_pubkey = self.cosign_script.script_args.bitcoin_vault_pubkey()?;
@@ -193,11 +611,37 @@ impl UtxoUnlocker {
 		}
 		witness.push(self.cosign_script.script.clone());
 
-		psbt.inputs[0].final_script_witness = Some(witness);
+		psbt.inputs[self.input_index].final_script_witness = Some(witness);
 		Ok(())
 	}
 
+	/// Combines the signed inputs of a batch (see [`UtxoUnlocker::batch`]) into the final
+	/// transaction
+	pub fn extract_batch_tx(unlockers: &mut [UtxoUnlocker]) -> Result<Transaction, Error> {
+		let mut psbt = unlockers.first().ok_or(Error::BatchEmpty)?.psbt.clone();
+		ensure!(psbt.inputs.len() == unlockers.len(), Error::BatchInputMismatch);
+		for unlocker in unlockers.iter_mut() {
+			ensure!(unlocker.psbt.unsigned_tx == psbt.unsigned_tx, Error::BatchInputMismatch);
+			if unlocker.cosign_script.script_type == BitcoinCosignScriptType::P2TR &&
+				!unlocker.musig_partial_sigs.is_empty()
+			{
+				unlocker.aggregate_musig_signature()?;
+			}
+			psbt.inputs[unlocker.input_index] = unlocker.psbt.inputs[unlocker.input_index].clone();
+		}
+		let psbt = psbt.finalize(&Secp256k1::new()).map_err(|(_, e)| {
+			log::error!("Error finalizing PSBT: {:#?}", e);
+			Error::PsbtFinalizeError
+		})?;
+		psbt.extract_tx().map_err(Error::ExtractTxError)
+	}
+
 	pub fn extract_tx(&mut self) -> Result<Transaction, Error> {
+		if self.cosign_script.script_type == BitcoinCosignScriptType::P2TR &&
+			!self.musig_partial_sigs.is_empty()
+		{
+			self.aggregate_musig_signature()?;
+		}
 		let tx = {
 			let mut psbt = self.psbt.clone();
 			psbt = psbt.finalize(&Secp256k1::new()).map_err(|(_, e)| {
@@ -210,11 +654,16 @@ impl UtxoUnlocker {
 		// Clear all the data fields as per the spec.
 		{
 			let psbt = &mut self.psbt;
-			psbt.inputs[0].partial_sigs.clear();
-			psbt.inputs[0].sighash_type = None;
-			psbt.inputs[0].redeem_script = None;
-			psbt.inputs[0].witness_script = None;
-			psbt.inputs[0].bip32_derivation.clear();
+			psbt.inputs[self.input_index].partial_sigs.clear();
+			psbt.inputs[self.input_index].sighash_type = None;
+			psbt.inputs[self.input_index].redeem_script = None;
+			psbt.inputs[self.input_index].witness_script = None;
+			psbt.inputs[self.input_index].bip32_derivation.clear();
+			psbt.inputs[self.input_index].tap_key_sig = None;
+			psbt.inputs[self.input_index].tap_script_sigs.clear();
+			psbt.inputs[self.input_index].tap_scripts.clear();
+			psbt.inputs[self.input_index].tap_key_origins.clear();
+			self.musig_partial_sigs.clear();
 		}
 
 		Ok(tx)
@@ -250,11 +699,13 @@ mod hwi_ext {
 			let x_pubkey = client.get_xpub(&key_source.1, false)?;
 			let pubkey = x_pubkey.public_key;
 
-			psbt.inputs[0].bip32_derivation.insert(pubkey, key_source);
+			psbt.inputs[self.input_index].bip32_derivation.insert(pubkey, key_source);
 
 			psbt.combine(client.sign_tx(psbt)?.psbt)?;
-			let Some((_, signature)) =
-				psbt.inputs[0].partial_sigs.iter().find(|(k, _)| k.inner == pubkey)
+			let Some((_, signature)) = psbt.inputs[self.input_index]
+				.partial_sigs
+				.iter()
+				.find(|(k, _)| k.inner == pubkey)
 			else {
 				bail!("Could not sign with hardware wallet");
 			};
@@ -1,5 +1,5 @@
 use crate::{api::runtime_types, BlakeTwo256};
-use argon_primitives::BlockNumber;
+use argon_primitives::{Balance, BlockNumber};
 use sp_arithmetic::FixedU128;
 use subxt::config::substrate::{DigestItem, SubstrateHeader};
 
@@ -33,10 +33,42 @@ impl From<runtime_types::argon_primitives::bitcoin::BitcoinCosignScriptPubkey>
 			} => argon_primitives::bitcoin::BitcoinCosignScriptPubkey::P2WSH {
 				wscript_hash: wscript_hash.into(),
 			},
+			runtime_types::argon_primitives::bitcoin::BitcoinCosignScriptPubkey::P2TR {
+				output_key,
+			} => argon_primitives::bitcoin::BitcoinCosignScriptPubkey::P2TR {
+				output_key: output_key.into(),
+			},
 		}
 	}
 }
 
+impl From<argon_primitives::bitcoin::BitcoinCosignScriptType>
+	for runtime_types::argon_primitives::bitcoin::BitcoinCosignScriptType
+{
+	fn from(value: argon_primitives::bitcoin::BitcoinCosignScriptType) -> Self {
+		match value {
+			argon_primitives::bitcoin::BitcoinCosignScriptType::P2WSH => Self::P2WSH,
+			argon_primitives::bitcoin::BitcoinCosignScriptType::P2TR => Self::P2TR,
+		}
+	}
+}
+
+impl From<runtime_types::argon_primitives::bitcoin::BitcoinMusigNonce>
+	for argon_primitives::bitcoin::BitcoinMusigNonce
+{
+	fn from(value: runtime_types::argon_primitives::bitcoin::BitcoinMusigNonce) -> Self {
+		Self(value.0)
+	}
+}
+
+impl From<argon_primitives::bitcoin::BitcoinMusigNonce>
+	for runtime_types::argon_primitives::bitcoin::BitcoinMusigNonce
+{
+	fn from(value: argon_primitives::bitcoin::BitcoinMusigNonce) -> Self {
+		Self(value.0)
+	}
+}
+
 impl From<argon_primitives::bitcoin::BitcoinNetwork>
 	for runtime_types::argon_primitives::bitcoin::BitcoinNetwork
 {
@@ -119,6 +151,45 @@ impl From<argon_primitives::bitcoin::CompressedBitcoinPubkey>
 	}
 }
 
+impl From<runtime_types::argon_primitives::bitcoin::BitcoinXPub>
+	for argon_primitives::bitcoin::BitcoinXPub
+{
+	fn from(value: runtime_types::argon_primitives::bitcoin::BitcoinXPub) -> Self {
+		Self {
+			public_key: value.public_key.into(),
+			depth: value.depth,
+			parent_fingerprint: value.parent_fingerprint,
+			child_number: value.child_number,
+			chain_code: value.chain_code,
+			network: match value.network {
+				runtime_types::argon_primitives::bitcoin::NetworkKind::Main =>
+					argon_primitives::bitcoin::NetworkKind::Main,
+				runtime_types::argon_primitives::bitcoin::NetworkKind::Test =>
+					argon_primitives::bitcoin::NetworkKind::Test,
+			},
+		}
+	}
+}
+
+impl From<runtime_types::argon_primitives::bitcoin::VaultMultisigPubkeys>
+	for argon_primitives::bitcoin::VaultMultisigPubkeys
+{
+	fn from(value: runtime_types::argon_primitives::bitcoin::VaultMultisigPubkeys) -> Self {
+		let into_pubkeys = |a: runtime_types::bounded_collections::bounded_vec::BoundedVec<
+			runtime_types::argon_primitives::bitcoin::CompressedBitcoinPubkey,
+		>| {
+			sp_core::bounded_vec::BoundedVec::truncate_from(
+				a.0.into_iter().map(Into::into).collect::<Vec<_>>(),
+			)
+		};
+		Self {
+			threshold: value.threshold,
+			cosign_pubkeys: into_pubkeys(value.cosign_pubkeys),
+			claim_pubkeys: into_pubkeys(value.claim_pubkeys),
+		}
+	}
+}
+
 impl TryFrom<runtime_types::argon_primitives::bitcoin::BitcoinSignature>
 	for argon_primitives::bitcoin::BitcoinSignature
 {
@@ -180,6 +251,23 @@ impl SubxtRuntime for SubstrateHeader<BlockNumber, BlakeTwo256> {
 	}
 }
 
+// ----- bond -----
+impl From<runtime_types::argon_primitives::bond::BondQuote<Balance>>
+	for argon_primitives::bond::BondQuote<Balance>
+{
+	fn from(value: runtime_types::argon_primitives::bond::BondQuote<Balance>) -> Self {
+		Self {
+			amount: value.amount,
+			base_fee: value.base_fee,
+			prorated_fee: value.prorated_fee,
+			total_fee: value.total_fee,
+			redemption_price: value.redemption_price,
+			vault_claim_height: value.vault_claim_height,
+			open_claim_height: value.open_claim_height,
+		}
+	}
+}
+
 impl From<[u8; 78]> for runtime_types::argon_primitives::bitcoin::OpaqueBitcoinXpub {
 	fn from(value: [u8; 78]) -> Self {
 		Self(value)
@@ -31,7 +31,8 @@ use tokio::{
 use tracing::{log::debug, warn};
 
 use argon_primitives::{
-	tick::Tick, AccountId, BlockNumber, Chain, ChainIdentity, Nonce, VotingSchedule,
+	bitcoin::Satoshis, bond::BondQuote, tick::Tick, AccountId, Balance, BlockNumber, Chain,
+	ChainIdentity, Nonce, VaultId, VotingSchedule,
 };
 pub use spec::api;
 
@@ -362,6 +363,46 @@ impl MainchainClient {
 		Ok(ticker_data.into())
 	}
 
+	/// Quotes what bonding the satoshis with a vault would cost right now. Fails with the reason
+	/// `bond_bitcoin` would be rejected.
+	pub async fn quote_bitcoin_bond(
+		&self,
+		vault_id: VaultId,
+		satoshis: Satoshis,
+		at: Option<H256>,
+	) -> anyhow::Result<BondQuote<Balance>> {
+		let quote = self
+			.call(
+				api::runtime_apis::vault_apis::VaultApis.quote_bitcoin_bond(vault_id, satoshis),
+				at,
+			)
+			.await?;
+		quote
+			.map(Into::into)
+			.map_err(|e| anyhow!("Unable to bond with vault {vault_id}: {e:?}"))
+	}
+
+	/// Quotes what a mining bond of the given argons would cost with a vault for a number of
+	/// blocks
+	pub async fn quote_mining_bond(
+		&self,
+		vault_id: VaultId,
+		amount: Balance,
+		blocks: BlockNumber,
+		at: Option<H256>,
+	) -> anyhow::Result<BondQuote<Balance>> {
+		let quote = self
+			.call(
+				api::runtime_apis::vault_apis::VaultApis
+					.quote_mining_bond(vault_id, amount, blocks),
+				at,
+			)
+			.await?;
+		quote
+			.map(Into::into)
+			.map_err(|e| anyhow!("Unable to bond with vault {vault_id}: {e:?}"))
+	}
+
 	/// Get the system chain and genesis hash.
 	pub async fn get_chain_identity(&self) -> Result<ChainIdentity, Error> {
 		let chain: Chain = self.methods.system_chain().await?.try_into()?;
@@ -482,6 +482,7 @@ async fn confirm_bond(
 				vault_claim_height: utxo_api.vault_claim_height,
 				open_claim_height: utxo_api.open_claim_height,
 				created_at_height: utxo_api.created_at_height,
+				vault_multisig: None,
 			},
 			bitcoin_network,
 		)
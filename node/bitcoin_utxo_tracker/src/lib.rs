@@ -3,21 +3,30 @@
 mod metrics;
 
 use anyhow::ensure;
-use argon_bitcoin::{BlockFilter, UtxoSpendFilter};
+use argon_bitcoin::{BitcoinBackend, BlockFilter, UtxoSpendFilter};
 use argon_primitives::{
-	bitcoin::{BitcoinSyncStatus, UtxoRef, UtxoValue},
+	bitcoin::{
+		BitcoinBlock, BitcoinBlockHash, BitcoinHeight, BitcoinSyncStatus, H256Le,
+		OpaqueBitcoinHeader, UtxoRef, UtxoValue,
+	},
 	inherents::BitcoinUtxoSync,
 	Balance, BitcoinApis,
 };
 use codec::{Decode, Encode};
-use log::info;
+use log::{info, warn};
 pub use metrics::BitcoinMetrics;
 use parking_lot::Mutex;
 use prometheus_endpoint::Registry;
 use sc_client_api::{backend::AuxStore, HeaderBackend};
-use sp_api::ProvideRuntimeApi;
+use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_runtime::traits::Block as BlockT;
-use std::{sync::Arc, time::Instant};
+use std::{collections::BTreeSet, sync::Arc, time::Instant};
+
+const FILTER_HASHES_KEY: &[u8] = b"bitcoin_utxo_tracker_filter_hashes";
+const FILTER_KEY_PREFIX: &[u8] = b"bitcoin_utxo_tracker_filter:";
+const LEGACY_FILTERS_KEY: &[u8] = b"bitcoin_utxo_tracker_filters";
+/// The most bitcoin headers to add to the runtime header chain in a single inherent
+const MAX_HEADERS_PER_SYNC: u32 = 100;
 
 pub fn get_bitcoin_inherent<C, B>(
 	tracker: &Arc<UtxoTracker>,
@@ -41,7 +50,25 @@ where
 	for (_, utxo) in &utxos {
 		satoshis += utxo.satoshis;
 	}
-	let result = tracker.sync(sync_status, utxos, client)?;
+	let confirmed_height = sync_status.confirmed_block.block_height;
+	let mut result = tracker.sync(sync_status, utxos, client)?;
+	// runtimes before version 2 of the api don't track bitcoin headers
+	let has_header_chain =
+		api.has_api_with::<dyn BitcoinApis<B, Balance>, _>(*block_hash, |v| v >= 2)?;
+	let header_tip = if has_header_chain { api.bitcoin_header_tip(*block_hash)? } else { None };
+	if let Some(header_tip) = header_tip {
+		let headers = tracker.get_headers_to_submit(
+			&header_tip,
+			|block_height| Ok(api.bitcoin_header_hash(*block_hash, block_height)?),
+			confirmed_height,
+		);
+		match headers {
+			Ok(headers) => result.headers = headers,
+			// utxos can still be synched against the headers the runtime already has
+			Err(e) =>
+				warn!(target: "node::bitcoin_utxo_tracker", "Unable to load bitcoin headers for the runtime: {:?}", e),
+		}
+	}
 	if let Some(ref metrics) = tracker.metrics {
 		metrics.track(&result, satoshis, utxo_count, start_time);
 	}
@@ -64,6 +91,12 @@ impl UtxoTracker {
 		Ok(Self { filter: Arc::new(Mutex::new(filter)), metrics })
 	}
 
+	pub fn with_backend(backend: Box<dyn BitcoinBackend>, registry: Option<&Registry>) -> Self {
+		let filter = UtxoSpendFilter::with_backend(backend);
+		let metrics = registry.and_then(|a| BitcoinMetrics::new(a).ok());
+		Self { filter: Arc::new(Mutex::new(filter)), metrics }
+	}
+
 	pub fn ensure_correct_network<B, C>(&self, client: &Arc<C>) -> anyhow::Result<()>
 	where
 		B: BlockT,
@@ -90,25 +123,92 @@ impl UtxoTracker {
 		aux_store: &Arc<impl AuxStore>,
 	) -> anyhow::Result<()> {
 		let filter = self.filter.lock();
-		const UTXO_KEY: &[u8; 28] = b"bitcoin_utxo_tracker_filters";
 
-		{
-			let synched_filters = filter.get_stored_filters();
-			if synched_filters.is_empty() {
-				if let Ok(Some(bytes)) = aux_store.get_aux(&UTXO_KEY[..]) {
-					let synched_filters =
-						<Vec<BlockFilter>>::decode(&mut &bytes[..]).ok().unwrap_or_default();
-					filter.load_filters(synched_filters);
-				}
-			}
+		let mut previous_filters = filter.get_stored_filters();
+		if previous_filters.is_empty() {
+			previous_filters = Self::load_stored_filters(aux_store);
+			filter.load_filters(previous_filters.clone());
 		}
 		filter.sync_to_block(sync_status)?;
 
-		let encoded = filter.get_stored_filters().encode();
-		aux_store.insert_aux(&[(&UTXO_KEY[..], encoded.as_slice())], &[])?;
+		Self::store_filters(aux_store, &previous_filters, &filter.get_stored_filters())?;
+		Ok(())
+	}
+
+	fn filter_key(block_hash: &H256Le) -> Vec<u8> {
+		[FILTER_KEY_PREFIX, &block_hash.0[..]].concat()
+	}
+
+	/// Loads the filters persisted by a previous run so a restart doesn't need to re-download
+	/// them. Filters that no longer link together are cleaned up by the next sync.
+	fn load_stored_filters(aux_store: &Arc<impl AuxStore>) -> Vec<BlockFilter> {
+		let Ok(Some(bytes)) = aux_store.get_aux(FILTER_HASHES_KEY) else {
+			// fall back to the filters stored by older versions in a single entry
+			return aux_store
+				.get_aux(LEGACY_FILTERS_KEY)
+				.ok()
+				.flatten()
+				.and_then(|bytes| <Vec<BlockFilter>>::decode(&mut &bytes[..]).ok())
+				.unwrap_or_default();
+		};
+		let block_hashes = <Vec<H256Le>>::decode(&mut &bytes[..]).ok().unwrap_or_default();
+		block_hashes
+			.iter()
+			.filter_map(|hash| {
+				let bytes = aux_store.get_aux(&Self::filter_key(hash)).ok()??;
+				BlockFilter::decode(&mut &bytes[..]).ok()
+			})
+			.collect()
+	}
+
+	/// Writes new filters individually and deletes the ones that were pruned, so each sync only
+	/// touches the blocks that changed.
+	fn store_filters(
+		aux_store: &Arc<impl AuxStore>,
+		previous_filters: &[BlockFilter],
+		filters: &[BlockFilter],
+	) -> anyhow::Result<()> {
+		let previous_hashes =
+			previous_filters.iter().map(|a| &a.block_hash).collect::<BTreeSet<_>>();
+		let hashes = filters.iter().map(|a| &a.block_hash).collect::<BTreeSet<_>>();
+
+		let mut inserts = filters
+			.iter()
+			.filter(|a| !previous_hashes.contains(&a.block_hash))
+			.map(|a| (Self::filter_key(&a.block_hash), a.encode()))
+			.collect::<Vec<_>>();
+		inserts.push((
+			FILTER_HASHES_KEY.to_vec(),
+			filters.iter().map(|a| a.block_hash.clone()).collect::<Vec<_>>().encode(),
+		));
+		let mut deletes = previous_filters
+			.iter()
+			.filter(|a| !hashes.contains(&a.block_hash))
+			.map(|a| Self::filter_key(&a.block_hash))
+			.collect::<Vec<_>>();
+		deletes.push(LEGACY_FILTERS_KEY.to_vec());
+
+		let inserts = inserts.iter().map(|(k, v)| (&k[..], &v[..])).collect::<Vec<_>>();
+		let deletes = deletes.iter().map(|k| &k[..]).collect::<Vec<_>>();
+		aux_store.insert_aux(&inserts, &deletes)?;
 		Ok(())
 	}
 
+	/// Headers that extend the runtime's bitcoin header chain toward the confirmed block
+	pub fn get_headers_to_submit(
+		&self,
+		header_tip: &BitcoinBlock,
+		runtime_header_hash: impl Fn(BitcoinHeight) -> anyhow::Result<Option<BitcoinBlockHash>>,
+		confirmed_height: BitcoinHeight,
+	) -> anyhow::Result<Vec<OpaqueBitcoinHeader>> {
+		self.filter.lock().get_headers_to_submit(
+			header_tip,
+			runtime_header_hash,
+			confirmed_height,
+			MAX_HEADERS_PER_SYNC,
+		)
+	}
+
 	/// Synchronize with the latest blocks on the network.
 	pub fn sync(
 		&self,
@@ -124,18 +224,28 @@ impl UtxoTracker {
 
 #[cfg(test)]
 mod test {
-	use std::{collections::BTreeMap, sync::Arc};
+	use std::{
+		collections::BTreeMap,
+		sync::{
+			atomic::{AtomicUsize, Ordering},
+			Arc,
+		},
+	};
 
-	use bitcoin::{hashes::Hash, Address, Amount, CompressedPublicKey, Network};
+	use bitcoin::{
+		block::Header, hashes::Hash, Address, Amount, BlockHash, CompressedPublicKey, Network,
+		ScriptBuf, Transaction, Txid,
+	};
 	use bitcoincore_rpc::RpcApi;
 	use bitcoind::BitcoinD;
 	use lazy_static::lazy_static;
 	use parking_lot::Mutex;
 	use sc_client_api::backend::AuxStore;
 
-	use argon_bitcoin::{CosignScript, CosignScriptArgs};
+	use argon_bitcoin::{BlockHeaderInfo, CosignScript, CosignScriptArgs};
 	use argon_primitives::bitcoin::{
-		BitcoinBlock, BitcoinRejectedReason, BitcoinSyncStatus, H256Le, UtxoRef, UtxoValue,
+		BitcoinBlock, BitcoinNetwork, BitcoinRejectedReason, BitcoinSyncStatus, H256Le, UtxoRef,
+		UtxoValue,
 	};
 	use argon_testing::{add_blocks, add_wallet_address, fund_script_address};
 
@@ -164,6 +274,7 @@ mod test {
 				vault_claim_height: block_height + 100,
 				open_claim_height: block_height + 200,
 				created_at_height: block_height,
+				vault_multisig: None,
 			},
 			network,
 		)
@@ -227,6 +338,95 @@ mod test {
 		drop(bitcoind);
 	}
 
+	#[test]
+	fn can_resume_from_stored_filters() {
+		let aux = Arc::new(TestAuxStore::new());
+		let sync_status = |block_height: u64| BitcoinSyncStatus {
+			confirmed_block: BitcoinBlock {
+				block_hash: H256Le([block_height as u8; 32]),
+				block_height,
+			},
+			synched_block: None,
+			oldest_allowed_block_height: block_height - 5,
+		};
+
+		let (tracker, header_requests) = mock_tracker();
+		tracker.update_filters(&sync_status(10), &aux).unwrap();
+		assert_eq!(header_requests.load(Ordering::Relaxed), 6);
+		let filters = tracker.filter.lock().get_stored_filters();
+
+		// a restarted node shouldn't need to download anything
+		let (tracker, header_requests) = mock_tracker();
+		tracker.update_filters(&sync_status(10), &aux).unwrap();
+		assert_eq!(header_requests.load(Ordering::Relaxed), 0);
+		assert_eq!(tracker.filter.lock().get_stored_filters(), filters);
+
+		tracker.update_filters(&sync_status(11), &aux).unwrap();
+		assert_eq!(header_requests.load(Ordering::Relaxed), 1);
+		let stored = aux.aux.lock();
+		assert!(!stored.contains_key(&UtxoTracker::filter_key(&H256Le([5; 32]))));
+		for i in 6..=11 {
+			assert!(stored.contains_key(&UtxoTracker::filter_key(&H256Le([i; 32]))));
+		}
+		assert!(!stored.contains_key(&LEGACY_FILTERS_KEY[..]));
+	}
+
+	struct MockBackend {
+		header_requests: Arc<AtomicUsize>,
+	}
+
+	impl BitcoinBackend for MockBackend {
+		fn get_network(&self) -> anyhow::Result<BitcoinNetwork> {
+			Ok(BitcoinNetwork::Regtest)
+		}
+
+		fn get_block_header(
+			&self,
+			block_hash: &BlockHash,
+			_block_height: u64,
+		) -> anyhow::Result<BlockHeaderInfo> {
+			self.header_requests.fetch_add(1, Ordering::Relaxed);
+			let block_height = block_hash.to_byte_array()[0];
+			Ok(BlockHeaderInfo {
+				block_height: block_height as u64,
+				previous_block_hash: Some(BlockHash::from_byte_array([block_height - 1; 32])),
+			})
+		}
+
+		fn get_block_filter(&self, _block_hash: &BlockHash) -> anyhow::Result<Option<Vec<u8>>> {
+			// an empty bip158 filter
+			Ok(Some(vec![0]))
+		}
+
+		fn get_block_transactions(
+			&self,
+			_block_hash: &BlockHash,
+			_block_height: u64,
+			_scripts: &[ScriptBuf],
+		) -> anyhow::Result<Vec<Transaction>> {
+			Ok(vec![])
+		}
+
+		fn get_header_by_height(&self, _block_height: u64) -> anyhow::Result<Header> {
+			anyhow::bail!("Headers are not tracked by the mock backend")
+		}
+
+		fn get_merkle_branch(
+			&self,
+			_block_hash: &BlockHash,
+			_block_height: u64,
+			_txid: &Txid,
+		) -> anyhow::Result<(u32, Vec<H256Le>)> {
+			Ok((0, vec![]))
+		}
+	}
+
+	fn mock_tracker() -> (UtxoTracker, Arc<AtomicUsize>) {
+		let header_requests = Arc::new(AtomicUsize::new(0));
+		let backend = MockBackend { header_requests: header_requests.clone() };
+		(UtxoTracker::with_backend(Box::new(backend), None), header_requests)
+	}
+
 	lazy_static! {
 		static ref BITCOIND_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
 	}
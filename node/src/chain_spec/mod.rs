@@ -95,10 +95,13 @@ pub(crate) fn testnet_genesis(
 	let config = RuntimeGenesisConfig {
 		balances: BalancesConfig { balances: endowed_accounts },
 		bonds: BondsConfig { minimum_bitcoin_bond_satoshis, ..Default::default() },
-		price_index: PriceIndexConfig { operator: Some(price_index_operator) },
+		price_index: PriceIndexConfig { operators: vec![price_index_operator], quorum: None },
 		bitcoin_utxos: BitcoinUtxosConfig {
-			tip_oracle_operator: Some(bitcoin_tip_operator),
+			tip_oracle_operators: vec![bitcoin_tip_operator],
+			tip_oracle_confirmation_threshold: None,
 			network: bitcoin_network,
+			// start the header chain at the genesis block of the network
+			bitcoin_header_checkpoint: None,
 		},
 		mining_slot: MiningSlotPalletConfig { mining_config, ..Default::default() },
 		sudo: SudoConfig { key: Some(sudo_key) },
@@ -14,6 +14,13 @@ pub struct Cli {
 	/// full node. Include optional auth inline
 	#[arg(long, global = true)]
 	pub bitcoin_rpc_url: Option<String>,
+
+	/// The type of service at the bitcoin rpc url.
+	/// - bitcoind: a full node with block filters enabled (`-blockfilterindex`)
+	/// - esplora: an esplora rest api (eg, https://blockstream.info/api)
+	/// - electrum: an electrum server (eg, ssl://electrum.blockstream.info:50002)
+	#[arg(long, global = true, value_enum, default_value_t = BitcoinBackendKind::Bitcoind, verbatim_doc_comment)]
+	pub bitcoin_backend: BitcoinBackendKind,
 }
 
 #[derive(Debug, Clone, Parser)]
@@ -54,6 +61,13 @@ pub enum RandomxFlag {
 	Secure,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum BitcoinBackendKind {
+	Bitcoind,
+	Esplora,
+	Electrum,
+}
+
 #[derive(Debug, clap::Subcommand)]
 #[allow(clippy::large_enum_variant)]
 pub enum Subcommand {
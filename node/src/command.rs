@@ -1,6 +1,6 @@
 use crate::{
 	chain_spec,
-	cli::{Cli, RandomxFlag, Subcommand},
+	cli::{BitcoinBackendKind, Cli, RandomxFlag, Subcommand},
 	runtime_api::opaque::Block,
 	service,
 	service::new_partial,
@@ -236,6 +236,7 @@ pub struct MiningConfig {
 	compute_threads: Option<u32>,
 	pub compute_author: Option<AccountId32>,
 	bitcoin_rpc_url: Option<String>,
+	pub bitcoin_backend: BitcoinBackendKind,
 	pub notebook_archive_hosts: Vec<String>,
 }
 
@@ -265,6 +266,7 @@ impl MiningConfig {
 			compute_threads,
 			compute_author,
 			bitcoin_rpc_url,
+			bitcoin_backend: cli.bitcoin_backend,
 			notebook_archive_hosts: cli.run.notebook_archive_hosts.clone(),
 		}
 	}
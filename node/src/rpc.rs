@@ -7,8 +7,12 @@
 
 use std::sync::Arc;
 
+pub mod vaults;
+
 use crate::runtime_api::opaque::{Block, Hash};
-use argon_primitives::{AccountId, Balance, BlockNumber, BlockSealAuthorityId, MiningApis, Nonce};
+use argon_primitives::{
+	AccountId, Balance, BlockNumber, BlockSealAuthorityId, MiningApis, Nonce, VaultApis,
+};
 use jsonrpsee::RpcModule;
 use pallet_ismp_runtime_api::IsmpRuntimeApi;
 use sc_client_api::{AuxStore, BlockBackend, ProofProvider};
@@ -62,6 +66,7 @@ where
 	C::Api: BlockBuilder<Block>,
 	C::Api: MiningApis<Block, AccountId, BlockSealAuthorityId>,
 	C::Api: IsmpRuntimeApi<Block, H256>,
+	C::Api: VaultApis<Block, Balance>,
 	P: TransactionPool + 'static,
 	B: sc_client_api::Backend<Block> + Send + Sync + 'static,
 	B::State: sc_client_api::StateBackend<sp_runtime::traits::HashingFor<Block>>,
@@ -70,6 +75,7 @@ where
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApiServer};
 	use sc_consensus_grandpa_rpc::{Grandpa, GrandpaApiServer};
 	use substrate_frame_rpc_system::{System, SystemApiServer};
+	use vaults::{Vaults, VaultsApiServer};
 
 	let mut module = RpcModule::new(());
 	let FullDeps { client, pool, backend, grandpa } = deps;
@@ -83,6 +89,7 @@ where
 
 	module.merge(System::new(client.clone(), pool).into_rpc())?;
 	module.merge(TransactionPayment::new(client.clone()).into_rpc())?;
+	module.merge(Vaults::new(client.clone()).into_rpc())?;
 	module.merge(IsmpRpcHandler::new(client, backend)?.into_rpc())?;
 
 	module.merge(
@@ -0,0 +1,134 @@
+//! Rpc methods to look into the vaults of the runtime
+
+use std::{marker::PhantomData, sync::Arc};
+
+use argon_primitives::{
+	bitcoin::Satoshis,
+	bond::{BondError, BondQuote, VaultReputation},
+	Balance, BlockNumber, VaultApis, VaultId,
+};
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::{error::ErrorObject, ErrorObjectOwned},
+};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::Block as BlockT;
+
+/// Vault rpc methods
+#[rpc(server, namespace = "vaults")]
+pub trait VaultsApi<BlockHash> {
+	/// Get the cosign performance, losses and bonded volume of a vault
+	#[method(name = "getReputation")]
+	fn reputation(
+		&self,
+		vault_id: VaultId,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<VaultReputation<Balance>>>;
+
+	/// Quote the fees, redemption price and claim heights of bonding the satoshis with a vault.
+	/// Fails with the error `bond_bitcoin` would return.
+	#[method(name = "quoteBitcoinBond")]
+	fn quote_bitcoin_bond(
+		&self,
+		vault_id: VaultId,
+		satoshis: Satoshis,
+		at: Option<BlockHash>,
+	) -> RpcResult<BondQuote<Balance>>;
+
+	/// Quote the fees of a mining bond of the given argons with a vault for a number of blocks
+	#[method(name = "quoteMiningBond")]
+	fn quote_mining_bond(
+		&self,
+		vault_id: VaultId,
+		amount: Balance,
+		blocks: BlockNumber,
+		at: Option<BlockHash>,
+	) -> RpcResult<BondQuote<Balance>>;
+}
+
+/// Provides rpc methods to query the vaults of the runtime
+pub struct Vaults<C, Block> {
+	client: Arc<C>,
+	_marker: PhantomData<Block>,
+}
+
+impl<C, Block> Vaults<C, Block> {
+	/// Creates a new instance of the vaults rpc
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+impl<C, Block> VaultsApiServer<<Block as BlockT>::Hash> for Vaults<C, Block>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: VaultApis<Block, Balance>,
+{
+	fn reputation(
+		&self,
+		vault_id: VaultId,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Option<VaultReputation<Balance>>> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		self.client
+			.runtime_api()
+			.vault_reputation(at, vault_id)
+			.map_err(|e| runtime_error("Unable to query the vault reputation.", e))
+	}
+
+	fn quote_bitcoin_bond(
+		&self,
+		vault_id: VaultId,
+		satoshis: Satoshis,
+		at: Option<Block::Hash>,
+	) -> RpcResult<BondQuote<Balance>> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		let quote = self
+			.client
+			.runtime_api()
+			.quote_bitcoin_bond(at, vault_id, satoshis)
+			.map_err(|e| runtime_error("Unable to quote the bitcoin bond.", e))?;
+		quote.map_err(bond_error)
+	}
+
+	fn quote_mining_bond(
+		&self,
+		vault_id: VaultId,
+		amount: Balance,
+		blocks: BlockNumber,
+		at: Option<Block::Hash>,
+	) -> RpcResult<BondQuote<Balance>> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		let quote = self
+			.client
+			.runtime_api()
+			.quote_mining_bond(at, vault_id, amount, blocks)
+			.map_err(|e| runtime_error("Unable to quote the mining bond.", e))?;
+		quote.map_err(bond_error)
+	}
+}
+
+fn runtime_error(message: &str, e: sp_api::ApiError) -> ErrorObjectOwned {
+	ErrorObject::owned(ErrorCode::RuntimeError.into(), message, Some(e.to_string()))
+}
+
+fn bond_error(e: BondError) -> ErrorObjectOwned {
+	ErrorObject::owned(ErrorCode::BondError.into(), format!("{:?}", e), None::<()>)
+}
+
+/// Error codes of the vaults rpc
+pub enum ErrorCode {
+	/// The call to the runtime failed
+	RuntimeError = 1,
+	/// The bond could not be created (the message is the `BondError`)
+	BondError = 2,
+}
+
+impl From<ErrorCode> for i32 {
+	fn from(e: ErrorCode) -> i32 {
+		e as i32
+	}
+}
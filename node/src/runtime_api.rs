@@ -1,6 +1,6 @@
 use argon_primitives::{
 	AccountId, Balance, BitcoinApis, BlockCreatorApis, BlockSealApis, BlockSealAuthorityId,
-	MiningApis, Nonce, NotaryApis, NotebookApis, TickApis,
+	MiningApis, Nonce, NotaryApis, NotebookApis, TickApis, VaultApis,
 };
 use argon_runtime::{NotaryRecordT, NotebookVerifyError};
 use pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi;
@@ -47,6 +47,7 @@ pub trait BaseHostRuntimeApis:
 	+ BlockSealApis<opaque::Block, AccountId, BlockSealAuthorityId>
 	+ BlockCreatorApis<opaque::Block, AccountId, NotebookVerifyError>
 	+ BitcoinApis<opaque::Block, Balance>
+	+ VaultApis<opaque::Block, Balance>
 	+ NotaryApis<opaque::Block, NotaryRecordT>
 	+ MiningApis<opaque::Block, AccountId, BlockSealAuthorityId>
 {
@@ -67,6 +68,7 @@ impl<Api> BaseHostRuntimeApis for Api where
 		+ BlockSealApis<opaque::Block, AccountId, BlockSealAuthorityId>
 		+ BlockCreatorApis<opaque::Block, AccountId, NotebookVerifyError>
 		+ BitcoinApis<opaque::Block, Balance>
+		+ VaultApis<opaque::Block, Balance>
 		+ NotaryApis<opaque::Block, NotaryRecordT>
 		+ MiningApis<opaque::Block, AccountId, BlockSealAuthorityId>
 {
@@ -1,10 +1,12 @@
 //! Service and ServiceFactory implementation. Specialized wrapper over substrate service.
 use crate::{
+	cli::BitcoinBackendKind,
 	command::MiningConfig,
 	rpc,
 	rpc::GrandpaDeps,
 	runtime_api::{opaque::Block, BaseHostRuntimeApis},
 };
+use argon_bitcoin::{BitcoinBackend, BitcoindBackend, ElectrumBackend, EsploraBackend};
 use argon_bitcoin_utxo_tracker::UtxoTracker;
 use argon_node_consensus::{
 	aux_client::ArgonAux, create_import_queue, run_block_builder_task, run_notary_sync,
@@ -114,12 +116,17 @@ where
 	let (bitcoin_url, bitcoin_auth) = mining_config
 		.bitcoin_rpc_url_with_auth()
 		.map_err(|e| ServiceError::Other(format!("Failed to parse bitcoin rpc url {:?}", e)))?;
-	let utxo_tracker = UtxoTracker::new(
-		bitcoin_url.origin().unicode_serialization(),
-		bitcoin_auth,
-		config.prometheus_registry(),
-	)
-	.map_err(|e| ServiceError::Other(format!("Failed to initialize bitcoin monitoring {:?}", e)))?;
+	let bitcoin_backend: Box<dyn BitcoinBackend> = match mining_config.bitcoin_backend {
+		BitcoinBackendKind::Bitcoind =>
+			BitcoindBackend::new(&bitcoin_url.origin().unicode_serialization(), bitcoin_auth)
+				.map(|a| Box::new(a) as _),
+		BitcoinBackendKind::Esplora =>
+			EsploraBackend::new(bitcoin_url.as_str()).map(|a| Box::new(a) as _),
+		BitcoinBackendKind::Electrum =>
+			ElectrumBackend::new(bitcoin_url.as_str()).map(|a| Box::new(a) as _),
+	}
+	.map_err(|e| ServiceError::Other(format!("Failed to connect to bitcoin {:?}", e)))?;
+	let utxo_tracker = UtxoTracker::with_backend(bitcoin_backend, config.prometheus_registry());
 
 	let utxo_tracker = Arc::new(utxo_tracker);
 
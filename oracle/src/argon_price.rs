@@ -1,4 +1,7 @@
-use crate::uniswap_oracle::{UniswapOracle, USDC_ADDRESS, USDC_ADDRESS_SEPOLIA};
+use crate::{
+	price_config::ArgonPoolConfig,
+	uniswap_oracle::{UniswapOracle, USDC_ADDRESS, USDC_ADDRESS_SEPOLIA},
+};
 use anyhow::Result;
 use argon_client::api::runtime_types::pallet_price_index::PriceIndex;
 use argon_primitives::tick::{Tick, Ticker};
@@ -43,12 +46,22 @@ impl ArgonPriceLookup {
 		})
 	}
 
-	pub async fn from_env(ticker: &Ticker, last_price: Option<PriceIndex>) -> Result<Self> {
-		let use_sepolia = env::var("USE_SEPOLIA").unwrap_or_default() == "true";
-		let argon_token_address =
-			env::var("ARGON_TOKEN_ADDRESS").expect("ARGON_TOKEN_ADDRESS must be set");
+	/// Looks up the uniswap pool from the config, falling back to env vars for any missing values
+	pub async fn from_config(
+		ticker: &Ticker,
+		last_price: Option<PriceIndex>,
+		config: &ArgonPoolConfig,
+	) -> Result<Self> {
+		let use_sepolia = config
+			.use_sepolia
+			.unwrap_or_else(|| env::var("USE_SEPOLIA").unwrap_or_default() == "true");
+		let argon_token_address = config.argon_token_address.clone().unwrap_or_else(|| {
+			env::var("ARGON_TOKEN_ADDRESS").expect("ARGON_TOKEN_ADDRESS must be set")
+		});
 		let network = if use_sepolia { ChainId::SEPOLIA } else { ChainId::MAINNET };
-		let project_id = env::var("INFURA_PROJECT_ID").expect("INFURA_PROJECT_ID must be set");
+		let project_id = config.infura_project_id.clone().unwrap_or_else(|| {
+			env::var("INFURA_PROJECT_ID").expect("INFURA_PROJECT_ID must be set")
+		});
 
 		let usdc_token = get_usdc_token(network);
 		let lookup_token = token!(network as u64, argon_token_address, 18, "ARGON", "Argon");
@@ -231,7 +244,8 @@ mod test {
 	async fn test_get_target_price() {
 		before_each();
 		let ticker = Ticker::start(Duration::from_secs(60), 2);
-		let argon_price_lookup = ArgonPriceLookup::from_env(&ticker, None).await.unwrap();
+		let argon_price_lookup =
+			ArgonPriceLookup::from_config(&ticker, None, &Default::default()).await.unwrap();
 		let us_cpi_ratio = FixedI128::from_float(0.00);
 		assert_eq!(argon_price_lookup.get_target_price(us_cpi_ratio), FixedU128::from_u32(1));
 	}
@@ -240,7 +254,8 @@ mod test {
 	async fn test_get_target_price_with_cpi() {
 		before_each();
 		let ticker = Ticker::start(Duration::from_secs(60), 2);
-		let argon_price_lookup = ArgonPriceLookup::from_env(&ticker, None).await.unwrap();
+		let argon_price_lookup =
+			ArgonPriceLookup::from_config(&ticker, None, &Default::default()).await.unwrap();
 		let us_cpi_ratio = FixedI128::from_float(0.1);
 		assert_eq!(argon_price_lookup.get_target_price(us_cpi_ratio).to_float(), 1.1);
 	}
@@ -250,7 +265,8 @@ mod test {
 	async fn can_use_simulated_schedule() {
 		before_each();
 		let ticker = Ticker::start(Duration::from_secs(60), 2);
-		let mut argon_price_lookup = ArgonPriceLookup::from_env(&ticker, None).await.unwrap();
+		let mut argon_price_lookup =
+			ArgonPriceLookup::from_config(&ticker, None, &Default::default()).await.unwrap();
 
 		argon_price_lookup.last_price = FixedU128::from_float(1.01);
 		argon_price_lookup.last_price_tick = ticker.current();
@@ -263,7 +279,8 @@ mod test {
 	async fn adjusts_price_by_usdc_price() {
 		before_each();
 		let ticker = Ticker::start(Duration::from_secs(60), 2);
-		let mut argon_price_lookup = ArgonPriceLookup::from_env(&ticker, None).await.unwrap();
+		let mut argon_price_lookup =
+			ArgonPriceLookup::from_config(&ticker, None, &Default::default()).await.unwrap();
 
 		let argon_usdc_price = FixedU128::from_float(0.99);
 		use_mock_argon_prices(vec![argon_usdc_price]);
@@ -1,16 +1,18 @@
-use crate::utils::parse_f64;
+use crate::{
+	price_config::{OutlierRejection, PriceSourcesConfig},
+	price_sources::PriceSource,
+};
 use anyhow::{anyhow, Result};
+use futures::future::join_all;
 use reqwest::Client;
-use serde::Deserialize;
-use sp_runtime::{
-	traits::{One, Zero},
-	FixedU128,
-};
-use std::collections::HashMap;
-use tokio::{join, time::Instant};
+use sp_runtime::FixedU128;
+use tokio::time::Instant;
+use tracing::warn;
 
 pub struct CoinUsdPriceLookup {
 	pub client: Client,
+	pub sources: Vec<(Box<dyn PriceSource>, FixedU128)>,
+	pub outlier_rejection: OutlierRejection,
 	pub last_refresh: Option<(Instant, PriceLookups)>,
 }
 
@@ -24,8 +26,13 @@ lazy_static::lazy_static! {
 }
 
 impl CoinUsdPriceLookup {
-	pub fn new() -> Self {
-		Self { client: Client::new(), last_refresh: None }
+	pub fn new(config: &PriceSourcesConfig) -> Self {
+		Self {
+			client: Client::new(),
+			sources: config.build_sources(),
+			outlier_rejection: config.outlier_rejection.clone(),
+			last_refresh: None,
+		}
 	}
 
 	pub async fn get_latest_prices(&mut self) -> Result<PriceLookups> {
@@ -46,109 +53,39 @@ impl CoinUsdPriceLookup {
 			}
 		}
 
-		let (a, b, c, d) = join![
-			self.get_coinbase_prices(),
-			self.get_coindesk_price(),
-			self.get_kraken_price(),
-			self.get_coingecko_price()
-		];
+		let results =
+			join_all(self.sources.iter().map(|(source, _)| source.get_prices(&self.client))).await;
 
-		let mut usdc_total = FixedU128::zero();
-		let mut usdc_count = FixedU128::zero();
-		let mut btc_total = FixedU128::zero();
-		let mut btc_count = FixedU128::zero();
-		for price in [a, b, c, d] {
+		let mut btc_prices = vec![];
+		let mut usdc_prices = vec![];
+		for ((source, weight), price) in self.sources.iter().zip(results) {
 			match price {
 				Ok(price) => {
 					if let Some(usdc) = price.usdc {
-						usdc_total = usdc_total.add(usdc);
-						usdc_count = usdc_count + FixedU128::one();
+						usdc_prices.push((usdc, *weight));
 					}
-					btc_total = btc_total.add(price.bitcoin);
-					btc_count = btc_count + FixedU128::one();
+					btc_prices.push((price.bitcoin, *weight));
 				},
 				Err(err) => {
-					println!("Failed to get price: {}", err);
+					warn!("Failed to get price from {}: {}", source.name(), err);
 				},
 			}
 		}
 
-		let average_btc = btc_total / btc_count;
-		let average_usdc = usdc_total / usdc_count;
-		let latest = PriceLookups { bitcoin: average_btc, usdc: average_usdc };
+		let latest = PriceLookups {
+			bitcoin: self
+				.outlier_rejection
+				.weighted_price(&btc_prices)
+				.map_err(|e| anyhow!("Unable to get a bitcoin price: {e}"))?,
+			usdc: self
+				.outlier_rejection
+				.weighted_price(&usdc_prices)
+				.map_err(|e| anyhow!("Unable to get a usdc price: {e}"))?,
+		};
 		self.last_refresh = Some((Instant::now(), latest));
 
 		Ok(latest)
 	}
-
-	async fn get_coindesk_price(&self) -> Result<PriceLookupMaybe> {
-		let response = self
-			.client
-			.get("https://api.coindesk.com/v1/bpi/currentprice.json")
-			.send()
-			.await?
-			.json::<CoinDeskResponse>()
-			.await?;
-		Ok(PriceLookupMaybe {
-			bitcoin: FixedU128::from_float(response.bpi.usd.rate_float),
-			usdc: None,
-		})
-	}
-
-	async fn get_kraken_price(&self) -> Result<PriceLookupMaybe> {
-		let response = self
-			.client
-			.get("https://api.kraken.com/0/public/Ticker?pair=XXBTZUSD,USDCUSD")
-			.send()
-			.await?
-			.json::<KrakenResponse>()
-			.await?;
-		let btc = response.result.get("XXBTZUSD").ok_or(anyhow!("No price data"))?.last_trade_cost
-			[0]
-		.parse::<f64>()?;
-		let usdc = response.result.get("USDCUSD").ok_or(anyhow!("No price data"))?.last_trade_cost
-			[0]
-		.parse::<f64>()?;
-
-		Ok(PriceLookupMaybe {
-			bitcoin: FixedU128::from_float(btc),
-			usdc: Some(FixedU128::from_float(usdc)),
-		})
-	}
-	async fn get_coingecko_price(&self) -> Result<PriceLookupMaybe> {
-		let response = self
-			.client
-			.get("https://api.coingecko.com/api/v3/simple/price")
-			.query(&[("ids", "bitcoin,usd-coin"), ("vs_currencies", "usd")])
-			.send()
-			.await?
-			.json::<CoinGeckoResponse>()
-			.await?;
-
-		Ok(PriceLookupMaybe {
-			bitcoin: FixedU128::from_float(response.bitcoin.usd),
-			usdc: Some(FixedU128::from_float(response.usd_coin.usd)),
-		})
-	}
-
-	async fn get_coinbase_prices(&self) -> Result<PriceLookupMaybe> {
-		let (bitcoin, usdc) = join!(
-			self.get_coinbase_price("BTC".to_string()),
-			self.get_coinbase_price("USDC".to_string())
-		);
-		Ok(PriceLookupMaybe { bitcoin: bitcoin?, usdc: usdc.ok() })
-	}
-
-	async fn get_coinbase_price(&self, coin: String) -> Result<FixedU128> {
-		let response = self
-			.client
-			.get(format!("https://api.coinbase.com/v2/prices/{coin}-USD/spot"))
-			.send()
-			.await?
-			.json::<CoinbasePrice>()
-			.await?;
-		Ok(FixedU128::from_float(response.data.amount))
-	}
 }
 
 #[derive(Clone, Copy, Debug, PartialOrd, PartialEq)]
@@ -157,61 +94,6 @@ pub struct PriceLookups {
 	pub usdc: FixedU128,
 }
 
-struct PriceLookupMaybe {
-	bitcoin: FixedU128,
-	usdc: Option<FixedU128>,
-}
-
-#[derive(Deserialize)]
-struct CoinGeckoResponse {
-	bitcoin: CoinGeckoPriceData,
-	#[serde(rename = "usd-coin")]
-	usd_coin: CoinGeckoPriceData,
-}
-
-#[derive(Deserialize)]
-struct CoinGeckoPriceData {
-	usd: f64,
-}
-
-#[derive(Deserialize)]
-struct CoinDeskResponse {
-	bpi: CoindeskBpi,
-}
-
-#[derive(Deserialize)]
-struct CoindeskBpi {
-	#[serde(rename = "USD")]
-	usd: CoindeskBpiData,
-}
-
-#[derive(Deserialize)]
-struct CoindeskBpiData {
-	rate_float: f64,
-}
-#[derive(Deserialize, Debug)]
-pub struct CoinbasePrice {
-	pub data: CoinbasePriceData,
-}
-
-#[derive(Deserialize, Debug)]
-pub struct CoinbasePriceData {
-	// pub base: String,
-	// pub currency: String,
-	#[serde(deserialize_with = "parse_f64")]
-	pub amount: f64,
-}
-
-#[derive(Deserialize)]
-struct KrakenResponse {
-	result: HashMap<String, KrakenResponsePair>,
-}
-
-#[derive(Deserialize)]
-struct KrakenResponsePair {
-	#[serde(rename = "c")]
-	last_trade_cost: [String; 2],
-}
 #[cfg(test)]
 pub(crate) fn use_mock_price_lookups(prices: PriceLookups) {
 	*MOCK_PRICES.lock().unwrap() = Some(prices);
@@ -220,41 +102,51 @@ pub(crate) fn use_mock_price_lookups(prices: PriceLookups) {
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::{price_config::PriceSourceType, price_sources::tests::serve_json};
+	use serde_json::json;
+
 	#[tokio::test]
 	async fn test_get_price_lookups() {
-		let price_lookups = CoinUsdPriceLookup::new().get_latest_prices().await.unwrap();
+		let price_lookups = CoinUsdPriceLookup::new(&PriceSourcesConfig::default())
+			.get_latest_prices()
+			.await
+			.unwrap();
 		assert!(price_lookups.bitcoin > FixedU128::from_float(30_000.0));
 		assert!(price_lookups.usdc > FixedU128::from_float(0.9));
 		assert!(price_lookups.usdc < FixedU128::from_float(1.1));
 	}
 
 	#[tokio::test]
-	async fn test_get_coindesk_price() {
-		let price_lookups = CoinUsdPriceLookup::new().get_coindesk_price().await.unwrap();
-		assert!(price_lookups.bitcoin > FixedU128::from_float(30_000.0));
-	}
-
-	#[tokio::test]
-	async fn test_get_kraken_price() {
-		let price_lookups = CoinUsdPriceLookup::new().get_kraken_price().await.unwrap();
-		assert!(price_lookups.bitcoin > FixedU128::from_float(30_000.0));
-		assert!(price_lookups.usdc.unwrap() > FixedU128::from_float(0.9));
-		assert!(price_lookups.usdc.unwrap() < FixedU128::from_float(1.1));
-	}
-
-	#[tokio::test]
-	async fn test_get_coingecko_price() {
-		let price_lookups = CoinUsdPriceLookup::new().get_coingecko_price().await.unwrap();
-		assert!(price_lookups.bitcoin > FixedU128::from_float(30_000.0));
-		assert!(price_lookups.usdc.unwrap() > FixedU128::from_float(0.9));
-		assert!(price_lookups.usdc.unwrap() < FixedU128::from_float(1.1));
-	}
+	async fn averages_configured_sources() {
+		let mut config = PriceSourcesConfig { sources: vec![], ..Default::default() };
+		for (name, btc, usdc) in [("a", 62_000, "1.0"), ("b", 62_200, "1.0"), ("c", 99_000, "1.0")]
+		{
+			let url = serve_json(json!({ "btc": btc, "usdc": usdc })).await;
+			config.sources.push(
+				PriceSourceType::HttpJson {
+					name: name.to_string(),
+					url,
+					bitcoin_path: "$.btc".to_string(),
+					usdc_url: None,
+					usdc_path: Some("$.usdc".to_string()),
+				}
+				.into(),
+			);
+		}
+		// a source that can't be reached is skipped
+		config.sources.push(
+			PriceSourceType::HttpJson {
+				name: "offline".to_string(),
+				url: "http://127.0.0.1:1".to_string(),
+				bitcoin_path: "$.btc".to_string(),
+				usdc_url: None,
+				usdc_path: None,
+			}
+			.into(),
+		);
 
-	#[tokio::test]
-	async fn test_get_coinbase_price() {
-		let price_lookups = CoinUsdPriceLookup::new().get_coinbase_prices().await.unwrap();
-		assert!(price_lookups.bitcoin > FixedU128::from_float(30_000.0));
-		assert!(price_lookups.usdc.unwrap() > FixedU128::from_float(0.9));
-		assert!(price_lookups.usdc.unwrap() < FixedU128::from_float(1.1));
+		let price_lookups = CoinUsdPriceLookup::new(&config).get_latest_prices().await.unwrap();
+		assert_eq!(price_lookups.bitcoin, FixedU128::from_u32(62_100));
+		assert_eq!(price_lookups.usdc, FixedU128::from_u32(1));
 	}
 }
@@ -8,16 +8,20 @@ use sp_core::{
 	sr25519, Pair as PairT,
 };
 use sp_runtime::traits::IdentifyAccount;
-use std::env;
+use std::{env, path::PathBuf};
 use tracing::info;
 use url::Url;
 
-use crate::{bitcoin_tip::bitcoin_loop, price_index::price_index_loop};
+use crate::{
+	bitcoin_tip::bitcoin_loop, price_config::PriceSourcesConfig, price_index::price_index_loop,
+};
 
 mod argon_price;
 mod bitcoin_tip;
 mod coin_usd_prices;
+mod price_config;
 mod price_index;
+mod price_sources;
 mod uniswap_oracle;
 mod us_cpi;
 mod us_cpi_schedule;
@@ -58,6 +62,11 @@ pub enum Subcommand {
 		#[cfg(feature = "simulated-prices")]
 		#[clap(short, long)]
 		simulate_prices: bool,
+
+		/// A TOML file configuring the price sources, their weights and outlier rejection (see
+		/// oracle/price-sources.example.toml). Defaults to the built-in exchanges.
+		#[clap(long, env)]
+		price_sources_config: Option<PathBuf>,
 	},
 	Bitcoin {
 		/// The Bitcoin full node to follow for longest chain. Should be a hosted/trusted
@@ -84,6 +93,7 @@ impl Default for Subcommand {
 		Subcommand::PriceIndex {
 			#[cfg(feature = "simulated-prices")]
 			simulate_prices: false,
+			price_sources_config: None,
 		}
 	}
 }
@@ -180,14 +190,19 @@ async fn main() -> anyhow::Result<()> {
 		Subcommand::PriceIndex {
 			#[cfg(feature = "simulated-prices")]
 			simulate_prices,
+			price_sources_config,
 		} => {
+			let price_sources = match price_sources_config {
+				Some(path) => PriceSourcesConfig::load(&path)?,
+				None => PriceSourcesConfig::default(),
+			};
 			#[cfg(feature = "simulated-prices")]
 			{
-				price_index_loop(trusted_rpc_url, signer, simulate_prices).await?
+				price_index_loop(trusted_rpc_url, signer, simulate_prices, price_sources).await?
 			}
 			#[cfg(not(feature = "simulated-prices"))]
 			{
-				price_index_loop(trusted_rpc_url, signer, false).await?
+				price_index_loop(trusted_rpc_url, signer, false, price_sources).await?
 			}
 		},
 		Subcommand::Bitcoin { bitcoin_rpc_url } => {
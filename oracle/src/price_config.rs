@@ -0,0 +1,375 @@
+use crate::price_sources::{Coinbase, Coindesk, Coingecko, HttpJsonSource, Kraken, PriceSource};
+use anyhow::{anyhow, bail, ensure, Result};
+use serde::Deserialize;
+use sp_runtime::{
+	traits::{CheckedDiv, Zero},
+	FixedPointNumber, FixedU128, Saturating,
+};
+use std::{fs, path::Path};
+
+/// Configures where the oracle looks up prices. Loaded from a TOML file (see
+/// `oracle/price-sources.example.toml`). Any section left out uses the built-in defaults.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct PriceSourcesConfig {
+	#[serde(default)]
+	pub outlier_rejection: OutlierRejection,
+	/// The usd price sources for bitcoin and usdc. Defaults to the built-in exchanges
+	#[serde(default = "default_sources")]
+	pub sources: Vec<PriceSourceConfig>,
+	#[serde(default)]
+	pub argon_pool: ArgonPoolConfig,
+}
+
+impl Default for PriceSourcesConfig {
+	fn default() -> Self {
+		Self {
+			outlier_rejection: Default::default(),
+			sources: default_sources(),
+			argon_pool: Default::default(),
+		}
+	}
+}
+
+impl PriceSourcesConfig {
+	pub fn load(path: &Path) -> Result<Self> {
+		let contents = fs::read_to_string(path)
+			.map_err(|e| anyhow!("Unable to read price sources config {path:?}: {e}"))?;
+		Self::parse(&contents)
+	}
+
+	pub fn parse(contents: &str) -> Result<Self> {
+		let config = toml::from_str::<Self>(contents)
+			.map_err(|e| anyhow!("Invalid price sources config: {e}"))?;
+		config.validate()?;
+		Ok(config)
+	}
+
+	fn validate(&self) -> Result<()> {
+		let enabled = self.sources.iter().filter(|a| a.enabled).count();
+		ensure!(enabled > 0, "At least one price source must be enabled");
+		ensure!(
+			self.outlier_rejection.min_sources > 0 && self.outlier_rejection.min_sources <= enabled,
+			"min-sources must be between 1 and the number of enabled price sources ({enabled})"
+		);
+		let usdc_enabled =
+			self.sources.iter().filter(|a| a.enabled && a.source.provides_usdc()).count();
+		ensure!(
+			self.outlier_rejection.min_sources <= usdc_enabled,
+			"min-sources can't be more than the number of enabled price sources with a usdc price \
+			 ({usdc_enabled})"
+		);
+		ensure!(
+			self.outlier_rejection.max_deviation_percent.is_finite() &&
+				self.outlier_rejection.max_deviation_percent >= 0.0,
+			"max-deviation-percent can't be negative"
+		);
+		for source in &self.sources {
+			ensure!(
+				source.weight.is_finite() && source.weight > 0.0,
+				"The weight of price source {} must be more than 0",
+				source.source.name()
+			);
+		}
+		Ok(())
+	}
+
+	/// The enabled price sources with their weights
+	pub fn build_sources(&self) -> Vec<(Box<dyn PriceSource>, FixedU128)> {
+		self.sources
+			.iter()
+			.filter(|a| a.enabled)
+			.map(|a| (a.source.build(), FixedU128::from_float(a.weight)))
+			.collect()
+	}
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct PriceSourceConfig {
+	#[serde(flatten)]
+	pub source: PriceSourceType,
+	#[serde(default = "default_enabled")]
+	pub enabled: bool,
+	/// How much this source counts towards the average price
+	#[serde(default = "default_weight")]
+	pub weight: f64,
+}
+
+impl From<PriceSourceType> for PriceSourceConfig {
+	fn from(source: PriceSourceType) -> Self {
+		Self { source, enabled: default_enabled(), weight: default_weight() }
+	}
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum PriceSourceType {
+	Coinbase,
+	Coindesk,
+	Kraken,
+	Coingecko,
+	/// Reads prices from a json http api using JSON paths like `$.data.amount` or `$.c[0]`
+	#[serde(rename_all = "kebab-case")]
+	HttpJson {
+		name: String,
+		url: String,
+		bitcoin_path: String,
+		/// Defaults to the url
+		usdc_url: Option<String>,
+		usdc_path: Option<String>,
+	},
+}
+
+impl PriceSourceType {
+	pub fn name(&self) -> &str {
+		match self {
+			PriceSourceType::Coinbase => "coinbase",
+			PriceSourceType::Coindesk => "coindesk",
+			PriceSourceType::Kraken => "kraken",
+			PriceSourceType::Coingecko => "coingecko",
+			PriceSourceType::HttpJson { name, .. } => name,
+		}
+	}
+
+	/// Whether this source looks up a usdc price as well as bitcoin
+	pub fn provides_usdc(&self) -> bool {
+		match self {
+			PriceSourceType::Coindesk => false,
+			PriceSourceType::HttpJson { usdc_path, .. } => usdc_path.is_some(),
+			_ => true,
+		}
+	}
+
+	pub fn build(&self) -> Box<dyn PriceSource> {
+		match self.clone() {
+			PriceSourceType::Coinbase => Box::new(Coinbase),
+			PriceSourceType::Coindesk => Box::new(Coindesk),
+			PriceSourceType::Kraken => Box::new(Kraken),
+			PriceSourceType::Coingecko => Box::new(Coingecko),
+			PriceSourceType::HttpJson { name, url, bitcoin_path, usdc_url, usdc_path } =>
+				Box::new(HttpJsonSource { name, url, bitcoin_path, usdc_url, usdc_path }),
+		}
+	}
+}
+
+/// Rules to drop prices that are too far from the other sources
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct OutlierRejection {
+	/// Prices further than this percent from the median of all sources are dropped
+	#[serde(default = "default_max_deviation_percent")]
+	pub max_deviation_percent: f64,
+	/// The fewest prices that must be left after dropping outliers
+	#[serde(default = "default_min_sources")]
+	pub min_sources: usize,
+}
+
+impl Default for OutlierRejection {
+	fn default() -> Self {
+		Self {
+			max_deviation_percent: default_max_deviation_percent(),
+			min_sources: default_min_sources(),
+		}
+	}
+}
+
+impl OutlierRejection {
+	/// Drops the outliers and returns the weighted average of the remaining prices
+	pub fn weighted_price(&self, prices: &[(FixedU128, FixedU128)]) -> Result<FixedU128> {
+		if prices.is_empty() {
+			bail!("No prices found");
+		}
+		let mut sorted = prices.iter().map(|(price, _)| *price).collect::<Vec<_>>();
+		sorted.sort();
+		let middle = sorted.len() / 2;
+		let median = if sorted.len() % 2 == 0 {
+			FixedU128::from_inner(
+				sorted[middle - 1].into_inner().saturating_add(sorted[middle].into_inner()) / 2,
+			)
+		} else {
+			sorted[middle]
+		};
+
+		let max_deviation =
+			median.saturating_mul(FixedU128::from_float(self.max_deviation_percent / 100.0));
+		let kept = prices
+			.iter()
+			.filter(|(price, _)| {
+				let diff = if *price > median { *price - median } else { median - *price };
+				diff <= max_deviation
+			})
+			.collect::<Vec<_>>();
+		ensure!(
+			kept.len() >= self.min_sources,
+			"Only {} of {} prices are within {}% of the median",
+			kept.len(),
+			prices.len(),
+			self.max_deviation_percent
+		);
+
+		let mut total = FixedU128::zero();
+		let mut total_weight = FixedU128::zero();
+		for (price, weight) in kept {
+			total = total.saturating_add(price.saturating_mul(*weight));
+			total_weight = total_weight.saturating_add(*weight);
+		}
+		total.checked_div(&total_weight).ok_or(anyhow!("Price sources have no weight"))
+	}
+}
+
+/// The uniswap pool used to look up the argon price
+#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct ArgonPoolConfig {
+	/// Use the sepolia testnet pool. Defaults to the `USE_SEPOLIA` env var
+	pub use_sepolia: Option<bool>,
+	/// The argon token address on ethereum. Defaults to the `ARGON_TOKEN_ADDRESS` env var
+	pub argon_token_address: Option<String>,
+	/// The infura project used to query uniswap. Defaults to the `INFURA_PROJECT_ID` env var
+	pub infura_project_id: Option<String>,
+}
+
+fn default_sources() -> Vec<PriceSourceConfig> {
+	vec![
+		PriceSourceType::Coinbase.into(),
+		PriceSourceType::Coindesk.into(),
+		PriceSourceType::Kraken.into(),
+		PriceSourceType::Coingecko.into(),
+	]
+}
+
+fn default_enabled() -> bool {
+	true
+}
+
+fn default_weight() -> f64 {
+	1.0
+}
+
+fn default_max_deviation_percent() -> f64 {
+	5.0
+}
+
+fn default_min_sources() -> usize {
+	1
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn can_parse_a_config() {
+		let config = PriceSourcesConfig::parse(
+			r#"
+			[outlier-rejection]
+			max-deviation-percent = 2.5
+			min-sources = 2
+
+			[[sources]]
+			type = "kraken"
+			weight = 2
+
+			[[sources]]
+			type = "coindesk"
+			enabled = false
+
+			[[sources]]
+			type = "http-json"
+			name = "bitstamp"
+			url = "https://www.bitstamp.net/api/v2/ticker/btcusd/"
+			bitcoin-path = "$.last"
+			usdc-url = "https://www.bitstamp.net/api/v2/ticker/usdcusd/"
+			usdc-path = "$.last"
+
+			[argon-pool]
+			use-sepolia = true
+			"#,
+		)
+		.unwrap();
+
+		assert_eq!(
+			config.outlier_rejection,
+			OutlierRejection { max_deviation_percent: 2.5, min_sources: 2 }
+		);
+		assert_eq!(config.sources.len(), 3);
+		assert_eq!(config.sources[0].weight, 2.0);
+		assert!(!config.sources[1].enabled);
+		assert_eq!(
+			config.sources[2].source,
+			PriceSourceType::HttpJson {
+				name: "bitstamp".to_string(),
+				url: "https://www.bitstamp.net/api/v2/ticker/btcusd/".to_string(),
+				bitcoin_path: "$.last".to_string(),
+				usdc_url: Some("https://www.bitstamp.net/api/v2/ticker/usdcusd/".to_string()),
+				usdc_path: Some("$.last".to_string()),
+			}
+		);
+		assert_eq!(config.argon_pool.use_sepolia, Some(true));
+		let sources = config.build_sources();
+		assert_eq!(
+			sources.iter().map(|(a, _)| a.name()).collect::<Vec<_>>(),
+			vec!["kraken", "bitstamp"]
+		);
+	}
+
+	#[test]
+	fn can_load_the_example_config() {
+		let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("price-sources.example.toml");
+		let config = PriceSourcesConfig::load(&path).unwrap();
+		assert_eq!(config.build_sources().len(), 4);
+	}
+
+	#[test]
+	fn uses_defaults_for_an_empty_config() {
+		assert_eq!(PriceSourcesConfig::parse("").unwrap(), PriceSourcesConfig::default());
+		assert_eq!(PriceSourcesConfig::default().build_sources().len(), 4);
+	}
+
+	#[test]
+	fn rejects_invalid_configs() {
+		assert!(PriceSourcesConfig::parse("[[sources]]\ntype = \"bitfinex\"").is_err());
+		assert!(PriceSourcesConfig::parse("[[sources]]\ntype = \"kraken\"\nweight = 0").is_err());
+		assert!(PriceSourcesConfig::parse(
+			"[outlier-rejection]\nmin-sources = 2\n[[sources]]\ntype = \"kraken\""
+		)
+		.is_err());
+		assert!(
+			PriceSourcesConfig::parse("[[sources]]\ntype = \"kraken\"\nenabled = false").is_err()
+		);
+	}
+
+	#[test]
+	fn requires_min_sources_with_a_usdc_price() {
+		let config = r#"
+			[outlier-rejection]
+			min-sources = 2
+
+			[[sources]]
+			type = "kraken"
+
+			[[sources]]
+			type = "coindesk"
+			"#;
+		assert!(PriceSourcesConfig::parse(config).is_err());
+		assert!(PriceSourcesConfig::parse(&config.replace("coindesk", "coinbase")).is_ok());
+		assert!(PriceSourcesConfig::parse("[[sources]]\ntype = \"coindesk\"").is_err());
+	}
+
+	#[test]
+	fn drops_outliers_from_the_weighted_price() {
+		let rules = OutlierRejection { max_deviation_percent: 5.0, min_sources: 2 };
+		let one = FixedU128::from_u32(1);
+		let prices = [
+			(FixedU128::from_u32(60_000), one),
+			(FixedU128::from_u32(61_000), FixedU128::from_u32(3)),
+			(FixedU128::from_u32(90_000), one),
+		];
+		// 90,000 is dropped, and 61,000 counts 3 times
+		assert_eq!(rules.weighted_price(&prices).unwrap(), FixedU128::from_u32(60_750));
+
+		assert!(rules.weighted_price(&prices[1..]).is_err(), "only one price is left");
+		assert!(rules.weighted_price(&[]).is_err());
+	}
+}
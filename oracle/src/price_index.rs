@@ -5,7 +5,9 @@ use sp_runtime::{traits::One, FixedU128, Saturating};
 use tokio::{join, time::sleep};
 use tracing::info;
 
-use crate::{argon_price, coin_usd_prices, us_cpi::UsCpiRetriever};
+use crate::{
+	argon_price, coin_usd_prices, price_config::PriceSourcesConfig, us_cpi::UsCpiRetriever,
+};
 use argon_client::{
 	api::{constants, price_index::calls::types::submit::Index, storage, tx},
 	conversion::{from_api_fixed_u128, to_api_fixed_u128},
@@ -17,6 +19,7 @@ pub async fn price_index_loop(
 	trusted_rpc_url: String,
 	signer: KeystoreSigner,
 	use_simulated_schedule: bool,
+	price_sources: PriceSourcesConfig,
 ) -> anyhow::Result<()> {
 	let mut reconnecting_client = ReconnectingClient::new(vec![trusted_rpc_url.clone()]);
 	let mainchain_client = reconnecting_client.get().await?;
@@ -71,10 +74,11 @@ pub async fn price_index_loop(
 	}
 
 	let mut us_cpi = UsCpiRetriever::new(&ticker).await?;
-	let mut usd_price_lookups = coin_usd_prices::CoinUsdPriceLookup::new();
+	let mut usd_price_lookups = coin_usd_prices::CoinUsdPriceLookup::new(&price_sources);
 
 	let mut argon_price_lookup =
-		argon_price::ArgonPriceLookup::from_env(&ticker, last_price).await?;
+		argon_price::ArgonPriceLookup::from_config(&ticker, last_price, &price_sources.argon_pool)
+			.await?;
 
 	info!("Oracle Started.");
 	let account_id = signer.account_id();
@@ -192,7 +196,7 @@ mod tests {
 		let account_id: AccountId32 = keypair.public().into();
 
 		let signer = KeystoreSigner::new(keystore.into(), account_id, CryptoType::Sr25519);
-		spawn(price_index_loop(node.client.url.clone(), signer, false));
+		spawn(price_index_loop(node.client.url.clone(), signer, false, Default::default()));
 
 		let mut block_sub = node.client.live.blocks().subscribe_best().await.unwrap();
 
@@ -0,0 +1,346 @@
+use crate::utils::parse_f64;
+use anyhow::{anyhow, bail, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::Value;
+use sp_runtime::FixedU128;
+use std::collections::HashMap;
+use tokio::join;
+
+/// A source of usd prices for bitcoin (and optionally usdc)
+#[async_trait]
+pub trait PriceSource: Send + Sync {
+	/// The name of the source used in logs
+	fn name(&self) -> &str;
+
+	async fn get_prices(&self, client: &Client) -> Result<PriceLookupMaybe>;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PriceLookupMaybe {
+	pub bitcoin: FixedU128,
+	pub usdc: Option<FixedU128>,
+}
+
+pub struct Coindesk;
+
+#[async_trait]
+impl PriceSource for Coindesk {
+	fn name(&self) -> &str {
+		"coindesk"
+	}
+
+	async fn get_prices(&self, client: &Client) -> Result<PriceLookupMaybe> {
+		let response = client
+			.get("https://api.coindesk.com/v1/bpi/currentprice.json")
+			.send()
+			.await?
+			.json::<CoinDeskResponse>()
+			.await?;
+		Ok(PriceLookupMaybe {
+			bitcoin: FixedU128::from_float(response.bpi.usd.rate_float),
+			usdc: None,
+		})
+	}
+}
+
+pub struct Kraken;
+
+#[async_trait]
+impl PriceSource for Kraken {
+	fn name(&self) -> &str {
+		"kraken"
+	}
+
+	async fn get_prices(&self, client: &Client) -> Result<PriceLookupMaybe> {
+		let response = client
+			.get("https://api.kraken.com/0/public/Ticker?pair=XXBTZUSD,USDCUSD")
+			.send()
+			.await?
+			.json::<KrakenResponse>()
+			.await?;
+		let btc = response.result.get("XXBTZUSD").ok_or(anyhow!("No price data"))?.last_trade_cost
+			[0]
+		.parse::<f64>()?;
+		let usdc = response.result.get("USDCUSD").ok_or(anyhow!("No price data"))?.last_trade_cost
+			[0]
+		.parse::<f64>()?;
+
+		Ok(PriceLookupMaybe {
+			bitcoin: FixedU128::from_float(btc),
+			usdc: Some(FixedU128::from_float(usdc)),
+		})
+	}
+}
+
+pub struct Coingecko;
+
+#[async_trait]
+impl PriceSource for Coingecko {
+	fn name(&self) -> &str {
+		"coingecko"
+	}
+
+	async fn get_prices(&self, client: &Client) -> Result<PriceLookupMaybe> {
+		let response = client
+			.get("https://api.coingecko.com/api/v3/simple/price")
+			.query(&[("ids", "bitcoin,usd-coin"), ("vs_currencies", "usd")])
+			.send()
+			.await?
+			.json::<CoinGeckoResponse>()
+			.await?;
+
+		Ok(PriceLookupMaybe {
+			bitcoin: FixedU128::from_float(response.bitcoin.usd),
+			usdc: Some(FixedU128::from_float(response.usd_coin.usd)),
+		})
+	}
+}
+
+pub struct Coinbase;
+
+impl Coinbase {
+	async fn get_price(client: &Client, coin: &str) -> Result<FixedU128> {
+		let response = client
+			.get(format!("https://api.coinbase.com/v2/prices/{coin}-USD/spot"))
+			.send()
+			.await?
+			.json::<CoinbasePrice>()
+			.await?;
+		Ok(FixedU128::from_float(response.data.amount))
+	}
+}
+
+#[async_trait]
+impl PriceSource for Coinbase {
+	fn name(&self) -> &str {
+		"coinbase"
+	}
+
+	async fn get_prices(&self, client: &Client) -> Result<PriceLookupMaybe> {
+		let (bitcoin, usdc) =
+			join!(Self::get_price(client, "BTC"), Self::get_price(client, "USDC"));
+		Ok(PriceLookupMaybe { bitcoin: bitcoin?, usdc: usdc.ok() })
+	}
+}
+
+/// A custom source that reads prices out of a json http response
+pub struct HttpJsonSource {
+	pub name: String,
+	pub url: String,
+	/// The path to the bitcoin price in the response (eg, `$.data.amount`)
+	pub bitcoin_path: String,
+	/// A separate url to look up the usdc price. Defaults to the bitcoin url
+	pub usdc_url: Option<String>,
+	pub usdc_path: Option<String>,
+}
+
+impl HttpJsonSource {
+	async fn get_json(client: &Client, url: &str) -> Result<Value> {
+		Ok(client.get(url).send().await?.error_for_status()?.json::<Value>().await?)
+	}
+}
+
+#[async_trait]
+impl PriceSource for HttpJsonSource {
+	fn name(&self) -> &str {
+		&self.name
+	}
+
+	async fn get_prices(&self, client: &Client) -> Result<PriceLookupMaybe> {
+		let response = Self::get_json(client, &self.url).await?;
+		let bitcoin = read_json_price(&response, &self.bitcoin_path)?;
+		let usdc = match (&self.usdc_url, &self.usdc_path) {
+			(_, None) => None,
+			(None, Some(path)) => Some(read_json_price(&response, path)?),
+			(Some(url), Some(path)) =>
+				Some(read_json_price(&Self::get_json(client, url).await?, path)?),
+		};
+		Ok(PriceLookupMaybe { bitcoin, usdc })
+	}
+}
+
+/// Reads a price at a JSON path made of object keys and array indices (eg,
+/// `$.result.XXBTZUSD.c[0]`). Prices can be json numbers or numeric strings.
+pub(crate) fn read_json_price(json: &Value, path: &str) -> Result<FixedU128> {
+	let mut value = json;
+	for segment in path.strip_prefix('$').unwrap_or(path).split('.').filter(|a| !a.is_empty()) {
+		let (key, indices) = segment.split_at(segment.find('[').unwrap_or(segment.len()));
+		if !indices.is_empty() && !indices.ends_with(']') {
+			bail!("Invalid json path {path}");
+		}
+		if !key.is_empty() {
+			value = value.get(key).ok_or_else(|| anyhow!("No value at `{key}` of {path}"))?;
+		}
+		for index in indices.split_terminator(']') {
+			let index = index
+				.strip_prefix('[')
+				.and_then(|a| a.parse::<usize>().ok())
+				.ok_or_else(|| anyhow!("Invalid json path {path}"))?;
+			value = value.get(index).ok_or_else(|| anyhow!("No value at [{index}] of {path}"))?;
+		}
+	}
+	let price = match value {
+		Value::Number(number) => number.as_f64(),
+		Value::String(string) => string.parse::<f64>().ok(),
+		_ => None,
+	}
+	.ok_or_else(|| anyhow!("The value at {path} is not a price"))?;
+	Ok(FixedU128::from_float(price))
+}
+
+#[derive(Deserialize)]
+struct CoinGeckoResponse {
+	bitcoin: CoinGeckoPriceData,
+	#[serde(rename = "usd-coin")]
+	usd_coin: CoinGeckoPriceData,
+}
+
+#[derive(Deserialize)]
+struct CoinGeckoPriceData {
+	usd: f64,
+}
+
+#[derive(Deserialize)]
+struct CoinDeskResponse {
+	bpi: CoindeskBpi,
+}
+
+#[derive(Deserialize)]
+struct CoindeskBpi {
+	#[serde(rename = "USD")]
+	usd: CoindeskBpiData,
+}
+
+#[derive(Deserialize)]
+struct CoindeskBpiData {
+	rate_float: f64,
+}
+#[derive(Deserialize, Debug)]
+pub struct CoinbasePrice {
+	pub data: CoinbasePriceData,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CoinbasePriceData {
+	// pub base: String,
+	// pub currency: String,
+	#[serde(deserialize_with = "parse_f64")]
+	pub amount: f64,
+}
+
+#[derive(Deserialize)]
+struct KrakenResponse {
+	result: HashMap<String, KrakenResponsePair>,
+}
+
+#[derive(Deserialize)]
+struct KrakenResponsePair {
+	#[serde(rename = "c")]
+	last_trade_cost: [String; 2],
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+	use super::*;
+	use serde_json::json;
+	use tokio::{
+		io::{AsyncReadExt, AsyncWriteExt},
+		net::TcpListener,
+	};
+
+	/// Serves a json body to every request on a local port, and returns the url
+	pub(crate) async fn serve_json(body: Value) -> String {
+		let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let url = format!("http://{}", listener.local_addr().unwrap());
+		let body = body.to_string();
+		tokio::spawn(async move {
+			while let Ok((mut socket, _)) = listener.accept().await {
+				let mut request = [0u8; 1024];
+				let _ = socket.read(&mut request).await;
+				let response = format!(
+					"HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+					body.len(),
+					body
+				);
+				let _ = socket.write_all(response.as_bytes()).await;
+			}
+		});
+		url
+	}
+
+	#[test]
+	fn can_read_json_prices() {
+		let json = json!({
+			"data": { "amount": "62000.5" },
+			"result": { "XXBTZUSD": { "c": ["61000.1", "0.1"] } },
+			"usd": 1.01,
+		});
+		assert_eq!(
+			read_json_price(&json, "$.data.amount").unwrap(),
+			FixedU128::from_float(62000.5)
+		);
+		assert_eq!(
+			read_json_price(&json, "$.result.XXBTZUSD.c[0]").unwrap(),
+			FixedU128::from_float(61000.1)
+		);
+		assert_eq!(read_json_price(&json, "usd").unwrap(), FixedU128::from_float(1.01));
+		assert!(read_json_price(&json, "$.data").is_err());
+		assert!(read_json_price(&json, "$.result.XXBTZUSD.c[2]").is_err());
+		assert!(read_json_price(&json, "$.result.XXBTZUSD.c[x]").is_err());
+		assert!(read_json_price(&json, "$.result.XXBTZUSD.c[0").is_err());
+	}
+
+	#[tokio::test]
+	async fn can_use_a_custom_http_json_source() {
+		let url = serve_json(json!({ "btc": { "last": 62000.25 }, "usdc": "0.999" })).await;
+		let usdc_url = serve_json(json!({ "tickers": [{ "price": "1.001" }] })).await;
+
+		let mut source = HttpJsonSource {
+			name: "local".to_string(),
+			url,
+			bitcoin_path: "$.btc.last".to_string(),
+			usdc_url: None,
+			usdc_path: Some("$.usdc".to_string()),
+		};
+		let prices = source.get_prices(&Client::new()).await.unwrap();
+		assert_eq!(prices.bitcoin, FixedU128::from_float(62000.25));
+		assert_eq!(prices.usdc, Some(FixedU128::from_float(0.999)));
+
+		source.usdc_url = Some(usdc_url);
+		source.usdc_path = Some("$.tickers[0].price".to_string());
+		let prices = source.get_prices(&Client::new()).await.unwrap();
+		assert_eq!(prices.usdc, Some(FixedU128::from_float(1.001)));
+	}
+
+	#[tokio::test]
+	async fn test_get_coindesk_price() {
+		let price_lookups = Coindesk.get_prices(&Client::new()).await.unwrap();
+		assert!(price_lookups.bitcoin > FixedU128::from_float(30_000.0));
+	}
+
+	#[tokio::test]
+	async fn test_get_kraken_price() {
+		let price_lookups = Kraken.get_prices(&Client::new()).await.unwrap();
+		assert!(price_lookups.bitcoin > FixedU128::from_float(30_000.0));
+		assert!(price_lookups.usdc.unwrap() > FixedU128::from_float(0.9));
+		assert!(price_lookups.usdc.unwrap() < FixedU128::from_float(1.1));
+	}
+
+	#[tokio::test]
+	async fn test_get_coingecko_price() {
+		let price_lookups = Coingecko.get_prices(&Client::new()).await.unwrap();
+		assert!(price_lookups.bitcoin > FixedU128::from_float(30_000.0));
+		assert!(price_lookups.usdc.unwrap() > FixedU128::from_float(0.9));
+		assert!(price_lookups.usdc.unwrap() < FixedU128::from_float(1.1));
+	}
+
+	#[tokio::test]
+	async fn test_get_coinbase_price() {
+		let price_lookups = Coinbase.get_prices(&Client::new()).await.unwrap();
+		assert!(price_lookups.bitcoin > FixedU128::from_float(30_000.0));
+		assert!(price_lookups.usdc.unwrap() > FixedU128::from_float(0.9));
+		assert!(price_lookups.usdc.unwrap() < FixedU128::from_float(1.1));
+	}
+}
@@ -11,19 +11,21 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+pub mod migrations;
 pub mod weights;
 
 #[frame_support::pallet(dev_mode)]
 pub mod pallet {
 	use alloc::{vec, vec::Vec};
-	use frame_support::{pallet_prelude::*, storage::with_storage_layer};
+	use frame_support::{pallet_prelude::*, storage::with_storage_layer, traits::UnixTime};
 	use frame_system::pallet_prelude::*;
 	use log::{info, warn};
 
 	use argon_primitives::{
 		bitcoin::{
-			BitcoinBlock, BitcoinBlockHash, BitcoinCosignScriptPubkey, BitcoinHeight,
-			BitcoinRejectedReason, BitcoinSyncStatus, Satoshis, UtxoId, UtxoRef, UtxoValue,
+			BitcoinBlock, BitcoinBlockHash, BitcoinCosignScriptPubkey, BitcoinHeaderEntry,
+			BitcoinHeight, BitcoinRejectedReason, BitcoinSyncStatus, BitcoinTxInclusionProof,
+			OpaqueBitcoinHeader, Satoshis, UtxoId, UtxoRef, UtxoValue,
 		},
 		inherents::{BitcoinInherentData, BitcoinInherentError, BitcoinUtxoSync},
 		BitcoinUtxoEvents, BitcoinUtxoTracker,
@@ -31,7 +33,10 @@ pub mod pallet {
 
 	use super::*;
 
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	#[pallet::config]
@@ -48,6 +53,22 @@ pub mod pallet {
 		/// Maximum bitcoin blocks to watch a Utxo for confirmation before canceling
 		#[pallet::constant]
 		type MaxPendingConfirmationBlocks: Get<BitcoinHeight>;
+
+		/// The maximum number of oracle operators that can submit confirmed bitcoin blocks
+		#[pallet::constant]
+		type MaxOracleOperators: Get<u32>;
+
+		/// The number of bitcoin headers to keep below the tip of the header chain. Utxos can only
+		/// be verified in blocks that are still kept.
+		#[pallet::constant]
+		type MaxBitcoinHeaderHistory: Get<BitcoinHeight>;
+
+		/// The maximum number of bitcoin headers that can be added in a single sync
+		#[pallet::constant]
+		type MaxBitcoinHeadersPerSync: Get<u32>;
+
+		/// The current time, used to reject bitcoin headers from the future
+		type UnixTime: UnixTime;
 	}
 
 	#[pallet::storage]
@@ -81,6 +102,32 @@ pub mod pallet {
 	pub(super) type ConfirmedBitcoinBlockTip<T: Config> =
 		StorageValue<_, BitcoinBlock, OptionQuery>;
 
+	/// Verified headers of the bitcoin header chain with the most proof of work
+	#[pallet::storage]
+	pub(super) type BitcoinHeaders<T: Config> =
+		StorageMap<_, Blake2_128Concat, BitcoinBlockHash, BitcoinHeaderEntry, OptionQuery>;
+
+	/// The block hashes of the bitcoin header chain by height
+	#[pallet::storage]
+	pub(super) type BitcoinHeaderHashByHeight<T: Config> =
+		StorageMap<_, Twox64Concat, BitcoinHeight, BitcoinBlockHash, OptionQuery>;
+
+	/// The tip of the bitcoin header chain
+	#[pallet::storage]
+	pub(super) type BitcoinHeaderTip<T: Config> = StorageValue<_, BitcoinBlock, OptionQuery>;
+
+	/// Utxo state changes that depend on a bitcoin block. If the block is reorged out of the header
+	/// chain, the changes are reverted.
+	#[pallet::storage]
+	pub(super) type UtxoChangesByBitcoinBlock<T: Config> =
+		StorageMap<_, Blake2_128Concat, BitcoinBlockHash, Vec<UtxoStateChange>, ValueQuery>;
+
+	#[derive(Decode, Encode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	pub enum UtxoStateChange {
+		Verified { utxo_id: UtxoId, utxo_ref: UtxoRef },
+		Spent { utxo_id: UtxoId, utxo_ref: UtxoRef, utxo_value: UtxoValue },
+	}
+
 	/// Stores if parent block had a confirmed bitcoin block
 	#[pallet::storage]
 	pub(super) type TempParentHasSyncState<T: Config> = StorageValue<_, bool, ValueQuery>;
@@ -89,9 +136,25 @@ pub mod pallet {
 	#[pallet::storage]
 	pub(super) type SynchedBitcoinBlock<T: Config> = StorageValue<_, BitcoinBlock, OptionQuery>;
 
-	/// Bitcoin Oracle Operator Account
+	/// Bitcoin Oracle Operator Accounts
+	#[pallet::storage]
+	pub(super) type OracleOperatorAccounts<T: Config> =
+		StorageValue<_, BoundedVec<T::AccountId, T::MaxOracleOperators>, ValueQuery>;
+
+	/// The number of oracle operators that must agree on a bitcoin block before it's confirmed
+	#[pallet::storage]
+	pub(super) type OracleConfirmationThreshold<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// The latest confirmed bitcoin block submitted by each oracle operator
+	#[pallet::storage]
+	pub(super) type OracleConfirmedBlockVotes<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, BitcoinBlock, OptionQuery>;
+
+	/// The number of times each oracle operator submitted a block hash that conflicted with the
+	/// confirmed bitcoin block at the same height
 	#[pallet::storage]
-	pub(super) type OracleOperatorAccount<T: Config> = StorageValue<_, T::AccountId, OptionQuery>;
+	pub(super) type OracleOperatorDisagreements<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
 
 	/// Check if the inherent was included
 	#[pallet::storage]
@@ -119,6 +182,18 @@ pub mod pallet {
 		UtxoVerifiedError { utxo_id: UtxoId, error: DispatchError },
 		UtxoRejectedError { utxo_id: UtxoId, error: DispatchError },
 		UtxoExpiredError { utxo_ref: UtxoRef, error: DispatchError },
+
+		// an oracle operator submitted a different block than the confirmed block at that height
+		OracleDisagreement { operator: T::AccountId, block: BitcoinBlock, confirmed: BitcoinBlock },
+		OracleOperatorsSet { operators: Vec<T::AccountId>, confirmation_threshold: u32 },
+
+		BitcoinHeaderTipChanged { block_height: BitcoinHeight, block_hash: BitcoinBlockHash },
+		BitcoinHeadersRejected { error: DispatchError },
+		// the block a utxo was verified in was reorged out, so it's pending confirmation again
+		UtxoVerificationReverted { utxo_id: UtxoId, block_hash: BitcoinBlockHash },
+		// the block a utxo was spent in was reorged out of the bitcoin header chain
+		UtxoSpendReverted { utxo_id: UtxoId, block_hash: BitcoinBlockHash },
+		UtxoRevertError { utxo_id: UtxoId, error: DispatchError },
 	}
 
 	#[pallet::error]
@@ -145,22 +220,75 @@ pub mod pallet {
 		MaxUtxosExceeded,
 		/// Locking script has errors
 		InvalidBitcoinScript,
+		/// The confirmation threshold must be between one and the number of oracle operators
+		InvalidOracleThreshold,
+		/// An oracle operator was included more than once
+		DuplicateOracleOperator,
+		/// A bitcoin header is invalid or doesn't extend the header chain
+		InvalidBitcoinHeader,
+		/// The bitcoin block is not in the header chain
+		BitcoinHeaderNotFound,
+		/// The bitcoin headers have less proof of work than the current header chain
+		InsufficientBitcoinHeaderWork,
+		/// Too many bitcoin headers were submitted at once
+		TooManyBitcoinHeaders,
+		/// A verified utxo is missing a proof of inclusion in a bitcoin block
+		MissingInclusionProof,
+		/// The utxo is not proven to be in the bitcoin block
+		InvalidInclusionProof,
+		/// The header chain doesn't include the oracle confirmed bitcoin block
+		ConfirmedBlockNotInHeaderChain,
 	}
 
 	#[pallet::genesis_config]
 	#[derive(frame_support::DefaultNoBound)]
 	pub struct GenesisConfig<T: Config> {
-		pub tip_oracle_operator: Option<T::AccountId>,
+		pub tip_oracle_operators: Vec<T::AccountId>,
+		/// Defaults to requiring every operator
+		pub tip_oracle_confirmation_threshold: Option<u32>,
 		pub network: argon_primitives::bitcoin::BitcoinNetwork,
+		/// The height and raw 80 byte header to start the bitcoin header chain from. This must be
+		/// a recent block, since only `MaxBitcoinHeadersPerSync` headers are added per block.
+		/// Defaults to the genesis block on regtest, and is ignored on networks without header
+		/// verification (signet).
+		pub bitcoin_header_checkpoint: Option<(BitcoinHeight, Vec<u8>)>,
 	}
 
 	#[pallet::genesis_build]
 	impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
 		fn build(&self) {
-			if let Some(operator) = &self.tip_oracle_operator {
-				<OracleOperatorAccount<T>>::put(operator);
+			if !self.tip_oracle_operators.is_empty() {
+				let operators = BoundedVec::try_from(self.tip_oracle_operators.clone())
+					.expect("Too many bitcoin oracle operators");
+				let threshold =
+					self.tip_oracle_confirmation_threshold.unwrap_or(operators.len() as u32);
+				Pallet::<T>::ensure_valid_operators(&operators, threshold)
+					.expect("Invalid bitcoin oracle operators");
+				<OracleOperatorAccounts<T>>::put(operators);
+				<OracleConfirmationThreshold<T>>::put(threshold);
 			}
 			<BitcoinNetwork<T>>::put(self.network.clone());
+			if !argon_bitcoin::supports_spv(self.network.clone()) {
+				return;
+			}
+
+			let (block_height, header) = match &self.bitcoin_header_checkpoint {
+				Some((block_height, header)) => (
+					*block_height,
+					OpaqueBitcoinHeader(
+						header.as_slice().try_into().expect("Bitcoin headers are 80 bytes"),
+					),
+				),
+				None => {
+					assert!(
+						self.network == argon_primitives::bitcoin::BitcoinNetwork::Regtest,
+						"A recent bitcoin header checkpoint is required outside of regtest"
+					);
+					(0, argon_bitcoin::genesis_header(self.network.clone()))
+				},
+			};
+			Pallet::<T>::set_header_checkpoint(block_height, header)
+				.expect("Invalid bitcoin header checkpoint");
 		}
 	}
 	#[pallet::hooks]
@@ -216,6 +344,17 @@ pub mod pallet {
 				);
 			}
 
+			let mut reorged_to = None;
+			if !utxo_sync.headers.is_empty() {
+				match with_storage_layer(|| Self::import_headers(utxo_sync.headers)) {
+					Ok(common_block) => reorged_to = common_block,
+					Err(e) => {
+						warn!("Failed to import bitcoin headers: {:?}", e);
+						Self::deposit_event(Event::BitcoinHeadersRejected { error: e });
+					},
+				}
+			}
+
 			// watch for spent first, so we don't verify and then burn
 			for (utxo_id, block_height) in utxo_sync.spent.into_iter() {
 				let err = with_storage_layer(|| Self::utxo_spent(utxo_id, block_height));
@@ -226,7 +365,10 @@ pub mod pallet {
 			}
 
 			for (utxo_id, utxo_ref) in utxo_sync.verified.into_iter() {
-				let res = with_storage_layer(|| Self::utxo_verified(utxo_id, utxo_ref));
+				let proof = utxo_sync.inclusion_proofs.get(&utxo_id);
+				let res = with_storage_layer(|| {
+					Self::utxo_proven(utxo_id, utxo_ref, proof, &current_confirmed)
+				});
 				if let Err(e) = res {
 					warn!("Failed to verify UTXO {}: {:?}", utxo_id, e);
 					Self::deposit_event(Event::UtxoVerifiedError { utxo_id, error: e });
@@ -275,13 +417,21 @@ pub mod pallet {
 					}
 				}
 			}
-			<SynchedBitcoinBlock<T>>::set(Some(utxo_sync.sync_to_block));
+			let mut synched_block = utxo_sync.sync_to_block;
+			// blocks after a reorg need to be scanned again for reverted utxos
+			if let Some(common_block) = reorged_to {
+				if common_block.block_height < synched_block.block_height {
+					synched_block = common_block;
+				}
+			}
+			<SynchedBitcoinBlock<T>>::set(Some(synched_block));
 
 			Ok(())
 		}
 
-		/// Sets the most recent confirmed bitcoin block height (only executable by the Oracle
-		/// Operator account)
+		/// Submits the most recent confirmed bitcoin block height (only executable by an Oracle
+		/// Operator account). The confirmed block only changes once the confirmation threshold of
+		/// operators have submitted the same block.
 		///
 		/// # Arguments
 		/// * `bitcoin_height` - the latest bitcoin block height to be confirmed
@@ -293,30 +443,98 @@ pub mod pallet {
 			bitcoin_block_hash: BitcoinBlockHash,
 		) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
-			ensure!(Some(who) == <OracleOperatorAccount<T>>::get(), Error::<T>::NoPermissions);
-			if let Some(current) = <ConfirmedBitcoinBlockTip<T>>::get() {
+			let operators = <OracleOperatorAccounts<T>>::get();
+			ensure!(operators.contains(&who), Error::<T>::NoPermissions);
+			let current = <ConfirmedBitcoinBlockTip<T>>::get();
+			if let Some(current) = &current {
 				if bitcoin_height < current.block_height {
 					return Ok(Pays::No.into());
 				}
 			}
-			<ConfirmedBitcoinBlockTip<T>>::put(BitcoinBlock {
-				block_height: bitcoin_height,
-				block_hash: bitcoin_block_hash,
+			let block =
+				BitcoinBlock { block_height: bitcoin_height, block_hash: bitcoin_block_hash };
+			let previous_vote = <OracleConfirmedBlockVotes<T>>::mutate(&who, |vote| {
+				core::mem::replace(vote, Some(block.clone()))
 			});
+
+			if current.as_ref() == Some(&block) {
+				return Ok(Pays::No.into());
+			}
+			let agreeing = operators
+				.iter()
+				.filter(|a| <OracleConfirmedBlockVotes<T>>::get(a).as_ref() == Some(&block))
+				.count() as u32;
+
+			if agreeing < <OracleConfirmationThreshold<T>>::get().max(1) {
+				// a late submission for the current block can only disagree
+				if let Some(current) = &current {
+					if current.block_height == block.block_height &&
+						previous_vote.as_ref() != Some(&block)
+					{
+						Self::record_disagreement(who, &block, current);
+					}
+				}
+				return Ok(Pays::No.into());
+			}
+
+			for operator in operators {
+				let Some(vote) = <OracleConfirmedBlockVotes<T>>::get(&operator) else {
+					continue;
+				};
+				if vote.block_height == block.block_height && vote.block_hash != block.block_hash {
+					Self::record_disagreement(operator, &vote, &block);
+				}
+			}
+			<ConfirmedBitcoinBlockTip<T>>::put(block);
 			Ok(Pays::No.into())
 		}
 
-		/// Sets the oracle operator account id (only executable by the Root account)
+		/// Sets the oracle operator accounts and how many of them must agree on a confirmed
+		/// bitcoin block (only executable by the Root account)
 		///
 		/// # Arguments
-		/// * `account_id` - the account id of the operator
-		#[pallet::call_index(2)]
+		/// * `operators` - the account ids of the operators
+		/// * `confirmation_threshold` - the number of operators that must submit the same block
+		#[pallet::call_index(3)]
 		#[pallet::weight(0)]
-		pub fn set_operator(origin: OriginFor<T>, account_id: T::AccountId) -> DispatchResult {
+		pub fn set_operators(
+			origin: OriginFor<T>,
+			operators: BoundedVec<T::AccountId, T::MaxOracleOperators>,
+			confirmation_threshold: u32,
+		) -> DispatchResult {
 			ensure_root(origin)?;
-			<OracleOperatorAccount<T>>::put(account_id.clone());
+			Self::ensure_valid_operators(&operators, confirmation_threshold)?;
+			for operator in <OracleOperatorAccounts<T>>::get() {
+				if !operators.contains(&operator) {
+					<OracleConfirmedBlockVotes<T>>::remove(&operator);
+				}
+			}
+			<OracleOperatorAccounts<T>>::put(operators.clone());
+			<OracleConfirmationThreshold<T>>::put(confirmation_threshold);
+			Self::deposit_event(Event::OracleOperatorsSet {
+				operators: operators.into_inner(),
+				confirmation_threshold,
+			});
 			Ok(())
 		}
+
+		/// Restarts the bitcoin header chain from a new checkpoint (only executable by the Root
+		/// account)
+		///
+		/// # Arguments
+		/// * `block_height` - the height of the checkpoint block
+		/// * `header` - the header of the checkpoint block. Outside of regtest, this must be the
+		///   first block of a difficulty adjustment period
+		#[pallet::call_index(4)]
+		#[pallet::weight(0)]
+		pub fn set_bitcoin_header_checkpoint(
+			origin: OriginFor<T>,
+			block_height: BitcoinHeight,
+			header: OpaqueBitcoinHeader,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			Self::set_header_checkpoint(block_height, header)
+		}
 	}
 
 	impl<T: Config> BitcoinUtxoTracker for Pallet<T> {
@@ -384,6 +602,313 @@ pub mod pallet {
 	}
 
 	impl<T: Config> Pallet<T> {
+		pub(crate) fn ensure_valid_operators(
+			operators: &[T::AccountId],
+			confirmation_threshold: u32,
+		) -> Result<(), Error<T>> {
+			ensure!(
+				confirmation_threshold > 0 && confirmation_threshold as usize <= operators.len(),
+				Error::<T>::InvalidOracleThreshold
+			);
+			for (i, operator) in operators.iter().enumerate() {
+				ensure!(
+					!operators[i + 1..].contains(operator),
+					Error::<T>::DuplicateOracleOperator
+				);
+			}
+			Ok(())
+		}
+
+		pub fn bitcoin_header_tip() -> Option<BitcoinBlock> {
+			<BitcoinHeaderTip<T>>::get()
+		}
+
+		pub fn bitcoin_header_hash(block_height: BitcoinHeight) -> Option<BitcoinBlockHash> {
+			<BitcoinHeaderHashByHeight<T>>::get(block_height)
+		}
+
+		pub(crate) fn set_header_checkpoint(
+			block_height: BitcoinHeight,
+			header: OpaqueBitcoinHeader,
+		) -> DispatchResult {
+			let (block_hash, entry) = argon_bitcoin::create_header_checkpoint(
+				<BitcoinNetwork<T>>::get(),
+				block_height,
+				&header,
+			)
+			.map_err(|_| Error::<T>::InvalidBitcoinHeader)?;
+			let _ = <BitcoinHeaders<T>>::clear(u32::MAX, None);
+			let _ = <BitcoinHeaderHashByHeight<T>>::clear(u32::MAX, None);
+			let _ = <UtxoChangesByBitcoinBlock<T>>::clear(u32::MAX, None);
+			<BitcoinHeaders<T>>::insert(&block_hash, entry);
+			<BitcoinHeaderHashByHeight<T>>::insert(block_height, &block_hash);
+			<BitcoinHeaderTip<T>>::put(BitcoinBlock::new(block_height, block_hash.clone()));
+			Self::deposit_event(Event::BitcoinHeaderTipChanged { block_height, block_hash });
+			Ok(())
+		}
+
+		/// Adds headers that extend the header chain. The headers may fork from an earlier block
+		/// in the chain, but must then have more accumulated proof of work than the current tip.
+		/// Returns the last block in common with the previous chain if blocks were reorged out.
+		fn import_headers(
+			headers: Vec<OpaqueBitcoinHeader>,
+		) -> Result<Option<BitcoinBlock>, DispatchError> {
+			ensure!(
+				headers.len() as u32 <= T::MaxBitcoinHeadersPerSync::get(),
+				Error::<T>::TooManyBitcoinHeaders
+			);
+			let tip = <BitcoinHeaderTip<T>>::get().ok_or(Error::<T>::BitcoinHeaderNotFound)?;
+			let tip_entry = <BitcoinHeaders<T>>::get(&tip.block_hash)
+				.ok_or(Error::<T>::BitcoinHeaderNotFound)?;
+			let network = <BitcoinNetwork<T>>::get();
+			let now = T::UnixTime::now().as_secs();
+
+			let first = headers.first().ok_or(Error::<T>::InvalidBitcoinHeader)?;
+			let mut previous_hash: BitcoinBlockHash = argon_bitcoin::decode_header(first)
+				.map_err(|_| Error::<T>::InvalidBitcoinHeader)?
+				.prev_blockhash
+				.into();
+			let mut previous = <BitcoinHeaders<T>>::get(&previous_hash)
+				.ok_or(Error::<T>::BitcoinHeaderNotFound)?;
+			let mut verified = vec![];
+			for header in headers {
+				let (block_hash, entry) = argon_bitcoin::verify_next_header(
+					network.clone(),
+					&previous_hash,
+					&previous,
+					&header,
+					now,
+				)
+				.map_err(|_| Error::<T>::InvalidBitcoinHeader)?;
+				verified.push((block_hash.clone(), entry.clone()));
+				previous_hash = block_hash;
+				previous = entry;
+			}
+			ensure!(
+				previous.chain_work > tip_entry.chain_work,
+				Error::<T>::InsufficientBitcoinHeaderWork
+			);
+
+			// only the best chain is kept, so drop any blocks that were reorged out
+			let fork_height = verified[0].1.block_height;
+			// the oracle confirmed block can only be reorged out once the oracles confirm a block
+			// of the new chain
+			if let Some(confirmed) = <ConfirmedBitcoinBlockTip<T>>::get() {
+				if confirmed.block_height >= fork_height &&
+					confirmed.block_height <= tip.block_height
+				{
+					let new_hash = verified
+						.iter()
+						.find(|(_, entry)| entry.block_height == confirmed.block_height)
+						.map(|(hash, _)| hash);
+					ensure!(
+						new_hash == Some(&confirmed.block_hash),
+						Error::<T>::ConfirmedBlockNotInHeaderChain
+					);
+				}
+			}
+			let common_block = BitcoinBlock::new(
+				fork_height.saturating_sub(1),
+				verified[0].1.previous_block_hash.clone(),
+			);
+			let mut reorged = vec![];
+			for block_height in fork_height..=tip.block_height {
+				if let Some(block_hash) = <BitcoinHeaderHashByHeight<T>>::take(block_height) {
+					<BitcoinHeaders<T>>::remove(&block_hash);
+					reorged.push(block_hash);
+				}
+			}
+			// undo the newest changes first
+			for block_hash in reorged.iter().rev() {
+				Self::revert_block_changes(block_hash);
+			}
+			for (block_hash, entry) in verified {
+				<BitcoinHeaderHashByHeight<T>>::insert(entry.block_height, &block_hash);
+				<BitcoinHeaders<T>>::insert(block_hash, entry);
+			}
+
+			let history = T::MaxBitcoinHeaderHistory::get();
+			let prune_to = previous.block_height.saturating_sub(history);
+			for block_height in tip.block_height.saturating_sub(history)..prune_to {
+				if let Some(block_hash) = <BitcoinHeaderHashByHeight<T>>::take(block_height) {
+					<BitcoinHeaders<T>>::remove(&block_hash);
+					<UtxoChangesByBitcoinBlock<T>>::remove(block_hash);
+				}
+			}
+
+			<BitcoinHeaderTip<T>>::put(BitcoinBlock::new(
+				previous.block_height,
+				previous_hash.clone(),
+			));
+			Self::deposit_event(Event::BitcoinHeaderTipChanged {
+				block_height: previous.block_height,
+				block_hash: previous_hash,
+			});
+			Ok((!reorged.is_empty()).then_some(common_block))
+		}
+
+		/// Reverts the utxo changes that depended on a bitcoin block that is no longer in the
+		/// header chain
+		fn revert_block_changes(block_hash: &BitcoinBlockHash) {
+			for change in <UtxoChangesByBitcoinBlock<T>>::take(block_hash).into_iter().rev() {
+				let (utxo_id, res) = match change {
+					UtxoStateChange::Verified { utxo_id, utxo_ref } => (
+						utxo_id,
+						with_storage_layer(|| {
+							Self::revert_utxo_verified(utxo_id, utxo_ref, block_hash)
+						}),
+					),
+					UtxoStateChange::Spent { utxo_id, utxo_ref, utxo_value } => (
+						utxo_id,
+						with_storage_layer(|| {
+							Self::revert_utxo_spent(utxo_ref, utxo_value, block_hash)
+						}),
+					),
+				};
+				if let Err(e) = res {
+					warn!("Failed to revert UTXO {} after a bitcoin reorg: {:?}", utxo_id, e);
+					Self::deposit_event(Event::UtxoRevertError { utxo_id, error: e });
+				}
+			}
+		}
+
+		fn record_utxo_change(block_hash: &BitcoinBlockHash, change: UtxoStateChange) {
+			<UtxoChangesByBitcoinBlock<T>>::append(block_hash, change);
+		}
+
+		fn revert_utxo_verified(
+			utxo_id: UtxoId,
+			utxo_ref: UtxoRef,
+			block_hash: &BitcoinBlockHash,
+		) -> DispatchResult {
+			// the utxo might have been unwatched since
+			if <UtxoIdToRef<T>>::get(utxo_id) != Some(utxo_ref.clone()) {
+				return Ok(());
+			}
+			let Some(utxo_value) = <LockedUtxos<T>>::take(&utxo_ref) else {
+				return Ok(());
+			};
+			<UtxoIdToRef<T>>::remove(utxo_id);
+			if <LockedUtxoExpirationsByBlock<T>>::contains_key(
+				utxo_value.watch_for_spent_until_height,
+			) {
+				<LockedUtxoExpirationsByBlock<T>>::mutate(
+					utxo_value.watch_for_spent_until_height,
+					|utxos| utxos.retain(|a| *a != utxo_ref),
+				);
+			}
+			<UtxosPendingConfirmation<T>>::try_mutate(|pending| {
+				pending
+					.try_insert(utxo_id, utxo_value)
+					.map_err(|_| Error::<T>::MaxUtxosExceeded)
+			})?;
+			T::EventHandler::utxo_verification_reverted(utxo_id)?;
+			Self::deposit_event(Event::UtxoVerificationReverted {
+				utxo_id,
+				block_hash: block_hash.clone(),
+			});
+			Ok(())
+		}
+
+		fn revert_utxo_spent(
+			utxo_ref: UtxoRef,
+			utxo_value: UtxoValue,
+			block_hash: &BitcoinBlockHash,
+		) -> DispatchResult {
+			let utxo_id = utxo_value.utxo_id;
+			if <LockedUtxos<T>>::contains_key(&utxo_ref) {
+				return Ok(());
+			}
+			<LockedUtxoExpirationsByBlock<T>>::try_mutate(
+				utxo_value.watch_for_spent_until_height,
+				|utxos| -> DispatchResult {
+					if !utxos.contains(&utxo_ref) {
+						utxos
+							.try_push(utxo_ref.clone())
+							.map_err(|_| Error::<T>::MaxUtxosExceeded)?;
+					}
+					Ok(())
+				},
+			)?;
+			<UtxoIdToRef<T>>::insert(utxo_id, &utxo_ref);
+			<LockedUtxos<T>>::insert(utxo_ref, utxo_value);
+			T::EventHandler::utxo_spend_reverted(utxo_id)?;
+			Self::deposit_event(Event::UtxoSpendReverted {
+				utxo_id,
+				block_hash: block_hash.clone(),
+			});
+			Ok(())
+		}
+
+		/// Verifies a utxo with proof that it was created in a confirmed block of the header chain
+		fn utxo_proven(
+			utxo_id: UtxoId,
+			utxo_ref: UtxoRef,
+			proof: Option<&BitcoinTxInclusionProof>,
+			confirmed: &BitcoinBlock,
+		) -> DispatchResult {
+			let Some(entry) = <UtxosPendingConfirmation<T>>::get().get(&utxo_id).cloned() else {
+				return Ok(());
+			};
+			// without a header chain (signet, or until a checkpoint is set after an upgrade), utxos
+			// are verified by the block author's node
+			if !argon_bitcoin::supports_spv(<BitcoinNetwork<T>>::get()) ||
+				!<BitcoinHeaderTip<T>>::exists()
+			{
+				return Self::utxo_verified(utxo_id, utxo_ref);
+			}
+			let proof = proof.ok_or(Error::<T>::MissingInclusionProof)?;
+			let header = <BitcoinHeaders<T>>::get(&proof.block_hash)
+				.ok_or(Error::<T>::BitcoinHeaderNotFound)?;
+			ensure!(
+				header.block_height <= confirmed.block_height,
+				Error::<T>::BitcoinHeightNotConfirmed
+			);
+			// the header chain and oracles must agree, so the block is an ancestor of the confirmed
+			// block
+			ensure!(
+				<BitcoinHeaderHashByHeight<T>>::get(confirmed.block_height).as_ref() ==
+					Some(&confirmed.block_hash),
+				Error::<T>::ConfirmedBlockNotInHeaderChain
+			);
+			let satoshis = argon_bitcoin::verify_utxo_inclusion(
+				&header,
+				proof,
+				&utxo_ref,
+				entry.script_pubkey,
+			)
+			.map_err(|_| Error::<T>::InvalidInclusionProof)?;
+			if satoshis != entry.satoshis {
+				return Self::utxo_rejected(utxo_id, BitcoinRejectedReason::SatoshisMismatch);
+			}
+			Self::utxo_verified(utxo_id, utxo_ref.clone())?;
+			// duplicates are rejected instead of locked
+			if <UtxoIdToRef<T>>::get(utxo_id).as_ref() == Some(&utxo_ref) {
+				Self::record_utxo_change(
+					&proof.block_hash,
+					UtxoStateChange::Verified { utxo_id, utxo_ref },
+				);
+			}
+			Ok(())
+		}
+
+		fn record_disagreement(
+			operator: T::AccountId,
+			block: &BitcoinBlock,
+			confirmed_block: &BitcoinBlock,
+		) {
+			warn!(
+				"Bitcoin oracle operator {:?} submitted block {:?} at height {}, but {:?} is confirmed",
+				operator, block.block_hash, block.block_height, confirmed_block.block_hash
+			);
+			<OracleOperatorDisagreements<T>>::mutate(&operator, |a| *a = a.saturating_add(1));
+			Self::deposit_event(Event::OracleDisagreement {
+				operator,
+				block: block.clone(),
+				confirmed: confirmed_block.clone(),
+			});
+		}
+
 		pub fn get_sync_status() -> Option<BitcoinSyncStatus> {
 			let confirmed_block = ConfirmedBitcoinBlockTip::<T>::get()?;
 			let synched_block = SynchedBitcoinBlock::<T>::get();
@@ -462,9 +987,15 @@ pub mod pallet {
 				Self::reject_utxo(utxo_id, BitcoinRejectedReason::Spent)?;
 			}
 			if let Some(utxo_ref) = <UtxoIdToRef<T>>::take(utxo_id) {
-				if <LockedUtxos<T>>::take(utxo_ref.clone()).is_some() {
+				if let Some(utxo_value) = <LockedUtxos<T>>::take(utxo_ref.clone()) {
 					T::EventHandler::utxo_spent(utxo_id)?;
 					Self::deposit_event(Event::UtxoSpent { utxo_id, block_height });
+					if let Some(block_hash) = <BitcoinHeaderHashByHeight<T>>::get(block_height) {
+						Self::record_utxo_change(
+							&block_hash,
+							UtxoStateChange::Spent { utxo_id, utxo_ref, utxo_value },
+						);
+					}
 				}
 			}
 			Ok(())
@@ -0,0 +1,60 @@
+use crate::{
+	pallet::{OracleConfirmationThreshold, OracleOperatorAccounts},
+	Config, Pallet,
+};
+use alloc::vec;
+use frame_support::{
+	migrations::VersionedMigration, pallet_prelude::*, traits::UncheckedOnRuntimeUpgrade,
+};
+use log::info;
+
+/// Moves the single bitcoin oracle operator into the set of oracle operators, with a
+/// confirmation threshold of one
+pub mod v1 {
+	use super::*;
+
+	#[frame_support::storage_alias]
+	pub(super) type OracleOperatorAccount<T: Config> =
+		StorageValue<Pallet<T>, <T as frame_system::Config>::AccountId, OptionQuery>;
+
+	pub struct InnerMigrateV0ToV1<T: Config>(core::marker::PhantomData<T>);
+
+	impl<T: Config> UncheckedOnRuntimeUpgrade for InnerMigrateV0ToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let Some(operator) = OracleOperatorAccount::<T>::take() else {
+				return T::DbWeight::get().reads(1);
+			};
+			info!("Migrating bitcoin oracle operator {:?} to the oracle operators", operator);
+			OracleOperatorAccounts::<T>::put(BoundedVec::truncate_from(vec![operator]));
+			OracleConfirmationThreshold::<T>::put(1);
+			T::DbWeight::get().reads_writes(1, 3)
+		}
+	}
+
+	pub type MigrateV0ToV1<T> = VersionedMigration<
+		0,
+		1,
+		InnerMigrateV0ToV1<T>,
+		Pallet<T>,
+		<T as frame_system::Config>::DbWeight,
+	>;
+
+	#[cfg(test)]
+	mod test {
+		use super::*;
+		use crate::mock::{new_test_ext, Test};
+
+		#[test]
+		fn migrates_the_oracle_operator() {
+			new_test_ext().execute_with(|| {
+				OracleOperatorAccount::<Test>::put(5);
+
+				InnerMigrateV0ToV1::<Test>::on_runtime_upgrade();
+
+				assert_eq!(OracleOperatorAccount::<Test>::get(), None);
+				assert_eq!(OracleOperatorAccounts::<Test>::get().to_vec(), vec![5]);
+				assert_eq!(OracleConfirmationThreshold::<Test>::get(), 1);
+			});
+		}
+	}
+}
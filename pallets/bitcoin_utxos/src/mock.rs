@@ -1,6 +1,8 @@
+use core::time::Duration;
 use env_logger::{Builder, Env};
-use frame_support::{derive_impl, parameter_types};
+use frame_support::{derive_impl, parameter_types, traits::UnixTime};
 use sp_runtime::{BuildStorage, DispatchResult};
+use std::collections::BTreeSet;
 
 use argon_primitives::{
 	bitcoin::{BitcoinRejectedReason, UtxoId},
@@ -30,30 +32,57 @@ parameter_types! {
 	pub const MaxPendingConfirmationUtxos: u32 = 10;
 
 	pub const MaxPendingConfirmationBlocks: u32 = 10;
+	pub const MaxOracleOperators: u32 = 5;
+	pub const MaxBitcoinHeaderHistory: u32 = 100;
+	pub const MaxBitcoinHeadersPerSync: u32 = 10;
 	pub static UtxoVerifiedCallback: Option<fn(UtxoId) -> DispatchResult> = None;
+	pub static RevertedUtxos: Vec<UtxoId> = vec![];
+	/// The utxos a bond pallet would have as verified (and not spent)
+	pub static BondVerifiedUtxos: BTreeSet<UtxoId> = BTreeSet::new();
+	pub static CurrentUnixTime: u64 = 1_700_000_000;
+}
+
+pub struct StaticUnixTime;
+impl UnixTime for StaticUnixTime {
+	fn now() -> Duration {
+		Duration::from_secs(CurrentUnixTime::get())
+	}
 }
 
 pub struct StaticEventHandler;
 impl BitcoinUtxoEvents for StaticEventHandler {
-	fn utxo_verified(_utxo_id: UtxoId) -> DispatchResult {
+	fn utxo_verified(utxo_id: UtxoId) -> DispatchResult {
 		if let Some(callback) = UtxoVerifiedCallback::get() {
-			callback(_utxo_id)
-		} else {
-			Ok(())
+			callback(utxo_id)?;
 		}
+		BondVerifiedUtxos::mutate(|a| a.insert(utxo_id));
+		Ok(())
 	}
 
 	fn utxo_rejected(_utxo_id: UtxoId, _reason: BitcoinRejectedReason) -> DispatchResult {
 		Ok(())
 	}
 
-	fn utxo_spent(_utxo_id: UtxoId) -> DispatchResult {
+	fn utxo_spent(utxo_id: UtxoId) -> DispatchResult {
+		BondVerifiedUtxos::mutate(|a| a.remove(&utxo_id));
 		Ok(())
 	}
 
 	fn utxo_expired(_utxo_id: UtxoId) -> DispatchResult {
 		Ok(())
 	}
+
+	fn utxo_verification_reverted(utxo_id: UtxoId) -> DispatchResult {
+		RevertedUtxos::mutate(|a| a.push(utxo_id));
+		BondVerifiedUtxos::mutate(|a| a.remove(&utxo_id));
+		Ok(())
+	}
+
+	fn utxo_spend_reverted(utxo_id: UtxoId) -> DispatchResult {
+		RevertedUtxos::mutate(|a| a.push(utxo_id));
+		BondVerifiedUtxos::mutate(|a| a.insert(utxo_id));
+		Ok(())
+	}
 }
 
 impl pallet_bitcoin_utxos::Config for Test {
@@ -61,6 +90,10 @@ impl pallet_bitcoin_utxos::Config for Test {
 	type WeightInfo = ();
 	type MaxPendingConfirmationUtxos = MaxPendingConfirmationUtxos;
 	type MaxPendingConfirmationBlocks = MaxPendingConfirmationBlocks;
+	type MaxOracleOperators = MaxOracleOperators;
+	type MaxBitcoinHeaderHistory = MaxBitcoinHeaderHistory;
+	type MaxBitcoinHeadersPerSync = MaxBitcoinHeadersPerSync;
+	type UnixTime = StaticUnixTime;
 	type EventHandler = StaticEventHandler;
 }
 
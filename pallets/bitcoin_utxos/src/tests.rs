@@ -1,22 +1,35 @@
 use std::collections::BTreeMap;
 
+use argon_bitcoin::{create_merkle_branch, genesis_header};
+use bitcoin::{
+	absolute::LockTime,
+	block::{Header, Version},
+	consensus::serialize,
+	hashes::Hash,
+	transaction, Amount, Block, CompactTarget, OutPoint, ScriptBuf, Sequence, Transaction, TxIn,
+	TxMerkleNode, TxOut, Txid, Witness,
+};
+
 use argon_primitives::{
 	bitcoin::{
-		BitcoinBlock, BitcoinCosignScriptPubkey, BitcoinHeight, BitcoinRejectedReason, H256Le,
-		UtxoRef, UtxoValue,
+		BitcoinBlock, BitcoinCosignScriptPubkey, BitcoinHeight, BitcoinNetwork,
+		BitcoinRejectedReason, BitcoinTxInclusionProof, H256Le, Satoshis, UtxoId, UtxoRef,
+		UtxoValue,
 	},
 	inherents::BitcoinUtxoSync,
 	BitcoinUtxoTracker,
 };
 use frame_support::{assert_err, assert_noop, assert_ok, pallet_prelude::Hooks};
-use sp_core::H256;
+use sp_core::{bounded_vec, H256};
 use sp_runtime::DispatchError;
 
 use crate::{
 	mock::{System, *},
 	pallet::{
-		ConfirmedBitcoinBlockTip, InherentIncluded, LockedUtxoExpirationsByBlock, LockedUtxos,
-		UtxoIdToRef, UtxosPendingConfirmation,
+		BitcoinHeaderHashByHeight, BitcoinHeaderTip, BitcoinHeaders, ConfirmedBitcoinBlockTip,
+		InherentIncluded, LockedUtxoExpirationsByBlock, LockedUtxos, OracleConfirmedBlockVotes,
+		OracleOperatorDisagreements, SynchedBitcoinBlock, UtxoChangesByBitcoinBlock, UtxoIdToRef,
+		UtxosPendingConfirmation,
 	},
 	Error, Event,
 };
@@ -31,7 +44,7 @@ fn only_an_operator_can_submit_oracle_block() {
 			Error::<Test>::NoPermissions
 		);
 
-		assert_ok!(BitcoinUtxos::set_operator(RuntimeOrigin::root(), who));
+		assert_ok!(BitcoinUtxos::set_operators(RuntimeOrigin::root(), bounded_vec![who], 1));
 		assert_ok!(BitcoinUtxos::set_confirmed_block(
 			RuntimeOrigin::signed(who),
 			1,
@@ -44,6 +57,88 @@ fn only_an_operator_can_submit_oracle_block() {
 	});
 }
 
+#[test]
+fn requires_a_threshold_of_operators_to_confirm_a_block() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			BitcoinUtxos::set_operators(RuntimeOrigin::root(), bounded_vec![1, 2, 3], 4),
+			Error::<Test>::InvalidOracleThreshold
+		);
+		assert_noop!(
+			BitcoinUtxos::set_operators(RuntimeOrigin::root(), bounded_vec![1, 2, 1], 2),
+			Error::<Test>::DuplicateOracleOperator
+		);
+		assert_ok!(BitcoinUtxos::set_operators(RuntimeOrigin::root(), bounded_vec![1, 2, 3], 2));
+
+		assert_ok!(BitcoinUtxos::set_confirmed_block(
+			RuntimeOrigin::signed(1),
+			10,
+			H256Le([1; 32])
+		));
+		assert_eq!(ConfirmedBitcoinBlockTip::<Test>::get(), None);
+		// a single compromised operator can't move the tip
+		assert_ok!(BitcoinUtxos::set_confirmed_block(
+			RuntimeOrigin::signed(2),
+			10,
+			H256Le([2; 32])
+		));
+		assert_eq!(ConfirmedBitcoinBlockTip::<Test>::get(), None);
+
+		assert_ok!(BitcoinUtxos::set_confirmed_block(
+			RuntimeOrigin::signed(3),
+			10,
+			H256Le([1; 32])
+		));
+		assert_eq!(
+			ConfirmedBitcoinBlockTip::<Test>::get(),
+			Some(BitcoinBlock { block_height: 10, block_hash: H256Le([1; 32]) })
+		);
+		assert_eq!(OracleOperatorDisagreements::<Test>::get(2), 1);
+		System::assert_has_event(
+			Event::<Test>::OracleDisagreement {
+				operator: 2,
+				block: BitcoinBlock { block_height: 10, block_hash: H256Le([2; 32]) },
+				confirmed: BitcoinBlock { block_height: 10, block_hash: H256Le([1; 32]) },
+			}
+			.into(),
+		);
+
+		// a late submission of a conflicting hash is also tracked
+		assert_ok!(BitcoinUtxos::set_confirmed_block(
+			RuntimeOrigin::signed(2),
+			10,
+			H256Le([3; 32])
+		));
+		assert_eq!(OracleOperatorDisagreements::<Test>::get(2), 2);
+		assert_eq!(OracleOperatorDisagreements::<Test>::get(1), 0);
+
+		assert_ok!(BitcoinUtxos::set_confirmed_block(
+			RuntimeOrigin::signed(1),
+			11,
+			H256Le([4; 32])
+		));
+		assert_eq!(ConfirmedBitcoinBlockTip::<Test>::get().map(|a| a.block_height), Some(10));
+		assert_ok!(BitcoinUtxos::set_confirmed_block(
+			RuntimeOrigin::signed(2),
+			11,
+			H256Le([4; 32])
+		));
+		assert_eq!(
+			ConfirmedBitcoinBlockTip::<Test>::get(),
+			Some(BitcoinBlock { block_height: 11, block_hash: H256Le([4; 32]) })
+		);
+
+		assert_noop!(
+			BitcoinUtxos::set_confirmed_block(RuntimeOrigin::signed(4), 12, H256Le([5; 32])),
+			Error::<Test>::NoPermissions
+		);
+		assert_ok!(BitcoinUtxos::set_operators(RuntimeOrigin::root(), bounded_vec![1, 2], 2));
+		assert_eq!(OracleConfirmedBlockVotes::<Test>::get(3), None);
+		assert!(OracleConfirmedBlockVotes::<Test>::get(1).is_some());
+	});
+}
+
 #[test]
 fn can_watch_utxos() {
 	new_test_ext().execute_with(|| {
@@ -86,6 +181,8 @@ fn can_watch_utxos() {
 				invalid: Default::default(),
 				spent: Default::default(),
 				sync_to_block: BitcoinBlock { block_height: 10, block_hash: H256Le([0; 32]) },
+				headers: Default::default(),
+				inclusion_proofs: Default::default(),
 			},
 		));
 		System::assert_last_event(
@@ -109,6 +206,8 @@ fn it_requires_block_sync_to_be_newer() {
 			invalid: Default::default(),
 			spent: Default::default(),
 			sync_to_block: BitcoinBlock { block_height: 2, block_hash: H256Le([0; 32]) },
+			headers: Default::default(),
+			inclusion_proofs: Default::default(),
 		};
 		ConfirmedBitcoinBlockTip::<Test>::put(BitcoinBlock {
 			block_height: 1,
@@ -142,22 +241,24 @@ fn it_requires_block_sync_to_be_newer() {
 fn it_should_move_utxos_to_lock_once_verified() {
 	new_test_ext().execute_with(|| {
 		System::set_block_number(1);
+		let genesis = set_genesis_checkpoint();
 
 		let script_pubkey = make_pubkey([0u8; 34]);
 		let satoshis = 100;
 		let watch_for_spent_until = 10;
-		ConfirmedBitcoinBlockTip::<Test>::put(BitcoinBlock {
-			block_height: 2,
-			block_hash: H256Le([0; 32]),
-		});
+		let block = mine_block(&genesis, vec![create_tx(1, script_pubkey, satoshis)]);
+		let block2 = mine_block(&block.header, vec![create_tx(2, script_pubkey, satoshis)]);
+		ConfirmedBitcoinBlockTip::<Test>::put(BitcoinBlock::new(2, block2.block_hash().into()));
 		assert_ok!(BitcoinUtxos::watch_for_utxo(1, script_pubkey, satoshis, watch_for_spent_until),);
-		let utxo_ref = UtxoRef { txid: H256Le([0; 32]), output_index: 0 };
+		let utxo_ref = create_utxo_ref(&block, 0);
 
 		let utxo_sync = BitcoinUtxoSync {
 			verified: BTreeMap::from([(1, utxo_ref.clone())]),
 			invalid: Default::default(),
 			spent: Default::default(),
-			sync_to_block: BitcoinBlock { block_height: 1, block_hash: H256Le([0; 32]) },
+			sync_to_block: BitcoinBlock { block_height: 1, block_hash: block.block_hash().into() },
+			headers: vec![(&block.header).into(), (&block2.header).into()],
+			inclusion_proofs: BTreeMap::from([(1, create_proof(&block, 0))]),
 		};
 		assert_ok!(BitcoinUtxos::sync(RuntimeOrigin::none(), utxo_sync),);
 		System::assert_last_event(Event::UtxoVerified { utxo_id: 1 }.into());
@@ -189,6 +290,8 @@ fn it_should_move_utxos_to_lock_once_verified() {
 				invalid: Default::default(),
 				spent: Default::default(),
 				sync_to_block: BitcoinBlock { block_height: 10, block_hash: H256Le([0; 32]) },
+				headers: Default::default(),
+				inclusion_proofs: Default::default(),
 			},
 		));
 		System::assert_last_event(Event::UtxoUnwatched { utxo_id: 1 }.into());
@@ -202,25 +305,43 @@ fn it_should_move_utxos_to_lock_once_verified() {
 fn it_should_block_duplicated_utxos() {
 	new_test_ext().execute_with(|| {
 		System::set_block_number(1);
-		ConfirmedBitcoinBlockTip::<Test>::put(BitcoinBlock {
-			block_height: 2,
-			block_hash: H256Le([0; 32]),
-		});
+		let genesis = set_genesis_checkpoint();
+		let block = mine_block(&genesis, vec![create_tx(1, make_pubkey([0u8; 34]), 100)]);
+		let block2 = mine_block(&block.header, vec![create_tx(2, make_pubkey([1u8; 34]), 100)]);
+		ConfirmedBitcoinBlockTip::<Test>::put(BitcoinBlock::new(2, block2.block_hash().into()));
 
 		assert_ok!(BitcoinUtxos::watch_for_utxo(1, make_pubkey([0u8; 34]), 100, 100),);
-		assert_ok!(BitcoinUtxos::watch_for_utxo(2, make_pubkey([1u8; 34]), 101, 100),);
-		let utxo_ref = UtxoRef { txid: H256Le([0; 32]), output_index: 0 };
+		let utxo_ref = create_utxo_ref(&block, 0);
 
 		assert_ok!(BitcoinUtxos::sync(
 			RuntimeOrigin::none(),
 			BitcoinUtxoSync {
-				verified: BTreeMap::from([(1, utxo_ref.clone()), (2, utxo_ref.clone())]),
+				verified: BTreeMap::from([(1, utxo_ref.clone())]),
 				invalid: Default::default(),
 				spent: Default::default(),
 				sync_to_block: BitcoinBlock { block_height: 1, block_hash: H256Le([0; 32]) },
+				headers: vec![(&block.header).into(), (&block2.header).into()],
+				inclusion_proofs: BTreeMap::from([(1, create_proof(&block, 0))]),
 			}
 		),);
 		System::assert_has_event(Event::UtxoVerified { utxo_id: 1 }.into());
+
+		// simulate next block
+		BitcoinUtxos::on_finalize(1);
+		InherentIncluded::<Test>::set(false);
+		// the same script can be watched again once the first utxo is locked
+		assert_ok!(BitcoinUtxos::watch_for_utxo(2, make_pubkey([0u8; 34]), 100, 100),);
+		assert_ok!(BitcoinUtxos::sync(
+			RuntimeOrigin::none(),
+			BitcoinUtxoSync {
+				verified: BTreeMap::from([(2, utxo_ref.clone())]),
+				invalid: Default::default(),
+				spent: Default::default(),
+				sync_to_block: BitcoinBlock { block_height: 2, block_hash: H256Le([0; 32]) },
+				headers: Default::default(),
+				inclusion_proofs: BTreeMap::from([(2, create_proof(&block, 0))]),
+			}
+		),);
 		System::assert_has_event(
 			Event::UtxoRejected {
 				utxo_id: 2,
@@ -254,15 +375,21 @@ fn it_should_block_duplicated_utxos() {
 fn it_should_preserve_storage_if_one_sync_fails() {
 	new_test_ext().execute_with(|| {
 		System::set_block_number(1);
-		ConfirmedBitcoinBlockTip::<Test>::put(BitcoinBlock {
-			block_height: 2,
-			block_hash: H256Le([0; 32]),
-		});
+		let genesis = set_genesis_checkpoint();
+		let block = mine_block(
+			&genesis,
+			vec![
+				create_tx(1, make_pubkey([0u8; 34]), 100),
+				create_tx(2, make_pubkey([1u8; 34]), 101),
+			],
+		);
+		let block2 = mine_block(&block.header, vec![create_tx(3, make_pubkey([2u8; 34]), 100)]);
+		ConfirmedBitcoinBlockTip::<Test>::put(BitcoinBlock::new(2, block2.block_hash().into()));
 
 		assert_ok!(BitcoinUtxos::watch_for_utxo(1, make_pubkey([0u8; 34]), 100, 100),);
 		assert_ok!(BitcoinUtxos::watch_for_utxo(2, make_pubkey([1u8; 34]), 101, 100),);
-		let utxo_ref = UtxoRef { txid: H256Le([0; 32]), output_index: 0 };
-		let utxo_ref_2 = UtxoRef { txid: H256Le([1; 32]), output_index: 1 };
+		let utxo_ref = create_utxo_ref(&block, 0);
+		let utxo_ref_2 = create_utxo_ref(&block, 1);
 		UtxoVerifiedCallback::set(Some(|id| {
 			if id == 2 {
 				return Err(Error::<Test>::NoPermissions.into());
@@ -277,6 +404,11 @@ fn it_should_preserve_storage_if_one_sync_fails() {
 				invalid: Default::default(),
 				spent: Default::default(),
 				sync_to_block: BitcoinBlock { block_height: 1, block_hash: H256Le([0; 32]) },
+				headers: vec![(&block.header).into(), (&block2.header).into()],
+				inclusion_proofs: BTreeMap::from([
+					(1, create_proof(&block, 0)),
+					(2, create_proof(&block, 1)),
+				]),
 			}
 		),);
 		System::assert_has_event(Event::UtxoVerified { utxo_id: 1 }.into());
@@ -309,8 +441,522 @@ fn it_should_preserve_storage_if_one_sync_fails() {
 	});
 }
 
+#[test]
+fn it_tracks_the_bitcoin_header_chain() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let genesis = set_genesis_checkpoint();
+		let genesis_block = BitcoinBlock::new(0, genesis.block_hash().into());
+		ConfirmedBitcoinBlockTip::<Test>::put(genesis_block.clone());
+		let sync_headers = |headers: Vec<&Block>| {
+			InherentIncluded::<Test>::set(false);
+			assert_ok!(BitcoinUtxos::sync(
+				RuntimeOrigin::none(),
+				BitcoinUtxoSync {
+					verified: Default::default(),
+					invalid: Default::default(),
+					spent: Default::default(),
+					sync_to_block: genesis_block.clone(),
+					headers: headers.into_iter().map(|a| (&a.header).into()).collect(),
+					inclusion_proofs: Default::default(),
+				}
+			));
+		};
+
+		let block1 = mine_block(&genesis, vec![create_tx(1, make_pubkey([0u8; 34]), 100)]);
+		let block2 = mine_block(&block1.header, vec![create_tx(2, make_pubkey([0u8; 34]), 100)]);
+		sync_headers(vec![&block1, &block2]);
+		System::assert_last_event(
+			Event::BitcoinHeaderTipChanged {
+				block_height: 2,
+				block_hash: block2.block_hash().into(),
+			}
+			.into(),
+		);
+		assert_eq!(BitcoinUtxos::bitcoin_header_hash(1), Some(block1.block_hash().into()));
+		assert_eq!(
+			BitcoinUtxos::bitcoin_header_tip(),
+			Some(BitcoinBlock::new(2, block2.block_hash().into()))
+		);
+
+		// headers must connect to the chain
+		let block3 = mine_block(&block2.header, vec![create_tx(3, make_pubkey([0u8; 34]), 100)]);
+		let block4 = mine_block(&block3.header, vec![create_tx(4, make_pubkey([0u8; 34]), 100)]);
+		sync_headers(vec![&block4]);
+		let match_error: DispatchError = Error::<Test>::BitcoinHeaderNotFound.into();
+		System::assert_last_event(
+			Event::BitcoinHeadersRejected { error: match_error.stripped() }.into(),
+		);
+
+		// a fork needs more work than the current chain
+		let fork1 = mine_block(&genesis, vec![create_tx(5, make_pubkey([0u8; 34]), 100)]);
+		let fork2 = mine_block(&fork1.header, vec![create_tx(6, make_pubkey([0u8; 34]), 100)]);
+		sync_headers(vec![&fork1, &fork2]);
+		let match_error: DispatchError = Error::<Test>::InsufficientBitcoinHeaderWork.into();
+		System::assert_last_event(
+			Event::BitcoinHeadersRejected { error: match_error.stripped() }.into(),
+		);
+		assert_eq!(BitcoinUtxos::bitcoin_header_hash(2), Some(block2.block_hash().into()));
+
+		let fork3 = mine_block(&fork2.header, vec![create_tx(7, make_pubkey([0u8; 34]), 100)]);
+		sync_headers(vec![&fork1, &fork2, &fork3]);
+		assert_eq!(
+			BitcoinHeaderTip::<Test>::get(),
+			Some(BitcoinBlock::new(3, fork3.block_hash().into()))
+		);
+		assert_eq!(BitcoinHeaderHashByHeight::<Test>::get(1), Some(fork1.block_hash().into()));
+		assert!(!BitcoinHeaders::<Test>::contains_key(H256Le::from(block1.block_hash())));
+		assert!(!BitcoinHeaders::<Test>::contains_key(H256Le::from(block2.block_hash())));
+
+		// can't reorg out the oracle confirmed block until the oracles confirm the new chain
+		ConfirmedBitcoinBlockTip::<Test>::put(BitcoinBlock::new(1, fork1.block_hash().into()));
+		let block2 = mine_block(&block1.header, vec![create_tx(8, make_pubkey([0u8; 34]), 100)]);
+		let block3 = mine_block(&block2.header, vec![create_tx(9, make_pubkey([0u8; 34]), 100)]);
+		let block4 = mine_block(&block3.header, vec![create_tx(10, make_pubkey([0u8; 34]), 100)]);
+		let longer_chain = vec![&block1, &block2, &block3, &block4];
+		sync_headers(longer_chain.clone());
+		let match_error: DispatchError = Error::<Test>::ConfirmedBlockNotInHeaderChain.into();
+		System::assert_last_event(
+			Event::BitcoinHeadersRejected { error: match_error.stripped() }.into(),
+		);
+		ConfirmedBitcoinBlockTip::<Test>::put(BitcoinBlock::new(1, block1.block_hash().into()));
+		sync_headers(longer_chain);
+		assert_eq!(
+			BitcoinHeaderTip::<Test>::get(),
+			Some(BitcoinBlock::new(4, block4.block_hash().into()))
+		);
+
+		// headers can't be more than 2 hours in the future
+		CurrentUnixTime::set(block4.header.time as u64 - 2 * 60 * 60 - 1);
+		let block5 = mine_block(&block4.header, vec![create_tx(11, make_pubkey([0u8; 34]), 100)]);
+		sync_headers(vec![&block5]);
+		let match_error: DispatchError = Error::<Test>::InvalidBitcoinHeader.into();
+		System::assert_last_event(
+			Event::BitcoinHeadersRejected { error: match_error.stripped() }.into(),
+		);
+	});
+}
+
+#[test]
+fn it_requires_utxos_to_be_proven() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let genesis = set_genesis_checkpoint();
+		ConfirmedBitcoinBlockTip::<Test>::put(BitcoinBlock::new(0, genesis.block_hash().into()));
+
+		for (utxo_id, seed, satoshis) in [(1, 0u8, 100), (2, 1, 101), (3, 2, 102), (4, 3, 103)] {
+			assert_ok!(BitcoinUtxos::watch_for_utxo(
+				utxo_id,
+				make_pubkey([seed; 34]),
+				satoshis,
+				100
+			));
+		}
+		let block1 = mine_block(
+			&genesis,
+			vec![
+				create_tx(1, make_pubkey([0u8; 34]), 100),
+				create_tx(2, make_pubkey([2u8; 34]), 50),
+			],
+		);
+		let block2 = mine_block(&block1.header, vec![create_tx(3, make_pubkey([1u8; 34]), 101)]);
+		ConfirmedBitcoinBlockTip::<Test>::put(BitcoinBlock::new(1, block1.block_hash().into()));
+
+		assert_ok!(BitcoinUtxos::sync(
+			RuntimeOrigin::none(),
+			BitcoinUtxoSync {
+				verified: BTreeMap::from([
+					(1, create_utxo_ref(&block1, 0)),
+					(2, create_utxo_ref(&block2, 0)),
+					(3, create_utxo_ref(&block1, 1)),
+					(4, create_utxo_ref(&block1, 0)),
+				]),
+				invalid: Default::default(),
+				spent: Default::default(),
+				sync_to_block: BitcoinBlock { block_height: 1, block_hash: H256Le([0; 32]) },
+				headers: vec![(&block1.header).into(), (&block2.header).into()],
+				inclusion_proofs: BTreeMap::from([
+					(2, create_proof(&block2, 0)),
+					(3, create_proof(&block1, 1)),
+					(4, create_proof(&block1, 0)),
+				]),
+			}
+		));
+		let expect_error = |utxo_id: UtxoId, error: Error<Test>| {
+			let error: DispatchError = error.into();
+			System::assert_has_event(
+				Event::UtxoVerifiedError { utxo_id, error: error.stripped() }.into(),
+			);
+		};
+		expect_error(1, Error::<Test>::MissingInclusionProof);
+		// the block is not confirmed yet
+		expect_error(2, Error::<Test>::BitcoinHeightNotConfirmed);
+		// the output belongs to a different script
+		expect_error(4, Error::<Test>::InvalidInclusionProof);
+		System::assert_has_event(
+			Event::UtxoRejected {
+				utxo_id: 3,
+				rejected_reason: BitcoinRejectedReason::SatoshisMismatch,
+			}
+			.into(),
+		);
+		assert_eq!(UtxosPendingConfirmation::<Test>::get().len(), 3);
+		assert!(LockedUtxos::<Test>::iter().next().is_none());
+
+		// the header chain must include the oracle confirmed block
+		BitcoinUtxos::on_finalize(1);
+		InherentIncluded::<Test>::set(false);
+		ConfirmedBitcoinBlockTip::<Test>::put(BitcoinBlock::new(1, H256Le([9; 32])));
+		assert_ok!(BitcoinUtxos::sync(
+			RuntimeOrigin::none(),
+			BitcoinUtxoSync {
+				verified: BTreeMap::from([(1, create_utxo_ref(&block1, 0))]),
+				invalid: Default::default(),
+				spent: Default::default(),
+				sync_to_block: BitcoinBlock::new(1, block1.block_hash().into()),
+				headers: Default::default(),
+				inclusion_proofs: BTreeMap::from([(1, create_proof(&block1, 0))]),
+			}
+		));
+		expect_error(1, Error::<Test>::ConfirmedBlockNotInHeaderChain);
+		assert!(LockedUtxos::<Test>::iter().next().is_none());
+	});
+}
+
+#[test]
+fn it_verifies_utxos_without_proofs_on_signet() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		crate::pallet::BitcoinNetwork::<Test>::put(BitcoinNetwork::Signet);
+		assert_err!(
+			BitcoinUtxos::set_bitcoin_header_checkpoint(
+				RuntimeOrigin::root(),
+				0,
+				genesis_header(BitcoinNetwork::Regtest)
+			),
+			Error::<Test>::InvalidBitcoinHeader
+		);
+		ConfirmedBitcoinBlockTip::<Test>::put(BitcoinBlock::new(2, H256Le([0; 32])));
+		assert_ok!(BitcoinUtxos::watch_for_utxo(1, make_pubkey([0u8; 34]), 100, 100));
+		let utxo_ref = UtxoRef { txid: H256Le([1; 32]), output_index: 0 };
+		assert_ok!(BitcoinUtxos::sync(
+			RuntimeOrigin::none(),
+			BitcoinUtxoSync {
+				verified: BTreeMap::from([(1, utxo_ref.clone())]),
+				invalid: Default::default(),
+				spent: Default::default(),
+				sync_to_block: BitcoinBlock::new(1, H256Le([0; 32])),
+				headers: Default::default(),
+				inclusion_proofs: Default::default(),
+			}
+		));
+		System::assert_last_event(Event::UtxoVerified { utxo_id: 1 }.into());
+		assert!(LockedUtxos::<Test>::contains_key(&utxo_ref));
+	});
+}
+
+#[test]
+fn it_verifies_utxos_without_proofs_until_a_header_checkpoint_is_set() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		crate::pallet::BitcoinNetwork::<Test>::put(BitcoinNetwork::Regtest);
+		ConfirmedBitcoinBlockTip::<Test>::put(BitcoinBlock::new(2, H256Le([0; 32])));
+		assert_ok!(BitcoinUtxos::watch_for_utxo(1, make_pubkey([0u8; 34]), 100, 100));
+		assert_ok!(BitcoinUtxos::watch_for_utxo(2, make_pubkey([1u8; 34]), 100, 100));
+		let utxo_ref = UtxoRef { txid: H256Le([1; 32]), output_index: 0 };
+		assert_ok!(BitcoinUtxos::sync(
+			RuntimeOrigin::none(),
+			BitcoinUtxoSync {
+				verified: BTreeMap::from([(1, utxo_ref.clone())]),
+				invalid: Default::default(),
+				spent: Default::default(),
+				sync_to_block: BitcoinBlock::new(1, H256Le([0; 32])),
+				headers: Default::default(),
+				inclusion_proofs: Default::default(),
+			}
+		));
+		System::assert_last_event(Event::UtxoVerified { utxo_id: 1 }.into());
+		assert!(LockedUtxos::<Test>::contains_key(&utxo_ref));
+
+		// once the header chain starts, a proof is required
+		BitcoinUtxos::on_finalize(1);
+		InherentIncluded::<Test>::set(false);
+		set_genesis_checkpoint();
+		assert_ok!(BitcoinUtxos::sync(
+			RuntimeOrigin::none(),
+			BitcoinUtxoSync {
+				verified: BTreeMap::from([(2, UtxoRef { txid: H256Le([2; 32]), output_index: 0 })]),
+				invalid: Default::default(),
+				spent: Default::default(),
+				sync_to_block: BitcoinBlock::new(1, H256Le([0; 32])),
+				headers: Default::default(),
+				inclusion_proofs: Default::default(),
+			}
+		));
+		let error: DispatchError = Error::<Test>::MissingInclusionProof.into();
+		System::assert_has_event(
+			Event::UtxoVerifiedError { utxo_id: 2, error: error.stripped() }.into(),
+		);
+	});
+}
+
+#[test]
+fn it_reverts_utxo_changes_after_a_bitcoin_reorg() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let genesis = set_genesis_checkpoint();
+		let block1 = mine_block(&genesis, vec![create_tx(1, make_pubkey([0u8; 34]), 100)]);
+		let block2 = mine_block(&block1.header, vec![create_tx(2, make_pubkey([1u8; 34]), 101)]);
+		ConfirmedBitcoinBlockTip::<Test>::put(BitcoinBlock::new(2, block2.block_hash().into()));
+		assert_ok!(BitcoinUtxos::watch_for_utxo(1, make_pubkey([0u8; 34]), 100, 100));
+		assert_ok!(BitcoinUtxos::watch_for_utxo(2, make_pubkey([1u8; 34]), 101, 100));
+		let utxo_ref_1 = create_utxo_ref(&block1, 0);
+		let utxo_ref_2 = create_utxo_ref(&block2, 0);
+		assert_ok!(BitcoinUtxos::sync(
+			RuntimeOrigin::none(),
+			BitcoinUtxoSync {
+				verified: BTreeMap::from([(1, utxo_ref_1.clone()), (2, utxo_ref_2.clone())]),
+				invalid: Default::default(),
+				spent: Default::default(),
+				sync_to_block: BitcoinBlock::new(2, block2.block_hash().into()),
+				headers: vec![(&block1.header).into(), (&block2.header).into()],
+				inclusion_proofs: BTreeMap::from([
+					(1, create_proof(&block1, 0)),
+					(2, create_proof(&block2, 0)),
+				]),
+			}
+		));
+		assert!(LockedUtxos::<Test>::contains_key(&utxo_ref_1));
+		assert!(LockedUtxos::<Test>::contains_key(&utxo_ref_2));
+
+		// simulate next block
+		BitcoinUtxos::on_finalize(1);
+		InherentIncluded::<Test>::set(false);
+		let block3 = mine_block(&block2.header, vec![create_tx(3, make_pubkey([2u8; 34]), 100)]);
+		ConfirmedBitcoinBlockTip::<Test>::put(BitcoinBlock::new(3, block3.block_hash().into()));
+		assert_ok!(BitcoinUtxos::sync(
+			RuntimeOrigin::none(),
+			BitcoinUtxoSync {
+				verified: Default::default(),
+				invalid: Default::default(),
+				spent: BTreeMap::from([(2, 3)]),
+				sync_to_block: BitcoinBlock::new(3, block3.block_hash().into()),
+				headers: vec![(&block3.header).into()],
+				inclusion_proofs: Default::default(),
+			}
+		));
+		System::assert_has_event(Event::UtxoSpent { utxo_id: 2, block_height: 3 }.into());
+		assert!(!LockedUtxos::<Test>::contains_key(&utxo_ref_2));
+		assert_eq!(
+			UtxoChangesByBitcoinBlock::<Test>::get(H256Le::from(block3.block_hash())).len(),
+			1
+		);
+
+		// a longer chain forks off after block 1, and the oracles confirm it
+		BitcoinUtxos::on_finalize(2);
+		InherentIncluded::<Test>::set(false);
+		let fork2 = mine_block(&block1.header, vec![create_tx(4, make_pubkey([2u8; 34]), 100)]);
+		let fork3 = mine_block(&fork2.header, vec![create_tx(5, make_pubkey([2u8; 34]), 100)]);
+		let fork4 = mine_block(&fork3.header, vec![create_tx(6, make_pubkey([2u8; 34]), 100)]);
+		ConfirmedBitcoinBlockTip::<Test>::put(BitcoinBlock::new(4, fork4.block_hash().into()));
+		assert_ok!(BitcoinUtxos::sync(
+			RuntimeOrigin::none(),
+			BitcoinUtxoSync {
+				verified: Default::default(),
+				invalid: Default::default(),
+				spent: Default::default(),
+				sync_to_block: BitcoinBlock::new(4, fork4.block_hash().into()),
+				headers: vec![
+					(&fork2.header).into(),
+					(&fork3.header).into(),
+					(&fork4.header).into(),
+				],
+				inclusion_proofs: Default::default(),
+			}
+		));
+		System::assert_has_event(
+			Event::UtxoSpendReverted { utxo_id: 2, block_hash: block3.block_hash().into() }.into(),
+		);
+		System::assert_has_event(
+			Event::UtxoVerificationReverted { utxo_id: 2, block_hash: block2.block_hash().into() }
+				.into(),
+		);
+		assert_eq!(RevertedUtxos::get(), vec![2, 2]);
+
+		// utxo 1 was verified in a block that is still in the chain
+		assert!(LockedUtxos::<Test>::contains_key(&utxo_ref_1));
+		assert!(!LockedUtxos::<Test>::contains_key(&utxo_ref_2));
+		assert_eq!(UtxoIdToRef::<Test>::get(2), None);
+		assert!(UtxosPendingConfirmation::<Test>::get().contains_key(&2));
+		assert_eq!(LockedUtxoExpirationsByBlock::<Test>::get(100).to_vec(), vec![utxo_ref_1]);
+		assert!(!UtxoChangesByBitcoinBlock::<Test>::contains_key(H256Le::from(
+			block3.block_hash()
+		)));
+		// the blocks after the fork need to be scanned again
+		assert_eq!(
+			SynchedBitcoinBlock::<Test>::get(),
+			Some(BitcoinBlock::new(1, block1.block_hash().into()))
+		);
+	});
+}
+
+#[test]
+fn it_reverts_a_bonded_utxo_verification_when_a_heavier_fork_is_imported() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let genesis = set_genesis_checkpoint();
+		let utxo_tx = create_tx(1, make_pubkey([0u8; 34]), 100);
+		let block1 = mine_block(&genesis, vec![utxo_tx.clone()]);
+		ConfirmedBitcoinBlockTip::<Test>::put(BitcoinBlock::new(1, block1.block_hash().into()));
+		assert_ok!(BitcoinUtxos::watch_for_utxo(1, make_pubkey([0u8; 34]), 100, 100));
+		let utxo_ref = create_utxo_ref(&block1, 0);
+		assert_ok!(BitcoinUtxos::sync(
+			RuntimeOrigin::none(),
+			BitcoinUtxoSync {
+				verified: BTreeMap::from([(1, utxo_ref.clone())]),
+				invalid: Default::default(),
+				spent: Default::default(),
+				sync_to_block: BitcoinBlock::new(1, block1.block_hash().into()),
+				headers: vec![(&block1.header).into()],
+				inclusion_proofs: BTreeMap::from([(1, create_proof(&block1, 0))]),
+			}
+		));
+		assert!(BondVerifiedUtxos::get().contains(&1));
+		assert!(LockedUtxos::<Test>::contains_key(&utxo_ref));
+
+		// a heavier fork without block 1 is confirmed, and the utxo is only mined in its 2nd block
+		BitcoinUtxos::on_finalize(1);
+		InherentIncluded::<Test>::set(false);
+		let fork1 = mine_block(&genesis, vec![create_tx(2, make_pubkey([1u8; 34]), 100)]);
+		let fork2 = mine_block(&fork1.header, vec![create_tx(3, make_pubkey([1u8; 34]), 100)]);
+		let fork3 = mine_block(&fork2.header, vec![utxo_tx]);
+		ConfirmedBitcoinBlockTip::<Test>::put(BitcoinBlock::new(2, fork2.block_hash().into()));
+		assert_ok!(BitcoinUtxos::sync(
+			RuntimeOrigin::none(),
+			BitcoinUtxoSync {
+				verified: Default::default(),
+				invalid: Default::default(),
+				spent: Default::default(),
+				sync_to_block: BitcoinBlock::new(2, fork2.block_hash().into()),
+				headers: vec![(&fork1.header).into(), (&fork2.header).into()],
+				inclusion_proofs: Default::default(),
+			}
+		));
+		System::assert_has_event(
+			Event::UtxoVerificationReverted { utxo_id: 1, block_hash: block1.block_hash().into() }
+				.into(),
+		);
+		assert_eq!(
+			BitcoinHeaderTip::<Test>::get(),
+			Some(BitcoinBlock::new(2, fork2.block_hash().into()))
+		);
+		assert!(!BitcoinHeaders::<Test>::contains_key(H256Le::from(block1.block_hash())));
+		assert!(!UtxoChangesByBitcoinBlock::<Test>::contains_key(H256Le::from(
+			block1.block_hash()
+		)));
+		// the bonded utxo is pending confirmation again
+		assert!(!BondVerifiedUtxos::get().contains(&1));
+		assert!(!LockedUtxos::<Test>::contains_key(&utxo_ref));
+		assert_eq!(UtxoIdToRef::<Test>::get(1), None);
+		assert!(UtxosPendingConfirmation::<Test>::get().contains_key(&1));
+		assert!(LockedUtxoExpirationsByBlock::<Test>::get(100).is_empty());
+		assert_eq!(
+			SynchedBitcoinBlock::<Test>::get(),
+			Some(BitcoinBlock::new(0, genesis.block_hash().into()))
+		);
+
+		// it's verified again once it's confirmed in the new chain
+		BitcoinUtxos::on_finalize(2);
+		InherentIncluded::<Test>::set(false);
+		ConfirmedBitcoinBlockTip::<Test>::put(BitcoinBlock::new(3, fork3.block_hash().into()));
+		assert_eq!(create_utxo_ref(&fork3, 0), utxo_ref);
+		assert_ok!(BitcoinUtxos::sync(
+			RuntimeOrigin::none(),
+			BitcoinUtxoSync {
+				verified: BTreeMap::from([(1, utxo_ref.clone())]),
+				invalid: Default::default(),
+				spent: Default::default(),
+				sync_to_block: BitcoinBlock::new(3, fork3.block_hash().into()),
+				headers: vec![(&fork3.header).into()],
+				inclusion_proofs: BTreeMap::from([(1, create_proof(&fork3, 0))]),
+			}
+		));
+		System::assert_has_event(Event::UtxoVerified { utxo_id: 1 }.into());
+		assert!(BondVerifiedUtxos::get().contains(&1));
+		assert!(LockedUtxos::<Test>::contains_key(&utxo_ref));
+		assert_eq!(
+			UtxoChangesByBitcoinBlock::<Test>::get(H256Le::from(fork3.block_hash())).len(),
+			1
+		);
+	});
+}
+
 fn make_pubkey(pubkey: [u8; 34]) -> BitcoinCosignScriptPubkey {
 	let mut hash = [0u8; 32];
 	hash.copy_from_slice(&pubkey[0..32]);
 	BitcoinCosignScriptPubkey::P2WSH { wscript_hash: H256::from(hash) }
 }
+
+fn set_genesis_checkpoint() -> Header {
+	assert_ok!(BitcoinUtxos::set_bitcoin_header_checkpoint(
+		RuntimeOrigin::root(),
+		0,
+		genesis_header(BitcoinNetwork::Regtest)
+	));
+	bitcoin::constants::genesis_block(bitcoin::Network::Regtest).header
+}
+
+fn create_tx(
+	seed: u8,
+	script_pubkey: BitcoinCosignScriptPubkey,
+	satoshis: Satoshis,
+) -> Transaction {
+	Transaction {
+		version: transaction::Version::TWO,
+		lock_time: LockTime::ZERO,
+		input: vec![TxIn {
+			previous_output: OutPoint { txid: Txid::from_byte_array([seed; 32]), vout: 0 },
+			script_sig: ScriptBuf::new(),
+			sequence: Sequence::MAX,
+			witness: Witness::new(),
+		}],
+		output: vec![TxOut {
+			value: Amount::from_sat(satoshis),
+			script_pubkey: script_pubkey.into(),
+		}],
+	}
+}
+
+/// Mines a regtest block with the same difficulty as the previous block
+fn mine_block(prev: &Header, txdata: Vec<Transaction>) -> Block {
+	let mut block = Block {
+		header: Header {
+			version: Version::TWO,
+			prev_blockhash: prev.block_hash(),
+			merkle_root: TxMerkleNode::all_zeros(),
+			time: prev.time + 1,
+			bits: prev.bits,
+			nonce: 0,
+		},
+		txdata,
+	};
+	block.header.merkle_root = block.compute_merkle_root().expect("has transactions");
+	while block.header.validate_pow(block.header.target()).is_err() {
+		block.header.nonce += 1;
+	}
+	block
+}
+
+fn create_utxo_ref(block: &Block, tx_index: usize) -> UtxoRef {
+	UtxoRef { txid: block.txdata[tx_index].compute_txid().into(), output_index: 0 }
+}
+
+fn create_proof(block: &Block, tx_index: usize) -> BitcoinTxInclusionProof {
+	let txids = block.txdata.iter().map(|a| a.compute_txid()).collect::<Vec<_>>();
+	BitcoinTxInclusionProof {
+		block_hash: block.block_hash().into(),
+		transaction: serialize(&block.txdata[tx_index]),
+		tx_index: tx_index as u32,
+		merkle_branch: create_merkle_branch(&txids, tx_index),
+	}
+}
@@ -16,6 +16,7 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+pub mod migrations;
 pub mod weights;
 
 /// The bond pallet allows users to manage the lifecycle of Bitcoin bonds, and stores the state for
@@ -80,7 +81,7 @@ pub mod weights;
 /// the duration of the bitcoin bonds, and will be taken in the case of bitcoins not being returned.
 #[frame_support::pallet(dev_mode)]
 pub mod pallet {
-	use alloc::vec;
+	use alloc::{vec, vec::Vec};
 
 	use codec::Codec;
 	use frame_support::{
@@ -103,20 +104,27 @@ pub mod pallet {
 	};
 
 	use super::*;
-	use argon_bitcoin::{Amount, CosignScriptArgs, UnlockStep, UtxoUnlocker};
+	use argon_bitcoin::{Amount, CosignScript, CosignScriptArgs, UnlockStep, UtxoUnlocker};
 	use argon_primitives::{
 		bitcoin::{
-			BitcoinCosignScriptPubkey, BitcoinHeight, BitcoinRejectedReason, BitcoinScriptPubkey,
-			BitcoinSignature, CompressedBitcoinPubkey, Satoshis, UtxoId, XPubChildNumber,
-			XPubFingerprint,
+			BitcoinCosignScriptPubkey, BitcoinCosignScriptType, BitcoinHeight, BitcoinMusigNonce,
+			BitcoinRejectedReason, BitcoinScriptPubkey, BitcoinSignature, CompressedBitcoinPubkey,
+			Satoshis, UtxoId, VaultMultisigPubkeys, XPubChildNumber, XPubFingerprint,
+			MAX_VAULT_COSIGNERS,
 		},
 		block_seal::RewardSharing,
-		bond::{Bond, BondError, BondExpiration, BondProvider, BondType, VaultProvider},
+		bond::{
+			Bond, BondError, BondExpiration, BondProvider, BondQuote, BondType, VaultProvider,
+			VaultRole,
+		},
 		BitcoinUtxoEvents, BitcoinUtxoTracker, BondId, PriceProvider, RewardShare,
 		UtxoBondedEvents, VaultId,
 	};
 
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	/// Configure the pallet by specifying the parameters and types on which it depends.
@@ -192,12 +200,18 @@ pub mod pallet {
 		/// Number of bitcoin blocks a vault has to counter-sign a bitcoin unlock
 		#[pallet::constant]
 		type UtxoUnlockCosignDeadlineBlocks: Get<BitcoinHeight>;
+
+		/// Number of bitcoin blocks a utxo spend can be reverted by a bitcoin reorg (the header
+		/// history kept by the utxo tracker)
+		#[pallet::constant]
+		type BitcoinSpendRevertBlocks: Get<BitcoinHeight>;
 	}
 
 	/// A reason for the pallet placing a hold on funds.
 	#[pallet::composite_enum]
 	pub enum HoldReason {
 		UnlockingBitcoin,
+		RenewingBitcoinBond,
 	}
 
 	#[pallet::storage]
@@ -243,6 +257,22 @@ pub mod pallet {
 	pub(super) type UtxosCosignReleaseHeightById<T: Config> =
 		StorageMap<_, Twox64Concat, UtxoId, BlockNumberFor<T>, OptionQuery>;
 
+	/// Stores the block number where the vault last cosigned a fee bump of an unlock
+	#[pallet::storage]
+	pub(super) type UtxosFeeBumpCosignHeightById<T: Config> =
+		StorageMap<_, Twox64Concat, UtxoId, BlockNumberFor<T>, OptionQuery>;
+
+	/// Unlocks cosigned by a vault that have not been seen spent on bitcoin yet. The owner can ask
+	/// the vault to cosign a replacement transaction with a higher fee until then.
+	#[pallet::storage]
+	pub(super) type CosignedUnlocksByUtxoId<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		UtxoId,
+		(UtxoState, UtxoCosignRequest<T::AccountId, T::Balance>),
+		OptionQuery,
+	>;
+
 	/// The minimum number of satoshis that can be bonded
 	#[pallet::storage]
 	pub(super) type MinimumBitcoinBondSatoshis<T: Config> = StorageValue<_, Satoshis, ValueQuery>;
@@ -261,12 +291,41 @@ pub mod pallet {
 
 	/// Utxos that have been requested to be cosigned for unlocking
 	#[pallet::storage]
-	pub(super) type UtxosPendingUnlockByUtxoId<T: Config> = StorageValue<
+	pub(super) type UtxosPendingUnlockByUtxoId<T: Config> =
+		StorageValue<_, PendingUnlocks<T>, ValueQuery>;
+
+	/// The utxo of each bond's pending cosign request in `UtxosPendingUnlockByUtxoId`
+	#[pallet::storage]
+	pub(super) type PendingUnlockUtxoIdByBondId<T: Config> =
+		StorageMap<_, Twox64Concat, BondId, UtxoId, OptionQuery>;
+
+	/// Bonded utxos that were spent, kept until the spend can no longer be reverted by a bitcoin
+	/// reorg
+	#[pallet::storage]
+	pub(super) type SpentUtxosById<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		UtxoId,
+		SpentUtxo<T::AccountId, T::Balance, BlockNumberFor<T>>,
+		OptionQuery,
+	>;
+
+	/// The spent utxos that can no longer be reverted at a bitcoin height
+	#[pallet::storage]
+	pub(super) type SpentUtxoExpirationsByBitcoinHeight<T: Config> = StorageMap<
 		_,
-		BoundedBTreeMap<UtxoId, UtxoCosignRequest<T::Balance>, T::MaxUnlockingUtxos>,
+		Twox64Concat,
+		BitcoinHeight,
+		BoundedVec<UtxoId, T::MaxConcurrentlyExpiringBonds>,
 		ValueQuery,
 	>;
 
+	pub type PendingUnlocks<T> = BoundedBTreeMap<
+		UtxoId,
+		UtxoCosignRequest<<T as frame_system::Config>::AccountId, <T as Config>::Balance>,
+		<T as Config>::MaxUnlockingUtxos,
+	>;
+
 	#[derive(Decode, Encode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
 	pub struct UtxoState {
 		#[codec(compact)]
@@ -286,10 +345,22 @@ pub mod pallet {
 		pub created_at_height: BitcoinHeight,
 		pub utxo_script_pubkey: BitcoinCosignScriptPubkey,
 		pub is_verified: bool,
+		/// The satoshis backing the bond amount. A move to a new cosign script pays the bitcoin
+		/// fee out of the moved satoshis, so this can be greater than `satoshis`.
+		#[codec(compact)]
+		pub bonded_satoshis: Satoshis,
+		/// The utxo this one replaced (a partial redemption remainder, or a move to a new cosign
+		/// script). The bonded argons were already minted for the original utxo.
+		pub previous_utxo_id: Option<UtxoId>,
+		/// The other vault pubkeys if the vault is a multisig
+		pub vault_multisig: Option<VaultMultisigPubkeys>,
 	}
 
 	#[derive(Decode, Encode, CloneNoBound, PartialEqNoBound, EqNoBound, RuntimeDebug, TypeInfo)]
-	pub struct UtxoCosignRequest<Balance: Clone + Eq + PartialEq + TypeInfo + Codec> {
+	pub struct UtxoCosignRequest<
+		AccountId: Clone + Eq + PartialEq + TypeInfo + Codec,
+		Balance: Clone + Eq + PartialEq + TypeInfo + Codec,
+	> {
 		#[codec(compact)]
 		pub bond_id: BondId,
 		#[codec(compact)]
@@ -301,6 +372,91 @@ pub mod pallet {
 		pub to_script_pubkey: BitcoinScriptPubkey,
 		#[codec(compact)]
 		pub redemption_price: Balance,
+		/// The owner's MuSig2 public nonce for a P2TR key path unlock
+		pub owner_musig_nonce: Option<BitcoinMusigNonce>,
+		/// For a partial redemption, the utxo the unredeemed satoshis must be sent to. It will
+		/// remain bonded under the original terms, and the bond amount is reduced to its share.
+		pub remainder_utxo: Option<(UtxoId, UtxoState)>,
+		/// Set if the bitcoin is being moved to a new cosign script instead of unlocked. The
+		/// `to_script_pubkey` will be the script pubkey of the new utxo.
+		pub utxo_move: Option<UtxoMove<AccountId, Balance>>,
+		/// Set if the vault already cosigned this unlock, and is asked to cosign a replacement
+		/// transaction with a higher `bitcoin_network_fee`
+		pub is_fee_bump: bool,
+		/// The owner allows the vault to cosign this unlock as part of a batch transaction with
+		/// other unlocks (see `cosign_bitcoin_unlocks`)
+		pub allow_batching: bool,
+	}
+
+	#[derive(Decode, Encode, CloneNoBound, PartialEqNoBound, EqNoBound, RuntimeDebug, TypeInfo)]
+	pub struct UtxoMove<
+		AccountId: Clone + Eq + PartialEq + TypeInfo + Codec,
+		Balance: Clone + Eq + PartialEq + TypeInfo + Codec,
+	> {
+		/// The utxo the bitcoin is moving to. It stays bonded by the same bond.
+		#[codec(compact)]
+		pub utxo_id: UtxoId,
+		pub utxo: UtxoState,
+		/// The account the bond will be transferred to once the move is cosigned
+		pub transfer_to_account_id: Option<AccountId>,
+		/// The vault the bond will be renewed with once the move is cosigned
+		pub renew_with_vault_id: Option<VaultId>,
+		/// The renewal fee held from the owner until the move is cosigned
+		#[codec(compact)]
+		pub renewal_fee: Balance,
+		/// The vault the bond will be migrated to (from a closed vault) once the move is cosigned
+		pub migrate_to_vault_id: Option<VaultId>,
+	}
+
+	/// The bond state a utxo spend changed, so it can be restored if the spend is reverted
+	#[derive(Decode, Encode, CloneNoBound, PartialEqNoBound, EqNoBound, RuntimeDebug, TypeInfo)]
+	pub struct SpentUtxo<
+		AccountId: Clone + Eq + PartialEq + TypeInfo + Codec,
+		Balance: Clone + Eq + PartialEq + TypeInfo + Codec,
+		BlockNumber: Clone + Eq + PartialEq + TypeInfo + Codec,
+	> {
+		/// The utxo, if it was still bonded (ie, it was spent without a cosigned unlock)
+		pub utxo_state: Option<UtxoState>,
+		/// The cosigned unlock the spend completed
+		pub cosigned_unlock: Option<(UtxoState, UtxoCosignRequest<AccountId, Balance>)>,
+		/// The block the vault cosigned the unlock
+		pub cosign_release_height: Option<BlockNumber>,
+		/// The block the vault cosigned the last fee bump of the unlock
+		pub fee_bump_cosign_height: Option<BlockNumber>,
+		/// The bitcoin height the spend can no longer be reverted at
+		#[codec(compact)]
+		pub revertible_until_height: BitcoinHeight,
+	}
+
+	/// A vault signature for a pending bitcoin unlock (or move)
+	#[derive(Decode, Encode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	pub struct BitcoinUnlockCosign {
+		#[codec(compact)]
+		pub bond_id: BondId,
+		/// The signature of the vault pubkey. Required unless the vault is a multisig.
+		pub signature: Option<BitcoinSignature>,
+		/// The vault's MuSig2 public nonce for a P2TR key path unlock
+		pub vault_musig_nonce: Option<BitcoinMusigNonce>,
+		/// Signatures from the pubkeys of a multisig vault (the vault pubkey or its cosigner
+		/// pubkeys). Together with `signature`, they must meet the vault's threshold.
+		pub cosigner_signatures: CosignerSignatures,
+	}
+
+	/// Signatures from the pubkeys of a multisig vault
+	pub type CosignerSignatures = BoundedVec<
+		(CompressedBitcoinPubkey, BitcoinSignature),
+		ConstU32<{ MAX_VAULT_COSIGNERS + 1 }>,
+	>;
+
+	#[derive(Decode, Encode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	pub struct OwnerPubkeyRotation {
+		/// The owner pubkey the bitcoin will be moved to a new cosign script with
+		pub owner_pubkey: CompressedBitcoinPubkey,
+		/// The fee to pay to the bitcoin network for the move (paid from the utxo)
+		#[codec(compact)]
+		pub bitcoin_network_fee: Satoshis,
+		/// The current owner's MuSig2 public nonce to move a P2TR utxo
+		pub owner_musig_nonce: Option<BitcoinMusigNonce>,
 	}
 
 	#[pallet::event]
@@ -334,6 +490,16 @@ pub mod pallet {
 			amount_held: T::Balance,
 			was_utxo_spent: bool,
 		},
+		/// The bitcoin block a bonded utxo was verified in was reorged out
+		BitcoinUtxoVerificationReverted {
+			bond_id: BondId,
+			utxo_id: UtxoId,
+		},
+		/// The bitcoin block a bonded utxo was spent in was reorged out
+		BitcoinUtxoSpendReverted {
+			bond_id: BondId,
+			utxo_id: UtxoId,
+		},
 		BitcoinUtxoCosignRequested {
 			bond_id: BondId,
 			vault_id: VaultId,
@@ -343,7 +509,78 @@ pub mod pallet {
 			bond_id: BondId,
 			vault_id: VaultId,
 			utxo_id: UtxoId,
-			signature: BitcoinSignature,
+			/// The vault pubkey signature (if it signed)
+			signature: Option<BitcoinSignature>,
+			/// The vault's MuSig2 public nonce for a P2TR key path unlock
+			vault_musig_nonce: Option<BitcoinMusigNonce>,
+			/// The signatures of a multisig vault's pubkeys
+			cosigner_signatures: CosignerSignatures,
+		},
+		BitcoinBondPartiallyRedeemed {
+			bond_id: BondId,
+			vault_id: VaultId,
+			utxo_id: UtxoId,
+			redeemed_satoshis: Satoshis,
+			remainder_utxo_id: UtxoId,
+			remainder_satoshis: Satoshis,
+		},
+		BitcoinBondTransferred {
+			bond_id: BondId,
+			vault_id: VaultId,
+			from_account_id: T::AccountId,
+			to_account_id: T::AccountId,
+		},
+		BitcoinUtxoMoved {
+			bond_id: BondId,
+			vault_id: VaultId,
+			utxo_id: UtxoId,
+			to_utxo_id: UtxoId,
+		},
+		/// The utxos were cosigned as the inputs of a single bitcoin transaction. The inputs and
+		/// outputs are in the order of the utxos, and the fee is shared across the unlock outputs.
+		BitcoinUtxosBatchCosigned {
+			vault_id: VaultId,
+			utxo_ids: BoundedVec<UtxoId, T::MaxUnlockingUtxos>,
+			bitcoin_network_fee: Satoshis,
+		},
+		BitcoinBondRenewed {
+			bond_id: BondId,
+			vault_id: VaultId,
+			utxo_id: UtxoId,
+			amount: T::Balance,
+			total_fee: T::Balance,
+			expiration: BondExpiration<BlockNumberFor<T>>,
+		},
+		/// A bitcoin bond moved from a closed vault to the vault that accepted its bonds
+		BitcoinBondMigrated {
+			bond_id: BondId,
+			from_vault_id: VaultId,
+			to_vault_id: VaultId,
+			utxo_id: UtxoId,
+			/// The fee the bond had earned the closed vault
+			earned_fee: T::Balance,
+		},
+		/// The owner of a cosigned unlock asked the vault to cosign a replacement transaction with
+		/// a higher bitcoin network fee
+		BitcoinUnlockFeeBumpRequested {
+			bond_id: BondId,
+			vault_id: VaultId,
+			utxo_id: UtxoId,
+			bitcoin_network_fee: Satoshis,
+		},
+		/// A vault did not cosign a fee bump in time. The originally cosigned unlock is still
+		/// valid.
+		BitcoinUnlockFeeBumpCosignPastDue {
+			bond_id: BondId,
+			vault_id: VaultId,
+			utxo_id: UtxoId,
+		},
+		/// A vault did not cosign a move of a bitcoin utxo in time. The bitcoin stays in the
+		/// original utxo.
+		BitcoinUtxoMoveCosignPastDue {
+			bond_id: BondId,
+			vault_id: VaultId,
+			utxo_id: UtxoId,
 		},
 		BitcoinCosignPastDue {
 			bond_id: BondId,
@@ -385,8 +622,16 @@ pub mod pallet {
 		BondRedemptionNotLocked,
 		/// The bitcoin has passed the deadline to unlock it
 		BitcoinUnlockInitiationDeadlinePassed,
+		/// This bitcoin utxo already has a pending cosign request
+		BitcoinUtxoCosignPending,
+		/// This bitcoin utxo has not been verified on the bitcoin network yet
+		BitcoinUtxoNotVerified,
 		/// The fee for this bitcoin unlock is too high
 		BitcoinFeeTooHigh,
+		/// A fee bump must increase the bitcoin network fee
+		BitcoinFeeNotIncreased,
+		/// This bitcoin unlock hasn't been cosigned, or was already seen on the bitcoin network
+		BitcoinUnlockNotCosigned,
 		InvalidBondType,
 		BitcoinUtxoNotFound,
 		/// This bitcoin cosign script couldn't be decoded for unlock
@@ -397,10 +642,19 @@ pub mod pallet {
 		BitcoinPubkeyUnableToBeDecoded,
 		/// The cosign signature is not valid for the bitcoin unlock
 		BitcoinInvalidCosignature,
+		/// A MuSig2 nonce is required to unlock a taproot bitcoin script (and only allowed for
+		/// one)
+		BitcoinMusigNonceMismatch,
+		/// The cosigner signatures don't meet the threshold of a multisig vault (or the vault
+		/// isn't a multisig)
+		InvalidVaultCosignerSignatures,
 		InsufficientSatoshisBonded,
 		NoBitcoinPricesAvailable,
 		/// The bitcoin script to lock this bitcoin has errors
 		InvalidBitcoinScript,
+		/// The satoshis to redeem must be more than the network fee, and cannot exceed the bonded
+		/// utxo
+		InvalidRedemptionSatoshis,
 		ExpirationTooSoon,
 		NoPermissions,
 		HoldUnexpectedlyModified,
@@ -408,6 +662,14 @@ pub mod pallet {
 		VaultNotFound,
 		/// The fee for this bond exceeds the amount of the bond, which is unsafe
 		FeeExceedsBondAmount,
+		/// The vault of this bond has not had its bonds accepted by another vault
+		BondMigrationNotAccepted,
+		/// The owner of an unlock didn't allow it to be cosigned in a batch
+		BitcoinUnlockNotBatchable,
+		/// A batch can only include unlocks from a single vault
+		BitcoinBatchMixesVaults,
+		/// The bond of a spent bitcoin utxo was completed since, so the spend can't be reverted
+		BitcoinSpendCannotBeReverted,
 		GenericBondError(BondError),
 	}
 
@@ -432,6 +694,8 @@ pub mod pallet {
 				BondError::AccountWouldBeBelowMinimum =>
 					Error::<T>::AccountWouldGoBelowMinimumBalance,
 				BondError::InvalidBitcoinScript => Error::<T>::InvalidBitcoinScript,
+				BondError::InsufficientSatoshisBonded => Error::<T>::InsufficientSatoshisBonded,
+				BondError::NoBitcoinPricesAvailable => Error::<T>::NoBitcoinPricesAvailable,
 				_ => Error::<T>::GenericBondError(e),
 			}
 		}
@@ -466,17 +730,58 @@ pub mod pallet {
 			}
 
 			let mut overdue = vec![];
+			let mut overdue_moves = vec![];
+			let mut overdue_fee_bumps = vec![];
 			let bitcoin_block_height = T::BitcoinBlockHeight::get();
 			<UtxosPendingUnlockByUtxoId<T>>::mutate(|pending| {
 				pending.retain(|id, x| {
 					if x.cosign_due_block > bitcoin_block_height {
 						return true;
 					}
-					overdue.push((*id, x.redemption_price));
+					PendingUnlockUtxoIdByBondId::<T>::remove(x.bond_id);
+					// a fee bump is optional for the vault, since it already cosigned the unlock
+					if x.is_fee_bump {
+						overdue_fee_bumps.push((*id, x.bond_id, x.vault_id));
+						return false;
+					}
+					T::VaultProvider::record_missed_cosign_deadline(x.vault_id);
+					if let Some(utxo_move) = &x.utxo_move {
+						overdue_moves.push((*id, x.bond_id, x.vault_id, utxo_move.renewal_fee));
+					} else {
+						overdue.push((*id, x.redemption_price));
+					}
 					false
 				});
 			});
 
+			// a move leaves the bitcoin in the original utxo, so only a renewal fee is refunded
+			for (utxo_id, bond_id, vault_id, renewal_fee) in overdue_moves {
+				if let Some(bond) = BondsById::<T>::get(bond_id) {
+					if let Err(e) = Self::release_renewal_fee(&bond.bonded_account_id, renewal_fee)
+					{
+						log::error!(
+							"Bond id {:?} failed to release its renewal fee {:?}",
+							bond_id,
+							e
+						);
+					}
+				}
+				Self::deposit_event(Event::<T>::BitcoinUtxoMoveCosignPastDue {
+					bond_id,
+					vault_id,
+					utxo_id,
+				});
+			}
+
+			// the vault already cosigned the original unlock, which remains valid
+			for (utxo_id, bond_id, vault_id) in overdue_fee_bumps {
+				Self::deposit_event(Event::<T>::BitcoinUnlockFeeBumpCosignPastDue {
+					bond_id,
+					vault_id,
+					utxo_id,
+				});
+			}
+
 			for (utxo_id, redemption_amount) in overdue {
 				let res =
 					with_storage_layer(|| Self::cosign_bitcoin_overdue(utxo_id, redemption_amount));
@@ -486,6 +791,10 @@ pub mod pallet {
 				}
 			}
 
+			for utxo_id in SpentUtxoExpirationsByBitcoinHeight::<T>::take(bitcoin_block_height) {
+				SpentUtxosById::<T>::remove(utxo_id);
+			}
+
 			let bitcoin_bond_completions = BitcoinBondCompletions::<T>::take(bitcoin_block_height);
 			for bond_id in bitcoin_bond_completions {
 				let res = with_storage_layer(|| Self::bond_completed(bond_id));
@@ -508,6 +817,9 @@ pub mod pallet {
 		///
 		/// The pubkey submitted here will be used to create a script pubkey that will be used in a
 		/// timelock multisig script to lock the bitcoin.
+		///
+		/// A P2TR `script_type` will lock the bitcoin in a taproot output where the cosigned
+		/// unlock is a MuSig2 key path spend, and the timelocked claims are tapscript leaves.
 		#[pallet::call_index(0)]
 		#[pallet::weight(0)]
 		pub fn bond_bitcoin(
@@ -515,6 +827,7 @@ pub mod pallet {
 			vault_id: VaultId,
 			#[pallet::compact] satoshis: Satoshis,
 			bitcoin_pubkey: CompressedBitcoinPubkey,
+			script_type: BitcoinCosignScriptType,
 		) -> DispatchResult {
 			let account_id = ensure_signed(origin)?;
 
@@ -530,7 +843,7 @@ pub mod pallet {
 			let amount = T::PriceProvider::get_bitcoin_argon_price(satoshis)
 				.ok_or(Error::<T>::NoBitcoinPricesAvailable)?;
 
-			let (total_fee, prepaid_fee) = T::VaultProvider::bond_funds(
+			let (total_fee, prepaid_fee, annual_percent_rate) = T::VaultProvider::bond_funds(
 				vault_id,
 				amount,
 				BondType::Bitcoin,
@@ -543,10 +856,11 @@ pub mod pallet {
 
 			let utxo_id = T::BitcoinUtxoTracker::new_utxo_id();
 
-			let (vault_xpub, vault_claim_xpub, script_pubkey) =
+			let (vault_xpub, vault_claim_xpub, script_pubkey, vault_multisig) =
 				T::VaultProvider::create_utxo_script_pubkey(
 					vault_id,
 					utxo_id,
+					script_type,
 					bitcoin_pubkey,
 					vault_claim_height,
 					open_claim_height,
@@ -578,6 +892,7 @@ pub mod pallet {
 				BondExpiration::BitcoinBlock(vault_claim_height),
 				total_fee,
 				prepaid_fee,
+				annual_percent_rate,
 				Some(utxo_id),
 			)
 			.map_err(Error::<T>::from)?;
@@ -596,6 +911,9 @@ pub mod pallet {
 					created_at_height: T::BitcoinBlockHeight::get(),
 					utxo_script_pubkey: script_pubkey,
 					is_verified: false,
+					bonded_satoshis: satoshis,
+					previous_utxo_id: None,
+					vault_multisig,
 				},
 			);
 
@@ -607,7 +925,16 @@ pub mod pallet {
 		/// submitted here. The vault operator will have 10 days to counter-sign the transaction. It
 		/// will be published with the public key as a BitcoinUtxoCosigned Event.
 		///
-		/// Owner must submit a script pubkey and also a fee to pay to the bitcoin network.
+		/// Owner must submit a script pubkey and also a fee to pay to the bitcoin network. A
+		/// taproot (P2TR) bond must also include the owner's MuSig2 public nonce for the unlock.
+		///
+		/// To redeem only part of the bitcoin, `redeem_satoshis` can be provided. The redemption
+		/// price is only charged for those satoshis. The unlock transaction must send the remaining
+		/// satoshis to a new cosign script (found in the cosign request), which stays bonded with
+		/// the original terms and expiration.
+		///
+		/// If `allow_batching` is set, the vault may cosign the unlock as an input of a single
+		/// bitcoin transaction along with other unlocks of the vault, sharing the bitcoin fee.
 		#[pallet::call_index(4)]
 		#[pallet::weight(0)]
 		pub fn unlock_bitcoin_bond(
@@ -615,29 +942,50 @@ pub mod pallet {
 			bond_id: BondId,
 			to_script_pubkey: BitcoinScriptPubkey,
 			bitcoin_network_fee: Satoshis,
+			owner_musig_nonce: Option<BitcoinMusigNonce>,
+			redeem_satoshis: Option<Satoshis>,
+			allow_batching: bool,
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 			let bond = BondsById::<T>::get(bond_id).ok_or(Error::<T>::BondNotFound)?;
 			ensure!(bond.bond_type == BondType::Bitcoin, Error::<T>::NoPermissions);
 			ensure!(bond.bonded_account_id == who, Error::<T>::NoPermissions);
-			let expiration = match bond.expiration {
-				BondExpiration::BitcoinBlock(vault_claim_height) => vault_claim_height,
-				_ => return Err(Error::<T>::InvalidBondType.into()),
-			};
-			let unlock_due_date =
-				expiration.saturating_sub(T::UtxoUnlockCosignDeadlineBlocks::get());
-			ensure!(
-				T::BitcoinBlockHeight::get() <= unlock_due_date,
-				Error::<T>::BitcoinUnlockInitiationDeadlinePassed
-			);
+			Self::ensure_cosign_deadline_not_passed(&bond)?;
 
 			let utxo_id = bond.utxo_id.ok_or(Error::<T>::InvalidBondType)?;
+			ensure!(!Self::has_pending_cosign(bond_id), Error::<T>::BitcoinUtxoCosignPending);
 
 			let utxo = <UtxosById<T>>::get(utxo_id).ok_or(Error::<T>::BitcoinUtxoNotFound)?;
 			// If this is a confirmed utxo, we require the unlock price to be paid
 			if utxo.is_verified {
-				ensure!(bitcoin_network_fee < utxo.satoshis, Error::<T>::BitcoinFeeTooHigh);
-				let redemption_price = Self::get_redemption_price(&utxo.satoshis)?.min(bond.amount);
+				let redeem_satoshis = redeem_satoshis.unwrap_or(utxo.satoshis);
+				ensure!(redeem_satoshis <= utxo.satoshis, Error::<T>::InvalidRedemptionSatoshis);
+				ensure!(bitcoin_network_fee < redeem_satoshis, Error::<T>::BitcoinFeeTooHigh);
+				ensure!(
+					owner_musig_nonce.is_some() ==
+						(utxo.utxo_script_pubkey.script_type() == BitcoinCosignScriptType::P2TR),
+					Error::<T>::BitcoinMusigNonceMismatch
+				);
+				let remainder_utxo = if redeem_satoshis < utxo.satoshis {
+					let remainder_satoshis = utxo.satoshis - redeem_satoshis;
+					ensure!(
+						remainder_satoshis >= MinimumBitcoinBondSatoshis::<T>::get(),
+						Error::<T>::InsufficientSatoshisBonded
+					);
+					Some(Self::create_replacement_utxo(
+						bond.vault_id,
+						UtxoState {
+							satoshis: remainder_satoshis,
+							bonded_satoshis: remainder_satoshis,
+							previous_utxo_id: Some(utxo_id),
+							..utxo.clone()
+						},
+					)?)
+				} else {
+					None
+				};
+				let redemption_price = Self::get_redemption_price(&redeem_satoshis)?
+					.min(Self::bonded_share(&bond, &utxo, redeem_satoshis));
 				let cosign_due_block =
 					T::UtxoUnlockCosignDeadlineBlocks::get() + T::BitcoinBlockHeight::get();
 
@@ -656,20 +1004,22 @@ pub mod pallet {
 					})?;
 				frame_system::Pallet::<T>::inc_providers(&who);
 
-				<UtxosPendingUnlockByUtxoId<T>>::try_mutate(|a| {
-					a.try_insert(
-						utxo_id,
-						UtxoCosignRequest {
-							bond_id,
-							vault_id: bond.vault_id,
-							bitcoin_network_fee,
-							cosign_due_block,
-							to_script_pubkey,
-							redemption_price,
-						},
-					)
-				})
-				.map_err(|_| Error::<T>::ExpirationAtBlockOverflow)?;
+				Self::insert_pending_cosign(
+					utxo_id,
+					UtxoCosignRequest {
+						bond_id,
+						vault_id: bond.vault_id,
+						bitcoin_network_fee,
+						cosign_due_block,
+						to_script_pubkey,
+						redemption_price,
+						owner_musig_nonce,
+						remainder_utxo,
+						utxo_move: None,
+						is_fee_bump: false,
+						allow_batching,
+					},
+				)?;
 
 				Self::deposit_event(Event::<T>::BitcoinUtxoCosignRequested {
 					bond_id,
@@ -677,152 +1027,546 @@ pub mod pallet {
 					utxo_id,
 				});
 			} else {
+				// an unverified utxo can only be canceled as a whole
+				ensure!(redeem_satoshis.is_none(), Error::<T>::BitcoinUtxoNotVerified);
 				<Self as BondProvider>::cancel_bond(bond_id).map_err(Error::<T>::from)?;
 			}
 			Ok(())
 		}
 
-		/// Submitted by a Vault operator to cosign the unlock of a bitcoin utxo. The Bitcoin owner
-		/// unlock fee will be burned, and the bond will be allowed to expire without penalty.
+		/// Submitted by a Vault cosigner (the operator, or an account assigned the cosigner role)
+		/// to cosign the unlock of a bitcoin utxo. The Bitcoin owner unlock fee will be burned, and
+		/// the bond will be allowed to expire without penalty.
 		///
 		/// This is submitted as a no-fee transaction off chain to allow keys to remain in cold
 		/// wallets.
+		///
+		/// For a taproot (P2TR) bond, the signature is a MuSig2 partial signature, and the vault
+		/// must include the public nonce it signed with.
 		#[pallet::call_index(5)]
 		#[pallet::weight((0, DispatchClass::Operational))]
 		pub fn cosign_bitcoin_unlock(
 			origin: OriginFor<T>,
 			bond_id: BondId,
 			signature: BitcoinSignature,
+			vault_musig_nonce: Option<BitcoinMusigNonce>,
 		) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
-			let bond = BondsById::<T>::get(bond_id).ok_or(Error::<T>::BondNotFound)?;
-			ensure!(bond.bond_type == BondType::Bitcoin, Error::<T>::NoPermissions);
-
-			let utxo_id = bond.utxo_id.ok_or(Error::<T>::InvalidBondType)?;
-			let vault_id = bond.vault_id;
-
-			let vault = T::VaultProvider::get(vault_id).ok_or(Error::<T>::VaultNotFound)?;
-			ensure!(vault.operator_account_id == who, Error::<T>::NoPermissions);
-			let request = UtxosPendingUnlockByUtxoId::<T>::mutate(|a| a.remove(&utxo_id))
-				.ok_or(Error::<T>::BondRedemptionNotLocked)?;
-
-			let utxo_state = <UtxosById<T>>::get(utxo_id).ok_or(Error::<T>::BitcoinUtxoNotFound)?;
-			let utxo_ref =
-				T::BitcoinUtxoTracker::get(utxo_id).ok_or(Error::<T>::BitcoinUtxoNotFound)?;
-
-			let script_args = CosignScriptArgs {
-				vault_pubkey: utxo_state.vault_pubkey,
-				owner_pubkey: utxo_state.owner_pubkey,
-				vault_claim_pubkey: utxo_state.vault_claim_pubkey,
-				created_at_height: utxo_state.created_at_height,
-				vault_claim_height: utxo_state.vault_claim_height,
-				open_claim_height: utxo_state.open_claim_height,
+			let cosign = BitcoinUnlockCosign {
+				bond_id,
+				signature: Some(signature),
+				vault_musig_nonce,
+				cosigner_signatures: Default::default(),
 			};
-			let unlocker = UtxoUnlocker::new(
-				script_args,
-				utxo_state.satoshis,
-				utxo_ref.txid.into(),
-				utxo_ref.output_index,
-				UnlockStep::VaultCosign,
-				Amount::from_sat(request.bitcoin_network_fee),
-				request.to_script_pubkey.into(),
-				T::GetBitcoinNetwork::get().into(),
-			)
-			.map_err(|_| Error::<T>::BitcoinUnableToBeDecodedForUnlock)?;
-
-			T::BitcoinSignatureVerifier::verify_signature(
-				unlocker,
-				utxo_state.vault_pubkey,
-				&signature,
-			)?;
-
-			// burn the owner's held funds
-			let burn_amount = request.redemption_price;
-			let _ = T::Currency::burn_held(
-				&HoldReason::UnlockingBitcoin.into(),
-				&bond.bonded_account_id,
-				burn_amount,
-				Precision::Exact,
-				Fortitude::Force,
-			)?;
-			frame_system::Pallet::<T>::dec_providers(&who)?;
-			T::BondEvents::utxo_unlocked(utxo_id, false, burn_amount)?;
+			let (bond, utxo_id, utxo_state, request, unlocker) =
+				UtxosPendingUnlockByUtxoId::<T>::mutate(|pending| {
+					Self::take_cosign_request(&who, &cosign, pending)
+				})?;
+			Self::verify_cosign(unlocker, &utxo_state, &cosign)?;
+			Self::complete_cosign(cosign, bond, utxo_id, utxo_state, request)?;
 
-			<UtxosById<T>>::take(utxo_id);
-			<UtxosCosignReleaseHeightById<T>>::insert(
-				utxo_id,
-				frame_system::Pallet::<T>::block_number(),
-			);
+			// no fee for cosigning
+			Ok(Pays::No.into())
+		}
 
-			Self::deposit_event(Event::BitcoinUtxoCosigned {
+		/// Submitted by a cosigner of a multisig vault to cosign the unlock of a bitcoin utxo.
+		/// The `cosigner_signatures` must have a signature from `threshold` of the vault's pubkeys.
+		/// The vault pubkey counts like any of the cosigner pubkeys, so it isn't required.
+		/// Otherwise this is processed like `cosign_bitcoin_unlock`.
+		#[pallet::call_index(10)]
+		#[pallet::weight((0, DispatchClass::Operational))]
+		pub fn cosign_bitcoin_unlock_multisig(
+			origin: OriginFor<T>,
+			bond_id: BondId,
+			cosigner_signatures: CosignerSignatures,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let cosign = BitcoinUnlockCosign {
 				bond_id,
-				vault_id,
-				utxo_id,
-				signature,
-			});
+				signature: None,
+				vault_musig_nonce: None,
+				cosigner_signatures,
+			};
+			let (bond, utxo_id, utxo_state, request, unlocker) =
+				UtxosPendingUnlockByUtxoId::<T>::mutate(|pending| {
+					Self::take_cosign_request(&who, &cosign, pending)
+				})?;
+			Self::verify_cosign(unlocker, &utxo_state, &cosign)?;
+			Self::complete_cosign(cosign, bond, utxo_id, utxo_state, request)?;
 
 			// no fee for cosigning
 			Ok(Pays::No.into())
 		}
-	}
 
-	impl<T: Config> BitcoinUtxoEvents for Pallet<T> {
-		fn utxo_verified(utxo_id: UtxoId) -> DispatchResult {
-			UtxosById::<T>::mutate(utxo_id, |a| {
-				if let Some(utxo_state) = a {
-					utxo_state.is_verified = true;
-					let bond =
-						BondsById::<T>::get(utxo_state.bond_id).ok_or(Error::<T>::BondNotFound)?;
-					T::BondEvents::utxo_bonded(utxo_id, &bond.bonded_account_id, bond.amount)?;
-				} else {
-					warn!("Verified utxo_id {:?} not found", utxo_id);
-				}
-				Ok::<(), DispatchError>(())
-			})
-		}
+		/// Submitted by a Vault cosigner to cosign many pending bitcoin unlocks at once. Each
+		/// cosign is processed as if submitted with `cosign_bitcoin_unlock`.
+		///
+		/// If a `batch_bitcoin_network_fee` is provided, the signatures must instead be for a
+		/// single bitcoin transaction that spends every utxo (see `UtxoUnlocker::batch`). The
+		/// inputs and outputs are in the order of the cosigns, and the fee is shared across the
+		/// unlock outputs in proportion to the fee each owner requested. The fee can't exceed the
+		/// sum of the requested fees. Every owner must have allowed batching, and all unlocks must
+		/// be from the same vault.
+		#[pallet::call_index(8)]
+		#[pallet::weight((
+			T::WeightInfo::cosign_bitcoin_unlocks(cosigns.len() as u32),
+			DispatchClass::Operational
+		))]
+		pub fn cosign_bitcoin_unlocks(
+			origin: OriginFor<T>,
+			cosigns: BoundedVec<BitcoinUnlockCosign, T::MaxUnlockingUtxos>,
+			batch_bitcoin_network_fee: Option<Satoshis>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
 
-		fn utxo_rejected(utxo_id: UtxoId, _reason: BitcoinRejectedReason) -> DispatchResult {
-			if let Some(utxo_state) = UtxosById::<T>::take(utxo_id) {
-				Self::cancel_bond(utxo_state.bond_id).map_err(Error::<T>::from)?;
+			let mut requests = Vec::with_capacity(cosigns.len());
+			let mut unlockers = Vec::with_capacity(cosigns.len());
+			let mut pending = UtxosPendingUnlockByUtxoId::<T>::get();
+			for cosign in cosigns {
+				let (bond, utxo_id, utxo_state, request, unlocker) =
+					Self::take_cosign_request(&who, &cosign, &mut pending)?;
+				requests.push((cosign, bond, utxo_id, utxo_state, request));
+				unlockers.push(unlocker);
 			}
-			Ok(())
-		}
-
-		fn utxo_spent(utxo_id: UtxoId) -> DispatchResult {
-			UtxosCosignReleaseHeightById::<T>::remove(utxo_id);
-			if let Some(utxo) = UtxosById::<T>::take(utxo_id) {
-				Self::burn_bitcoin_bond(utxo_id, utxo, true)
-			} else {
-				Ok(())
+			UtxosPendingUnlockByUtxoId::<T>::put(pending);
+
+			let mut batch_event = None;
+			if let Some(bitcoin_network_fee) = batch_bitcoin_network_fee {
+				let vault_id =
+					requests.first().map(|(_, bond, ..)| bond.vault_id).unwrap_or_default();
+				for (_, bond, _, _, request) in &requests {
+					ensure!(request.allow_batching, Error::<T>::BitcoinUnlockNotBatchable);
+					ensure!(bond.vault_id == vault_id, Error::<T>::BitcoinBatchMixesVaults);
+				}
+				unlockers = UtxoUnlocker::batch(unlockers, Amount::from_sat(bitcoin_network_fee))
+					.map_err(|e| match e {
+					argon_bitcoin::Error::FeeOverflow => Error::<T>::BitcoinFeeTooHigh,
+					_ => Error::<T>::BitcoinUnableToBeDecodedForUnlock,
+				})?;
+				let utxo_ids = BoundedVec::truncate_from(
+					requests.iter().map(|(_, _, utxo_id, _, _)| *utxo_id).collect::<Vec<_>>(),
+				);
+				batch_event = Some(Event::<T>::BitcoinUtxosBatchCosigned {
+					vault_id,
+					utxo_ids,
+					bitcoin_network_fee,
+				});
 			}
-		}
 
-		fn utxo_expired(utxo_id: UtxoId) -> DispatchResult {
-			UtxosCosignReleaseHeightById::<T>::remove(utxo_id);
-			if let Some(utxo) = UtxosById::<T>::take(utxo_id) {
-				Self::burn_bitcoin_bond(utxo_id, utxo, false)
-			} else {
-				Ok(())
+			for ((cosign, bond, utxo_id, utxo_state, request), unlocker) in
+				requests.into_iter().zip(unlockers)
+			{
+				Self::verify_cosign(unlocker, &utxo_state, &cosign)?;
+				Self::complete_cosign(cosign, bond, utxo_id, utxo_state, request)?;
+			}
+			if let Some(event) = batch_event {
+				Self::deposit_event(event);
 			}
-		}
-	}
 
-	impl<T: Config> Pallet<T> {
-		#[allow(clippy::too_many_arguments)]
-		fn create_bond(
-			vault_id: VaultId,
-			account_id: T::AccountId,
-			bond_type: BondType,
-			amount: T::Balance,
-			expiration: BondExpiration<BlockNumberFor<T>>,
-			total_fee: T::Balance,
-			prepaid_fee: T::Balance,
-			utxo_id: Option<UtxoId>,
-		) -> Result<BondId, BondError> {
-			let bond_id = NextBondId::<T>::get().unwrap_or(1);
+			// no fee for cosigning
+			Ok(Pays::No.into())
+		}
 
-			let next_bond_id = bond_id.increment().ok_or(BondError::NoMoreBondIds)?;
-			NextBondId::<T>::set(Some(next_bond_id));
+		/// Transfer a bitcoin bond, and the right to unlock the bitcoin, to another account.
+		///
+		/// Without an `owner_pubkey_rotation`, the transfer happens immediately on Argon only. The
+		/// new owner must already have access to the bitcoin owner key.
+		///
+		/// With an `owner_pubkey_rotation`, the bitcoin will be moved to a new cosign script with
+		/// the new owner pubkey. The vault must cosign the move (like an unlock), and the current
+		/// owner must complete and broadcast it. The bond is transferred once the vault cosigns.
+		#[pallet::call_index(6)]
+		#[pallet::weight(0)]
+		pub fn transfer_bitcoin_bond(
+			origin: OriginFor<T>,
+			bond_id: BondId,
+			to_account_id: T::AccountId,
+			owner_pubkey_rotation: Option<OwnerPubkeyRotation>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let bond = BondsById::<T>::get(bond_id).ok_or(Error::<T>::BondNotFound)?;
+			ensure!(bond.bond_type == BondType::Bitcoin, Error::<T>::NoPermissions);
+			ensure!(bond.bonded_account_id == who, Error::<T>::NoPermissions);
+
+			let utxo_id = bond.utxo_id.ok_or(Error::<T>::InvalidBondType)?;
+			ensure!(!Self::has_pending_cosign(bond_id), Error::<T>::BitcoinUtxoCosignPending);
+
+			let Some(rotation) = owner_pubkey_rotation else {
+				return Self::transfer_bond(bond_id, to_account_id);
+			};
+
+			Self::ensure_cosign_deadline_not_passed(&bond)?;
+			let utxo = <UtxosById<T>>::get(utxo_id).ok_or(Error::<T>::BitcoinUtxoNotFound)?;
+			Self::ensure_utxo_can_move(
+				utxo_id,
+				&utxo,
+				rotation.bitcoin_network_fee,
+				&rotation.owner_musig_nonce,
+			)?;
+			let (move_utxo_id, move_utxo) = Self::create_replacement_utxo(
+				bond.vault_id,
+				UtxoState {
+					owner_pubkey: rotation.owner_pubkey,
+					satoshis: utxo.satoshis.saturating_sub(rotation.bitcoin_network_fee),
+					previous_utxo_id: Some(utxo_id),
+					..utxo.clone()
+				},
+			)?;
+
+			Self::request_utxo_move(
+				bond_id,
+				bond.vault_id,
+				utxo_id,
+				rotation.bitcoin_network_fee,
+				rotation.owner_musig_nonce,
+				UtxoMove {
+					utxo_id: move_utxo_id,
+					utxo: move_utxo,
+					transfer_to_account_id: Some(to_account_id),
+					renew_with_vault_id: None,
+					renewal_fee: T::Balance::zero(),
+					migrate_to_vault_id: None,
+				},
+			)
+		}
+
+		/// Renew a bitcoin bond before it expires. The bitcoin will be moved to a new cosign
+		/// script with new claim heights, using the pubkeys of the given vault (which can be the
+		/// current vault or a different one).
+		///
+		/// The current vault must cosign the move (like an unlock), and the owner must complete and
+		/// broadcast it. The renewal fee of the renewing vault is held until then. Once cosigned,
+		/// the current bond funds are released, and the same amount is bonded by the renewing
+		/// vault. The renewed bond keeps the same bond id, and the new utxo points back to the
+		/// previous one. If the move isn't cosigned in time, the held fee is released.
+		///
+		/// A taproot (P2TR) bond must include the owner's MuSig2 public nonce for the move.
+		#[pallet::call_index(7)]
+		#[pallet::weight(0)]
+		pub fn renew_bitcoin_bond(
+			origin: OriginFor<T>,
+			bond_id: BondId,
+			vault_id: VaultId,
+			bitcoin_network_fee: Satoshis,
+			owner_musig_nonce: Option<BitcoinMusigNonce>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let bond = BondsById::<T>::get(bond_id).ok_or(Error::<T>::BondNotFound)?;
+			ensure!(bond.bond_type == BondType::Bitcoin, Error::<T>::NoPermissions);
+			ensure!(bond.bonded_account_id == who, Error::<T>::NoPermissions);
+			Self::ensure_cosign_deadline_not_passed(&bond)?;
+
+			let vault = T::VaultProvider::get(vault_id).ok_or(Error::<T>::VaultNotFound)?;
+			ensure!(!vault.is_closed, Error::<T>::VaultClosed);
+
+			let utxo_id = bond.utxo_id.ok_or(Error::<T>::InvalidBondType)?;
+			let utxo = <UtxosById<T>>::get(utxo_id).ok_or(Error::<T>::BitcoinUtxoNotFound)?;
+			Self::ensure_utxo_can_move(utxo_id, &utxo, bitcoin_network_fee, &owner_musig_nonce)?;
+
+			// the renewing vault is paid once the move is cosigned, so hold the fee until then
+			let (renewal_fee, _) = T::VaultProvider::quote_bond_renewal(
+				&bond,
+				vault_id,
+				// charge in 1 year of blocks (even though we'll expire off bitcoin time)
+				T::ArgonBlocksPerDay::get() * 365u32.into(),
+			)
+			.map_err(Error::<T>::from)?;
+			if renewal_fee > T::Balance::zero() {
+				T::Currency::hold(&HoldReason::RenewingBitcoinBond.into(), &who, renewal_fee)
+					.map_err(|e| match e {
+						Token(TokenError::BelowMinimum) =>
+							Error::<T>::AccountWouldGoBelowMinimumBalance,
+						_ => Error::<T>::InsufficientFunds,
+					})?;
+				frame_system::Pallet::<T>::inc_providers(&who);
+			}
+
+			let vault_claim_height =
+				T::BitcoinBlockHeight::get() + T::BitcoinBondDurationBlocks::get();
+			let open_claim_height = vault_claim_height + T::BitcoinBondReclamationBlocks::get();
+			let (move_utxo_id, move_utxo) = Self::create_replacement_utxo(
+				vault_id,
+				UtxoState {
+					// the network fee comes out of the owner's satoshis, so the renewed bond keeps
+					// the same bonded satoshis (and amount)
+					satoshis: utxo.satoshis.saturating_sub(bitcoin_network_fee),
+					vault_claim_height,
+					open_claim_height,
+					previous_utxo_id: Some(utxo_id),
+					..utxo.clone()
+				},
+			)?;
+
+			Self::request_utxo_move(
+				bond_id,
+				bond.vault_id,
+				utxo_id,
+				bitcoin_network_fee,
+				owner_musig_nonce,
+				UtxoMove {
+					utxo_id: move_utxo_id,
+					utxo: move_utxo,
+					transfer_to_account_id: None,
+					renew_with_vault_id: Some(vault_id),
+					renewal_fee,
+					migrate_to_vault_id: None,
+				},
+			)
+		}
+
+		/// Ask the vault to cosign an unlock again with a higher `bitcoin_network_fee`. This is
+		/// only possible until the cosigned unlock is seen on the bitcoin network. Both
+		/// transactions spend the same utxo, so the replacement can be broadcast using
+		/// replace-by-fee. A taproot (P2TR) bond must include a new owner MuSig2 public nonce.
+		///
+		/// If the vault doesn't cosign in time, the original unlock remains valid.
+		#[pallet::call_index(9)]
+		#[pallet::weight(0)]
+		pub fn request_bitcoin_unlock_fee_bump(
+			origin: OriginFor<T>,
+			utxo_id: UtxoId,
+			bitcoin_network_fee: Satoshis,
+			owner_musig_nonce: Option<BitcoinMusigNonce>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let (utxo, mut request) = <CosignedUnlocksByUtxoId<T>>::get(utxo_id)
+				.ok_or(Error::<T>::BitcoinUnlockNotCosigned)?;
+			let bond = BondsById::<T>::get(request.bond_id).ok_or(Error::<T>::BondNotFound)?;
+			ensure!(bond.bonded_account_id == who, Error::<T>::NoPermissions);
+			ensure!(
+				!Self::has_pending_cosign(request.bond_id),
+				Error::<T>::BitcoinUtxoCosignPending
+			);
+			ensure!(
+				bitcoin_network_fee > request.bitcoin_network_fee,
+				Error::<T>::BitcoinFeeNotIncreased
+			);
+			let redeemed_satoshis = utxo.satoshis.saturating_sub(
+				request.remainder_utxo.as_ref().map(|(_, a)| a.satoshis).unwrap_or_default(),
+			);
+			ensure!(bitcoin_network_fee < redeemed_satoshis, Error::<T>::BitcoinFeeTooHigh);
+			ensure!(
+				owner_musig_nonce.is_some() ==
+					(utxo.utxo_script_pubkey.script_type() == BitcoinCosignScriptType::P2TR),
+				Error::<T>::BitcoinMusigNonceMismatch
+			);
+
+			let bond_id = request.bond_id;
+			let vault_id = request.vault_id;
+			request.bitcoin_network_fee = bitcoin_network_fee;
+			request.cosign_due_block =
+				T::UtxoUnlockCosignDeadlineBlocks::get() + T::BitcoinBlockHeight::get();
+			request.redemption_price = T::Balance::zero();
+			request.owner_musig_nonce = owner_musig_nonce;
+			request.is_fee_bump = true;
+			// the replacement only spends this utxo
+			request.allow_batching = false;
+			Self::insert_pending_cosign(utxo_id, request)?;
+
+			Self::deposit_event(Event::<T>::BitcoinUnlockFeeBumpRequested {
+				bond_id,
+				vault_id,
+				utxo_id,
+				bitcoin_network_fee,
+			});
+			Ok(())
+		}
+
+		/// Migrate a bitcoin bond off a closed vault to the vault that accepted its bonds (see
+		/// `offer_bond_migration` in the vaults pallet). The bitcoin is moved to a cosign script
+		/// using the pubkeys of the new vault, with the same claim heights.
+		///
+		/// The closed vault must cosign the move (like an unlock), and the owner must complete and
+		/// broadcast it. Once cosigned, the new vault takes over the bond. The closed vault is paid
+		/// the fee earned so far, and its funds for the bond are released. Only the bond owner can
+		/// request the move, since the bitcoin fee comes out of their bitcoin, and a taproot (P2TR)
+		/// bond must include their MuSig2 public nonce.
+		#[pallet::call_index(11)]
+		#[pallet::weight(0)]
+		pub fn migrate_bitcoin_bond(
+			origin: OriginFor<T>,
+			bond_id: BondId,
+			bitcoin_network_fee: Satoshis,
+			owner_musig_nonce: Option<BitcoinMusigNonce>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let bond = BondsById::<T>::get(bond_id).ok_or(Error::<T>::BondNotFound)?;
+			ensure!(bond.bond_type == BondType::Bitcoin, Error::<T>::NoPermissions);
+			ensure!(bond.bonded_account_id == who, Error::<T>::NoPermissions);
+			Self::ensure_cosign_deadline_not_passed(&bond)?;
+
+			let to_vault_id = T::VaultProvider::bond_migration_vault(bond.vault_id)
+				.ok_or(Error::<T>::BondMigrationNotAccepted)?;
+			let to_vault = T::VaultProvider::get(to_vault_id).ok_or(Error::<T>::VaultNotFound)?;
+			ensure!(!to_vault.is_closed, Error::<T>::VaultClosed);
+			ensure!(
+				to_vault.bitcoin_argons.free_balance() >= bond.amount,
+				Error::<T>::InsufficientVaultFunds
+			);
+
+			let utxo_id = bond.utxo_id.ok_or(Error::<T>::InvalidBondType)?;
+			let utxo = <UtxosById<T>>::get(utxo_id).ok_or(Error::<T>::BitcoinUtxoNotFound)?;
+			Self::ensure_utxo_can_move(utxo_id, &utxo, bitcoin_network_fee, &owner_musig_nonce)?;
+
+			let (move_utxo_id, move_utxo) = Self::create_replacement_utxo(
+				to_vault_id,
+				UtxoState {
+					satoshis: utxo.satoshis.saturating_sub(bitcoin_network_fee),
+					previous_utxo_id: Some(utxo_id),
+					..utxo.clone()
+				},
+			)?;
+
+			Self::request_utxo_move(
+				bond_id,
+				bond.vault_id,
+				utxo_id,
+				bitcoin_network_fee,
+				owner_musig_nonce,
+				UtxoMove {
+					utxo_id: move_utxo_id,
+					utxo: move_utxo,
+					transfer_to_account_id: None,
+					renew_with_vault_id: None,
+					renewal_fee: T::Balance::zero(),
+					migrate_to_vault_id: Some(to_vault_id),
+				},
+			)
+		}
+	}
+
+	impl<T: Config> BitcoinUtxoEvents for Pallet<T> {
+		fn utxo_verified(utxo_id: UtxoId) -> DispatchResult {
+			UtxosById::<T>::mutate(utxo_id, |a| {
+				if let Some(utxo_state) = a {
+					utxo_state.is_verified = true;
+					// a replacement utxo was already minted with the original
+					if utxo_state.previous_utxo_id.is_some() {
+						return Ok(());
+					}
+					let bond =
+						BondsById::<T>::get(utxo_state.bond_id).ok_or(Error::<T>::BondNotFound)?;
+					T::BondEvents::utxo_bonded(utxo_id, &bond.bonded_account_id, bond.amount)?;
+				} else {
+					warn!("Verified utxo_id {:?} not found", utxo_id);
+				}
+				Ok::<(), DispatchError>(())
+			})
+		}
+
+		fn utxo_rejected(utxo_id: UtxoId, _reason: BitcoinRejectedReason) -> DispatchResult {
+			if let Some(utxo_state) = UtxosById::<T>::take(utxo_id) {
+				Self::cancel_bond(utxo_state.bond_id).map_err(Error::<T>::from)?;
+			}
+			Ok(())
+		}
+
+		fn utxo_spent(utxo_id: UtxoId) -> DispatchResult {
+			let mut spent = SpentUtxo {
+				utxo_state: None,
+				cosigned_unlock: CosignedUnlocksByUtxoId::<T>::get(utxo_id),
+				cosign_release_height: UtxosCosignReleaseHeightById::<T>::get(utxo_id),
+				fee_bump_cosign_height: UtxosFeeBumpCosignHeightById::<T>::get(utxo_id),
+				revertible_until_height: T::BitcoinBlockHeight::get() +
+					T::BitcoinSpendRevertBlocks::get(),
+			};
+			Self::remove_cosigned_unlock(utxo_id);
+			if let Some(utxo) = UtxosById::<T>::take(utxo_id) {
+				spent.utxo_state = Some(utxo.clone());
+				Self::burn_bitcoin_bond(utxo_id, utxo, true)?;
+			}
+			if spent.utxo_state.is_some() || spent.cosigned_unlock.is_some() {
+				Self::record_spent_utxo(utxo_id, spent);
+			}
+			Ok(())
+		}
+
+		fn utxo_expired(utxo_id: UtxoId) -> DispatchResult {
+			Self::remove_cosigned_unlock(utxo_id);
+			if let Some(utxo) = UtxosById::<T>::take(utxo_id) {
+				Self::burn_bitcoin_bond(utxo_id, utxo, false)
+			} else {
+				Ok(())
+			}
+		}
+
+		fn utxo_verification_reverted(utxo_id: UtxoId) -> DispatchResult {
+			UtxosById::<T>::mutate(utxo_id, |a| {
+				let Some(utxo_state) = a else {
+					return Ok(());
+				};
+				if !utxo_state.is_verified {
+					return Ok(());
+				}
+				utxo_state.is_verified = false;
+				// argons that were already minted can't be recalled, but no more are minted until
+				// the utxo is verified again, and then only the remainder
+				if utxo_state.previous_utxo_id.is_none() {
+					T::BondEvents::utxo_verification_reverted(utxo_id)?;
+				}
+				Self::deposit_event(Event::BitcoinUtxoVerificationReverted {
+					bond_id: utxo_state.bond_id,
+					utxo_id,
+				});
+				Ok::<(), DispatchError>(())
+			})
+		}
+
+		/// Restores the bonded utxo, or the cosigned unlock, that the spend removed. Vault funds
+		/// burned for a spend outside the system can't be restored, so the bond keeps the reduced
+		/// amount.
+		fn utxo_spend_reverted(utxo_id: UtxoId) -> DispatchResult {
+			// nothing was changed by the spend
+			let Some(spent) = SpentUtxosById::<T>::take(utxo_id) else {
+				return Ok(());
+			};
+			SpentUtxoExpirationsByBitcoinHeight::<T>::mutate(spent.revertible_until_height, |a| {
+				a.retain(|id| *id != utxo_id)
+			});
+
+			let mut bond_id = None;
+			if let Some((utxo_state, request)) = spent.cosigned_unlock {
+				bond_id = Some(request.bond_id);
+				CosignedUnlocksByUtxoId::<T>::insert(utxo_id, (utxo_state, request));
+				if let Some(block_number) = spent.cosign_release_height {
+					UtxosCosignReleaseHeightById::<T>::insert(utxo_id, block_number);
+				}
+				if let Some(block_number) = spent.fee_bump_cosign_height {
+					UtxosFeeBumpCosignHeightById::<T>::insert(utxo_id, block_number);
+				}
+			}
+			if let Some(utxo_state) = spent.utxo_state {
+				ensure!(
+					BondsById::<T>::contains_key(utxo_state.bond_id),
+					Error::<T>::BitcoinSpendCannotBeReverted
+				);
+				bond_id = Some(utxo_state.bond_id);
+				UtxosById::<T>::insert(utxo_id, utxo_state);
+			}
+			if let Some(bond_id) = bond_id {
+				Self::deposit_event(Event::BitcoinUtxoSpendReverted { bond_id, utxo_id });
+			}
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		#[allow(clippy::too_many_arguments)]
+		fn create_bond(
+			vault_id: VaultId,
+			account_id: T::AccountId,
+			bond_type: BondType,
+			amount: T::Balance,
+			expiration: BondExpiration<BlockNumberFor<T>>,
+			total_fee: T::Balance,
+			prepaid_fee: T::Balance,
+			annual_percent_rate: FixedU128,
+			utxo_id: Option<UtxoId>,
+		) -> Result<BondId, BondError> {
+			let bond_id = NextBondId::<T>::get().unwrap_or(1);
+
+			let next_bond_id = bond_id.increment().ok_or(BondError::NoMoreBondIds)?;
+			NextBondId::<T>::set(Some(next_bond_id));
 
 			let bond = Bond {
 				vault_id,
@@ -834,6 +1578,7 @@ pub mod pallet {
 				total_fee,
 				start_block: frame_system::Pallet::<T>::block_number(),
 				prepaid_fee,
+				annual_percent_rate,
 			};
 			BondsById::<T>::set(bond_id, Some(bond));
 			match expiration {
@@ -868,7 +1613,7 @@ pub mod pallet {
 
 			if bond.bond_type == BondType::Bitcoin {
 				let utxo_id = bond.utxo_id.ok_or(Error::<T>::InvalidBondType)?;
-				UtxosCosignReleaseHeightById::<T>::remove(utxo_id);
+				Self::remove_cosigned_unlock(utxo_id);
 				if let Some(utxo) = <UtxosById<T>>::take(utxo_id) {
 					Self::burn_bitcoin_bond(utxo_id, utxo, false)?;
 					BondsById::<T>::remove(bond_id);
@@ -897,9 +1642,10 @@ pub mod pallet {
 			let mut bond = BondsById::<T>::get(bond_id).ok_or(Error::<T>::BondNotFound)?;
 
 			// burn the current redemption price from the bond
+			let bonded_share = Self::bonded_share(&bond, &utxo, utxo.satoshis);
 			let amount_to_burn = Self::get_redemption_price(&utxo.satoshis)
-				.unwrap_or(bond.amount)
-				.min(bond.amount);
+				.unwrap_or(bonded_share)
+				.min(bonded_share);
 
 			T::VaultProvider::burn_vault_bitcoin_funds(&bond, amount_to_burn)
 				.map_err(Error::<T>::from)?;
@@ -973,6 +1719,578 @@ pub mod pallet {
 
 			Ok(())
 		}
+
+		/// Removes the pending cosign request for a bond from `pending`, and recreates the unlock
+		/// transaction the vault must sign
+		#[allow(clippy::type_complexity)]
+		fn take_cosign_request(
+			who: &T::AccountId,
+			cosign: &BitcoinUnlockCosign,
+			pending: &mut PendingUnlocks<T>,
+		) -> Result<
+			(
+				Bond<T::AccountId, T::Balance, BlockNumberFor<T>>,
+				UtxoId,
+				UtxoState,
+				UtxoCosignRequest<T::AccountId, T::Balance>,
+				UtxoUnlocker,
+			),
+			DispatchError,
+		> {
+			let BitcoinUnlockCosign { bond_id, vault_musig_nonce, .. } = *cosign;
+			let bond = BondsById::<T>::get(bond_id).ok_or(Error::<T>::BondNotFound)?;
+			ensure!(bond.bond_type == BondType::Bitcoin, Error::<T>::NoPermissions);
+
+			let vault_id = bond.vault_id;
+			ensure!(
+				T::VaultProvider::has_vault_role(vault_id, VaultRole::Cosigner, who),
+				Error::<T>::NoPermissions
+			);
+			// a fee bump can be for a utxo the bond has already moved on from (eg, a partial
+			// redemption), so look up the request by bond
+			let utxo_id = PendingUnlockUtxoIdByBondId::<T>::take(bond_id)
+				.ok_or(Error::<T>::BondRedemptionNotLocked)?;
+			let request = pending.remove(&utxo_id).ok_or(Error::<T>::BondRedemptionNotLocked)?;
+
+			let utxo_state = if request.is_fee_bump {
+				<CosignedUnlocksByUtxoId<T>>::get(utxo_id).map(|(utxo, _)| utxo)
+			} else {
+				<UtxosById<T>>::get(utxo_id)
+			}
+			.ok_or(Error::<T>::BitcoinUtxoNotFound)?;
+			let utxo_ref =
+				T::BitcoinUtxoTracker::get(utxo_id).ok_or(Error::<T>::BitcoinUtxoNotFound)?;
+
+			let script_args = CosignScriptArgs {
+				vault_pubkey: utxo_state.vault_pubkey,
+				owner_pubkey: utxo_state.owner_pubkey,
+				vault_claim_pubkey: utxo_state.vault_claim_pubkey,
+				created_at_height: utxo_state.created_at_height,
+				vault_claim_height: utxo_state.vault_claim_height,
+				open_claim_height: utxo_state.open_claim_height,
+				vault_multisig: utxo_state.vault_multisig.clone(),
+			};
+			let script_type = utxo_state.utxo_script_pubkey.script_type();
+			ensure!(
+				vault_musig_nonce.is_some() == (script_type == BitcoinCosignScriptType::P2TR),
+				Error::<T>::BitcoinMusigNonceMismatch
+			);
+			let cosign_script = CosignScript::with_script_type(
+				script_args,
+				script_type,
+				T::GetBitcoinNetwork::get().into(),
+			)
+			.map_err(|_| Error::<T>::BitcoinUnableToBeDecodedForUnlock)?;
+			let mut unlocker = UtxoUnlocker::from_script(
+				cosign_script,
+				utxo_state.satoshis,
+				utxo_ref.txid.into(),
+				utxo_ref.output_index,
+				UnlockStep::VaultCosign,
+				Amount::from_sat(request.bitcoin_network_fee),
+				request.to_script_pubkey.into(),
+			)
+			.map_err(|_| Error::<T>::BitcoinUnableToBeDecodedForUnlock)?;
+			if let Some((_, remainder)) = &request.remainder_utxo {
+				unlocker
+					.add_remainder_output(remainder.utxo_script_pubkey.into(), remainder.satoshis)
+					.map_err(|_| Error::<T>::BitcoinUnableToBeDecodedForUnlock)?;
+			}
+
+			if let (Some(vault_nonce), Some(owner_nonce)) =
+				(vault_musig_nonce, request.owner_musig_nonce)
+			{
+				let vault_pubkey = utxo_state
+					.vault_pubkey
+					.try_into()
+					.map_err(|_| Error::<T>::BitcoinPubkeyUnableToBeDecoded)?;
+				let owner_pubkey = utxo_state
+					.owner_pubkey
+					.try_into()
+					.map_err(|_| Error::<T>::BitcoinPubkeyUnableToBeDecoded)?;
+				unlocker.add_musig_nonce(vault_pubkey, vault_nonce);
+				unlocker.add_musig_nonce(owner_pubkey, owner_nonce);
+			}
+
+			Ok((bond, utxo_id, utxo_state, request, unlocker))
+		}
+
+		/// Verifies the vault signature of a cosign. A multisig vault needs valid signatures from
+		/// `threshold` of its pubkeys, where the vault pubkey is one of them.
+		fn verify_cosign(
+			unlocker: UtxoUnlocker,
+			utxo_state: &UtxoState,
+			cosign: &BitcoinUnlockCosign,
+		) -> DispatchResult {
+			let Some(multisig) = &utxo_state.vault_multisig else {
+				ensure!(
+					cosign.cosigner_signatures.is_empty(),
+					Error::<T>::InvalidVaultCosignerSignatures
+				);
+				let signature =
+					cosign.signature.as_ref().ok_or(Error::<T>::BitcoinInvalidCosignature)?;
+				let is_valid = T::BitcoinSignatureVerifier::verify_signature(
+					unlocker,
+					utxo_state.vault_pubkey,
+					signature,
+				)?;
+				ensure!(is_valid, Error::<T>::BitcoinInvalidCosignature);
+				return Ok(());
+			};
+
+			let signatures = cosign
+				.signature
+				.iter()
+				.map(|a| (utxo_state.vault_pubkey, a))
+				.chain(cosign.cosigner_signatures.iter().map(|(pubkey, a)| (*pubkey, a)));
+			let mut signed_pubkeys = Vec::with_capacity(multisig.cosign_pubkeys.len() + 1);
+			for (pubkey, signature) in signatures {
+				ensure!(
+					(pubkey == utxo_state.vault_pubkey ||
+						multisig.cosign_pubkeys.contains(&pubkey)) &&
+						!signed_pubkeys.contains(&pubkey),
+					Error::<T>::InvalidVaultCosignerSignatures
+				);
+				let is_valid = T::BitcoinSignatureVerifier::verify_signature(
+					unlocker.clone(),
+					pubkey,
+					signature,
+				)?;
+				ensure!(is_valid, Error::<T>::BitcoinInvalidCosignature);
+				signed_pubkeys.push(pubkey);
+			}
+			ensure!(
+				signed_pubkeys.len() >= multisig.threshold as usize,
+				Error::<T>::InvalidVaultCosignerSignatures
+			);
+			Ok(())
+		}
+
+		/// Burns the owner's held funds for a cosigned unlock (or completes a move), and releases
+		/// the cosigned utxo
+		fn complete_cosign(
+			cosign: BitcoinUnlockCosign,
+			bond: Bond<T::AccountId, T::Balance, BlockNumberFor<T>>,
+			utxo_id: UtxoId,
+			utxo_state: UtxoState,
+			request: UtxoCosignRequest<T::AccountId, T::Balance>,
+		) -> DispatchResult {
+			let BitcoinUnlockCosign { bond_id, signature, vault_musig_nonce, cosigner_signatures } =
+				cosign;
+			let vault_id = bond.vault_id;
+			let requested_at_block = request
+				.cosign_due_block
+				.saturating_sub(T::UtxoUnlockCosignDeadlineBlocks::get());
+			T::VaultProvider::record_unlock_cosigned(
+				vault_id,
+				T::BitcoinBlockHeight::get().saturating_sub(requested_at_block),
+			);
+			// a move keeps the bitcoin bonded, and a fee bump was paid for by the original unlock,
+			// so there are no held funds to burn
+			if request.utxo_move.is_none() && !request.is_fee_bump {
+				// burn the owner's held funds
+				let burn_amount = request.redemption_price;
+				let _ = T::Currency::burn_held(
+					&HoldReason::UnlockingBitcoin.into(),
+					&bond.bonded_account_id,
+					burn_amount,
+					Precision::Exact,
+					Fortitude::Force,
+				)?;
+				frame_system::Pallet::<T>::dec_providers(&bond.bonded_account_id)?;
+				T::BondEvents::utxo_unlocked(utxo_id, false, burn_amount)?;
+			}
+
+			<UtxosById<T>>::take(utxo_id);
+			// the original unlock stays valid, so a fee bump keeps its release height
+			if request.is_fee_bump {
+				<UtxosFeeBumpCosignHeightById<T>>::insert(
+					utxo_id,
+					frame_system::Pallet::<T>::block_number(),
+				);
+			} else {
+				<UtxosCosignReleaseHeightById<T>>::insert(
+					utxo_id,
+					frame_system::Pallet::<T>::block_number(),
+				);
+			}
+			if request.utxo_move.is_none() {
+				<CosignedUnlocksByUtxoId<T>>::insert(
+					utxo_id,
+					(utxo_state.clone(), request.clone()),
+				);
+			}
+
+			Self::deposit_event(Event::BitcoinUtxoCosigned {
+				bond_id,
+				vault_id,
+				utxo_id,
+				signature,
+				vault_musig_nonce,
+				cosigner_signatures,
+			});
+
+			// the rest of the unlock was completed when the vault first cosigned it
+			if request.is_fee_bump {
+				return Ok(());
+			}
+
+			if let Some((remainder_utxo_id, remainder)) = request.remainder_utxo {
+				Self::deposit_event(Event::BitcoinBondPartiallyRedeemed {
+					bond_id,
+					vault_id,
+					utxo_id,
+					redeemed_satoshis: utxo_state.satoshis.saturating_sub(remainder.satoshis),
+					remainder_utxo_id,
+					remainder_satoshis: remainder.satoshis,
+				});
+				Self::release_redeemed_funds(bond_id, &utxo_state, remainder.satoshis)?;
+				Self::replace_bond_utxo(bond_id, remainder_utxo_id, remainder)?;
+			}
+
+			if let Some(utxo_move) = request.utxo_move {
+				Self::deposit_event(Event::BitcoinUtxoMoved {
+					bond_id,
+					vault_id,
+					utxo_id,
+					to_utxo_id: utxo_move.utxo_id,
+				});
+				let vault_claim_height = utxo_move.utxo.vault_claim_height;
+				Self::replace_bond_utxo(bond_id, utxo_move.utxo_id, utxo_move.utxo)?;
+				if let Some(to_account_id) = utxo_move.transfer_to_account_id {
+					Self::transfer_bond(bond_id, to_account_id)?;
+				}
+				if let Some(renew_vault_id) = utxo_move.renew_with_vault_id {
+					Self::renew_bond(
+						bond_id,
+						renew_vault_id,
+						utxo_move.renewal_fee,
+						vault_claim_height,
+					)?;
+				}
+				if let Some(to_vault_id) = utxo_move.migrate_to_vault_id {
+					Self::migrate_bond(bond_id, to_vault_id)?;
+				}
+			}
+
+			Ok(())
+		}
+
+		/// Creates a new cosign script for a utxo that will replace a bonded utxo (eg, the
+		/// unredeemed satoshis of a partial redemption). The owner pubkey, claim heights and
+		/// script type come from the given utxo, and new vault pubkeys are created.
+		fn create_replacement_utxo(
+			vault_id: VaultId,
+			mut utxo: UtxoState,
+		) -> Result<(UtxoId, UtxoState), Error<T>> {
+			let utxo_id = T::BitcoinUtxoTracker::new_utxo_id();
+			let current_bitcoin_height = T::BitcoinBlockHeight::get();
+			let (vault_xpub, vault_claim_xpub, script_pubkey, vault_multisig) =
+				T::VaultProvider::create_utxo_script_pubkey(
+					vault_id,
+					utxo_id,
+					utxo.utxo_script_pubkey.script_type(),
+					utxo.owner_pubkey,
+					utxo.vault_claim_height,
+					utxo.open_claim_height,
+					current_bitcoin_height,
+				)
+				.map_err(|_| Error::<T>::InvalidBitcoinScript)?;
+
+			utxo.vault_pubkey = vault_xpub.public_key;
+			utxo.vault_claim_pubkey = vault_claim_xpub.public_key;
+			utxo.vault_xpub_sources = (
+				vault_xpub.parent_fingerprint,
+				vault_xpub.child_number,
+				vault_claim_xpub.child_number,
+			);
+			utxo.utxo_script_pubkey = script_pubkey;
+			utxo.vault_multisig = vault_multisig;
+			utxo.created_at_height = current_bitcoin_height;
+			utxo.is_verified = false;
+			Ok((utxo_id, utxo))
+		}
+
+		/// Checks that a bonded utxo can be moved to a new cosign script
+		fn ensure_utxo_can_move(
+			utxo_id: UtxoId,
+			utxo: &UtxoState,
+			bitcoin_network_fee: Satoshis,
+			owner_musig_nonce: &Option<BitcoinMusigNonce>,
+		) -> DispatchResult {
+			ensure!(!Self::has_pending_cosign(utxo.bond_id), Error::<T>::BitcoinUtxoCosignPending);
+			ensure!(utxo.is_verified, Error::<T>::BitcoinUtxoNotVerified);
+			ensure!(bitcoin_network_fee < utxo.satoshis, Error::<T>::BitcoinFeeTooHigh);
+			ensure!(
+				owner_musig_nonce.is_some() ==
+					(utxo.utxo_script_pubkey.script_type() == BitcoinCosignScriptType::P2TR),
+				Error::<T>::BitcoinMusigNonceMismatch
+			);
+			Ok(())
+		}
+
+		/// Whether the bond already has a cosign request pending with its vault
+		fn has_pending_cosign(bond_id: BondId) -> bool {
+			PendingUnlockUtxoIdByBondId::<T>::contains_key(bond_id)
+		}
+
+		/// Adds a cosign request for the vault of the bond
+		fn insert_pending_cosign(
+			utxo_id: UtxoId,
+			request: UtxoCosignRequest<T::AccountId, T::Balance>,
+		) -> DispatchResult {
+			PendingUnlockUtxoIdByBondId::<T>::insert(request.bond_id, utxo_id);
+			<UtxosPendingUnlockByUtxoId<T>>::try_mutate(|a| a.try_insert(utxo_id, request))
+				.map_err(|_| Error::<T>::ExpirationAtBlockOverflow)?;
+			Ok(())
+		}
+
+		/// Keeps the bond state a spend changed until the spend can no longer be reverted. If too
+		/// many spends expire at the same height, the spend can't be reverted.
+		fn record_spent_utxo(
+			utxo_id: UtxoId,
+			spent: SpentUtxo<T::AccountId, T::Balance, BlockNumberFor<T>>,
+		) {
+			let res = SpentUtxoExpirationsByBitcoinHeight::<T>::try_mutate(
+				spent.revertible_until_height,
+				|a| a.try_push(utxo_id),
+			);
+			if res.is_err() {
+				log::warn!("Spent bitcoin utxo id {:?} can't be reverted", utxo_id);
+				return;
+			}
+			SpentUtxosById::<T>::insert(utxo_id, spent);
+		}
+
+		/// Clears a cosigned unlock (and any fee bump of it) once it can no longer be replaced
+		fn remove_cosigned_unlock(utxo_id: UtxoId) {
+			UtxosCosignReleaseHeightById::<T>::remove(utxo_id);
+			UtxosFeeBumpCosignHeightById::<T>::remove(utxo_id);
+			if CosignedUnlocksByUtxoId::<T>::take(utxo_id).is_some() {
+				UtxosPendingUnlockByUtxoId::<T>::mutate(|a| {
+					if a.get(&utxo_id).is_some_and(|x| x.is_fee_bump) {
+						if let Some(request) = a.remove(&utxo_id) {
+							PendingUnlockUtxoIdByBondId::<T>::remove(request.bond_id);
+						}
+					}
+				});
+			}
+		}
+
+		/// Asks the vault to cosign moving a utxo into the new cosign script of `utxo_move`
+		fn request_utxo_move(
+			bond_id: BondId,
+			vault_id: VaultId,
+			utxo_id: UtxoId,
+			bitcoin_network_fee: Satoshis,
+			owner_musig_nonce: Option<BitcoinMusigNonce>,
+			utxo_move: UtxoMove<T::AccountId, T::Balance>,
+		) -> DispatchResult {
+			let to_script_pubkey =
+				BitcoinScriptPubkey::try_from(utxo_move.utxo.utxo_script_pubkey.to_script_bytes())
+					.map_err(|_| Error::<T>::InvalidBitcoinScript)?;
+
+			Self::insert_pending_cosign(
+				utxo_id,
+				UtxoCosignRequest {
+					bond_id,
+					vault_id,
+					bitcoin_network_fee,
+					cosign_due_block: T::UtxoUnlockCosignDeadlineBlocks::get() +
+						T::BitcoinBlockHeight::get(),
+					to_script_pubkey,
+					redemption_price: T::Balance::zero(),
+					owner_musig_nonce,
+					remainder_utxo: None,
+					utxo_move: Some(utxo_move),
+					is_fee_bump: false,
+					allow_batching: false,
+				},
+			)?;
+
+			Self::deposit_event(Event::<T>::BitcoinUtxoCosignRequested {
+				bond_id,
+				vault_id,
+				utxo_id,
+			});
+			Ok(())
+		}
+
+		/// Releases the funds of the current vault, and bonds the same amount with the renewing
+		/// vault until the new vault claim height. The renewal fee held when the renewal was
+		/// requested is released to pay the renewing vault.
+		fn renew_bond(
+			bond_id: BondId,
+			vault_id: VaultId,
+			renewal_fee: T::Balance,
+			vault_claim_height: BitcoinHeight,
+		) -> DispatchResult {
+			let mut bond = BondsById::<T>::get(bond_id).ok_or(Error::<T>::BondNotFound)?;
+			Self::release_renewal_fee(&bond.bonded_account_id, renewal_fee)?;
+			T::VaultProvider::release_bonded_funds(&bond).map_err(Error::<T>::from)?;
+			Self::remove_bond_completion(bond_id, bond.expiration.clone());
+
+			let amount = bond.amount;
+			let (total_fee, prepaid_fee, annual_percent_rate) = T::VaultProvider::bond_funds(
+				vault_id,
+				amount,
+				BondType::Bitcoin,
+				// charge in 1 year of blocks (even though we'll expire off bitcoin time)
+				T::ArgonBlocksPerDay::get() * 365u32.into(),
+				&bond.bonded_account_id,
+			)
+			.map_err(Error::<T>::from)?;
+			ensure!(total_fee <= amount, Error::<T>::FeeExceedsBondAmount);
+
+			let expiration = BondExpiration::BitcoinBlock(vault_claim_height);
+			BitcoinBondCompletions::<T>::try_mutate(vault_claim_height, |a| {
+				a.try_push(bond_id).map_err(|_| Error::<T>::ExpirationAtBlockOverflow)
+			})?;
+			bond.vault_id = vault_id;
+			bond.amount = amount;
+			bond.total_fee = total_fee;
+			bond.prepaid_fee = prepaid_fee;
+			bond.annual_percent_rate = annual_percent_rate;
+			bond.start_block = frame_system::Pallet::<T>::block_number();
+			bond.expiration = expiration.clone();
+
+			Self::deposit_event(Event::BitcoinBondRenewed {
+				bond_id,
+				vault_id,
+				utxo_id: bond.utxo_id.ok_or(Error::<T>::InvalidBondType)?,
+				amount,
+				total_fee,
+				expiration,
+			});
+			BondsById::<T>::insert(bond_id, bond);
+			Ok(())
+		}
+
+		/// Releases the renewal fee held from the owner when a bond renewal was requested
+		fn release_renewal_fee(
+			account_id: &T::AccountId,
+			renewal_fee: T::Balance,
+		) -> DispatchResult {
+			if renewal_fee == T::Balance::zero() {
+				return Ok(());
+			}
+			T::Currency::release(
+				&HoldReason::RenewingBitcoinBond.into(),
+				account_id,
+				renewal_fee,
+				Precision::Exact,
+			)?;
+			frame_system::Pallet::<T>::dec_providers(account_id)?;
+			Ok(())
+		}
+
+		/// Moves the bond funds from a closed vault to the vault that accepted its bonds. The bond
+		/// keeps its amount, fees and expiration. The fee earned by the closed vault is counted as
+		/// prepaid, and the new vault earns the rest from this block.
+		fn migrate_bond(bond_id: BondId, to_vault_id: VaultId) -> DispatchResult {
+			let mut bond = BondsById::<T>::get(bond_id).ok_or(Error::<T>::BondNotFound)?;
+			let earned_fee = T::VaultProvider::migrate_bonded_funds(&bond, to_vault_id)
+				.map_err(Error::<T>::from)?;
+
+			let from_vault_id = core::mem::replace(&mut bond.vault_id, to_vault_id);
+			bond.prepaid_fee = bond.prepaid_fee.saturating_add(earned_fee);
+			bond.start_block = frame_system::Pallet::<T>::block_number();
+			Self::deposit_event(Event::BitcoinBondMigrated {
+				bond_id,
+				from_vault_id,
+				to_vault_id,
+				utxo_id: bond.utxo_id.ok_or(Error::<T>::InvalidBondType)?,
+				earned_fee,
+			});
+			BondsById::<T>::insert(bond_id, bond);
+			Ok(())
+		}
+
+		/// Starts watching for the replacement utxo of a bond, which continues the bond
+		fn replace_bond_utxo(bond_id: BondId, utxo_id: UtxoId, utxo: UtxoState) -> DispatchResult {
+			T::BitcoinUtxoTracker::watch_for_utxo(
+				utxo_id,
+				utxo.utxo_script_pubkey,
+				utxo.satoshis,
+				utxo.vault_claim_height,
+			)?;
+			BondsById::<T>::try_mutate(bond_id, |a| {
+				let bond = a.as_mut().ok_or(Error::<T>::BondNotFound)?;
+				bond.utxo_id = Some(utxo_id);
+				Ok::<(), Error<T>>(())
+			})?;
+			<UtxosById<T>>::insert(utxo_id, utxo);
+			Ok(())
+		}
+
+		/// Releases the vault argons backing the redeemed satoshis of a partial redemption, leaving
+		/// the bond amount that backs the remainder
+		fn release_redeemed_funds(
+			bond_id: BondId,
+			utxo: &UtxoState,
+			remainder_satoshis: Satoshis,
+		) -> DispatchResult {
+			BondsById::<T>::try_mutate(bond_id, |a| {
+				let bond = a.as_mut().ok_or(Error::<T>::BondNotFound)?;
+				let released_amount =
+					bond.amount.saturating_sub(Self::bonded_share(bond, utxo, remainder_satoshis));
+				let paid_fee =
+					T::VaultProvider::release_partial_bonded_funds(bond, released_amount)
+						.map_err(Error::<T>::from)?;
+				bond.amount = bond.amount.saturating_sub(released_amount);
+				// the fee paid to the vault is no longer on hold
+				bond.prepaid_fee = bond.prepaid_fee.saturating_add(paid_fee);
+				Ok::<(), DispatchError>(())
+			})
+		}
+
+		fn transfer_bond(bond_id: BondId, to_account_id: T::AccountId) -> DispatchResult {
+			let mut bond = BondsById::<T>::get(bond_id).ok_or(Error::<T>::BondNotFound)?;
+			T::VaultProvider::transfer_bond_fee_hold(&bond, &to_account_id)
+				.map_err(Error::<T>::from)?;
+			let from_account_id =
+				core::mem::replace(&mut bond.bonded_account_id, to_account_id.clone());
+			Self::deposit_event(Event::BitcoinBondTransferred {
+				bond_id,
+				vault_id: bond.vault_id,
+				from_account_id,
+				to_account_id,
+			});
+			BondsById::<T>::insert(bond_id, bond);
+			Ok(())
+		}
+
+		fn ensure_cosign_deadline_not_passed(
+			bond: &Bond<T::AccountId, T::Balance, BlockNumberFor<T>>,
+		) -> DispatchResult {
+			let expiration = match bond.expiration {
+				BondExpiration::BitcoinBlock(vault_claim_height) => vault_claim_height,
+				_ => return Err(Error::<T>::InvalidBondType.into()),
+			};
+			let unlock_due_date =
+				expiration.saturating_sub(T::UtxoUnlockCosignDeadlineBlocks::get());
+			ensure!(
+				T::BitcoinBlockHeight::get() <= unlock_due_date,
+				Error::<T>::BitcoinUnlockInitiationDeadlinePassed
+			);
+			Ok(())
+		}
+
+		/// The portion of the bond amount backing the given satoshis of a utxo
+		fn bonded_share(
+			bond: &Bond<T::AccountId, T::Balance, BlockNumberFor<T>>,
+			utxo: &UtxoState,
+			satoshis: Satoshis,
+		) -> T::Balance {
+			if satoshis >= utxo.bonded_satoshis {
+				return bond.amount;
+			}
+			let amount: u128 = bond.amount.unique_saturated_into();
+			FixedU128::from_rational(satoshis.into(), utxo.bonded_satoshis.into())
+				.saturating_mul_int(amount)
+				.into()
+		}
+
 		fn remove_bond_completion(bond_id: BondId, expiration: BondExpiration<BlockNumberFor<T>>) {
 			match expiration {
 				BondExpiration::BitcoinBlock(completion_block) => {
@@ -1027,6 +2345,66 @@ pub mod pallet {
 
 			Ok(price.into())
 		}
+
+		/// Quotes what a bitcoin bond of the given satoshis would cost with a vault right now,
+		/// including any error `bond_bitcoin` would fail with
+		pub fn quote_bitcoin_bond(
+			vault_id: VaultId,
+			satoshis: Satoshis,
+		) -> Result<BondQuote<T::Balance>, BondError> {
+			ensure!(
+				satoshis >= MinimumBitcoinBondSatoshis::<T>::get(),
+				BondError::InsufficientSatoshisBonded
+			);
+
+			let vault_claim_height =
+				T::BitcoinBlockHeight::get() + T::BitcoinBondDurationBlocks::get();
+			let open_claim_height = vault_claim_height + T::BitcoinBondReclamationBlocks::get();
+
+			let amount = T::PriceProvider::get_bitcoin_argon_price(satoshis)
+				.ok_or(BondError::NoBitcoinPricesAvailable)?;
+			let (total_fee, base_fee) = T::VaultProvider::quote_bond_funds(
+				vault_id,
+				amount,
+				BondType::Bitcoin,
+				// charge in 1 year of blocks (even though we'll expire off bitcoin time)
+				T::ArgonBlocksPerDay::get() * 365u32.into(),
+			)?;
+			let redemption_price = Self::get_redemption_price(&satoshis)
+				.map_err(|_| BondError::NoBitcoinPricesAvailable)?;
+
+			Ok(BondQuote {
+				amount,
+				base_fee,
+				prorated_fee: total_fee.saturating_sub(base_fee),
+				total_fee,
+				redemption_price: Some(redemption_price),
+				vault_claim_height: Some(vault_claim_height),
+				open_claim_height: Some(open_claim_height),
+			})
+		}
+
+		/// Quotes what a mining bond of the given argons would cost with a vault for the given
+		/// number of blocks
+		pub fn quote_mining_bond(
+			vault_id: VaultId,
+			amount: T::Balance,
+			blocks: BlockNumberFor<T>,
+		) -> Result<BondQuote<T::Balance>, BondError> {
+			ensure!(!blocks.is_zero(), BondError::ExpirationTooSoon);
+			let (total_fee, base_fee) =
+				T::VaultProvider::quote_bond_funds(vault_id, amount, BondType::Mining, blocks)?;
+
+			Ok(BondQuote {
+				amount,
+				base_fee,
+				prorated_fee: total_fee.saturating_sub(base_fee),
+				total_fee,
+				redemption_price: None,
+				vault_claim_height: None,
+				open_claim_height: None,
+			})
+		}
 	}
 
 	impl<T: Config> BondProvider for Pallet<T> {
@@ -1045,7 +2423,7 @@ pub mod pallet {
 			let block_number = frame_system::Pallet::<T>::block_number();
 			ensure!(bond_until_block > block_number, BondError::ExpirationTooSoon);
 
-			let (total_fee, prepaid_fee) = T::VaultProvider::bond_funds(
+			let (total_fee, prepaid_fee, annual_percent_rate) = T::VaultProvider::bond_funds(
 				vault_id,
 				amount,
 				BondType::Mining,
@@ -1061,6 +2439,7 @@ pub mod pallet {
 				BondExpiration::ArgonBlock(bond_until_block),
 				total_fee,
 				prepaid_fee,
+				annual_percent_rate,
 				None,
 			)?;
 			let vault = T::VaultProvider::get(vault_id).ok_or(BondError::VaultNotFound)?;
@@ -1093,6 +2472,9 @@ pub mod pallet {
 			if let Some(utxo_id) = bond.utxo_id {
 				UtxosById::<T>::take(utxo_id);
 				T::BitcoinUtxoTracker::unwatch(utxo_id);
+				// clears any argons held back from a reverted verification
+				T::BondEvents::utxo_unlocked(utxo_id, true, T::Balance::zero())
+					.map_err(|_| BondError::InternalError)?;
 			}
 
 			Ok(())
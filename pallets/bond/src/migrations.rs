@@ -0,0 +1,305 @@
+use crate::{
+	pallet::{
+		BondsById, PendingUnlockUtxoIdByBondId, UtxoCosignRequest, UtxoState, UtxosById,
+		UtxosPendingUnlockByUtxoId,
+	},
+	Config, Pallet,
+};
+use alloc::collections::BTreeMap;
+use codec::Codec;
+use frame_support::{
+	migrations::VersionedMigration, pallet_prelude::*, traits::UncheckedOnRuntimeUpgrade,
+};
+use frame_system::pallet_prelude::BlockNumberFor;
+use log::info;
+use sp_arithmetic::{traits::UniqueSaturatedInto, FixedPointNumber, FixedU128};
+
+/// Adds the fields for taproot (MuSig2) unlocks, partial redemptions, moves to a new cosign
+/// script, fee bumps and multisig vaults to the stored utxos and pending cosign requests, and
+/// the annual percent rate each bond was charged
+pub mod v1 {
+	use super::*;
+	use argon_primitives::{
+		bitcoin::{
+			BitcoinCosignScriptPubkey, BitcoinHeight, BitcoinScriptPubkey, CompressedBitcoinPubkey,
+			Satoshis, UtxoId, XPubChildNumber, XPubFingerprint,
+		},
+		bond::{Bond, BondExpiration, BondType},
+		BondId, VaultId,
+	};
+
+	mod old {
+		use super::*;
+
+		#[derive(Decode, Encode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+		pub struct Bond<AccountId: Codec, Balance: Codec, BlockNumber: Codec> {
+			pub bond_type: BondType,
+			#[codec(compact)]
+			pub vault_id: VaultId,
+			pub utxo_id: Option<UtxoId>,
+			pub bonded_account_id: AccountId,
+			#[codec(compact)]
+			pub total_fee: Balance,
+			#[codec(compact)]
+			pub prepaid_fee: Balance,
+			#[codec(compact)]
+			pub amount: Balance,
+			#[codec(compact)]
+			pub start_block: BlockNumber,
+			pub expiration: BondExpiration<BlockNumber>,
+		}
+
+		#[derive(Decode, Encode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+		pub struct UtxoState {
+			#[codec(compact)]
+			pub bond_id: BondId,
+			#[codec(compact)]
+			pub satoshis: Satoshis,
+			pub vault_pubkey: CompressedBitcoinPubkey,
+			pub vault_claim_pubkey: CompressedBitcoinPubkey,
+			pub vault_xpub_sources: (XPubFingerprint, XPubChildNumber, XPubChildNumber),
+			pub owner_pubkey: CompressedBitcoinPubkey,
+			#[codec(compact)]
+			pub vault_claim_height: BitcoinHeight,
+			#[codec(compact)]
+			pub open_claim_height: BitcoinHeight,
+			#[codec(compact)]
+			pub created_at_height: BitcoinHeight,
+			pub utxo_script_pubkey: BitcoinCosignScriptPubkey,
+			pub is_verified: bool,
+		}
+
+		#[derive(Decode, Encode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+		pub struct UtxoCosignRequest<Balance> {
+			#[codec(compact)]
+			pub bond_id: BondId,
+			#[codec(compact)]
+			pub vault_id: VaultId,
+			#[codec(compact)]
+			pub bitcoin_network_fee: Satoshis,
+			#[codec(compact)]
+			pub cosign_due_block: BitcoinHeight,
+			pub to_script_pubkey: BitcoinScriptPubkey,
+			#[codec(compact)]
+			pub redemption_price: Balance,
+		}
+
+		pub type PendingUnlocks<T> = BoundedBTreeMap<
+			UtxoId,
+			UtxoCosignRequest<<T as Config>::Balance>,
+			<T as Config>::MaxUnlockingUtxos,
+		>;
+	}
+
+	impl From<old::UtxoState> for UtxoState {
+		fn from(old: old::UtxoState) -> Self {
+			Self {
+				bond_id: old.bond_id,
+				satoshis: old.satoshis,
+				vault_pubkey: old.vault_pubkey,
+				vault_claim_pubkey: old.vault_claim_pubkey,
+				vault_xpub_sources: old.vault_xpub_sources,
+				owner_pubkey: old.owner_pubkey,
+				vault_claim_height: old.vault_claim_height,
+				open_claim_height: old.open_claim_height,
+				created_at_height: old.created_at_height,
+				utxo_script_pubkey: old.utxo_script_pubkey,
+				is_verified: old.is_verified,
+				bonded_satoshis: old.satoshis,
+				previous_utxo_id: None,
+				vault_multisig: None,
+			}
+		}
+	}
+
+	/// Recovers the rate a bond was charged from the fee held for its duration. Bitcoin bonds are
+	/// charged for a year of blocks.
+	fn charged_annual_percent_rate<T: Config>(
+		bond: &old::Bond<T::AccountId, T::Balance, BlockNumberFor<T>>,
+	) -> FixedU128 {
+		let blocks_per_year: u128 =
+			(T::ArgonBlocksPerDay::get() * 365u32.into()).unique_saturated_into();
+		let blocks: u128 = match bond.expiration {
+			BondExpiration::ArgonBlock(end) =>
+				end.saturating_sub(bond.start_block).unique_saturated_into(),
+			BondExpiration::BitcoinBlock(_) => blocks_per_year,
+		};
+		let fee: u128 = bond.total_fee.saturating_sub(bond.prepaid_fee).unique_saturated_into();
+		let amount: u128 = bond.amount.unique_saturated_into();
+		FixedU128::checked_from_rational(
+			fee.saturating_mul(blocks_per_year),
+			amount.saturating_mul(blocks),
+		)
+		.unwrap_or_default()
+	}
+
+	pub struct InnerMigrateV0ToV1<T: Config>(core::marker::PhantomData<T>);
+
+	impl<T: Config> UncheckedOnRuntimeUpgrade for InnerMigrateV0ToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let mut bonds = 0u64;
+			BondsById::<T>::translate::<old::Bond<T::AccountId, T::Balance, BlockNumberFor<T>>, _>(
+				|_, old| {
+					bonds += 1;
+					Some(Bond {
+						annual_percent_rate: charged_annual_percent_rate::<T>(&old),
+						bond_type: old.bond_type,
+						vault_id: old.vault_id,
+						utxo_id: old.utxo_id,
+						bonded_account_id: old.bonded_account_id,
+						total_fee: old.total_fee,
+						prepaid_fee: old.prepaid_fee,
+						amount: old.amount,
+						start_block: old.start_block,
+						expiration: old.expiration,
+					})
+				},
+			);
+			info!("Migrated {} bonds", bonds);
+
+			let mut count = 0u64;
+			UtxosById::<T>::translate::<old::UtxoState, _>(|_, old| {
+				count += 1;
+				Some(old.into())
+			});
+			info!("Migrated {} bitcoin utxos", count);
+
+			// only P2WSH scripts existed before, so there are no MuSig2 nonces to carry over
+			let _ =
+				UtxosPendingUnlockByUtxoId::<T>::translate::<old::PendingUnlocks<T>, _>(|old| {
+					let pending = old?
+						.into_iter()
+						.map(|(utxo_id, request)| {
+							let request = UtxoCosignRequest {
+								bond_id: request.bond_id,
+								vault_id: request.vault_id,
+								bitcoin_network_fee: request.bitcoin_network_fee,
+								cosign_due_block: request.cosign_due_block,
+								to_script_pubkey: request.to_script_pubkey,
+								redemption_price: request.redemption_price,
+								owner_musig_nonce: None,
+								remainder_utxo: None,
+								utxo_move: None,
+								is_fee_bump: false,
+								allow_batching: false,
+							};
+							(utxo_id, request)
+						})
+						.collect::<BTreeMap<_, _>>();
+					BoundedBTreeMap::try_from(pending).ok()
+				});
+			let pending = UtxosPendingUnlockByUtxoId::<T>::get();
+			for (utxo_id, request) in pending.iter() {
+				PendingUnlockUtxoIdByBondId::<T>::insert(request.bond_id, utxo_id);
+			}
+			let pending = pending.len() as u64;
+
+			T::DbWeight::get().reads_writes(bonds + count + 1, bonds + count + pending + 1)
+		}
+	}
+
+	pub type MigrateV0ToV1<T> = VersionedMigration<
+		0,
+		1,
+		InnerMigrateV0ToV1<T>,
+		Pallet<T>,
+		<T as frame_system::Config>::DbWeight,
+	>;
+
+	#[cfg(test)]
+	mod test {
+		use super::*;
+		use crate::mock::{new_test_ext, Test};
+		use frame_support::storage::unhashed;
+
+		#[test]
+		fn migrates_utxos_and_pending_unlocks() {
+			new_test_ext().execute_with(|| {
+				let utxo = old::UtxoState {
+					bond_id: 1,
+					satoshis: 100_000,
+					vault_pubkey: CompressedBitcoinPubkey([1; 33]),
+					vault_claim_pubkey: CompressedBitcoinPubkey([2; 33]),
+					vault_xpub_sources: ([0; 4], 1, 2),
+					owner_pubkey: CompressedBitcoinPubkey([3; 33]),
+					vault_claim_height: 10,
+					open_claim_height: 20,
+					created_at_height: 1,
+					utxo_script_pubkey: BitcoinCosignScriptPubkey::P2WSH {
+						wscript_hash: Default::default(),
+					},
+					is_verified: true,
+				};
+				unhashed::put(&UtxosById::<Test>::hashed_key_for(1), &utxo);
+				let request = old::UtxoCosignRequest {
+					bond_id: 1,
+					vault_id: 1,
+					bitcoin_network_fee: 10,
+					cosign_due_block: 5,
+					to_script_pubkey: BitcoinScriptPubkey(Default::default()),
+					redemption_price: 1_000u128,
+				};
+				let pending =
+					old::PendingUnlocks::<Test>::try_from(BTreeMap::from([(1, request.clone())]))
+						.unwrap();
+				unhashed::put(&UtxosPendingUnlockByUtxoId::<Test>::hashed_key(), &pending);
+
+				let weight = InnerMigrateV0ToV1::<Test>::on_runtime_upgrade();
+				assert!(weight.ref_time() > 0);
+
+				assert_eq!(UtxosById::<Test>::get(1), Some(utxo.clone().into()));
+				let migrated = UtxosById::<Test>::get(1).unwrap();
+				assert_eq!(migrated.bonded_satoshis, 100_000);
+				assert_eq!(migrated.previous_utxo_id, None);
+
+				let pending = UtxosPendingUnlockByUtxoId::<Test>::get();
+				let migrated = pending.get(&1).expect("request");
+				assert_eq!(migrated.redemption_price, request.redemption_price);
+				assert_eq!(migrated.to_script_pubkey, request.to_script_pubkey);
+				assert_eq!(migrated.owner_musig_nonce, None);
+				assert!(!migrated.is_fee_bump);
+				assert!(!migrated.allow_batching);
+				assert_eq!(PendingUnlockUtxoIdByBondId::<Test>::get(1), Some(1));
+			});
+		}
+
+		#[test]
+		fn migrates_bonds_with_the_charged_rate() {
+			new_test_ext().execute_with(|| {
+				let bitcoin_bond = old::Bond {
+					bond_type: BondType::Bitcoin,
+					vault_id: 1,
+					utxo_id: Some(1),
+					bonded_account_id: 2,
+					total_fee: 1_100,
+					prepaid_fee: 100,
+					amount: 10_000,
+					start_block: 1,
+					expiration: BondExpiration::BitcoinBlock(100),
+				};
+				unhashed::put(&BondsById::<Test>::hashed_key_for(1), &bitcoin_bond);
+				// charged for half a year of blocks
+				let mining_bond = old::Bond {
+					bond_type: BondType::Mining,
+					utxo_id: None,
+					total_fee: 500,
+					prepaid_fee: 0,
+					expiration: BondExpiration::ArgonBlock(1 + 1440 * 365 / 2),
+					..bitcoin_bond.clone()
+				};
+				unhashed::put(&BondsById::<Test>::hashed_key_for(2), &mining_bond);
+
+				InnerMigrateV0ToV1::<Test>::on_runtime_upgrade();
+
+				let migrated = BondsById::<Test>::get(1).expect("bond");
+				assert_eq!(migrated.annual_percent_rate, FixedU128::from_rational(1, 10));
+				assert_eq!(migrated.total_fee, 1_100);
+				assert_eq!(migrated.prepaid_fee, 100);
+				assert_eq!(migrated.expiration, BondExpiration::BitcoinBlock(100));
+				let migrated = BondsById::<Test>::get(2).expect("bond");
+				assert_eq!(migrated.annual_percent_rate, FixedU128::from_rational(1, 10));
+				assert_eq!(migrated.bond_type, BondType::Mining);
+			});
+		}
+	}
+}
@@ -4,7 +4,7 @@ use bitcoin::PublicKey;
 use env_logger::{Builder, Env};
 use frame_support::{derive_impl, parameter_types, traits::Currency};
 use frame_system::pallet_prelude::BlockNumberFor;
-use sp_arithmetic::{FixedI128, FixedU128};
+use sp_arithmetic::{traits::Zero, FixedI128, FixedU128};
 use sp_core::{ConstU32, ConstU64, H256};
 use sp_runtime::{BuildStorage, DispatchError, DispatchResult};
 
@@ -13,10 +13,11 @@ use crate::BitcoinVerifier;
 use argon_bitcoin::UtxoUnlocker;
 use argon_primitives::{
 	bitcoin::{
-		BitcoinCosignScriptPubkey, BitcoinHeight, BitcoinNetwork, BitcoinSignature, BitcoinXPub,
-		CompressedBitcoinPubkey, NetworkKind, Satoshis, UtxoId, UtxoRef,
+		BitcoinCosignScriptPubkey, BitcoinCosignScriptType, BitcoinHeight, BitcoinNetwork,
+		BitcoinSignature, BitcoinXPub, CompressedBitcoinPubkey, NetworkKind, Satoshis, UtxoId,
+		UtxoRef, VaultMultisigPubkeys,
 	},
-	bond::{Bond, BondError, BondType, Vault, VaultArgons, VaultProvider},
+	bond::{Bond, BondError, BondType, Vault, VaultArgons, VaultProvider, VaultRole},
 	ensure, BitcoinUtxoTracker, PriceProvider, UtxoBondedEvents, VaultId,
 };
 
@@ -74,6 +75,7 @@ parameter_types! {
 	pub static ArgonCPI: Option<argon_primitives::ArgonCPI> = Some(FixedI128::from_float(0.1));
 	pub static UtxoUnlockCosignDeadlineBlocks: BitcoinHeight = 5;
 	pub static BitcoinBondReclamationBlocks: BitcoinHeight = 30;
+	pub static BitcoinSpendRevertBlocks: BitcoinHeight = 10;
 	pub static BitcoinBondDurationBlocks: BitcoinHeight = 365;
 	pub static BitcoinBlockHeight: BitcoinHeight = 0;
 	pub static MinimumBondSatoshis: Satoshis = 10_000_000;
@@ -83,12 +85,14 @@ parameter_types! {
 			bonded: 0,
 			annual_percent_rate: FixedU128::from_float(10.0),
 			base_fee: 0,
+			pricing_curve: None,
 		},
 		bitcoin_argons: VaultArgons {
 			allocated: 200_000_000_000,
 			bonded: 0,
 			annual_percent_rate: FixedU128::from_float(10.0),
 			base_fee: 0,
+			pricing_curve: None,
 		},
 		operator_account_id: 1,
 		securitization_percent: FixedU128::from_float(0.0),
@@ -105,11 +109,20 @@ parameter_types! {
 
 	pub static LastBondEvent: Option<(UtxoId, u64, Balance)> = None;
 	pub static LastUnlockEvent: Option<(UtxoId, bool, Balance)> = None;
+	pub static LastMintRevertEvent: Option<UtxoId> = None;
+	pub static InvalidSignaturePubkeys: Vec<CompressedBitcoinPubkey> = vec![];
+	pub static CosignLatencies: Vec<(VaultId, BitcoinHeight)> = vec![];
+	pub static MissedCosignDeadlines: Vec<VaultId> = vec![];
+	pub static BondMigrationVaultId: Option<VaultId> = None;
+	pub static VaultCosigners: Vec<u64> = vec![];
+	pub static MigratedBondFunds: Vec<(VaultId, VaultId, Balance)> = vec![];
+	pub static RenewalFee: Balance = 0;
 
 	pub static GetBitcoinNetwork: BitcoinNetwork = BitcoinNetwork::Regtest;
 
 	pub static DefaultVaultBitcoinPubkey: PublicKey = "02e3af28965693b9ce1228f9d468149b831d6a0540b25e8a9900f71372c11fb277".parse::<PublicKey>().unwrap();
 	pub static DefaultVaultReclaimBitcoinPubkey: PublicKey = "026c468be64d22761c30cd2f12cbc7de255d592d7904b1bab07236897cc4c2e766".parse::<PublicKey>().unwrap();
+	pub static DefaultVaultMultisig: Option<VaultMultisigPubkeys> = None;
 }
 
 pub struct EventHandler;
@@ -122,6 +135,10 @@ impl UtxoBondedEvents<u64, Balance> for EventHandler {
 		LastBondEvent::set(Some((utxo_id, *account_id, amount)));
 		Ok(())
 	}
+	fn utxo_verification_reverted(utxo_id: UtxoId) -> DispatchResult {
+		LastMintRevertEvent::set(Some(utxo_id));
+		Ok(())
+	}
 	fn utxo_unlocked(
 		utxo_id: UtxoId,
 		remove_pending_mints: bool,
@@ -153,13 +170,20 @@ impl VaultProvider for StaticVaultProvider {
 	type BlockNumber = BlockNumberFor<Test>;
 
 	fn get(vault_id: VaultId) -> Option<Vault<Self::AccountId, Self::Balance, Self::BlockNumber>> {
-		if vault_id == 1 {
+		if vault_id == 1 || BondMigrationVaultId::get() == Some(vault_id) {
 			Some(DefaultVault::get())
 		} else {
 			None
 		}
 	}
 
+	fn has_vault_role(vault_id: VaultId, role: VaultRole, account_id: &Self::AccountId) -> bool {
+		Self::get(vault_id).is_some_and(|a| {
+			a.operator_account_id == *account_id ||
+				(role == VaultRole::Cosigner && VaultCosigners::get().contains(account_id))
+		})
+	}
+
 	fn compensate_lost_bitcoin(
 		_bond: &Bond<Self::AccountId, Self::Balance, Self::BlockNumber>,
 		market_rate: Self::Balance,
@@ -187,15 +211,46 @@ impl VaultProvider for StaticVaultProvider {
 		bond_type: BondType,
 		_blocks: Self::BlockNumber,
 		_bond_account_id: &Self::AccountId,
-	) -> Result<(Self::Balance, Self::Balance), BondError> {
+	) -> Result<(Self::Balance, Self::Balance, FixedU128), BondError> {
 		ensure!(
 			DefaultVault::get().mut_argons(&bond_type).allocated >= amount,
 			BondError::InsufficientVaultFunds
 		);
 		DefaultVault::mutate(|a| a.mut_argons(&bond_type).bonded += amount);
+		Ok((0, 0, FixedU128::zero()))
+	}
+
+	fn quote_bond_funds(
+		vault_id: VaultId,
+		amount: Self::Balance,
+		bond_type: BondType,
+		_blocks: Self::BlockNumber,
+	) -> Result<(Self::Balance, Self::Balance), BondError> {
+		ensure!(vault_id == 1, BondError::VaultNotFound);
+		ensure!(
+			DefaultVault::get().argons(&bond_type).free_balance() >= amount,
+			BondError::InsufficientVaultFunds
+		);
 		Ok((0, 0))
 	}
 
+	fn quote_bond_renewal(
+		bond: &Bond<Self::AccountId, Self::Balance, Self::BlockNumber>,
+		vault_id: VaultId,
+		_blocks: Self::BlockNumber,
+	) -> Result<(Self::Balance, Self::Balance), BondError> {
+		ensure!(vault_id == 1, BondError::VaultNotFound);
+		let mut vault = DefaultVault::get();
+		if bond.vault_id == vault_id {
+			vault.mut_argons(&bond.bond_type).reduce_bonded(bond.amount);
+		}
+		ensure!(
+			vault.argons(&bond.bond_type).free_balance() >= bond.amount,
+			BondError::InsufficientVaultFunds
+		);
+		Ok((RenewalFee::get(), 0))
+	}
+
 	fn release_bonded_funds(
 		bond: &Bond<Self::AccountId, Self::Balance, Self::BlockNumber>,
 	) -> Result<Self::Balance, BondError> {
@@ -203,14 +258,54 @@ impl VaultProvider for StaticVaultProvider {
 		Ok(bond.total_fee.saturating_sub(bond.prepaid_fee))
 	}
 
+	fn release_partial_bonded_funds(
+		bond: &Bond<Self::AccountId, Self::Balance, Self::BlockNumber>,
+		amount: Self::Balance,
+	) -> Result<Self::Balance, BondError> {
+		DefaultVault::mutate(|a| a.mut_argons(&bond.bond_type).reduce_bonded(amount));
+		Ok(0)
+	}
+
+	fn bond_migration_vault(_vault_id: VaultId) -> Option<VaultId> {
+		BondMigrationVaultId::get()
+	}
+
+	fn migrate_bonded_funds(
+		bond: &Bond<Self::AccountId, Self::Balance, Self::BlockNumber>,
+		to_vault_id: VaultId,
+	) -> Result<Self::Balance, BondError> {
+		ensure!(BondMigrationVaultId::get() == Some(to_vault_id), BondError::NoPermissions);
+		MigratedBondFunds::mutate(|a| a.push((bond.vault_id, to_vault_id, bond.amount)));
+		Ok(0)
+	}
+
+	fn transfer_bond_fee_hold(
+		_bond: &Bond<Self::AccountId, Self::Balance, Self::BlockNumber>,
+		_to_account_id: &Self::AccountId,
+	) -> Result<(), BondError> {
+		Ok(())
+	}
+
+	fn record_unlock_cosigned(vault_id: VaultId, latency_blocks: BitcoinHeight) {
+		CosignLatencies::mutate(|a| a.push((vault_id, latency_blocks)));
+	}
+
+	fn record_missed_cosign_deadline(vault_id: VaultId) {
+		MissedCosignDeadlines::mutate(|a| a.push(vault_id));
+	}
+
 	fn create_utxo_script_pubkey(
 		_vault_id: VaultId,
 		_utxo_id: UtxoId,
+		script_type: BitcoinCosignScriptType,
 		_owner_pubkey: CompressedBitcoinPubkey,
 		_vault_claim_height: BitcoinHeight,
 		_open_claim_height: BitcoinHeight,
 		_current_height: BitcoinHeight,
-	) -> Result<(BitcoinXPub, BitcoinXPub, BitcoinCosignScriptPubkey), BondError> {
+	) -> Result<
+		(BitcoinXPub, BitcoinXPub, BitcoinCosignScriptPubkey, Option<VaultMultisigPubkeys>),
+		BondError,
+	> {
 		Ok((
 			BitcoinXPub {
 				public_key: DefaultVaultBitcoinPubkey::get().into(),
@@ -228,7 +323,13 @@ impl VaultProvider for StaticVaultProvider {
 				child_number: 1,
 				network: NetworkKind::Test,
 			},
-			BitcoinCosignScriptPubkey::P2WSH { wscript_hash: H256::from([0; 32]) },
+			match script_type {
+				BitcoinCosignScriptType::P2WSH =>
+					BitcoinCosignScriptPubkey::P2WSH { wscript_hash: H256::from([0; 32]) },
+				BitcoinCosignScriptType::P2TR =>
+					BitcoinCosignScriptPubkey::P2TR { output_key: H256::from([0; 32]) },
+			},
+			DefaultVaultMultisig::get(),
 		))
 	}
 }
@@ -237,10 +338,10 @@ pub struct StaticBitcoinVerifier;
 impl BitcoinVerifier<Test> for StaticBitcoinVerifier {
 	fn verify_signature(
 		_utxo_unlocker: UtxoUnlocker,
-		_pubkey: CompressedBitcoinPubkey,
+		pubkey: CompressedBitcoinPubkey,
 		_signature: &BitcoinSignature,
 	) -> Result<bool, DispatchError> {
-		Ok(true)
+		Ok(!InvalidSignaturePubkeys::get().contains(&pubkey))
 	}
 }
 
@@ -289,6 +390,7 @@ impl pallet_bond::Config for Test {
 	type VaultProvider = StaticVaultProvider;
 	type MaxUnlockingUtxos = MaxUnlockingUtxos;
 	type UtxoUnlockCosignDeadlineBlocks = UtxoUnlockCosignDeadlineBlocks;
+	type BitcoinSpendRevertBlocks = BitcoinSpendRevertBlocks;
 	type BitcoinUtxoTracker = StaticBitcoinUtxoTracker;
 	type BitcoinBondReclamationBlocks = BitcoinBondReclamationBlocks;
 	type BitcoinBondDurationBlocks = BitcoinBondDurationBlocks;
@@ -9,17 +9,22 @@ use sp_core::H256;
 use crate::{
 	mock::*,
 	pallet::{
-		BitcoinBondCompletions, BondsById, MiningBondCompletions, OwedUtxoAggrieved, UtxosById,
-		UtxosCosignReleaseHeightById, UtxosPendingUnlockByUtxoId,
+		BitcoinBondCompletions, BondsById, CosignedUnlocksByUtxoId, MiningBondCompletions,
+		OwedUtxoAggrieved, PendingUnlockUtxoIdByBondId, SpentUtxoExpirationsByBitcoinHeight,
+		SpentUtxosById, UtxosById, UtxosCosignReleaseHeightById, UtxosFeeBumpCosignHeightById,
+		UtxosPendingUnlockByUtxoId,
 	},
-	Error, Event, HoldReason, UtxoCosignRequest, UtxoState,
+	BitcoinUnlockCosign, Error, Event, HoldReason, OwnerPubkeyRotation, UtxoCosignRequest,
+	UtxoState,
 };
+use argon_bitcoin::MusigSecretNonce;
 use argon_primitives::{
 	bitcoin::{
-		BitcoinCosignScriptPubkey, BitcoinRejectedReason, BitcoinScriptPubkey, BitcoinSignature,
-		CompressedBitcoinPubkey, H256Le, Satoshis, UtxoRef, SATOSHIS_PER_BITCOIN,
+		BitcoinCosignScriptPubkey, BitcoinCosignScriptType, BitcoinRejectedReason,
+		BitcoinScriptPubkey, BitcoinSignature, CompressedBitcoinPubkey, H256Le, Satoshis, UtxoRef,
+		VaultMultisigPubkeys, SATOSHIS_PER_BITCOIN,
 	},
-	bond::{Bond, BondExpiration, BondProvider, BondType},
+	bond::{Bond, BondError, BondExpiration, BondProvider, BondType},
 	BitcoinUtxoEvents, BondId, PriceProvider,
 };
 
@@ -33,10 +38,22 @@ fn can_bond_a_bitcoin_utxo() {
 		let pubkey = CompressedBitcoinPubkey([1; 33]);
 
 		assert_err!(
-			Bonds::bond_bitcoin(RuntimeOrigin::signed(2), 1, 1_000_000, pubkey),
+			Bonds::bond_bitcoin(
+				RuntimeOrigin::signed(2),
+				1,
+				1_000_000,
+				pubkey,
+				BitcoinCosignScriptType::P2WSH
+			),
 			Error::<Test>::InsufficientSatoshisBonded
 		);
-		assert_ok!(Bonds::bond_bitcoin(RuntimeOrigin::signed(2), 1, SATOSHIS_PER_BITCOIN, pubkey));
+		assert_ok!(Bonds::bond_bitcoin(
+			RuntimeOrigin::signed(2),
+			1,
+			SATOSHIS_PER_BITCOIN,
+			pubkey,
+			BitcoinCosignScriptType::P2WSH
+		));
 		assert_eq!(UtxosById::<Test>::get(1).unwrap(), default_utxo_state(1, SATOSHIS_PER_BITCOIN));
 		assert_eq!(
 			BondsById::<Test>::get(1).unwrap().amount,
@@ -62,6 +79,53 @@ fn can_bond_a_bitcoin_utxo() {
 	});
 }
 
+#[test]
+fn can_quote_bonds() {
+	BitcoinBlockHeight::set(12);
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_eq!(
+			Bonds::quote_bitcoin_bond(1, 1_000_000),
+			Err(BondError::InsufficientSatoshisBonded)
+		);
+		assert_eq!(
+			Bonds::quote_bitcoin_bond(2, SATOSHIS_PER_BITCOIN),
+			Err(BondError::VaultNotFound)
+		);
+
+		let quote = Bonds::quote_bitcoin_bond(1, SATOSHIS_PER_BITCOIN).expect("should quote");
+		assert_eq!(
+			quote.amount,
+			StaticPriceProvider::get_bitcoin_argon_price(SATOSHIS_PER_BITCOIN)
+				.expect("should have price")
+		);
+		assert_eq!(
+			quote.redemption_price,
+			Some(Bonds::get_redemption_price(&SATOSHIS_PER_BITCOIN).expect("should have price"))
+		);
+		assert_eq!(quote.vault_claim_height, Some(12 + BitcoinBondDurationBlocks::get()));
+		assert_eq!(
+			quote.open_claim_height,
+			Some(12 + BitcoinBondDurationBlocks::get() + BitcoinBondReclamationBlocks::get())
+		);
+		// nothing is bonded by a quote
+		assert_eq!(DefaultVault::get().bitcoin_argons.bonded, 0);
+		assert_eq!(UtxosById::<Test>::get(1), None);
+
+		assert_eq!(Bonds::quote_mining_bond(1, 1_000, 0), Err(BondError::ExpirationTooSoon));
+		let quote = Bonds::quote_mining_bond(1, 1_000, 10).expect("should quote");
+		assert_eq!(quote.amount, 1_000);
+		assert_eq!(quote.redemption_price, None);
+
+		BitcoinPricePerUsd::set(None);
+		assert_eq!(
+			Bonds::quote_bitcoin_bond(1, SATOSHIS_PER_BITCOIN),
+			Err(BondError::NoBitcoinPricesAvailable)
+		);
+	});
+}
+
 #[test]
 fn cleans_up_a_rejected_bitcoin() {
 	BitcoinBlockHeight::set(12);
@@ -76,7 +140,8 @@ fn cleans_up_a_rejected_bitcoin() {
 			RuntimeOrigin::signed(who),
 			1,
 			SATOSHIS_PER_BITCOIN,
-			pubkey
+			pubkey,
+			BitcoinCosignScriptType::P2WSH
 		));
 		let price = StaticPriceProvider::get_bitcoin_argon_price(SATOSHIS_PER_BITCOIN)
 			.expect("should have price");
@@ -104,7 +169,8 @@ fn marks_a_verified_bitcoin() {
 			RuntimeOrigin::signed(who),
 			1,
 			SATOSHIS_PER_BITCOIN,
-			pubkey
+			pubkey,
+			BitcoinCosignScriptType::P2WSH
 		));
 		let price = StaticPriceProvider::get_bitcoin_argon_price(SATOSHIS_PER_BITCOIN)
 			.expect("should have price");
@@ -117,6 +183,79 @@ fn marks_a_verified_bitcoin() {
 	});
 }
 
+#[test]
+fn reverts_a_verified_bitcoin_after_a_reorg() {
+	BitcoinBlockHeight::set(12);
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+
+		let who = 1;
+		set_argons(who, 2_000_000);
+		let pubkey = CompressedBitcoinPubkey([1; 33]);
+
+		assert_ok!(Bonds::bond_bitcoin(
+			RuntimeOrigin::signed(who),
+			1,
+			SATOSHIS_PER_BITCOIN,
+			pubkey,
+			BitcoinCosignScriptType::P2WSH
+		));
+		assert_ok!(Bonds::utxo_verified(1));
+		assert!(UtxosById::<Test>::get(1).unwrap().is_verified);
+
+		assert_ok!(Bonds::utxo_verification_reverted(1));
+		assert!(!UtxosById::<Test>::get(1).unwrap().is_verified);
+		// pending mints are held back without burning anything
+		assert_eq!(LastMintRevertEvent::get(), Some(1));
+		assert_eq!(LastUnlockEvent::get(), None);
+		System::assert_last_event(
+			Event::<Test>::BitcoinUtxoVerificationReverted { bond_id: 1, utxo_id: 1 }.into(),
+		);
+		// the bond stays open while the utxo waits to be confirmed again
+		assert!(BondsById::<Test>::get(1).is_some());
+		assert_eq!(WatchedUtxosById::get().len(), 1);
+
+		// a second revert is a no-op
+		LastMintRevertEvent::set(None);
+		assert_ok!(Bonds::utxo_verification_reverted(1));
+		assert_eq!(LastMintRevertEvent::get(), None);
+
+		assert_ok!(Bonds::utxo_verified(1));
+		assert!(UtxosById::<Test>::get(1).unwrap().is_verified);
+
+		// the utxo is restored if the spend is reverted, but the burned funds are not
+		let utxo = UtxosById::<Test>::get(1).unwrap();
+		assert_ok!(Bonds::utxo_spent(1));
+		assert_eq!(UtxosById::<Test>::get(1), None);
+		let bond = BondsById::<Test>::get(1).unwrap();
+		assert_eq!(SpentUtxosById::<Test>::get(1).unwrap().utxo_state, Some(utxo.clone()));
+		let revertible_until_height = BitcoinBlockHeight::get() + BitcoinSpendRevertBlocks::get();
+		assert_eq!(
+			SpentUtxoExpirationsByBitcoinHeight::<Test>::get(revertible_until_height).to_vec(),
+			vec![1]
+		);
+
+		assert_ok!(Bonds::utxo_spend_reverted(1));
+		System::assert_last_event(
+			Event::<Test>::BitcoinUtxoSpendReverted { bond_id: 1, utxo_id: 1 }.into(),
+		);
+		assert_eq!(UtxosById::<Test>::get(1), Some(utxo));
+		assert_eq!(BondsById::<Test>::get(1), Some(bond));
+		assert_eq!(SpentUtxosById::<Test>::get(1), None);
+		assert!(
+			SpentUtxoExpirationsByBitcoinHeight::<Test>::get(revertible_until_height).is_empty()
+		);
+
+		// once the spend is final, it's no longer kept
+		assert_ok!(Bonds::utxo_spent(1));
+		BitcoinBlockHeight::set(revertible_until_height);
+		Bonds::on_initialize(2);
+		assert_eq!(SpentUtxosById::<Test>::get(1), None);
+		assert_ok!(Bonds::utxo_spend_reverted(1));
+		assert_eq!(UtxosById::<Test>::get(1), None);
+	});
+}
+
 #[test]
 fn calculates_redemption_prices() {
 	new_test_ext().execute_with(|| {
@@ -159,7 +298,8 @@ fn burns_a_spent_bitcoin() {
 			RuntimeOrigin::signed(who),
 			1,
 			SATOSHIS_PER_BITCOIN,
-			pubkey
+			pubkey,
+			BitcoinCosignScriptType::P2WSH
 		));
 		let expiration_block = BitcoinBlockHeight::get() + BitcoinBondDurationBlocks::get();
 
@@ -187,6 +327,7 @@ fn burns_a_spent_bitcoin() {
 				bond_type: BondType::Bitcoin,
 				total_fee: 0,
 				prepaid_fee: 0,
+				annual_percent_rate: FixedU128::zero(),
 				vault_id: 1,
 				expiration: BondExpiration::BitcoinBlock(expiration_block),
 				bonded_account_id: who,
@@ -236,7 +377,8 @@ fn cancels_an_unverified_spent_bitcoin() {
 			RuntimeOrigin::signed(who),
 			1,
 			SATOSHIS_PER_BITCOIN,
-			pubkey
+			pubkey,
+			BitcoinCosignScriptType::P2WSH
 		));
 		let bond = BondsById::<Test>::get(1).unwrap();
 		let expiration_block = match bond.expiration {
@@ -278,7 +420,8 @@ fn can_unlock_a_bitcoin() {
 			RuntimeOrigin::signed(who),
 			1,
 			SATOSHIS_PER_BITCOIN,
-			pubkey
+			pubkey,
+			BitcoinCosignScriptType::P2WSH
 		));
 		let bond = BondsById::<Test>::get(1).unwrap();
 		let expiration_block = match bond.expiration {
@@ -301,7 +444,10 @@ fn can_unlock_a_bitcoin() {
 				RuntimeOrigin::signed(2),
 				1,
 				unlock_script_pubkey.clone(),
-				1000
+				1000,
+				None,
+				None,
+				false
 			),
 			Error::<Test>::NoPermissions
 		);
@@ -312,7 +458,10 @@ fn can_unlock_a_bitcoin() {
 				RuntimeOrigin::signed(who),
 				1,
 				unlock_script_pubkey.clone(),
-				1000
+				1000,
+				None,
+				None,
+				false
 			),
 			Error::<Test>::BitcoinUnlockInitiationDeadlinePassed
 		);
@@ -321,7 +470,10 @@ fn can_unlock_a_bitcoin() {
 			RuntimeOrigin::signed(who),
 			1,
 			unlock_script_pubkey.clone(),
-			1000
+			1000,
+			None,
+			None,
+			false
 		));
 		assert!(UtxosById::<Test>::get(1).is_some());
 		let redemption_price =
@@ -336,7 +488,12 @@ fn can_unlock_a_bitcoin() {
 				cosign_due_block: BitcoinBlockHeight::get() + UtxoUnlockCosignDeadlineBlocks::get(),
 				redemption_price: bond.amount,
 				to_script_pubkey: unlock_script_pubkey,
-				bitcoin_network_fee: 1000
+				bitcoin_network_fee: 1000,
+				owner_musig_nonce: None,
+				remainder_utxo: None,
+				utxo_move: None,
+				is_fee_bump: false,
+				allow_batching: false,
 			})
 			.as_ref()
 		);
@@ -364,7 +521,13 @@ fn penalizes_vault_if_not_unlock_countersigned() {
 		let who = 1;
 		let satoshis = SATOSHIS_PER_BITCOIN + 5000;
 		set_argons(who, 2_000);
-		assert_ok!(Bonds::bond_bitcoin(RuntimeOrigin::signed(who), 1, satoshis, pubkey));
+		assert_ok!(Bonds::bond_bitcoin(
+			RuntimeOrigin::signed(who),
+			1,
+			satoshis,
+			pubkey,
+			BitcoinCosignScriptType::P2WSH
+		));
 		let vault = DefaultVault::get();
 		let mut bond = BondsById::<Test>::get(1).unwrap();
 		assert_eq!(vault.bitcoin_argons.bonded, bond.amount);
@@ -377,7 +540,10 @@ fn penalizes_vault_if_not_unlock_countersigned() {
 			RuntimeOrigin::signed(who),
 			1,
 			unlock_script_pubkey.clone(),
-			2000
+			2000,
+			None,
+			None,
+			false
 		));
 		assert!(UtxosById::<Test>::get(1).is_some());
 
@@ -391,7 +557,12 @@ fn penalizes_vault_if_not_unlock_countersigned() {
 				cosign_due_block: cosign_due,
 				redemption_price,
 				to_script_pubkey: unlock_script_pubkey,
-				bitcoin_network_fee: 2000
+				bitcoin_network_fee: 2000,
+				owner_musig_nonce: None,
+				remainder_utxo: None,
+				utxo_move: None,
+				is_fee_bump: false,
+				allow_batching: false,
 			})
 			.as_ref()
 		);
@@ -406,6 +577,8 @@ fn penalizes_vault_if_not_unlock_countersigned() {
 		assert_eq!(UtxosPendingUnlockByUtxoId::<Test>::get().get(&1), None);
 		assert_eq!(UtxosById::<Test>::get(1), None);
 		assert_eq!(OwedUtxoAggrieved::<Test>::get(1), None);
+		assert_eq!(MissedCosignDeadlines::get(), vec![1]);
+		assert_eq!(CosignLatencies::get(), vec![]);
 		System::assert_last_event(
 			Event::<Test>::BitcoinCosignPastDue {
 				bond_id: 1,
@@ -445,7 +618,13 @@ fn clears_unlocked_bitcoin_bonds() {
 		let who = 2;
 		let satoshis = SATOSHIS_PER_BITCOIN + 25000;
 		set_argons(who, 2_000);
-		assert_ok!(Bonds::bond_bitcoin(RuntimeOrigin::signed(who), 1, satoshis, pubkey.into()));
+		assert_ok!(Bonds::bond_bitcoin(
+			RuntimeOrigin::signed(who),
+			1,
+			satoshis,
+			pubkey.into(),
+			BitcoinCosignScriptType::P2WSH
+		));
 		let vault = DefaultVault::get();
 		let bond = BondsById::<Test>::get(1).unwrap();
 		assert_eq!(vault.bitcoin_argons.bonded, bond.amount);
@@ -458,12 +637,16 @@ fn clears_unlocked_bitcoin_bonds() {
 			RuntimeOrigin::signed(who),
 			1,
 			unlock_script_pubkey.clone(),
-			11
+			11,
+			None,
+			None,
+			false
 		));
 		assert!(UtxosById::<Test>::get(1).is_some());
 
 		let redemption_price = Bonds::get_redemption_price(&satoshis).expect("should have price");
 		let cosign_due_block = BitcoinBlockHeight::get() + UtxoUnlockCosignDeadlineBlocks::get();
+		assert_eq!(PendingUnlockUtxoIdByBondId::<Test>::get(1), Some(1));
 		assert_eq!(
 			UtxosPendingUnlockByUtxoId::<Test>::get().get(&1),
 			Some(UtxoCosignRequest {
@@ -472,7 +655,12 @@ fn clears_unlocked_bitcoin_bonds() {
 				cosign_due_block,
 				redemption_price,
 				to_script_pubkey: unlock_script_pubkey,
-				bitcoin_network_fee: 11
+				bitcoin_network_fee: 11,
+				owner_musig_nonce: None,
+				remainder_utxo: None,
+				utxo_move: None,
+				is_fee_bump: false,
+				allow_batching: false,
 			})
 			.as_ref()
 		);
@@ -480,19 +668,43 @@ fn clears_unlocked_bitcoin_bonds() {
 			Bonds::cosign_bitcoin_unlock(
 				RuntimeOrigin::signed(2),
 				1,
-				BitcoinSignature(BoundedVec::truncate_from([0u8; 73].to_vec()))
+				BitcoinSignature(BoundedVec::truncate_from([0u8; 73].to_vec())),
+				None
 			),
 			Error::<Test>::NoPermissions
 		);
 		GetUtxoRef::set(Some(UtxoRef { txid: H256Le([0; 32]), output_index: 0 }));
 
+		// the signature must be valid for the vault pubkey
+		InvalidSignaturePubkeys::set(vec![UtxosById::<Test>::get(1).unwrap().vault_pubkey]);
+		assert_err!(
+			Bonds::cosign_bitcoin_unlock(
+				RuntimeOrigin::signed(1),
+				1,
+				BitcoinSignature(BoundedVec::truncate_from([0u8; 73].to_vec())),
+				None
+			),
+			Error::<Test>::BitcoinInvalidCosignature
+		);
+		InvalidSignaturePubkeys::set(vec![]);
+
+		// a cosigner account of the vault can cosign in place of the operator
+		VaultCosigners::set(vec![3]);
+		let providers = System::providers(&who);
+		BitcoinBlockHeight::set(3);
 		assert_ok!(Bonds::cosign_bitcoin_unlock(
-			RuntimeOrigin::signed(1),
+			RuntimeOrigin::signed(3),
 			1,
-			BitcoinSignature(BoundedVec::truncate_from([0u8; 73].to_vec()))
+			BitcoinSignature(BoundedVec::truncate_from([0u8; 73].to_vec())),
+			None
 		));
 		assert_eq!(LastUnlockEvent::get(), Some((1, false, redemption_price)));
+		assert_eq!(System::providers(&who), providers - 1);
+		// cosigned 2 bitcoin blocks after the unlock was requested
+		assert_eq!(CosignLatencies::get(), vec![(1, 2)]);
+		assert_eq!(MissedCosignDeadlines::get(), vec![]);
 		assert_eq!(UtxosPendingUnlockByUtxoId::<Test>::get().get(&1), None);
+		assert_eq!(PendingUnlockUtxoIdByBondId::<Test>::get(1), None);
 		assert_eq!(UtxosById::<Test>::get(1), None);
 		assert_eq!(OwedUtxoAggrieved::<Test>::get(1), None);
 		// should keep bond for the year
@@ -506,7 +718,9 @@ fn clears_unlocked_bitcoin_bonds() {
 				bond_id: 1,
 				vault_id: 1,
 				utxo_id: 1,
-				signature: BitcoinSignature(BoundedVec::truncate_from([0u8; 73].to_vec())),
+				signature: Some(BitcoinSignature(BoundedVec::truncate_from([0u8; 73].to_vec()))),
+				vault_musig_nonce: None,
+				cosigner_signatures: Default::default(),
 			}
 			.into(),
 		);
@@ -531,6 +745,208 @@ fn clears_unlocked_bitcoin_bonds() {
 	});
 }
 
+#[test]
+fn requires_musig_nonces_to_unlock_taproot_bonds() {
+	new_test_ext().execute_with(|| {
+		BitcoinBlockHeight::set(1);
+		System::set_block_number(1);
+
+		let secp = bitcoin::secp256k1::Secp256k1::new();
+		let rng = &mut rand::thread_rng();
+		let keypair = bitcoin::secp256k1::SecretKey::new(rng);
+		let pubkey: CompressedBitcoinPubkey = keypair.public_key(&secp).serialize().into();
+		let who = 2;
+		let satoshis = SATOSHIS_PER_BITCOIN + 25000;
+		set_argons(who, 2_000);
+		assert_ok!(Bonds::bond_bitcoin(
+			RuntimeOrigin::signed(who),
+			1,
+			satoshis,
+			pubkey,
+			BitcoinCosignScriptType::P2TR
+		));
+		assert!(matches!(
+			UtxosById::<Test>::get(1).unwrap().utxo_script_pubkey,
+			BitcoinCosignScriptPubkey::P2TR { .. }
+		));
+		assert_ok!(Bonds::utxo_verified(1));
+		let bond = BondsById::<Test>::get(1).unwrap();
+		assert_ok!(Balances::mint_into(&who, bond.amount));
+
+		let unlock_script_pubkey = make_script_pubkey(&[0; 32]);
+		assert_err!(
+			Bonds::unlock_bitcoin_bond(
+				RuntimeOrigin::signed(who),
+				1,
+				unlock_script_pubkey.clone(),
+				11,
+				None,
+				None,
+				false
+			),
+			Error::<Test>::BitcoinMusigNonceMismatch
+		);
+		let owner_nonce =
+			MusigSecretNonce::new([1; 32], pubkey, None).expect("nonce").public_nonce();
+		assert_ok!(Bonds::unlock_bitcoin_bond(
+			RuntimeOrigin::signed(who),
+			1,
+			unlock_script_pubkey.clone(),
+			11,
+			Some(owner_nonce),
+			None,
+			false
+		));
+		assert_eq!(
+			UtxosPendingUnlockByUtxoId::<Test>::get().get(&1).unwrap().owner_musig_nonce,
+			Some(owner_nonce)
+		);
+
+		GetUtxoRef::set(Some(UtxoRef { txid: H256Le([0; 32]), output_index: 0 }));
+		let signature = BitcoinSignature(BoundedVec::truncate_from([0u8; 32].to_vec()));
+		assert_err!(
+			Bonds::cosign_bitcoin_unlock(RuntimeOrigin::signed(1), 1, signature.clone(), None),
+			Error::<Test>::BitcoinMusigNonceMismatch
+		);
+		let vault_nonce =
+			MusigSecretNonce::new([2; 32], DefaultVaultBitcoinPubkey::get().into(), None)
+				.expect("nonce")
+				.public_nonce();
+		assert_ok!(Bonds::cosign_bitcoin_unlock(
+			RuntimeOrigin::signed(1),
+			1,
+			signature.clone(),
+			Some(vault_nonce)
+		));
+		System::assert_last_event(
+			Event::<Test>::BitcoinUtxoCosigned {
+				bond_id: 1,
+				vault_id: 1,
+				utxo_id: 1,
+				signature: Some(signature),
+				vault_musig_nonce: Some(vault_nonce),
+				cosigner_signatures: Default::default(),
+			}
+			.into(),
+		);
+	});
+}
+
+#[test]
+fn requires_cosigner_signatures_for_multisig_vaults() {
+	new_test_ext().execute_with(|| {
+		BitcoinBlockHeight::set(1);
+		System::set_block_number(1);
+
+		let cosigner_a = CompressedBitcoinPubkey([2; 33]);
+		let cosigner_b = CompressedBitcoinPubkey([3; 33]);
+		let vault_multisig = VaultMultisigPubkeys {
+			threshold: 2,
+			cosign_pubkeys: BoundedVec::truncate_from(vec![cosigner_a, cosigner_b]),
+			claim_pubkeys: BoundedVec::truncate_from(vec![
+				CompressedBitcoinPubkey([4; 33]),
+				CompressedBitcoinPubkey([5; 33]),
+			]),
+		};
+		DefaultVaultMultisig::set(Some(vault_multisig.clone()));
+
+		let who = 2;
+		set_argons(who, 2_000);
+		assert_ok!(Bonds::bond_bitcoin(
+			RuntimeOrigin::signed(who),
+			1,
+			SATOSHIS_PER_BITCOIN,
+			CompressedBitcoinPubkey([1; 33]),
+			BitcoinCosignScriptType::P2WSH
+		));
+		assert_eq!(UtxosById::<Test>::get(1).unwrap().vault_multisig, Some(vault_multisig));
+		assert_ok!(Bonds::utxo_verified(1));
+		let bond = BondsById::<Test>::get(1).unwrap();
+		assert_ok!(Balances::mint_into(&who, bond.amount));
+		assert_ok!(Bonds::unlock_bitcoin_bond(
+			RuntimeOrigin::signed(who),
+			1,
+			make_script_pubkey(&[0; 32]),
+			11,
+			None,
+			None,
+			false
+		));
+		GetUtxoRef::set(Some(UtxoRef { txid: H256Le([0; 32]), output_index: 0 }));
+
+		let signature = BitcoinSignature(BoundedVec::truncate_from([0u8; 73].to_vec()));
+		let vault_pubkey = UtxosById::<Test>::get(1).unwrap().vault_pubkey;
+		// the vault signature alone doesn't meet the threshold
+		assert_err!(
+			Bonds::cosign_bitcoin_unlock(RuntimeOrigin::signed(1), 1, signature.clone(), None),
+			Error::<Test>::InvalidVaultCosignerSignatures
+		);
+		// signatures must be from the multisig pubkeys
+		assert_err!(
+			Bonds::cosign_bitcoin_unlock_multisig(
+				RuntimeOrigin::signed(1),
+				1,
+				BoundedVec::truncate_from(vec![
+					(cosigner_a, signature.clone()),
+					(CompressedBitcoinPubkey([4; 33]), signature.clone())
+				]),
+			),
+			Error::<Test>::InvalidVaultCosignerSignatures
+		);
+		// a pubkey can only sign once
+		assert_err!(
+			Bonds::cosign_bitcoin_unlock_multisig(
+				RuntimeOrigin::signed(1),
+				1,
+				BoundedVec::truncate_from(vec![
+					(cosigner_a, signature.clone()),
+					(cosigner_a, signature.clone())
+				]),
+			),
+			Error::<Test>::InvalidVaultCosignerSignatures
+		);
+
+		// every signature must be valid, including the vault pubkey signature
+		for invalid_pubkey in [cosigner_b, vault_pubkey] {
+			InvalidSignaturePubkeys::set(vec![invalid_pubkey]);
+			assert_err!(
+				Bonds::cosign_bitcoin_unlock_multisig(
+					RuntimeOrigin::signed(1),
+					1,
+					BoundedVec::truncate_from(vec![
+						(vault_pubkey, signature.clone()),
+						(cosigner_b, signature.clone())
+					]),
+				),
+				Error::<Test>::BitcoinInvalidCosignature
+			);
+		}
+		// any 2 of the 3 pubkeys can cosign without the vault pubkey
+		InvalidSignaturePubkeys::set(vec![vault_pubkey]);
+		let cosigner_signatures = BoundedVec::truncate_from(vec![
+			(cosigner_a, signature.clone()),
+			(cosigner_b, signature.clone()),
+		]);
+		assert_ok!(Bonds::cosign_bitcoin_unlock_multisig(
+			RuntimeOrigin::signed(1),
+			1,
+			cosigner_signatures.clone(),
+		));
+		assert_eq!(UtxosPendingUnlockByUtxoId::<Test>::get().get(&1), None);
+		System::assert_last_event(
+			Event::<Test>::BitcoinUtxoCosigned {
+				bond_id: 1,
+				vault_id: 1,
+				utxo_id: 1,
+				signature: None,
+				vault_musig_nonce: None,
+				cosigner_signatures,
+			}
+			.into(),
+		);
+	});
+}
+
 #[test]
 fn it_should_aggregate_holds_for_a_second_unlock() {
 	new_test_ext().execute_with(|| {
@@ -541,8 +957,20 @@ fn it_should_aggregate_holds_for_a_second_unlock() {
 		let who = 1;
 		let satoshis = 2 * SATOSHIS_PER_BITCOIN;
 		set_argons(who, 2_000);
-		assert_ok!(Bonds::bond_bitcoin(RuntimeOrigin::signed(who), 1, satoshis, pubkey));
-		assert_ok!(Bonds::bond_bitcoin(RuntimeOrigin::signed(who), 1, satoshis, pubkey));
+		assert_ok!(Bonds::bond_bitcoin(
+			RuntimeOrigin::signed(who),
+			1,
+			satoshis,
+			pubkey,
+			BitcoinCosignScriptType::P2WSH
+		));
+		assert_ok!(Bonds::bond_bitcoin(
+			RuntimeOrigin::signed(who),
+			1,
+			satoshis,
+			pubkey,
+			BitcoinCosignScriptType::P2WSH
+		));
 		let bond = BondsById::<Test>::get(1).unwrap();
 		assert_ok!(Bonds::utxo_verified(1));
 		assert_ok!(Bonds::utxo_verified(2));
@@ -554,13 +982,19 @@ fn it_should_aggregate_holds_for_a_second_unlock() {
 			RuntimeOrigin::signed(who),
 			1,
 			make_script_pubkey(&[0; 32]),
-			10
+			10,
+			None,
+			None,
+			false
 		));
 		assert_ok!(Bonds::unlock_bitcoin_bond(
 			RuntimeOrigin::signed(who),
 			2,
 			make_script_pubkey(&[0; 32]),
-			10
+			10,
+			None,
+			None,
+			false
 		));
 		assert_eq!(Balances::free_balance(who), 2_000 + (2 * (bond.amount - redemption_price)));
 		assert_eq!(
@@ -587,6 +1021,7 @@ fn it_can_create_a_mining_bond() {
 				bond_type: BondType::Mining,
 				total_fee: 0,
 				prepaid_fee: 0,
+				annual_percent_rate: FixedU128::zero(),
 				vault_id: 1,
 				expiration: BondExpiration::ArgonBlock(10),
 				bonded_account_id: who,
@@ -617,6 +1052,879 @@ fn it_can_create_a_mining_bond() {
 	});
 }
 
+#[test]
+fn can_partially_redeem_a_bitcoin() {
+	new_test_ext().execute_with(|| {
+		BitcoinBlockHeight::set(1);
+		System::set_block_number(1);
+
+		let secp = bitcoin::secp256k1::Secp256k1::new();
+		let rng = &mut rand::thread_rng();
+		let keypair = bitcoin::secp256k1::SecretKey::new(rng);
+		let pubkey = keypair.public_key(&secp).serialize();
+		let who = 2;
+		let satoshis = 2 * SATOSHIS_PER_BITCOIN;
+		let redeem_satoshis = SATOSHIS_PER_BITCOIN / 2;
+		set_argons(who, 2_000);
+		assert_ok!(Bonds::bond_bitcoin(
+			RuntimeOrigin::signed(who),
+			1,
+			satoshis,
+			pubkey.into(),
+			BitcoinCosignScriptType::P2WSH
+		));
+		let bond = BondsById::<Test>::get(1).unwrap();
+		let unlock_script_pubkey = make_script_pubkey(&[0; 32]);
+		assert_err!(
+			Bonds::unlock_bitcoin_bond(
+				RuntimeOrigin::signed(who),
+				1,
+				unlock_script_pubkey.clone(),
+				11,
+				None,
+				Some(redeem_satoshis),
+				false
+			),
+			Error::<Test>::BitcoinUtxoNotVerified
+		);
+		assert_ok!(Bonds::utxo_verified(1));
+		assert_ok!(Balances::mint_into(&who, bond.amount));
+
+		assert_err!(
+			Bonds::unlock_bitcoin_bond(
+				RuntimeOrigin::signed(who),
+				1,
+				unlock_script_pubkey.clone(),
+				11,
+				None,
+				Some(satoshis + 1),
+				false
+			),
+			Error::<Test>::InvalidRedemptionSatoshis
+		);
+		assert_err!(
+			Bonds::unlock_bitcoin_bond(
+				RuntimeOrigin::signed(who),
+				1,
+				unlock_script_pubkey.clone(),
+				11,
+				None,
+				Some(satoshis - 1000),
+				false
+			),
+			Error::<Test>::InsufficientSatoshisBonded
+		);
+		assert_err!(
+			Bonds::unlock_bitcoin_bond(
+				RuntimeOrigin::signed(who),
+				1,
+				unlock_script_pubkey.clone(),
+				11,
+				None,
+				Some(11),
+				false
+			),
+			Error::<Test>::BitcoinFeeTooHigh
+		);
+
+		assert_ok!(Bonds::unlock_bitcoin_bond(
+			RuntimeOrigin::signed(who),
+			1,
+			unlock_script_pubkey.clone(),
+			11,
+			None,
+			Some(redeem_satoshis),
+			false
+		));
+		let redemption_price =
+			Bonds::get_redemption_price(&redeem_satoshis).expect("should have price");
+		assert!(redemption_price < bond.amount / 4);
+		let request = UtxosPendingUnlockByUtxoId::<Test>::get().get(&1).cloned().unwrap();
+		assert_eq!(request.redemption_price, redemption_price);
+		let (remainder_utxo_id, remainder) = request.remainder_utxo.expect("should have remainder");
+		assert_eq!(remainder_utxo_id, 2);
+		assert_eq!(remainder.bond_id, 1);
+		assert_eq!(remainder.satoshis, satoshis - redeem_satoshis);
+		assert_eq!(remainder.bonded_satoshis, satoshis - redeem_satoshis);
+		assert_eq!(remainder.owner_pubkey, pubkey.into());
+		assert_eq!(
+			remainder.vault_claim_height,
+			UtxosById::<Test>::get(1).unwrap().vault_claim_height
+		);
+		assert!(!remainder.is_verified);
+		assert_eq!(remainder.previous_utxo_id, Some(1));
+		assert_eq!(
+			Balances::balance_on_hold(&HoldReason::UnlockingBitcoin.into(), &who),
+			redemption_price
+		);
+
+		let vault_bonded = DefaultVault::get().bitcoin_argons.bonded;
+		GetUtxoRef::set(Some(UtxoRef { txid: H256Le([0; 32]), output_index: 0 }));
+		assert_ok!(Bonds::cosign_bitcoin_unlock(
+			RuntimeOrigin::signed(1),
+			1,
+			BitcoinSignature(BoundedVec::truncate_from([0u8; 73].to_vec())),
+			None
+		));
+		System::assert_last_event(
+			Event::<Test>::BitcoinBondPartiallyRedeemed {
+				bond_id: 1,
+				vault_id: 1,
+				utxo_id: 1,
+				redeemed_satoshis: redeem_satoshis,
+				remainder_utxo_id: 2,
+				remainder_satoshis: satoshis - redeem_satoshis,
+			}
+			.into(),
+		);
+		assert_eq!(LastUnlockEvent::get(), Some((1, false, redemption_price)));
+		assert_eq!(UtxosById::<Test>::get(1), None);
+		assert_eq!(UtxosById::<Test>::get(2), Some(remainder.clone()));
+		assert_eq!(
+			WatchedUtxosById::get().get(&2),
+			Some(&(remainder.utxo_script_pubkey, remainder.satoshis, remainder.vault_claim_height))
+		);
+		// the bond keeps the expiration, but only the amount backing the remainder stays bonded
+		let remaining_amount =
+			FixedU128::from_rational((satoshis - redeem_satoshis).into(), satoshis.into())
+				.saturating_mul_int(bond.amount);
+		assert_eq!(
+			BondsById::<Test>::get(1),
+			Some(Bond { utxo_id: Some(2), amount: remaining_amount, ..bond.clone() })
+		);
+		assert_eq!(
+			DefaultVault::get().bitcoin_argons.bonded,
+			vault_bonded - (bond.amount - remaining_amount)
+		);
+		assert_eq!(Balances::balance_on_hold(&HoldReason::UnlockingBitcoin.into(), &who), 0);
+		assert_eq!(Balances::balance(&who), 2000 + bond.amount - redemption_price);
+
+		// the remainder should not be minted again
+		LastBondEvent::set(None);
+		assert_ok!(Bonds::utxo_verified(2));
+		assert!(UtxosById::<Test>::get(2).unwrap().is_verified);
+		assert_eq!(LastBondEvent::get(), None);
+	});
+}
+
+#[test]
+fn can_cosign_many_bitcoin_unlocks() {
+	new_test_ext().execute_with(|| {
+		BitcoinBlockHeight::set(1);
+		System::set_block_number(1);
+
+		let secp = bitcoin::secp256k1::Secp256k1::new();
+		let rng = &mut rand::thread_rng();
+		let satoshis = SATOSHIS_PER_BITCOIN;
+		for who in [2, 3, 4] {
+			let pubkey = bitcoin::secp256k1::SecretKey::new(rng).public_key(&secp).serialize();
+			set_argons(who, 2_000);
+			assert_ok!(Bonds::bond_bitcoin(
+				RuntimeOrigin::signed(who),
+				1,
+				satoshis,
+				pubkey.into(),
+				BitcoinCosignScriptType::P2WSH
+			));
+			let bond_id = who - 1;
+			assert_ok!(Bonds::utxo_verified(bond_id));
+			let bond = BondsById::<Test>::get(bond_id).unwrap();
+			assert_ok!(Balances::mint_into(&who, bond.amount));
+			assert_ok!(Bonds::unlock_bitcoin_bond(
+				RuntimeOrigin::signed(who),
+				bond_id,
+				make_script_pubkey(&[who as u8; 32]),
+				1000,
+				None,
+				None,
+				// the first owner doesn't allow batching
+				who != 2
+			));
+		}
+		GetUtxoRef::set(Some(UtxoRef { txid: H256Le([0; 32]), output_index: 0 }));
+		let cosign = |bond_id: BondId| BitcoinUnlockCosign {
+			bond_id,
+			signature: Some(BitcoinSignature(BoundedVec::truncate_from([0u8; 73].to_vec()))),
+			vault_musig_nonce: None,
+			cosigner_signatures: Default::default(),
+		};
+
+		assert_err!(
+			frame_support::storage::with_storage_layer(|| Bonds::cosign_bitcoin_unlocks(
+				RuntimeOrigin::signed(1),
+				BoundedVec::truncate_from(vec![cosign(1), cosign(2)]),
+				Some(1500)
+			)),
+			Error::<Test>::BitcoinUnlockNotBatchable
+		);
+
+		// without a batch fee, each unlock is its own transaction
+		assert_ok!(Bonds::cosign_bitcoin_unlocks(
+			RuntimeOrigin::signed(1),
+			BoundedVec::truncate_from(vec![cosign(1)]),
+			None
+		));
+		System::assert_last_event(
+			Event::<Test>::BitcoinUtxoCosigned {
+				bond_id: 1,
+				vault_id: 1,
+				utxo_id: 1,
+				signature: cosign(1).signature,
+				vault_musig_nonce: None,
+				cosigner_signatures: Default::default(),
+			}
+			.into(),
+		);
+		assert_eq!(UtxosPendingUnlockByUtxoId::<Test>::get().get(&1), None);
+		assert_eq!(UtxosById::<Test>::get(1), None);
+
+		let batch = BoundedVec::truncate_from(vec![cosign(2), cosign(3)]);
+		// can't charge more than the owners agreed to pay
+		assert_err!(
+			frame_support::storage::with_storage_layer(|| Bonds::cosign_bitcoin_unlocks(
+				RuntimeOrigin::signed(1),
+				batch.clone(),
+				Some(2001)
+			)),
+			Error::<Test>::BitcoinFeeTooHigh
+		);
+		assert_eq!(UtxosPendingUnlockByUtxoId::<Test>::get().len(), 2);
+
+		// can't combine the unlocks of different vaults
+		BondMigrationVaultId::set(Some(2));
+		BondsById::<Test>::mutate(3, |a| a.as_mut().unwrap().vault_id = 2);
+		assert_err!(
+			frame_support::storage::with_storage_layer(|| Bonds::cosign_bitcoin_unlocks(
+				RuntimeOrigin::signed(1),
+				batch.clone(),
+				Some(1500)
+			)),
+			Error::<Test>::BitcoinBatchMixesVaults
+		);
+		BondsById::<Test>::mutate(3, |a| a.as_mut().unwrap().vault_id = 1);
+		BondMigrationVaultId::set(None);
+
+		assert_ok!(Bonds::cosign_bitcoin_unlocks(RuntimeOrigin::signed(1), batch, Some(1500)));
+		System::assert_last_event(
+			Event::<Test>::BitcoinUtxosBatchCosigned {
+				vault_id: 1,
+				utxo_ids: BoundedVec::truncate_from(vec![2, 3]),
+				bitcoin_network_fee: 1500,
+			}
+			.into(),
+		);
+		assert!(UtxosPendingUnlockByUtxoId::<Test>::get().is_empty());
+		assert_eq!(UtxosById::<Test>::get(2), None);
+		assert_eq!(UtxosById::<Test>::get(3), None);
+		for who in [2, 3, 4] {
+			assert_eq!(Balances::balance_on_hold(&HoldReason::UnlockingBitcoin.into(), &who), 0);
+		}
+	});
+}
+
+#[test]
+fn can_bump_the_fee_of_a_cosigned_unlock() {
+	new_test_ext().execute_with(|| {
+		BitcoinBlockHeight::set(1);
+		System::set_block_number(1);
+
+		let secp = bitcoin::secp256k1::Secp256k1::new();
+		let rng = &mut rand::thread_rng();
+		let pubkey = bitcoin::secp256k1::SecretKey::new(rng).public_key(&secp).serialize();
+		let who = 2;
+		set_argons(who, 2_000);
+		assert_ok!(Bonds::bond_bitcoin(
+			RuntimeOrigin::signed(who),
+			1,
+			SATOSHIS_PER_BITCOIN,
+			pubkey.into(),
+			BitcoinCosignScriptType::P2WSH
+		));
+		assert_ok!(Bonds::utxo_verified(1));
+		let bond = BondsById::<Test>::get(1).unwrap();
+		assert_ok!(Balances::mint_into(&who, bond.amount));
+		assert_ok!(Bonds::unlock_bitcoin_bond(
+			RuntimeOrigin::signed(who),
+			1,
+			make_script_pubkey(&[0; 32]),
+			1000,
+			None,
+			None,
+			false
+		));
+		assert_err!(
+			Bonds::request_bitcoin_unlock_fee_bump(RuntimeOrigin::signed(who), 1, 2000, None),
+			Error::<Test>::BitcoinUnlockNotCosigned
+		);
+		GetUtxoRef::set(Some(UtxoRef { txid: H256Le([0; 32]), output_index: 0 }));
+		let signature = BitcoinSignature(BoundedVec::truncate_from([0u8; 73].to_vec()));
+		assert_ok!(Bonds::cosign_bitcoin_unlock(
+			RuntimeOrigin::signed(1),
+			1,
+			signature.clone(),
+			None
+		));
+		let balance = Balances::balance(&who);
+		assert_eq!(CosignedUnlocksByUtxoId::<Test>::get(1).unwrap().1.bitcoin_network_fee, 1000);
+
+		assert_err!(
+			Bonds::request_bitcoin_unlock_fee_bump(RuntimeOrigin::signed(3), 1, 2000, None),
+			Error::<Test>::NoPermissions
+		);
+		assert_err!(
+			Bonds::request_bitcoin_unlock_fee_bump(RuntimeOrigin::signed(who), 1, 1000, None),
+			Error::<Test>::BitcoinFeeNotIncreased
+		);
+		assert_err!(
+			Bonds::request_bitcoin_unlock_fee_bump(
+				RuntimeOrigin::signed(who),
+				1,
+				SATOSHIS_PER_BITCOIN,
+				None
+			),
+			Error::<Test>::BitcoinFeeTooHigh
+		);
+		assert_ok!(Bonds::request_bitcoin_unlock_fee_bump(
+			RuntimeOrigin::signed(who),
+			1,
+			2000,
+			None
+		));
+		System::assert_last_event(
+			Event::<Test>::BitcoinUnlockFeeBumpRequested {
+				bond_id: 1,
+				vault_id: 1,
+				utxo_id: 1,
+				bitcoin_network_fee: 2000,
+			}
+			.into(),
+		);
+		let request = UtxosPendingUnlockByUtxoId::<Test>::get().get(&1).cloned().unwrap();
+		assert!(request.is_fee_bump);
+		assert_eq!(request.redemption_price, 0);
+		assert_err!(
+			Bonds::request_bitcoin_unlock_fee_bump(RuntimeOrigin::signed(who), 1, 3000, None),
+			Error::<Test>::BitcoinUtxoCosignPending
+		);
+
+		System::set_block_number(2);
+		assert_ok!(Bonds::cosign_bitcoin_unlock(
+			RuntimeOrigin::signed(1),
+			1,
+			signature.clone(),
+			None
+		));
+		System::assert_last_event(
+			Event::<Test>::BitcoinUtxoCosigned {
+				bond_id: 1,
+				vault_id: 1,
+				utxo_id: 1,
+				signature: Some(signature),
+				vault_musig_nonce: None,
+				cosigner_signatures: Default::default(),
+			}
+			.into(),
+		);
+		assert_eq!(CosignedUnlocksByUtxoId::<Test>::get(1).unwrap().1.bitcoin_network_fee, 2000);
+		// the original unlock was released at block 1
+		assert_eq!(UtxosCosignReleaseHeightById::<Test>::get(1), Some(1));
+		assert_eq!(UtxosFeeBumpCosignHeightById::<Test>::get(1), Some(2));
+		// nothing more is charged
+		assert_eq!(Balances::balance(&who), balance);
+		assert_eq!(BondsById::<Test>::get(1), Some(bond));
+
+		// the original unlock is still valid if the vault doesn't cosign
+		assert_ok!(Bonds::request_bitcoin_unlock_fee_bump(
+			RuntimeOrigin::signed(who),
+			1,
+			3000,
+			None
+		));
+		BitcoinBlockHeight::set(BitcoinBlockHeight::get() + UtxoUnlockCosignDeadlineBlocks::get());
+		Bonds::on_initialize(3);
+		System::assert_last_event(
+			Event::<Test>::BitcoinUnlockFeeBumpCosignPastDue {
+				bond_id: 1,
+				vault_id: 1,
+				utxo_id: 1,
+			}
+			.into(),
+		);
+		assert!(UtxosPendingUnlockByUtxoId::<Test>::get().is_empty());
+		assert_eq!(Balances::balance(&who), balance);
+		// the vault already cosigned the unlock, so it didn't miss a deadline
+		assert_eq!(MissedCosignDeadlines::get(), vec![]);
+
+		let cosigned_unlock = CosignedUnlocksByUtxoId::<Test>::get(1);
+		assert_ok!(Bonds::utxo_spent(1));
+		assert_eq!(CosignedUnlocksByUtxoId::<Test>::get(1), None);
+		assert_eq!(UtxosCosignReleaseHeightById::<Test>::get(1), None);
+		assert_eq!(UtxosFeeBumpCosignHeightById::<Test>::get(1), None);
+		assert_err!(
+			Bonds::request_bitcoin_unlock_fee_bump(RuntimeOrigin::signed(who), 1, 3000, None),
+			Error::<Test>::BitcoinUnlockNotCosigned
+		);
+
+		// if the unlock is reorged out, it can be broadcast (or bumped) again
+		assert_ok!(Bonds::utxo_spend_reverted(1));
+		System::assert_last_event(
+			Event::<Test>::BitcoinUtxoSpendReverted { bond_id: 1, utxo_id: 1 }.into(),
+		);
+		assert_eq!(CosignedUnlocksByUtxoId::<Test>::get(1), cosigned_unlock);
+		assert_eq!(UtxosCosignReleaseHeightById::<Test>::get(1), Some(1));
+		assert_eq!(UtxosFeeBumpCosignHeightById::<Test>::get(1), Some(2));
+		assert_eq!(UtxosById::<Test>::get(1), None);
+		assert_eq!(Balances::balance(&who), balance);
+	});
+}
+
+#[test]
+fn can_transfer_a_bitcoin_bond() {
+	new_test_ext().execute_with(|| {
+		BitcoinBlockHeight::set(1);
+		System::set_block_number(1);
+
+		let pubkey = CompressedBitcoinPubkey([1; 33]);
+		let who = 2;
+		set_argons(who, 2_000);
+		assert_ok!(Bonds::bond_bitcoin(
+			RuntimeOrigin::signed(who),
+			1,
+			SATOSHIS_PER_BITCOIN,
+			pubkey,
+			BitcoinCosignScriptType::P2WSH
+		));
+		assert_err!(
+			Bonds::transfer_bitcoin_bond(RuntimeOrigin::signed(3), 1, 3, None),
+			Error::<Test>::NoPermissions
+		);
+		assert_ok!(Bonds::transfer_bitcoin_bond(RuntimeOrigin::signed(who), 1, 3, None));
+		System::assert_last_event(
+			Event::<Test>::BitcoinBondTransferred {
+				bond_id: 1,
+				vault_id: 1,
+				from_account_id: who,
+				to_account_id: 3,
+			}
+			.into(),
+		);
+		assert_eq!(BondsById::<Test>::get(1).unwrap().bonded_account_id, 3);
+		// the utxo is untouched
+		assert_eq!(UtxosById::<Test>::get(1).unwrap().owner_pubkey, pubkey);
+		assert_err!(
+			Bonds::unlock_bitcoin_bond(
+				RuntimeOrigin::signed(who),
+				1,
+				make_script_pubkey(&[0; 32]),
+				11,
+				None,
+				None,
+				false
+			),
+			Error::<Test>::NoPermissions
+		);
+	});
+}
+
+#[test]
+fn can_transfer_a_bitcoin_bond_with_a_new_owner_pubkey() {
+	new_test_ext().execute_with(|| {
+		BitcoinBlockHeight::set(1);
+		System::set_block_number(1);
+
+		let secp = bitcoin::secp256k1::Secp256k1::new();
+		let rng = &mut rand::thread_rng();
+		let pubkey = bitcoin::secp256k1::SecretKey::new(rng).public_key(&secp).serialize();
+		let new_pubkey: CompressedBitcoinPubkey =
+			bitcoin::secp256k1::SecretKey::new(rng).public_key(&secp).serialize().into();
+		let who = 2;
+		let satoshis = SATOSHIS_PER_BITCOIN;
+		set_argons(who, 2_000);
+		assert_ok!(Bonds::bond_bitcoin(
+			RuntimeOrigin::signed(who),
+			1,
+			satoshis,
+			pubkey.into(),
+			BitcoinCosignScriptType::P2WSH
+		));
+		let bond = BondsById::<Test>::get(1).unwrap();
+		let rotation = OwnerPubkeyRotation {
+			owner_pubkey: new_pubkey,
+			bitcoin_network_fee: 1000,
+			owner_musig_nonce: None,
+		};
+		assert_err!(
+			Bonds::transfer_bitcoin_bond(RuntimeOrigin::signed(who), 1, 3, Some(rotation.clone())),
+			Error::<Test>::BitcoinUtxoNotVerified
+		);
+		assert_ok!(Bonds::utxo_verified(1));
+		assert_ok!(Bonds::transfer_bitcoin_bond(
+			RuntimeOrigin::signed(who),
+			1,
+			3,
+			Some(rotation.clone())
+		));
+		// nothing moves until the vault cosigns
+		assert_eq!(BondsById::<Test>::get(1), Some(bond.clone()));
+		let request = UtxosPendingUnlockByUtxoId::<Test>::get().get(&1).cloned().unwrap();
+		assert_eq!(request.redemption_price, 0);
+		assert_eq!(Balances::balance_on_hold(&HoldReason::UnlockingBitcoin.into(), &who), 0);
+		let utxo_move = request.utxo_move.expect("should be a move");
+		assert_eq!(utxo_move.utxo_id, 2);
+		assert_eq!(utxo_move.transfer_to_account_id, Some(3));
+		assert_eq!(utxo_move.utxo.owner_pubkey, new_pubkey);
+		assert_eq!(utxo_move.utxo.satoshis, satoshis - 1000);
+		assert_eq!(utxo_move.utxo.previous_utxo_id, Some(1));
+		assert_eq!(
+			request.to_script_pubkey,
+			BitcoinScriptPubkey::try_from(utxo_move.utxo.utxo_script_pubkey.to_script_bytes())
+				.unwrap()
+		);
+
+		// can't double up cosign requests
+		assert_err!(
+			Bonds::unlock_bitcoin_bond(
+				RuntimeOrigin::signed(who),
+				1,
+				make_script_pubkey(&[0; 32]),
+				11,
+				None,
+				None,
+				false
+			),
+			Error::<Test>::BitcoinUtxoCosignPending
+		);
+
+		GetUtxoRef::set(Some(UtxoRef { txid: H256Le([0; 32]), output_index: 0 }));
+		LastUnlockEvent::set(None);
+		assert_ok!(Bonds::cosign_bitcoin_unlock(
+			RuntimeOrigin::signed(1),
+			1,
+			BitcoinSignature(BoundedVec::truncate_from([0u8; 73].to_vec())),
+			None
+		));
+		System::assert_has_event(
+			Event::<Test>::BitcoinUtxoMoved { bond_id: 1, vault_id: 1, utxo_id: 1, to_utxo_id: 2 }
+				.into(),
+		);
+		System::assert_last_event(
+			Event::<Test>::BitcoinBondTransferred {
+				bond_id: 1,
+				vault_id: 1,
+				from_account_id: who,
+				to_account_id: 3,
+			}
+			.into(),
+		);
+		// nothing was unlocked
+		assert_eq!(LastUnlockEvent::get(), None);
+		assert_eq!(UtxosById::<Test>::get(1), None);
+		assert_eq!(UtxosById::<Test>::get(2), Some(utxo_move.utxo));
+		assert!(WatchedUtxosById::get().contains_key(&2));
+		assert_eq!(
+			BondsById::<Test>::get(1),
+			Some(Bond { utxo_id: Some(2), bonded_account_id: 3, ..bond })
+		);
+	});
+}
+
+#[test]
+fn can_renew_a_bitcoin_bond() {
+	new_test_ext().execute_with(|| {
+		BitcoinBlockHeight::set(1);
+		System::set_block_number(1);
+
+		let who = 2;
+		let satoshis = SATOSHIS_PER_BITCOIN;
+		set_argons(who, 2_000);
+		assert_ok!(Bonds::bond_bitcoin(
+			RuntimeOrigin::signed(who),
+			1,
+			satoshis,
+			CompressedBitcoinPubkey([1; 33]),
+			BitcoinCosignScriptType::P2WSH
+		));
+		let bond = BondsById::<Test>::get(1).unwrap();
+		let vault_bonded = DefaultVault::get().bitcoin_argons.bonded;
+		assert_err!(
+			Bonds::renew_bitcoin_bond(RuntimeOrigin::signed(3), 1, 1, 1000, None),
+			Error::<Test>::NoPermissions
+		);
+		assert_err!(
+			Bonds::renew_bitcoin_bond(RuntimeOrigin::signed(who), 1, 2, 1000, None),
+			Error::<Test>::VaultNotFound
+		);
+		assert_err!(
+			Bonds::renew_bitcoin_bond(RuntimeOrigin::signed(who), 1, 1, 1000, None),
+			Error::<Test>::BitcoinUtxoNotVerified
+		);
+		assert_ok!(Bonds::utxo_verified(1));
+
+		BitcoinBlockHeight::set(100);
+		System::set_block_number(2);
+		RenewalFee::set(100);
+		let balance = Balances::free_balance(who);
+		assert_ok!(Bonds::renew_bitcoin_bond(RuntimeOrigin::signed(who), 1, 1, 1000, None));
+		// the renewal fee is held until the vault cosigns
+		assert_eq!(Balances::balance_on_hold(&HoldReason::RenewingBitcoinBond.into(), &who), 100);
+		assert_eq!(Balances::free_balance(who), balance - 100);
+		// nothing else changes until the vault cosigns
+		assert_eq!(BondsById::<Test>::get(1), Some(bond.clone()));
+		let request = UtxosPendingUnlockByUtxoId::<Test>::get().get(&1).cloned().unwrap();
+		assert_eq!(request.redemption_price, 0);
+		let utxo_move = request.utxo_move.expect("should be a move");
+		let vault_claim_height = 100 + BitcoinBondDurationBlocks::get();
+		assert_eq!(utxo_move.utxo_id, 2);
+		assert_eq!(utxo_move.renew_with_vault_id, Some(1));
+		assert_eq!(utxo_move.renewal_fee, 100);
+		assert_eq!(utxo_move.transfer_to_account_id, None);
+		assert_eq!(utxo_move.utxo.satoshis, satoshis - 1000);
+		assert_eq!(utxo_move.utxo.bonded_satoshis, satoshis);
+		assert_eq!(utxo_move.utxo.vault_claim_height, vault_claim_height);
+		assert_eq!(
+			utxo_move.utxo.open_claim_height,
+			vault_claim_height + BitcoinBondReclamationBlocks::get()
+		);
+		assert_eq!(utxo_move.utxo.previous_utxo_id, Some(1));
+
+		GetUtxoRef::set(Some(UtxoRef { txid: H256Le([0; 32]), output_index: 0 }));
+		assert_ok!(Bonds::cosign_bitcoin_unlock(
+			RuntimeOrigin::signed(1),
+			1,
+			BitcoinSignature(BoundedVec::truncate_from([0u8; 73].to_vec())),
+			None
+		));
+		System::assert_has_event(
+			Event::<Test>::BitcoinUtxoMoved { bond_id: 1, vault_id: 1, utxo_id: 1, to_utxo_id: 2 }
+				.into(),
+		);
+		System::assert_last_event(
+			Event::<Test>::BitcoinBondRenewed {
+				bond_id: 1,
+				vault_id: 1,
+				utxo_id: 2,
+				amount: bond.amount,
+				total_fee: 0,
+				expiration: BondExpiration::BitcoinBlock(vault_claim_height),
+			}
+			.into(),
+		);
+		assert_eq!(UtxosById::<Test>::get(1), None);
+		assert_eq!(UtxosById::<Test>::get(2), Some(utxo_move.utxo));
+		assert_eq!(WatchedUtxosById::get().get(&2).map(|a| a.2), Some(vault_claim_height));
+		assert_eq!(
+			BitcoinBondCompletions::<Test>::get(1 + BitcoinBondDurationBlocks::get()).to_vec(),
+			Vec::<BondId>::new()
+		);
+		assert_eq!(BitcoinBondCompletions::<Test>::get(vault_claim_height).to_vec(), vec![1]);
+		assert_eq!(DefaultVault::get().bitcoin_argons.bonded, vault_bonded);
+		// the held fee is released to pay the renewing vault
+		assert_eq!(Balances::balance_on_hold(&HoldReason::RenewingBitcoinBond.into(), &who), 0);
+		assert_eq!(Balances::free_balance(who), balance);
+		assert_eq!(
+			BondsById::<Test>::get(1),
+			Some(Bond {
+				utxo_id: Some(2),
+				start_block: 2,
+				expiration: BondExpiration::BitcoinBlock(vault_claim_height),
+				..bond
+			})
+		);
+	});
+}
+
+#[test]
+fn renewing_keeps_the_bond_amount_and_releases_the_fee_if_not_cosigned() {
+	new_test_ext().execute_with(|| {
+		BitcoinBlockHeight::set(1);
+		System::set_block_number(1);
+
+		let who = 2;
+		set_argons(who, 2_000);
+		assert_ok!(Bonds::bond_bitcoin(
+			RuntimeOrigin::signed(who),
+			1,
+			SATOSHIS_PER_BITCOIN,
+			CompressedBitcoinPubkey([1; 33]),
+			BitcoinCosignScriptType::P2WSH
+		));
+		assert_ok!(Bonds::utxo_verified(1));
+		let amount = BondsById::<Test>::get(1).unwrap().amount;
+		GetUtxoRef::set(Some(UtxoRef { txid: H256Le([0; 32]), output_index: 0 }));
+
+		// the first renewal moves less satoshis than were bonded (the network fee is paid)
+		assert_ok!(Bonds::renew_bitcoin_bond(RuntimeOrigin::signed(who), 1, 1, 1000, None));
+		assert_ok!(Bonds::cosign_bitcoin_unlock(
+			RuntimeOrigin::signed(1),
+			1,
+			BitcoinSignature(BoundedVec::truncate_from([0u8; 73].to_vec())),
+			None
+		));
+		assert_ok!(Bonds::utxo_verified(2));
+		assert_eq!(BondsById::<Test>::get(1).unwrap().amount, amount);
+
+		RenewalFee::set(100);
+		let balance = Balances::free_balance(who);
+		assert_ok!(Bonds::renew_bitcoin_bond(RuntimeOrigin::signed(who), 1, 1, 1000, None));
+		assert_eq!(Balances::balance_on_hold(&HoldReason::RenewingBitcoinBond.into(), &who), 100);
+
+		let cosign_due_block = UtxosPendingUnlockByUtxoId::<Test>::get()[&2].cosign_due_block;
+		BitcoinBlockHeight::set(cosign_due_block);
+		System::set_block_number(2);
+		Bonds::on_initialize(2);
+		System::assert_last_event(
+			Event::<Test>::BitcoinUtxoMoveCosignPastDue { bond_id: 1, vault_id: 1, utxo_id: 2 }
+				.into(),
+		);
+		assert_eq!(Balances::balance_on_hold(&HoldReason::RenewingBitcoinBond.into(), &who), 0);
+		assert_eq!(Balances::free_balance(who), balance);
+
+		// a renewal of the moved utxo still bonds the full amount
+		assert_ok!(Bonds::renew_bitcoin_bond(RuntimeOrigin::signed(who), 1, 1, 1000, None));
+		assert_ok!(Bonds::cosign_bitcoin_unlock(
+			RuntimeOrigin::signed(1),
+			1,
+			BitcoinSignature(BoundedVec::truncate_from([0u8; 73].to_vec())),
+			None
+		));
+		assert_eq!(BondsById::<Test>::get(1).unwrap().amount, amount);
+		assert_eq!(Balances::free_balance(who), balance);
+	});
+}
+
+#[test]
+fn can_migrate_a_bitcoin_bond_off_a_closed_vault() {
+	new_test_ext().execute_with(|| {
+		BitcoinBlockHeight::set(1);
+		System::set_block_number(1);
+
+		let who = 2;
+		let satoshis = SATOSHIS_PER_BITCOIN;
+		set_argons(who, 2_000);
+		assert_ok!(Bonds::bond_bitcoin(
+			RuntimeOrigin::signed(who),
+			1,
+			satoshis,
+			CompressedBitcoinPubkey([1; 33]),
+			BitcoinCosignScriptType::P2WSH
+		));
+		assert_ok!(Bonds::utxo_verified(1));
+		let bond = BondsById::<Test>::get(1).unwrap();
+		let utxo = UtxosById::<Test>::get(1).unwrap();
+
+		assert_err!(
+			Bonds::migrate_bitcoin_bond(RuntimeOrigin::signed(who), 1, 1000, None),
+			Error::<Test>::BondMigrationNotAccepted
+		);
+		BondMigrationVaultId::set(Some(2));
+		assert_err!(
+			Bonds::migrate_bitcoin_bond(RuntimeOrigin::signed(3), 1, 1000, None),
+			Error::<Test>::NoPermissions
+		);
+		// only the owner can move their bitcoin, not the operator of the closed vault
+		assert_err!(
+			Bonds::migrate_bitcoin_bond(RuntimeOrigin::signed(1), 1, 1000, None),
+			Error::<Test>::NoPermissions
+		);
+
+		BitcoinBlockHeight::set(100);
+		System::set_block_number(2);
+		assert_ok!(Bonds::migrate_bitcoin_bond(RuntimeOrigin::signed(who), 1, 1000, None));
+		// nothing changes until the closed vault cosigns
+		assert_eq!(BondsById::<Test>::get(1), Some(bond.clone()));
+		assert!(MigratedBondFunds::get().is_empty());
+		let request = UtxosPendingUnlockByUtxoId::<Test>::get().get(&1).cloned().unwrap();
+		assert_eq!(request.vault_id, 1);
+		assert_eq!(request.redemption_price, 0);
+		let utxo_move = request.utxo_move.expect("should be a move");
+		assert_eq!(utxo_move.utxo_id, 2);
+		assert_eq!(utxo_move.migrate_to_vault_id, Some(2));
+		assert_eq!(utxo_move.renew_with_vault_id, None);
+		assert_eq!(utxo_move.transfer_to_account_id, None);
+		assert_eq!(utxo_move.utxo.satoshis, satoshis - 1000);
+		assert_eq!(utxo_move.utxo.bonded_satoshis, utxo.bonded_satoshis);
+		// the liabilities move with the same claim heights
+		assert_eq!(utxo_move.utxo.vault_claim_height, utxo.vault_claim_height);
+		assert_eq!(utxo_move.utxo.open_claim_height, utxo.open_claim_height);
+		assert_eq!(utxo_move.utxo.previous_utxo_id, Some(1));
+
+		GetUtxoRef::set(Some(UtxoRef { txid: H256Le([0; 32]), output_index: 0 }));
+		assert_ok!(Bonds::cosign_bitcoin_unlock(
+			RuntimeOrigin::signed(1),
+			1,
+			BitcoinSignature(BoundedVec::truncate_from([0u8; 73].to_vec())),
+			None
+		));
+		System::assert_has_event(
+			Event::<Test>::BitcoinUtxoMoved { bond_id: 1, vault_id: 1, utxo_id: 1, to_utxo_id: 2 }
+				.into(),
+		);
+		System::assert_last_event(
+			Event::<Test>::BitcoinBondMigrated {
+				bond_id: 1,
+				from_vault_id: 1,
+				to_vault_id: 2,
+				utxo_id: 2,
+				earned_fee: 0,
+			}
+			.into(),
+		);
+		assert_eq!(MigratedBondFunds::get(), vec![(1, 2, bond.amount)]);
+		assert_eq!(UtxosById::<Test>::get(1), None);
+		assert_eq!(UtxosById::<Test>::get(2), Some(utxo_move.utxo));
+		assert!(WatchedUtxosById::get().contains_key(&2));
+		assert_eq!(BitcoinBondCompletions::<Test>::get(utxo.vault_claim_height).to_vec(), vec![1]);
+		assert_eq!(
+			BondsById::<Test>::get(1),
+			Some(Bond { vault_id: 2, utxo_id: Some(2), start_block: 2, ..bond })
+		);
+	});
+}
+
+#[test]
+fn drops_overdue_utxo_moves() {
+	new_test_ext().execute_with(|| {
+		BitcoinBlockHeight::set(1);
+		System::set_block_number(1);
+
+		let who = 2;
+		set_argons(who, 2_000);
+		assert_ok!(Bonds::bond_bitcoin(
+			RuntimeOrigin::signed(who),
+			1,
+			SATOSHIS_PER_BITCOIN,
+			CompressedBitcoinPubkey([1; 33]),
+			BitcoinCosignScriptType::P2WSH
+		));
+		assert_ok!(Bonds::utxo_verified(1));
+		let vault = DefaultVault::get();
+		assert_ok!(Bonds::transfer_bitcoin_bond(
+			RuntimeOrigin::signed(who),
+			1,
+			3,
+			Some(OwnerPubkeyRotation {
+				owner_pubkey: CompressedBitcoinPubkey([2; 33]),
+				bitcoin_network_fee: 1000,
+				owner_musig_nonce: None,
+			})
+		));
+
+		BitcoinBlockHeight::set(1 + UtxoUnlockCosignDeadlineBlocks::get());
+		System::set_block_number(2);
+		Bonds::on_initialize(2);
+		System::assert_last_event(
+			Event::<Test>::BitcoinUtxoMoveCosignPastDue { bond_id: 1, vault_id: 1, utxo_id: 1 }
+				.into(),
+		);
+		assert_eq!(UtxosPendingUnlockByUtxoId::<Test>::get().get(&1), None);
+		assert!(UtxosById::<Test>::get(1).is_some());
+		assert_eq!(BondsById::<Test>::get(1).unwrap().bonded_account_id, who);
+		// the vault is not penalized, but the missed deadline is recorded
+		assert_eq!(DefaultVault::get(), vault);
+		assert_eq!(MissedCosignDeadlines::get(), vec![1]);
+	});
+}
+
 fn default_utxo_state(bond_id: BondId, satoshis: Satoshis) -> UtxoState {
 	let current_height = BitcoinBlockHeight::get();
 	UtxoState {
@@ -633,6 +1941,9 @@ fn default_utxo_state(bond_id: BondId, satoshis: Satoshis) -> UtxoState {
 		vault_pubkey: DefaultVaultBitcoinPubkey::get().into(),
 		created_at_height: current_height,
 		vault_xpub_sources: ([0; 4], 0, 1),
+		bonded_satoshis: satoshis,
+		previous_utxo_id: None,
+		vault_multisig: None,
 	}
 }
 
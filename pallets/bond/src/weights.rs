@@ -37,6 +37,7 @@ use frame_support::{traits::Get, weights::{constants::RocksDbWeight, Weight}};
 pub trait WeightInfo {
 	fn do_something() -> Weight;
 	fn cause_error() -> Weight;
+	fn cosign_bitcoin_unlocks(c: u32, ) -> Weight;
 }
 
 /// Weights for pallet_template using the Substrate node and recommended hardware.
@@ -63,6 +64,24 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(1_u64))
 			.saturating_add(T::DbWeight::get().writes(1_u64))
 	}
+	/// Storage: Bonds BondsById (r:c w:0)
+	/// Storage: Vaults VaultsById (r:c w:c)
+	/// Storage: Bonds UtxosPendingUnlockByUtxoId (r:1 w:1)
+	/// Storage: Bonds PendingUnlockUtxoIdByBondId (r:c w:c)
+	/// Storage: Bonds UtxosById (r:c w:c)
+	/// Storage: Bonds UtxosCosignReleaseHeightById (r:0 w:c)
+	/// Storage: Bonds CosignedUnlocksByUtxoId (r:0 w:c)
+	/// Storage: Balances Holds (r:c w:c)
+	/// The range of component `c` is `[1, 1000]`.
+	fn cosign_bitcoin_unlocks(c: u32, ) -> Weight {
+		Weight::from_parts(20_000_000, 1489)
+			// each cosign verifies its signatures
+			.saturating_add(Weight::from_parts(120_000_000, 0).saturating_mul(c.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().reads((6_u64).saturating_mul(c.into())))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+			.saturating_add(T::DbWeight::get().writes((7_u64).saturating_mul(c.into())))
+	}
 }
 
 // For backwards compatibility and tests
@@ -88,4 +107,22 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(1_u64))
 			.saturating_add(RocksDbWeight::get().writes(1_u64))
 	}
+	/// Storage: Bonds BondsById (r:c w:0)
+	/// Storage: Vaults VaultsById (r:c w:c)
+	/// Storage: Bonds UtxosPendingUnlockByUtxoId (r:1 w:1)
+	/// Storage: Bonds PendingUnlockUtxoIdByBondId (r:c w:c)
+	/// Storage: Bonds UtxosById (r:c w:c)
+	/// Storage: Bonds UtxosCosignReleaseHeightById (r:0 w:c)
+	/// Storage: Bonds CosignedUnlocksByUtxoId (r:0 w:c)
+	/// Storage: Balances Holds (r:c w:c)
+	/// The range of component `c` is `[1, 1000]`.
+	fn cosign_bitcoin_unlocks(c: u32, ) -> Weight {
+		Weight::from_parts(20_000_000, 1489)
+			// each cosign verifies its signatures
+			.saturating_add(Weight::from_parts(120_000_000, 0).saturating_mul(c.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().reads((6_u64).saturating_mul(c.into())))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+			.saturating_add(RocksDbWeight::get().writes((7_u64).saturating_mul(c.into())))
+	}
 }
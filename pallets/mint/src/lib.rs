@@ -75,6 +75,12 @@ pub mod pallet {
 		ValueQuery,
 	>;
 
+	/// The argons that were still pending mint for a utxo whose verification was reverted by a
+	/// bitcoin reorg. Only these are queued again once the utxo is verified again.
+	#[pallet::storage]
+	pub(super) type PausedMintUtxos<T: Config> =
+		StorageMap<_, Twox64Concat, UtxoId, T::Balance, OptionQuery>;
+
 	#[pallet::storage]
 	pub(super) type MintedMiningArgons<T: Config> = StorageValue<_, U256, ValueQuery>;
 
@@ -285,6 +291,10 @@ pub mod pallet {
 			account_id: &T::AccountId,
 			amount: T::Balance,
 		) -> sp_runtime::DispatchResult {
+			let amount = <PausedMintUtxos<T>>::take(utxo_id).unwrap_or(amount);
+			if amount.is_zero() {
+				return Ok(());
+			}
 			<PendingMintUtxos<T>>::try_mutate(|x| -> DispatchResult {
 				x.try_push((utxo_id, account_id.clone(), amount))
 					.map_err(|_| Error::<T>::TooManyPendingMints.into())
@@ -292,6 +302,21 @@ pub mod pallet {
 			Ok(())
 		}
 
+		fn utxo_verification_reverted(utxo_id: UtxoId) -> sp_runtime::DispatchResult {
+			let mut remaining = T::Balance::zero();
+			<PendingMintUtxos<T>>::mutate(|x| {
+				x.retain(|(id, _, amount)| {
+					if id == &utxo_id {
+						remaining = remaining.saturating_add(*amount);
+						return false;
+					}
+					true
+				});
+			});
+			<PausedMintUtxos<T>>::insert(utxo_id, remaining);
+			Ok(())
+		}
+
 		fn utxo_unlocked(
 			utxo_id: UtxoId,
 			remove_pending_mints: bool,
@@ -302,6 +327,7 @@ pub mod pallet {
 					x.retain(|(id, _, _)| id != &utxo_id);
 				});
 			}
+			<PausedMintUtxos<T>>::remove(utxo_id);
 
 			let amount_burned: u128 = amount_burned.into();
 
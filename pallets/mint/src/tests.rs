@@ -9,7 +9,7 @@ use sp_runtime::{DispatchError, TokenError};
 
 use crate::{
 	mock::*,
-	pallet::{MintedBitcoinArgons, MintedMiningArgons, PendingMintUtxos},
+	pallet::{MintedBitcoinArgons, MintedMiningArgons, PausedMintUtxos, PendingMintUtxos},
 	Event, MintType,
 };
 
@@ -351,3 +351,33 @@ fn it_decrements_unlocked_bitcoins() {
 		assert!(PendingMintUtxos::<Test>::get().is_empty());
 	});
 }
+
+#[test]
+fn it_only_requeues_unminted_argons_after_a_reverted_verification() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(Mint::utxo_bonded(1, &1, 1_000));
+		assert_ok!(Mint::utxo_bonded(2, &2, 500));
+		PendingMintUtxos::<Test>::mutate(|x| x[0].2 = 400);
+
+		assert_ok!(Mint::utxo_verification_reverted(1));
+		assert_eq!(PendingMintUtxos::<Test>::get().to_vec(), vec![(2, 2, 500)]);
+		assert_eq!(PausedMintUtxos::<Test>::get(1), Some(400));
+
+		assert_ok!(Mint::utxo_bonded(1, &1, 1_000));
+		assert_eq!(PendingMintUtxos::<Test>::get().to_vec(), vec![(2, 2, 500), (1, 1, 400)]);
+		assert_eq!(PausedMintUtxos::<Test>::get(1), None);
+
+		// a fully minted utxo isn't queued again
+		PendingMintUtxos::<Test>::mutate(|x| x.retain(|(id, _, _)| *id != 2));
+		assert_ok!(Mint::utxo_verification_reverted(2));
+		assert_eq!(PausedMintUtxos::<Test>::get(2), Some(0));
+		assert_ok!(Mint::utxo_bonded(2, &2, 500));
+		assert_eq!(PendingMintUtxos::<Test>::get().to_vec(), vec![(1, 1, 400)]);
+
+		// unlocking clears the held back argons
+		assert_ok!(Mint::utxo_verification_reverted(1));
+		assert_ok!(Mint::utxo_unlocked(1, true, 0));
+		assert_eq!(PausedMintUtxos::<Test>::get(1), None);
+	});
+}
@@ -16,6 +16,7 @@ pub use weights::WeightInfo;
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 
+pub mod migrations;
 #[cfg(test)]
 mod tests;
 pub mod weights;
@@ -61,7 +62,7 @@ impl PriceIndex {
 
 #[frame_support::pallet(dev_mode)]
 pub mod pallet {
-	use alloc::vec;
+	use alloc::{vec, vec::Vec};
 	use core::fmt::Debug;
 	use frame_support::pallet_prelude::*;
 	use frame_system::pallet_prelude::*;
@@ -71,7 +72,10 @@ pub mod pallet {
 
 	use super::*;
 
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	/// ## Configuration
@@ -113,6 +117,15 @@ pub mod pallet {
 		type MaxArgonChangePerTickAwayFromTarget: Get<FixedU128>;
 		#[pallet::constant]
 		type MaxArgonTargetChangePerTick: Get<FixedU128>;
+
+		/// The maximum number of oracle operators that can submit price indexes
+		#[pallet::constant]
+		type MaxOracleOperators: Get<u32>;
+
+		/// The max ratio an operator's submitted prices can differ from the median of a tick before
+		/// the operator is flagged
+		#[pallet::constant]
+		type MaxOracleDeviation: Get<FixedU128>;
 	}
 
 	#[pallet::event]
@@ -120,8 +133,19 @@ pub mod pallet {
 	pub enum Event<T: Config> {
 		/// Event emitted when a new price index is submitted
 		NewIndex,
-		OperatorChanged {
-			operator_id: T::AccountId,
+		OracleOperatorsSet {
+			operators: Vec<T::AccountId>,
+			quorum: u32,
+		},
+		/// An oracle operator submitted prices too far from the median of the tick
+		OracleOperatorDeviated {
+			operator: T::AccountId,
+			tick: Tick,
+		},
+		/// An oracle operator was removed for not submitting prices
+		StaleOracleOperatorRemoved {
+			operator: T::AccountId,
+			last_active_tick: Tick,
 		},
 	}
 
@@ -135,27 +159,63 @@ pub mod pallet {
 		PricesTooOld,
 		/// Change in argon price is too large
 		MaxPriceChangePerTickExceeded,
+		/// The quorum must be between one and the number of oracle operators
+		InvalidOracleQuorum,
+		/// An oracle operator was included more than once
+		DuplicateOracleOperator,
 	}
 
 	/// Stores the active price index
 	#[pallet::storage]
 	pub type Current<T: Config> = StorageValue<_, PriceIndex>;
 
-	/// The price index operator account
+	/// The price index oracle operator accounts
 	#[pallet::storage]
-	pub type Operator<T: Config> = StorageValue<_, T::AccountId, OptionQuery>;
+	pub type OracleOperators<T: Config> =
+		StorageValue<_, BoundedVec<T::AccountId, T::MaxOracleOperators>, ValueQuery>;
+
+	/// The number of oracle operators that must submit prices for a tick before the median becomes
+	/// the current price index
+	#[pallet::storage]
+	pub type OracleQuorum<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// The latest price index submitted by each oracle operator
+	#[pallet::storage]
+	pub type OracleSubmissions<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, PriceIndex, OptionQuery>;
+
+	/// The last tick each oracle operator was added or submitted prices
+	#[pallet::storage]
+	pub type OracleLastActiveTick<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, Tick, OptionQuery>;
+
+	/// The number of times each oracle operator submitted prices too far from the median
+	#[pallet::storage]
+	pub type OracleOperatorDeviations<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
 
 	#[pallet::genesis_config]
 	#[derive(frame_support::DefaultNoBound)]
 	pub struct GenesisConfig<T: Config> {
-		pub operator: Option<T::AccountId>,
+		pub operators: Vec<T::AccountId>,
+		/// Defaults to a majority of the operators
+		pub quorum: Option<u32>,
 	}
 
 	#[pallet::genesis_build]
 	impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
 		fn build(&self) {
-			if let Some(operator) = &self.operator {
-				<Operator<T>>::put(operator);
+			if !self.operators.is_empty() {
+				let operators = BoundedVec::try_from(self.operators.clone())
+					.expect("Too many price index oracle operators");
+				let quorum = self.quorum.unwrap_or(operators.len() as u32 / 2 + 1);
+				Pallet::<T>::ensure_valid_operators(&operators, quorum)
+					.expect("Invalid price index oracle operators");
+				for operator in &operators {
+					<OracleLastActiveTick<T>>::insert(operator, 0);
+				}
+				<OracleOperators<T>>::put(operators);
+				<OracleQuorum<T>>::put(quorum);
 			}
 		}
 	}
@@ -163,15 +223,20 @@ pub mod pallet {
 	#[pallet::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
 		fn on_initialize(_: BlockNumberFor<T>) -> Weight {
-			T::DbWeight::get().reads_writes(3, 3)
+			let operators = T::MaxOracleOperators::get() as u64;
+			T::DbWeight::get().reads_writes(4 + operators, 3 + 2 * operators)
 		}
 
 		fn on_finalize(_: BlockNumberFor<T>) {
+			let current_tick = T::CurrentTick::get();
+			let oldest_active_tick =
+				current_tick.saturating_sub(T::MaxDowntimeTicksBeforeReset::get());
+			Self::remove_stale_operators(oldest_active_tick);
+
 			let Some(current) = Current::<T>::get() else {
 				return;
 			};
-			let current_tick = T::CurrentTick::get();
-			if current.tick < current_tick.saturating_sub(T::MaxDowntimeTicksBeforeReset::get()) {
+			if current.tick < oldest_active_tick {
 				Current::<T>::take();
 			}
 		}
@@ -179,50 +244,198 @@ pub mod pallet {
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
-		/// Submit the latest price index. Only valid for the configured operator account
+		/// Submit the latest price index. Only valid for an oracle operator account. Once the
+		/// quorum of operators have submitted prices for a tick, the median of their prices
+		/// becomes the current price index.
 		#[pallet::call_index(0)]
 		#[pallet::weight((0, DispatchClass::Operational))]
 		pub fn submit(origin: OriginFor<T>, index: PriceIndex) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
 
-			let operator = <Operator<T>>::get().ok_or(Error::<T>::NotAuthorizedOperator)?;
-			ensure!(operator == who, Error::<T>::NotAuthorizedOperator);
+			let operators = <OracleOperators<T>>::get();
+			ensure!(operators.contains(&who), Error::<T>::NotAuthorizedOperator);
 
-			let oldest_age = T::CurrentTick::get().saturating_sub(T::MaxPriceAgeInTicks::get());
+			let current_tick = T::CurrentTick::get();
+			let oldest_age = current_tick.saturating_sub(T::MaxPriceAgeInTicks::get());
 
 			if index.tick < oldest_age {
 				return Ok(Pays::No.into());
 			}
+			<OracleLastActiveTick<T>>::insert(&who, current_tick);
 
-			let mut index = index;
-			if let Some(current) = <Current<T>>::get() {
-				if index.tick <= current.tick {
+			let current = <Current<T>>::get();
+			if let Some(current) = &current {
+				if index.tick < current.tick {
 					return Ok(Pays::No.into());
 				}
-				Self::clamp_argon_prices(&current, &mut index);
+			}
+			<OracleSubmissions<T>>::insert(&who, index);
+
+			let submissions = operators
+				.iter()
+				.filter_map(|operator| {
+					let submission = <OracleSubmissions<T>>::get(operator)?;
+					(submission.tick == index.tick).then_some((operator, submission))
+				})
+				.collect::<Vec<_>>();
+			if (submissions.len() as u32) < <OracleQuorum<T>>::get().max(1) {
+				return Ok(Pays::No.into());
+			}
+			let median = Self::median(&submissions.iter().map(|(_, a)| *a).collect::<Vec<_>>());
+
+			// a late submission for the current tick is only checked against the median
+			if current.as_ref().is_some_and(|a| a.tick == index.tick) {
+				if Self::is_deviation(&median, &index) {
+					Self::record_deviation(who, index.tick);
+				}
+				return Ok(Pays::No.into());
+			}
+
+			for (operator, submission) in submissions {
+				if Self::is_deviation(&median, &submission) {
+					Self::record_deviation(operator.clone(), submission.tick);
+				}
+			}
+
+			let mut next = median;
+			if let Some(current) = &current {
+				Self::clamp_argon_prices(current, &mut next);
 			}
 
-			<Current<T>>::put(index);
+			<Current<T>>::put(next);
 			Self::deposit_event(Event::<T>::NewIndex);
 
 			Ok(Pays::No.into())
 		}
 
-		/// Sets the operator account id (only executable by the Root account)
+		/// Sets the oracle operator accounts and how many of them must submit prices for a tick
+		/// (only executable by the Root account)
 		///
 		/// # Arguments
-		/// * `account_id` - the account id of the operator
-		#[pallet::call_index(1)]
-		#[pallet::weight(<T as Config>::WeightInfo::insert_oracle_operator())]
-		pub fn set_operator(origin: OriginFor<T>, account_id: T::AccountId) -> DispatchResult {
+		/// * `operators` - the account ids of the operators
+		/// * `quorum` - the number of operators that must submit prices for a tick
+		#[pallet::call_index(2)]
+		#[pallet::weight(<T as Config>::WeightInfo::set_operators(T::MaxOracleOperators::get()))]
+		pub fn set_operators(
+			origin: OriginFor<T>,
+			operators: BoundedVec<T::AccountId, T::MaxOracleOperators>,
+			quorum: u32,
+		) -> DispatchResult {
 			ensure_root(origin)?;
-			<Operator<T>>::put(account_id.clone());
-			Self::deposit_event(Event::OperatorChanged { operator_id: account_id });
+			Self::ensure_valid_operators(&operators, quorum)?;
+			for operator in <OracleOperators<T>>::get() {
+				if !operators.contains(&operator) {
+					<OracleSubmissions<T>>::remove(&operator);
+					<OracleLastActiveTick<T>>::remove(&operator);
+				}
+			}
+			// new operators get a full downtime window before they're considered stale
+			let current_tick = T::CurrentTick::get();
+			for operator in &operators {
+				<OracleLastActiveTick<T>>::mutate(operator, |a| {
+					a.get_or_insert(current_tick);
+				});
+			}
+			<OracleOperators<T>>::put(operators.clone());
+			<OracleQuorum<T>>::put(quorum);
+			Self::deposit_event(Event::OracleOperatorsSet {
+				operators: operators.into_inner(),
+				quorum,
+			});
 			Ok(())
 		}
 	}
 
 	impl<T: Config> Pallet<T> {
+		pub(crate) fn ensure_valid_operators(
+			operators: &[T::AccountId],
+			quorum: u32,
+		) -> Result<(), Error<T>> {
+			ensure!(
+				quorum > 0 && quorum as usize <= operators.len(),
+				Error::<T>::InvalidOracleQuorum
+			);
+			for (i, operator) in operators.iter().enumerate() {
+				ensure!(
+					!operators[i + 1..].contains(operator),
+					Error::<T>::DuplicateOracleOperator
+				);
+			}
+			Ok(())
+		}
+
+		/// Removes operators that haven't submitted prices since the oldest active tick. Operators
+		/// are never removed below the quorum, so stale operators can't halt the price index on
+		/// their own.
+		fn remove_stale_operators(oldest_active_tick: Tick) {
+			let mut operators = <OracleOperators<T>>::get();
+			let quorum = <OracleQuorum<T>>::get() as usize;
+			let operator_count = operators.len();
+			let mut i = 0;
+			while i < operators.len() && operators.len() > quorum {
+				let operator = operators[i].clone();
+				let last_active_tick =
+					<OracleLastActiveTick<T>>::get(&operator).unwrap_or_default();
+				if last_active_tick >= oldest_active_tick {
+					i += 1;
+					continue;
+				}
+				operators.remove(i);
+				<OracleSubmissions<T>>::remove(&operator);
+				<OracleLastActiveTick<T>>::remove(&operator);
+				Self::deposit_event(Event::StaleOracleOperatorRemoved {
+					operator,
+					last_active_tick,
+				});
+			}
+			if operators.len() != operator_count {
+				<OracleOperators<T>>::put(operators);
+			}
+		}
+
+		/// The median of each price in the submissions. All submissions must be for the same tick.
+		pub(crate) fn median(submissions: &[PriceIndex]) -> PriceIndex {
+			let median_of = |price: fn(&PriceIndex) -> FixedU128| {
+				let mut prices = submissions.iter().map(price).collect::<Vec<_>>();
+				prices.sort();
+				let middle = prices.len() / 2;
+				if prices.len() % 2 == 0 {
+					let sum =
+						prices[middle - 1].into_inner().saturating_add(prices[middle].into_inner());
+					FixedU128::from_inner(sum / 2)
+				} else {
+					prices[middle]
+				}
+			};
+			PriceIndex {
+				btc_usd_price: median_of(|a| a.btc_usd_price),
+				argon_usd_price: median_of(|a| a.argon_usd_price),
+				argon_usd_target_price: median_of(|a| a.argon_usd_target_price),
+				tick: submissions[0].tick,
+			}
+		}
+
+		pub(crate) fn is_deviation(median: &PriceIndex, submission: &PriceIndex) -> bool {
+			let max_deviation = T::MaxOracleDeviation::get();
+			let deviates = |median: FixedU128, price: FixedU128| {
+				let diff = if price > median { price - median } else { median - price };
+				diff > median * max_deviation
+			};
+			deviates(median.btc_usd_price, submission.btc_usd_price) ||
+				deviates(median.argon_usd_price, submission.argon_usd_price) ||
+				deviates(median.argon_usd_target_price, submission.argon_usd_target_price)
+		}
+
+		fn record_deviation(operator: T::AccountId, tick: Tick) {
+			log::warn!(
+				"Price index oracle operator {:?} submitted prices too far from the median at tick {}",
+				operator,
+				tick
+			);
+			<OracleOperatorDeviations<T>>::mutate(&operator, |a| *a = a.saturating_add(1));
+			Self::deposit_event(Event::OracleOperatorDeviated { operator, tick });
+		}
+
 		fn get_current() -> Option<PriceIndex> {
 			let price = <Current<T>>::get()?;
 			if price.tick < T::CurrentTick::get().saturating_sub(T::MaxPriceAgeInTicks::get()) {
@@ -0,0 +1,62 @@
+use crate::{
+	pallet::{OracleLastActiveTick, OracleOperators, OracleQuorum},
+	Config, Pallet,
+};
+use alloc::vec;
+use frame_support::{
+	migrations::VersionedMigration, pallet_prelude::*, traits::UncheckedOnRuntimeUpgrade,
+};
+use log::info;
+
+/// Moves the single price index operator into the set of oracle operators, with a quorum of one
+pub mod v1 {
+	use super::*;
+
+	#[frame_support::storage_alias]
+	pub(super) type Operator<T: Config> =
+		StorageValue<Pallet<T>, <T as frame_system::Config>::AccountId, OptionQuery>;
+
+	pub struct InnerMigrateV0ToV1<T: Config>(core::marker::PhantomData<T>);
+
+	impl<T: Config> UncheckedOnRuntimeUpgrade for InnerMigrateV0ToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let Some(operator) = Operator::<T>::take() else {
+				return T::DbWeight::get().reads(1);
+			};
+			info!("Migrating price index operator {:?} to the oracle operators", operator);
+			OracleLastActiveTick::<T>::insert(&operator, T::CurrentTick::get());
+			OracleOperators::<T>::put(BoundedVec::truncate_from(vec![operator]));
+			OracleQuorum::<T>::put(1);
+			T::DbWeight::get().reads_writes(1, 4)
+		}
+	}
+
+	pub type MigrateV0ToV1<T> = VersionedMigration<
+		0,
+		1,
+		InnerMigrateV0ToV1<T>,
+		Pallet<T>,
+		<T as frame_system::Config>::DbWeight,
+	>;
+
+	#[cfg(test)]
+	mod test {
+		use super::*;
+		use crate::mock::{new_test_ext, CurrentTick, Test};
+
+		#[test]
+		fn migrates_the_operator() {
+			new_test_ext(None).execute_with(|| {
+				CurrentTick::set(10);
+				Operator::<Test>::put(5);
+
+				InnerMigrateV0ToV1::<Test>::on_runtime_upgrade();
+
+				assert_eq!(Operator::<Test>::get(), None);
+				assert_eq!(OracleOperators::<Test>::get().to_vec(), vec![5]);
+				assert_eq!(OracleQuorum::<Test>::get(), 1);
+				assert_eq!(OracleLastActiveTick::<Test>::get(5), Some(10));
+			});
+		}
+	}
+}
@@ -1,7 +1,7 @@
 use crate as pallet_price_index;
 use argon_primitives::tick::Tick;
 use env_logger::{Builder, Env};
-use frame_support::{derive_impl, parameter_types};
+use frame_support::{derive_impl, parameter_types, traits::ConstU32};
 use sp_arithmetic::FixedU128;
 use sp_runtime::{traits::IdentityLookup, BuildStorage};
 
@@ -29,6 +29,7 @@ parameter_types! {
 	pub static CurrentTick:Tick = 0;
 	pub const MaxArgonChangePerTickAwayFromTarget: FixedU128 = FixedU128::from_rational(1, 100);
 	pub const MaxArgonTargetChangePerTick: FixedU128 = FixedU128::from_rational(1, 100);
+	pub const MaxOracleDeviation: FixedU128 = FixedU128::from_rational(1, 10);
 }
 
 impl pallet_price_index::Config for Test {
@@ -41,15 +42,20 @@ impl pallet_price_index::Config for Test {
 	type MaxPriceAgeInTicks = MaxPriceAgeInTicks;
 	type MaxArgonChangePerTickAwayFromTarget = MaxArgonChangePerTickAwayFromTarget;
 	type MaxArgonTargetChangePerTick = MaxArgonTargetChangePerTick;
+	type MaxOracleOperators = ConstU32<5>;
+	type MaxOracleDeviation = MaxOracleDeviation;
 }
 
 pub fn new_test_ext(operator: Option<u64>) -> sp_io::TestExternalities {
 	let env = Env::new().default_filter_or("debug");
 	let _ = Builder::from_env(env).is_test(true).try_init();
 	let mut t = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
-	pallet_price_index::GenesisConfig::<Test> { operator }
-		.assimilate_storage(&mut t)
-		.unwrap();
+	pallet_price_index::GenesisConfig::<Test> {
+		operators: operator.into_iter().collect(),
+		quorum: None,
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
 
 	sp_io::TestExternalities::new(t)
 }
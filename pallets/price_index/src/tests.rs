@@ -1,9 +1,13 @@
-use frame_support::{assert_err, assert_ok};
+use frame_support::{assert_err, assert_ok, pallet_prelude::Hooks};
 use sp_arithmetic::{FixedPointNumber, FixedU128};
+use sp_core::bounded_vec;
 
 use argon_primitives::{bitcoin::SATOSHIS_PER_BITCOIN, ArgonCPI, PriceProvider};
 
-use crate::{mock::*, Current, Operator, PriceIndex as PriceIndexEntry};
+use crate::{
+	mock::*, Current, OracleOperatorDeviations, OracleOperators, OracleQuorum,
+	PriceIndex as PriceIndexEntry,
+};
 
 type Event = crate::Event<Test>;
 type Error = crate::Error<Test>;
@@ -22,7 +26,7 @@ fn should_require_an_operator_to_submit() {
 }
 
 #[test]
-fn can_set_an_operator() {
+fn can_set_oracle_operators() {
 	new_test_ext(None).execute_with(|| {
 		System::set_block_number(1);
 		assert_err!(
@@ -30,10 +34,95 @@ fn can_set_an_operator() {
 			Error::NotAuthorizedOperator
 		);
 
-		assert_ok!(PriceIndex::set_operator(RuntimeOrigin::root(), 1));
+		assert_err!(
+			PriceIndex::set_operators(RuntimeOrigin::root(), bounded_vec![1, 2], 3),
+			Error::InvalidOracleQuorum
+		);
+		assert_err!(
+			PriceIndex::set_operators(RuntimeOrigin::root(), bounded_vec![1, 2, 1], 2),
+			Error::DuplicateOracleOperator
+		);
+		assert_ok!(PriceIndex::set_operators(RuntimeOrigin::root(), bounded_vec![1, 2, 3], 2));
+
+		assert_eq!(OracleOperators::<Test>::get().to_vec(), vec![1, 2, 3]);
+		assert_eq!(OracleQuorum::<Test>::get(), 2);
+		System::assert_last_event(
+			Event::OracleOperatorsSet { operators: vec![1, 2, 3], quorum: 2 }.into(),
+		);
+	});
+}
+
+#[test]
+fn uses_the_median_once_a_quorum_submits() {
+	new_test_ext(None).execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(PriceIndex::set_operators(RuntimeOrigin::root(), bounded_vec![1, 2, 3, 4], 3));
+
+		let mut entry = create_index();
+		entry.tick = 1;
+		let with_prices = |btc: f64, argon: f64| {
+			let mut index = entry;
+			index.btc_usd_price = FixedU128::from_float(btc);
+			index.argon_usd_price = FixedU128::from_float(argon);
+			index
+		};
+		assert_ok!(PriceIndex::submit(RuntimeOrigin::signed(1), with_prices(62_000.0, 1.0)));
+		assert_ok!(PriceIndex::submit(RuntimeOrigin::signed(2), with_prices(61_000.0, 1.01)));
+		assert_eq!(Current::<Test>::get(), None);
 
-		assert_eq!(Operator::<Test>::get(), Some(1));
-		System::assert_last_event(Event::OperatorChanged { operator_id: 1 }.into());
+		// an outlier can't move the median, and is flagged
+		assert_ok!(PriceIndex::submit(RuntimeOrigin::signed(3), with_prices(80_000.0, 1.005)));
+		assert_eq!(Current::<Test>::get(), Some(with_prices(62_000.0, 1.005)));
+		assert_eq!(OracleOperatorDeviations::<Test>::get(3), 1);
+		assert_eq!(OracleOperatorDeviations::<Test>::get(1), 0);
+		System::assert_has_event(Event::OracleOperatorDeviated { operator: 3, tick: 1 }.into());
+		System::assert_last_event(Event::NewIndex.into());
+
+		// a late submission is checked, but doesn't change the current index
+		System::reset_events();
+		assert_ok!(PriceIndex::submit(RuntimeOrigin::signed(4), with_prices(50_000.0, 1.0)));
+		assert_eq!(Current::<Test>::get(), Some(with_prices(62_000.0, 1.005)));
+		assert_eq!(OracleOperatorDeviations::<Test>::get(4), 1);
+		System::assert_last_event(Event::OracleOperatorDeviated { operator: 4, tick: 1 }.into());
+
+		// an even number of submissions uses the average of the middle prices
+		let submissions = [62_000, 62_100, 62_300, 62_400].map(|btc| {
+			let mut index = entry;
+			index.btc_usd_price = FixedU128::saturating_from_integer(btc);
+			index
+		});
+		assert_eq!(
+			PriceIndex::median(&submissions).btc_usd_price,
+			FixedU128::saturating_from_integer(62_200)
+		);
+	});
+}
+
+#[test]
+fn removes_stale_operators_down_to_the_quorum() {
+	new_test_ext(None).execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(PriceIndex::set_operators(RuntimeOrigin::root(), bounded_vec![1, 2, 3], 2));
+
+		CurrentTick::set(30);
+		let mut entry = create_index();
+		entry.tick = 30;
+		assert_ok!(PriceIndex::submit(RuntimeOrigin::signed(1), entry));
+		assert_ok!(PriceIndex::submit(RuntimeOrigin::signed(2), entry));
+		assert!(Current::<Test>::get().is_some());
+
+		CurrentTick::set(MaxDowntimeBeforeReset::get() + 1);
+		PriceIndex::on_finalize(1);
+		assert_eq!(OracleOperators::<Test>::get().to_vec(), vec![1, 2]);
+		System::assert_last_event(
+			Event::StaleOracleOperatorRemoved { operator: 3, last_active_tick: 0 }.into(),
+		);
+
+		// operators are kept once there are only enough left for the quorum
+		CurrentTick::set(30 + MaxDowntimeBeforeReset::get() + 1);
+		PriceIndex::on_finalize(2);
+		assert_eq!(OracleOperators::<Test>::get().to_vec(), vec![1, 2]);
+		assert_eq!(Current::<Test>::get(), None);
 	});
 }
 
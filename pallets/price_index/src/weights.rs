@@ -41,6 +41,7 @@ pub trait WeightInfo {
 	fn submit(u: u32, ) -> Weight;
 	fn insert_oracle_operator() -> Weight;
 	fn remove_oracle_operator() -> Weight;
+	fn set_operators(u: u32, ) -> Weight;
 	fn on_finalize() -> Weight;
 }
 
@@ -120,6 +121,18 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 		Weight::from_parts(21_636_000, 1021)
 			.saturating_add(T::DbWeight::get().writes(1_u64))
 	}
+	/// Storage: PriceIndex OracleOperators (r:1 w:1)
+	/// Storage: PriceIndex OracleQuorum (r:0 w:1)
+	/// Storage: PriceIndex OracleLastActiveTick (r:u w:2u)
+	/// Storage: PriceIndex OracleSubmissions (r:0 w:u)
+	fn set_operators(u: u32, ) -> Weight {
+		Weight::from_parts(21_512_000, 1021)
+			.saturating_add(Weight::from_parts(2_000_000, 0).saturating_mul(u.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(u.into())))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+			.saturating_add(T::DbWeight::get().writes((3_u64).saturating_mul(u.into())))
+	}
 	fn on_finalize() -> Weight {
 		Weight::from_parts(3_000_000, 0)
 			.saturating_add(RocksDbWeight::get().writes(1_u64))
@@ -201,6 +214,18 @@ impl WeightInfo for () {
 		Weight::from_parts(21_636_000, 1021)
 			.saturating_add(RocksDbWeight::get().writes(1_u64))
 	}
+	/// Storage: PriceIndex OracleOperators (r:1 w:1)
+	/// Storage: PriceIndex OracleQuorum (r:0 w:1)
+	/// Storage: PriceIndex OracleLastActiveTick (r:u w:2u)
+	/// Storage: PriceIndex OracleSubmissions (r:0 w:u)
+	fn set_operators(u: u32, ) -> Weight {
+		Weight::from_parts(21_512_000, 1021)
+			.saturating_add(Weight::from_parts(2_000_000, 0).saturating_mul(u.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(u.into())))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+			.saturating_add(RocksDbWeight::get().writes((3_u64).saturating_mul(u.into())))
+	}
 	fn on_finalize() -> Weight {
 		Weight::from_parts(3_000_000, 0)
 			.saturating_add(RocksDbWeight::get().writes(1_u64))
@@ -10,6 +10,7 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+pub mod migrations;
 pub mod weights;
 
 /// The vaults pallet allows a user to offer argons for lease to other users. There are two types of
@@ -23,9 +24,22 @@ pub mod weights;
 /// bitcoin argons. This allows a vault to issue more mining bonds, but the funds are locked up for
 /// the duration of the bitcoin bonds, and will be taken in the case of bitcoins not being cosiged
 /// on unlock.
+///
+/// ** Ownership Token Securitization **
+///
+/// A vault operator may also stake ownership tokens as additional securitization. These are the
+/// last recourse when a vault fails to cosign a bitcoin unlock, and are valued at the
+/// `OwnershipTokenArgonPrice`. Staked tokens can only be withdrawn once no bitcoin bonds remain.
+///
+/// ** Operator Roles **
+///
+/// The operator account holds the vault funds, and can assign parts of running the vault to other
+/// accounts: a funding manager, a terms manager, a set of cosigners and an emergency closer. The
+/// operator keeps every role, and is the only account that can replace the bitcoin xpub, stake
+/// ownership tokens or assign roles.
 #[frame_support::pallet(dev_mode)]
 pub mod pallet {
-	use alloc::vec;
+	use alloc::{vec, vec::Vec};
 	use core::fmt::Debug;
 
 	use codec::Codec;
@@ -50,14 +64,21 @@ pub mod pallet {
 	use argon_bitcoin::{CosignScript, CosignScriptArgs};
 	use argon_primitives::{
 		bitcoin::{
-			BitcoinCosignScriptPubkey, BitcoinHeight, BitcoinNetwork, BitcoinXPub,
-			CompressedBitcoinPubkey, OpaqueBitcoinXpub, UtxoId,
+			BitcoinCosignScriptPubkey, BitcoinCosignScriptType, BitcoinHeight, BitcoinNetwork,
+			BitcoinVaultMultisig, BitcoinXPub, CompressedBitcoinPubkey, OpaqueBitcoinXpub, UtxoId,
+			VaultMultisigPubkeys,
+		},
+		bond::{
+			Bond, BondError, BondType, Vault, VaultArgons, VaultProvider, VaultReputation,
+			VaultRole, VaultTerms,
 		},
-		bond::{Bond, BondError, BondType, Vault, VaultArgons, VaultProvider, VaultTerms},
 		MiningSlotProvider, VaultId,
 	};
 
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	/// Configure the pallet by specifying the parameters and types on which it depends.
@@ -95,6 +116,10 @@ pub mod pallet {
 		#[pallet::constant]
 		type MaxPendingTermModificationsPerBlock: Get<u32>;
 
+		/// The max accounts that can be assigned the cosigner role of a vault
+		#[pallet::constant]
+		type MaxVaultCosigners: Get<u32>;
+
 		/// The number of blocks that a change in terms will take before applying. Terms only apply
 		/// on a slot changeover, so this setting is the minimum blocks that must pass, in
 		/// addition to the time to the next slot after that
@@ -106,6 +131,15 @@ pub mod pallet {
 
 		/// Provides the bitcoin network this blockchain is connected to
 		type GetBitcoinNetwork: Get<BitcoinNetwork>;
+
+		/// The currency representing ownership in the network. Vaults can stake these tokens as
+		/// additional securitization
+		type OwnershipCurrency: MutateHold<Self::AccountId, Reason = Self::RuntimeHoldReason, Balance = Self::Balance>
+			+ Inspect<Self::AccountId, Balance = Self::Balance>;
+
+		/// The price of a single ownership token in argons. Staked ownership tokens are not used
+		/// to compensate lost bitcoins if no price is available
+		type OwnershipTokenArgonPrice: Get<Option<FixedU128>>;
 	}
 
 	/// A reason for the pallet placing a hold on funds.
@@ -113,6 +147,7 @@ pub mod pallet {
 	pub enum HoldReason {
 		EnterVault,
 		BondFee,
+		OwnershipSecuritization,
 	}
 
 	#[pallet::storage]
@@ -133,6 +168,37 @@ pub mod pallet {
 	pub(super) type VaultXPubById<T: Config> =
 		StorageMap<_, Twox64Concat, VaultId, (BitcoinXPub, u32), OptionQuery>;
 
+	/// The cosigner xpubs of a multisig vault by VaultId. Cosigner pubkeys are derived at the same
+	/// child numbers as the vault xpub.
+	#[pallet::storage]
+	pub(super) type VaultMultisigById<T: Config> =
+		StorageMap<_, Twox64Concat, VaultId, BitcoinVaultMultisig<BitcoinXPub>, OptionQuery>;
+
+	/// How reliably each vault has performed its bond duties
+	#[pallet::storage]
+	pub(super) type VaultReputationById<T: Config> =
+		StorageMap<_, Twox64Concat, VaultId, VaultReputation<T::Balance>, ValueQuery>;
+
+	/// Ownership tokens staked by the vault operator as additional securitization
+	#[pallet::storage]
+	pub(super) type OwnershipTokensStakedByVaultId<T: Config> =
+		StorageMap<_, Twox64Concat, VaultId, T::Balance, ValueQuery>;
+
+	/// The accounts the operator of a vault has assigned roles to
+	#[pallet::storage]
+	pub(super) type VaultRolesById<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		VaultId,
+		VaultRoles<T::AccountId, T::MaxVaultCosigners>,
+		OptionQuery,
+	>;
+
+	/// The vault a closed vault has offered its bitcoin bonds to, by the closed VaultId
+	#[pallet::storage]
+	pub(super) type BondMigrationsByVaultId<T: Config> =
+		StorageMap<_, Twox64Concat, VaultId, BondMigration, OptionQuery>;
+
 	/// Pending terms that will be committed at the given block number (must be a minimum of 1 slot
 	/// change away)
 	#[pallet::storage]
@@ -176,6 +242,44 @@ pub mod pallet {
 		VaultBitcoinXpubChange {
 			vault_id: VaultId,
 		},
+		/// A closed vault offered its bitcoin bonds to another vault
+		VaultBondMigrationOffered {
+			vault_id: VaultId,
+			to_vault_id: VaultId,
+		},
+		/// A vault accepted the bitcoin bonds of a closed vault
+		VaultBondMigrationAccepted {
+			vault_id: VaultId,
+			to_vault_id: VaultId,
+		},
+		VaultRolesChanged {
+			vault_id: VaultId,
+		},
+		VaultOwnershipTokensStaked {
+			vault_id: VaultId,
+			amount: T::Balance,
+			total_staked: T::Balance,
+		},
+		VaultOwnershipTokensUnstaked {
+			vault_id: VaultId,
+			amount: T::Balance,
+			total_staked: T::Balance,
+		},
+		/// Argons were taken from the vault to compensate a bitcoin holder for a lost bitcoin
+		VaultArgonsSlashed {
+			vault_id: VaultId,
+			source: SlashedArgonsSource,
+			amount: T::Balance,
+			compensated_account_id: T::AccountId,
+		},
+		/// Staked ownership tokens were taken from the vault to compensate a bitcoin holder for a
+		/// lost bitcoin
+		VaultOwnershipTokensSlashed {
+			vault_id: VaultId,
+			ownership_tokens: T::Balance,
+			argon_value: T::Balance,
+			compensated_account_id: T::AccountId,
+		},
 	}
 
 	#[pallet::error]
@@ -216,6 +320,8 @@ pub mod pallet {
 		WrongXpubNetwork,
 		/// The XPub is unsafe to use in a public blockchain (aka, unhardened)
 		UnsafeXpubkey,
+		/// The multisig threshold can't be met by the vault xpubs, or an xpub is repeated
+		InvalidVaultMultisig,
 		/// Unable to derive xpubkey child
 		UnableToDeriveVaultXpubChild,
 		/// Bitcoin conversion to compressed pubkey failed
@@ -239,6 +345,18 @@ pub mod pallet {
 		UnableToGenerateVaultBitcoinPubkey,
 		/// Unable to decode vault bitcoin pubkey
 		UnableToDecodeVaultBitcoinPubkey,
+		/// The account does not have enough ownership tokens
+		InsufficientOwnershipTokens,
+		/// Staked ownership tokens cannot be withdrawn while bitcoins are bonded
+		OwnershipTokensStillSecuritizing,
+		/// A pricing curve must have a minimum rate at or below its maximum rate
+		InvalidPricingCurve,
+		/// Bonds can only be migrated away from a closed vault
+		VaultNotClosed,
+		/// Bonds must be migrated to a different vault
+		InvalidBondMigrationVault,
+		/// The vault has not offered its bonds to the given vault
+		BondMigrationNotOffered,
 	}
 
 	impl<T> From<BondError> for Error<T> {
@@ -268,6 +386,8 @@ pub mod pallet {
 					Error::<T>::UnableToGenerateVaultBitcoinPubkey,
 				BondError::UnableToDecodeVaultBitcoinPubkey =>
 					Error::<T>::UnableToDecodeVaultBitcoinPubkey,
+				BondError::InsufficientSatoshisBonded => Error::<T>::InsufficientSatoshisBonded,
+				BondError::NoBitcoinPricesAvailable => Error::<T>::NoBitcoinPricesAvailable,
 			}
 		}
 	}
@@ -292,6 +412,10 @@ pub mod pallet {
 		pub bitcoin_amount_allocated: Balance,
 		/// Bytes for a hardened XPub. Will be used to generate child public keys
 		pub bitcoin_xpubkey: OpaqueBitcoinXpub,
+		/// Hardened cosigner XPubs to make the vault side of each cosign script a multisig (eg, a
+		/// 2-of-3 across the vault's own HSMs). Only P2WSH bonds can be created with a multisig
+		/// vault.
+		pub bitcoin_multisig: Option<BitcoinVaultMultisig<OpaqueBitcoinXpub>>,
 		/// The amount of argons to be vaulted for mining bonds
 		#[codec(compact)]
 		pub mining_amount_allocated: Balance,
@@ -300,6 +424,70 @@ pub mod pallet {
 		pub securitization_percent: FixedU128,
 	}
 
+	/// The vault funds used to compensate a lost bitcoin, in the order they are used
+	#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub enum SlashedArgonsSource {
+		/// Argons bonded to the lost bitcoin
+		Bonded,
+		/// Unbonded argons allocated to bitcoin bonds
+		Allocated,
+		/// Securitization argons
+		Securitized,
+	}
+
+	/// The accounts the operator of a vault has assigned roles to. The operator can always act in
+	/// every role, so a role that isn't assigned stays with the operator only.
+	#[derive(
+		Encode,
+		Decode,
+		CloneNoBound,
+		PartialEqNoBound,
+		EqNoBound,
+		RuntimeDebugNoBound,
+		TypeInfo,
+		MaxEncodedLen,
+		DefaultNoBound,
+	)]
+	#[scale_info(skip_type_params(MaxCosigners))]
+	pub struct VaultRoles<
+		AccountId: Codec + MaxEncodedLen + Clone + PartialEq + Eq + Debug,
+		MaxCosigners: Get<u32>,
+	> {
+		/// Can change the argons allocated to the vault, and hand its bonds to another vault
+		pub funding_manager: Option<AccountId>,
+		/// Can change the terms of the vault
+		pub terms_manager: Option<AccountId>,
+		/// Can submit the vault's cosignatures of bitcoin unlocks
+		pub cosigners: BoundedVec<AccountId, MaxCosigners>,
+		/// Can close the vault
+		pub emergency_closer: Option<AccountId>,
+	}
+
+	impl<
+			AccountId: Codec + MaxEncodedLen + Clone + PartialEq + Eq + Debug,
+			MaxCosigners: Get<u32>,
+		> VaultRoles<AccountId, MaxCosigners>
+	{
+		pub fn has_role(&self, role: VaultRole, account_id: &AccountId) -> bool {
+			match role {
+				VaultRole::FundingManager => self.funding_manager.as_ref() == Some(account_id),
+				VaultRole::TermsManager => self.terms_manager.as_ref() == Some(account_id),
+				VaultRole::Cosigner => self.cosigners.contains(account_id),
+				VaultRole::EmergencyCloser => self.emergency_closer.as_ref() == Some(account_id),
+			}
+		}
+	}
+
+	/// An offer by a closed vault to migrate its bitcoin bonds to another vault
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct BondMigration {
+		/// The vault that will take over the bonds
+		#[codec(compact)]
+		pub to_vault_id: VaultId,
+		/// Whether the operator of the receiving vault has accepted the bonds
+		pub is_accepted: bool,
+	}
+
 	#[pallet::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
 		fn on_initialize(_n: BlockNumberFor<T>) -> Weight {
@@ -316,8 +504,10 @@ pub mod pallet {
 						vault.bitcoin_argons.annual_percent_rate =
 							terms.bitcoin_annual_percent_rate;
 						vault.bitcoin_argons.base_fee = terms.bitcoin_base_fee;
+						vault.bitcoin_argons.pricing_curve = terms.bitcoin_pricing_curve;
 						vault.mining_argons.annual_percent_rate = terms.mining_annual_percent_rate;
 						vault.mining_argons.base_fee = terms.mining_base_fee;
+						vault.mining_argons.pricing_curve = terms.mining_pricing_curve;
 						vault.mining_reward_sharing_percent_take =
 							terms.mining_reward_sharing_percent_take;
 						Self::deposit_event(Event::VaultTermsChanged { vault_id });
@@ -341,12 +531,16 @@ pub mod pallet {
 				bitcoin_amount_allocated,
 				mining_amount_allocated,
 				bitcoin_xpubkey,
+				bitcoin_multisig,
 			} = vault_config;
+			Self::ensure_valid_pricing_curves(&terms)?;
 			let VaultTerms {
 				bitcoin_annual_percent_rate,
+				bitcoin_pricing_curve,
 				bitcoin_base_fee,
 				mining_base_fee,
 				mining_annual_percent_rate,
+				mining_pricing_curve,
 				mining_reward_sharing_percent_take,
 			} = terms;
 
@@ -355,18 +549,10 @@ pub mod pallet {
 				Error::<T>::InvalidVaultAmount
 			);
 
-			let xpub: BitcoinXPub = bitcoin_xpubkey.try_into().map_err(|e| {
-				log::error!("Unable to decode xpubkey: {:?}", e);
-				Error::<T>::InvalidXpubkey
-			})?;
-			ensure!(xpub.is_hardened(), Error::<T>::UnsafeXpubkey);
-			ensure!(
-				xpub.matches_network(T::GetBitcoinNetwork::get()),
-				Error::<T>::WrongXpubNetwork
-			);
-			// make sure we can derive
-			let _xpub =
-				xpub.derive_pubkey(0).map_err(|_| Error::<T>::UnableToDeriveVaultXpubChild)?;
+			let xpub = Self::decode_vault_xpub(bitcoin_xpubkey)?;
+			let multisig = bitcoin_multisig
+				.map(|multisig| Self::decode_vault_multisig(&xpub, multisig))
+				.transpose()?;
 
 			let vault_id = NextVaultId::<T>::get().unwrap_or(1);
 			let next_vault_id = vault_id.increment().ok_or(Error::<T>::NoMoreVaultIds)?;
@@ -379,12 +565,14 @@ pub mod pallet {
 					allocated: bitcoin_amount_allocated,
 					bonded: 0u32.into(),
 					base_fee: bitcoin_base_fee,
+					pricing_curve: bitcoin_pricing_curve,
 				},
 				mining_argons: VaultArgons {
 					annual_percent_rate: mining_annual_percent_rate,
 					allocated: mining_amount_allocated,
 					bonded: 0u32.into(),
 					base_fee: mining_base_fee,
+					pricing_curve: mining_pricing_curve,
 				},
 				mining_reward_sharing_percent_take,
 				securitization_percent,
@@ -394,6 +582,9 @@ pub mod pallet {
 			};
 			vault.securitized_argons = vault.get_minimum_securitization_needed();
 			VaultXPubById::<T>::insert(vault_id, (xpub, 0));
+			if let Some(multisig) = multisig {
+				VaultMultisigById::<T>::insert(vault_id, multisig);
+			}
 
 			Self::hold(
 				&who,
@@ -433,7 +624,7 @@ pub mod pallet {
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 			let mut vault = VaultsById::<T>::get(vault_id).ok_or(Error::<T>::VaultNotFound)?;
-			ensure!(vault.operator_account_id == who, Error::<T>::NoPermissions);
+			Self::ensure_vault_role(vault_id, &vault, VaultRole::FundingManager, &who)?;
 
 			let mut amount_to_hold: i128 = 0;
 			// NOTE: We're not changing the amount of bonded argons, so nothing needs to be checked
@@ -475,11 +666,15 @@ pub mod pallet {
 
 			#[allow(clippy::comparison_chain)]
 			if amount_to_hold > 0 {
-				Self::hold(&who, (amount_to_hold as u128).into(), HoldReason::EnterVault)
-					.map_err(Error::<T>::from)?;
+				Self::hold(
+					&vault.operator_account_id,
+					(amount_to_hold as u128).into(),
+					HoldReason::EnterVault,
+				)
+				.map_err(Error::<T>::from)?;
 			} else if amount_to_hold < 0 {
 				Self::release_hold(
-					&who,
+					&vault.operator_account_id,
 					amount_to_hold.unsigned_abs().into(),
 					HoldReason::EnterVault,
 				)?;
@@ -509,8 +704,9 @@ pub mod pallet {
 			let mut vault =
 				VaultsById::<T>::get(vault_id).ok_or::<Error<T>>(Error::<T>::VaultNotFound)?;
 
-			ensure!(vault.operator_account_id == who, Error::<T>::NoPermissions);
+			Self::ensure_vault_role(vault_id, &vault, VaultRole::TermsManager, &who)?;
 			ensure!(vault.pending_terms.is_none(), Error::<T>::TermsChangeAlreadyScheduled);
+			Self::ensure_valid_pricing_curves(&terms)?;
 
 			let block_number = frame_system::Pallet::<T>::block_number();
 			let mut terms_change_block = T::MiningSlotProvider::get_next_slot_block_number();
@@ -543,7 +739,8 @@ pub mod pallet {
 		}
 
 		/// Stop offering additional bonds from this vault. Will not affect existing bond.
-		/// As funds are returned, they will be released to the vault owner.
+		/// As funds are returned, they will be released to the vault owner. Existing bitcoin bonds
+		/// can be handed to another vault with `offer_bond_migration`.
 		#[pallet::call_index(3)]
 		#[pallet::weight(0)]
 		pub fn close(origin: OriginFor<T>, vault_id: VaultId) -> DispatchResult {
@@ -552,7 +749,7 @@ pub mod pallet {
 			let mut vault =
 				VaultsById::<T>::get(vault_id).ok_or::<Error<T>>(Error::<T>::VaultNotFound)?;
 
-			ensure!(vault.operator_account_id == who, Error::<T>::NoPermissions);
+			Self::ensure_vault_role(vault_id, &vault, VaultRole::EmergencyCloser, &who)?;
 
 			vault.is_closed = true;
 
@@ -564,12 +761,14 @@ pub mod pallet {
 				vault.mining_argons.free_balance() +
 				free_securitization;
 
+			let operator = &vault.operator_account_id;
 			ensure!(
-				T::Currency::balance_on_hold(&HoldReason::EnterVault.into(), &who) >= return_amount,
+				T::Currency::balance_on_hold(&HoldReason::EnterVault.into(), operator) >=
+					return_amount,
 				Error::<T>::HoldUnexpectedlyModified
 			);
 
-			Self::release_hold(&who, return_amount, HoldReason::EnterVault)?;
+			Self::release_hold(operator, return_amount, HoldReason::EnterVault)?;
 
 			vault.bitcoin_argons.allocated = vault.bitcoin_argons.bonded;
 			vault.mining_argons.allocated = vault.mining_argons.bonded;
@@ -587,12 +786,16 @@ pub mod pallet {
 
 		/// Replace the bitcoin xpubkey for this vault. This will not affect existing bonds, but
 		/// will be used for any bonds after this point. Will be rejected if already used.
+		///
+		/// The cosigner xpubs of a multisig vault are replaced too, so they must be provided again
+		/// to stay a multisig.
 		#[pallet::call_index(4)]
 		#[pallet::weight(0)]
 		pub fn replace_bitcoin_xpub(
 			origin: OriginFor<T>,
 			vault_id: VaultId,
 			bitcoin_xpub: OpaqueBitcoinXpub,
+			bitcoin_multisig: Option<BitcoinVaultMultisig<OpaqueBitcoinXpub>>,
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 
@@ -601,26 +804,262 @@ pub mod pallet {
 
 			ensure!(vault.operator_account_id == who, Error::<T>::NoPermissions);
 
-			let xpub =
-				BitcoinXPub::try_from(bitcoin_xpub).map_err(|_| Error::<T>::InvalidXpubkey)?;
+			let xpub = Self::decode_vault_xpub(bitcoin_xpub)?;
 			if let Some(existing) = VaultXPubById::<T>::get(vault_id) {
 				ensure!(existing.0 != xpub, Error::<T>::ReusedVaultBitcoinXpub);
 			}
+			let multisig = bitcoin_multisig
+				.map(|multisig| Self::decode_vault_multisig(&xpub, multisig))
+				.transpose()?;
+			VaultXPubById::<T>::insert(vault_id, (xpub, 0));
+			VaultMultisigById::<T>::set(vault_id, multisig);
+			Self::deposit_event(Event::VaultBitcoinXpubChange { vault_id });
+
+			Ok(())
+		}
+
+		/// Stake ownership tokens as additional securitization for this vault. They will be used
+		/// to compensate bitcoin holders after all vault argons if the vault fails to cosign an
+		/// unlock.
+		#[pallet::call_index(5)]
+		#[pallet::weight(T::WeightInfo::stake_ownership_tokens())]
+		pub fn stake_ownership_tokens(
+			origin: OriginFor<T>,
+			vault_id: VaultId,
+			amount: T::Balance,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let vault =
+				VaultsById::<T>::get(vault_id).ok_or::<Error<T>>(Error::<T>::VaultNotFound)?;
+			ensure!(vault.operator_account_id == who, Error::<T>::NoPermissions);
+			ensure!(!vault.is_closed, Error::<T>::VaultClosed);
+
+			if T::OwnershipCurrency::balance_on_hold(
+				&HoldReason::OwnershipSecuritization.into(),
+				&who,
+			) == T::Balance::zero()
+			{
+				frame_system::Pallet::<T>::inc_providers(&who);
+			}
+			T::OwnershipCurrency::hold(&HoldReason::OwnershipSecuritization.into(), &who, amount)
+				.map_err(|_| Error::<T>::InsufficientOwnershipTokens)?;
+
+			let total_staked = OwnershipTokensStakedByVaultId::<T>::mutate(vault_id, |a| {
+				*a = a.saturating_add(amount);
+				*a
+			});
+			Self::deposit_event(Event::VaultOwnershipTokensStaked {
+				vault_id,
+				amount,
+				total_staked,
+			});
+			Ok(())
+		}
+
+		/// Withdraw staked ownership tokens from this vault. Only allowed once the vault has no
+		/// bitcoins bonded.
+		#[pallet::call_index(6)]
+		#[pallet::weight(T::WeightInfo::unstake_ownership_tokens())]
+		pub fn unstake_ownership_tokens(
+			origin: OriginFor<T>,
+			vault_id: VaultId,
+			amount: T::Balance,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let vault =
+				VaultsById::<T>::get(vault_id).ok_or::<Error<T>>(Error::<T>::VaultNotFound)?;
+			ensure!(vault.operator_account_id == who, Error::<T>::NoPermissions);
+			ensure!(
+				vault.bitcoin_argons.bonded == T::Balance::zero(),
+				Error::<T>::OwnershipTokensStillSecuritizing
+			);
+
+			let total_staked = OwnershipTokensStakedByVaultId::<T>::get(vault_id)
+				.checked_sub(&amount)
+				.ok_or(Error::<T>::InsufficientOwnershipTokens)?;
+
+			Self::release_ownership_hold(&who, amount)?;
+			if total_staked == T::Balance::zero() {
+				OwnershipTokensStakedByVaultId::<T>::remove(vault_id);
+			} else {
+				OwnershipTokensStakedByVaultId::<T>::insert(vault_id, total_staked);
+			}
+			Self::deposit_event(Event::VaultOwnershipTokensUnstaked {
+				vault_id,
+				amount,
+				total_staked,
+			});
+			Ok(())
+		}
+
+		/// Offer the bitcoin bonds of a closed vault to another vault. Once the operator of that
+		/// vault accepts, each bond can be migrated by moving its bitcoin into a cosign script of
+		/// the receiving vault. The closed vault must cosign each move, and its funds and
+		/// securitization are released as each bond moves.
+		///
+		/// Offering the bonds to a different vault replaces any previous offer.
+		#[pallet::call_index(7)]
+		#[pallet::weight(T::WeightInfo::offer_bond_migration())]
+		pub fn offer_bond_migration(
+			origin: OriginFor<T>,
+			vault_id: VaultId,
+			to_vault_id: VaultId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let vault =
+				VaultsById::<T>::get(vault_id).ok_or::<Error<T>>(Error::<T>::VaultNotFound)?;
+			Self::ensure_vault_role(vault_id, &vault, VaultRole::FundingManager, &who)?;
+			ensure!(vault.is_closed, Error::<T>::VaultNotClosed);
+			ensure!(vault_id != to_vault_id, Error::<T>::InvalidBondMigrationVault);
+
+			let to_vault =
+				VaultsById::<T>::get(to_vault_id).ok_or::<Error<T>>(Error::<T>::VaultNotFound)?;
+			ensure!(!to_vault.is_closed, Error::<T>::VaultClosed);
+
+			BondMigrationsByVaultId::<T>::insert(
+				vault_id,
+				BondMigration { to_vault_id, is_accepted: false },
+			);
+			Self::deposit_event(Event::VaultBondMigrationOffered { vault_id, to_vault_id });
+			Ok(())
+		}
+
+		/// Accept the bitcoin bonds offered by a closed vault. The bonds will be added to the
+		/// bonded bitcoin argons of the accepting vault as they are migrated, so it must have
+		/// enough free bitcoin argons to take each one.
+		#[pallet::call_index(8)]
+		#[pallet::weight(T::WeightInfo::accept_bond_migration())]
+		pub fn accept_bond_migration(
+			origin: OriginFor<T>,
+			vault_id: VaultId,
+			to_vault_id: VaultId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let to_vault =
+				VaultsById::<T>::get(to_vault_id).ok_or::<Error<T>>(Error::<T>::VaultNotFound)?;
+			Self::ensure_vault_role(to_vault_id, &to_vault, VaultRole::FundingManager, &who)?;
+			ensure!(!to_vault.is_closed, Error::<T>::VaultClosed);
+
+			BondMigrationsByVaultId::<T>::try_mutate(vault_id, |a| {
+				let migration = a
+					.as_mut()
+					.filter(|x| x.to_vault_id == to_vault_id)
+					.ok_or(Error::<T>::BondMigrationNotOffered)?;
+				migration.is_accepted = true;
+				Ok::<(), Error<T>>(())
+			})?;
+			Self::deposit_event(Event::VaultBondMigrationAccepted { vault_id, to_vault_id });
+			Ok(())
+		}
+
+		/// Assign the roles of a vault to other accounts, replacing any previous assignments. This
+		/// lets the operator keep treasury control apart from the accounts that manage the vault
+		/// day to day (eg, a hot account that only cosigns bitcoin unlocks). Funds always stay
+		/// with the operator account. Only the operator can assign roles.
+		#[pallet::call_index(9)]
+		#[pallet::weight(T::WeightInfo::set_vault_roles(T::MaxVaultCosigners::get()))]
+		pub fn set_vault_roles(
+			origin: OriginFor<T>,
+			vault_id: VaultId,
+			roles: VaultRoles<T::AccountId, T::MaxVaultCosigners>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let vault =
+				VaultsById::<T>::get(vault_id).ok_or::<Error<T>>(Error::<T>::VaultNotFound)?;
+			ensure!(vault.operator_account_id == who, Error::<T>::NoPermissions);
+
+			if roles == VaultRoles::default() {
+				VaultRolesById::<T>::remove(vault_id);
+			} else {
+				VaultRolesById::<T>::insert(vault_id, roles);
+			}
+			Self::deposit_event(Event::VaultRolesChanged { vault_id });
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		fn ensure_vault_role(
+			vault_id: VaultId,
+			vault: &Vault<T::AccountId, T::Balance, BlockNumberFor<T>>,
+			role: VaultRole,
+			who: &T::AccountId,
+		) -> Result<(), Error<T>> {
+			ensure!(Self::has_role(vault_id, vault, role, who), Error::<T>::NoPermissions);
+			Ok(())
+		}
+
+		/// The operator holds every role of a vault
+		fn has_role(
+			vault_id: VaultId,
+			vault: &Vault<T::AccountId, T::Balance, BlockNumberFor<T>>,
+			role: VaultRole,
+			who: &T::AccountId,
+		) -> bool {
+			vault.operator_account_id == *who ||
+				VaultRolesById::<T>::get(vault_id).is_some_and(|a| a.has_role(role, who))
+		}
+
+		fn ensure_valid_pricing_curves(terms: &VaultTerms<T::Balance>) -> Result<(), Error<T>> {
+			for curve in [terms.bitcoin_pricing_curve, terms.mining_pricing_curve].iter().flatten()
+			{
+				ensure!(curve.is_valid(), Error::<T>::InvalidPricingCurve);
+			}
+			Ok(())
+		}
+
+		/// The reputation of a vault, or None if the vault doesn't exist
+		pub fn vault_reputation(vault_id: VaultId) -> Option<VaultReputation<T::Balance>> {
+			if !VaultsById::<T>::contains_key(vault_id) {
+				return None;
+			}
+			Some(VaultReputationById::<T>::get(vault_id))
+		}
+
+		/// Decodes a vault xpub and checks it is safe to derive bond pubkeys from
+		fn decode_vault_xpub(xpub: OpaqueBitcoinXpub) -> Result<BitcoinXPub, Error<T>> {
+			let xpub: BitcoinXPub = xpub.try_into().map_err(|e| {
+				log::error!("Unable to decode xpubkey: {:?}", e);
+				Error::<T>::InvalidXpubkey
+			})?;
 			ensure!(xpub.is_hardened(), Error::<T>::UnsafeXpubkey);
 			ensure!(
 				xpub.matches_network(T::GetBitcoinNetwork::get()),
 				Error::<T>::WrongXpubNetwork
 			);
-			let _try_derive =
+			// make sure we can derive
+			let _xpub =
 				xpub.derive_pubkey(0).map_err(|_| Error::<T>::UnableToDeriveVaultXpubChild)?;
-			VaultXPubById::<T>::insert(vault_id, (xpub, 0));
-			Self::deposit_event(Event::VaultBitcoinXpubChange { vault_id });
+			Ok(xpub)
+		}
 
-			Ok(())
+		/// Decodes the cosigner xpubs of a multisig vault. The threshold must be met by the vault
+		/// xpub and the cosigners, and each xpub must be distinct.
+		fn decode_vault_multisig(
+			xpub: &BitcoinXPub,
+			multisig: BitcoinVaultMultisig<OpaqueBitcoinXpub>,
+		) -> Result<BitcoinVaultMultisig<BitcoinXPub>, Error<T>> {
+			let BitcoinVaultMultisig { threshold, cosigner_xpubs } = multisig;
+			ensure!(
+				!cosigner_xpubs.is_empty() &&
+					threshold > 0 && threshold as usize <= cosigner_xpubs.len() + 1,
+				Error::<T>::InvalidVaultMultisig
+			);
+			let mut decoded = BoundedVec::new();
+			for cosigner_xpub in cosigner_xpubs {
+				let cosigner_xpub = Self::decode_vault_xpub(cosigner_xpub)?;
+				ensure!(
+					cosigner_xpub.public_key != xpub.public_key &&
+						!decoded
+							.iter()
+							.any(|a: &BitcoinXPub| a.public_key == cosigner_xpub.public_key),
+					Error::<T>::InvalidVaultMultisig
+				);
+				decoded.try_push(cosigner_xpub).map_err(|_| Error::<T>::InvalidVaultMultisig)?;
+			}
+			Ok(BitcoinVaultMultisig { threshold, cosigner_xpubs: decoded })
 		}
-	}
 
-	impl<T: Config> Pallet<T> {
 		fn hold(
 			who: &T::AccountId,
 			amount: T::Balance,
@@ -665,6 +1104,152 @@ pub mod pallet {
 			T::Currency::release(&reason.into(), who, amount, Precision::Exact)
 		}
 
+		fn release_ownership_hold(who: &T::AccountId, amount: T::Balance) -> DispatchResult {
+			if amount == T::Balance::zero() {
+				return Ok(());
+			}
+			let reason = HoldReason::OwnershipSecuritization.into();
+			T::OwnershipCurrency::release(&reason, who, amount, Precision::Exact)?;
+			if T::OwnershipCurrency::balance_on_hold(&reason, who) == T::Balance::zero() {
+				let _ = frame_system::Pallet::<T>::dec_providers(who);
+			}
+			Ok(())
+		}
+
+		/// Transfers staked ownership tokens to the compensated account. Returns the ownership
+		/// tokens taken and their argon value
+		fn slash_ownership_tokens(
+			vault_id: VaultId,
+			vault_operator: &T::AccountId,
+			compensated_account_id: &T::AccountId,
+			argons_owed: T::Balance,
+		) -> Result<(T::Balance, T::Balance), BondError> {
+			let zero = T::Balance::zero();
+			let staked = OwnershipTokensStakedByVaultId::<T>::get(vault_id);
+			let Some(price) = T::OwnershipTokenArgonPrice::get() else {
+				return Ok((zero, zero));
+			};
+			if staked == zero || price.is_zero() {
+				return Ok((zero, zero));
+			}
+
+			let owed = FixedU128::saturating_from_integer(argons_owed);
+			let tokens_owed: T::Balance =
+				(owed.checked_div(&price).ok_or(BondError::InternalError)?.ceil().into_inner() /
+					FixedU128::accuracy())
+				.unique_saturated_into();
+			let ownership_tokens = tokens_owed.min(staked);
+			let argon_value = price.saturating_mul_int(ownership_tokens).min(argons_owed);
+
+			T::OwnershipCurrency::transfer_on_hold(
+				&HoldReason::OwnershipSecuritization.into(),
+				vault_operator,
+				compensated_account_id,
+				ownership_tokens,
+				Precision::Exact,
+				Restriction::Free,
+				Fortitude::Force,
+			)
+			.map_err(|_| BondError::UnrecoverableHold)?;
+			if T::OwnershipCurrency::balance_on_hold(
+				&HoldReason::OwnershipSecuritization.into(),
+				vault_operator,
+			) == zero
+			{
+				let _ = frame_system::Pallet::<T>::dec_providers(vault_operator);
+			}
+
+			let remaining = staked.saturating_sub(ownership_tokens);
+			if remaining == zero {
+				OwnershipTokensStakedByVaultId::<T>::remove(vault_id);
+			} else {
+				OwnershipTokensStakedByVaultId::<T>::insert(vault_id, remaining);
+			}
+			Ok((ownership_tokens, argon_value))
+		}
+
+		/// Checks a bond of the given amount can be issued by the vault, and returns the (total
+		/// fee, base fee, annual percent rate) it will be charged
+		fn calculate_bond_fees(
+			vault: &Vault<T::AccountId, T::Balance, BlockNumberFor<T>>,
+			amount: T::Balance,
+			bond_type: &BondType,
+			blocks: BlockNumberFor<T>,
+		) -> Result<(T::Balance, T::Balance, FixedU128), BondError> {
+			ensure!(amount >= T::MinimumBondAmount::get(), BondError::MinimumBondAmountNotMet);
+			ensure!(!vault.is_closed, BondError::VaultClosed);
+
+			let available = match bond_type {
+				BondType::Bitcoin => vault.bitcoin_argons.free_balance(),
+				BondType::Mining => vault.amount_eligible_for_mining(),
+			};
+			ensure!(available >= amount, BondError::InsufficientVaultFunds);
+
+			let vault_argons = vault.argons(bond_type);
+			let apr = vault_argons.annual_percent_rate_for(amount);
+			let base_fee = vault_argons.base_fee;
+
+			let fee = Self::calculate_block_fees(apr, amount, blocks).saturating_add(base_fee);
+			ensure!(fee <= amount, BondError::FeeExceedsBondAmount);
+			Ok((fee, base_fee, apr))
+		}
+
+		/// Removes the bond from the bonded funds of the vault. A closed vault releases the funds,
+		/// and any securitization it no longer needs, to the operator.
+		fn remove_bonded_funds(
+			vault: &mut Vault<T::AccountId, T::Balance, BlockNumberFor<T>>,
+			bond_type: &BondType,
+			amount: T::Balance,
+		) -> Result<(), BondError> {
+			vault.mut_argons(bond_type).reduce_bonded(amount);
+
+			// after reducing the bonded, we can check the minimum securitization needed
+			let minimum_securitization = vault.get_minimum_securitization_needed();
+			if vault.is_closed {
+				let free_securitization =
+					vault.securitized_argons.saturating_sub(minimum_securitization);
+
+				Self::release_hold(
+					&vault.operator_account_id,
+					amount.saturating_add(free_securitization),
+					HoldReason::EnterVault,
+				)
+				.map_err(|_| BondError::UnrecoverableHold)?;
+
+				vault.securitized_argons = minimum_securitization;
+				vault.mut_argons(bond_type).reduce_allocated(amount);
+			}
+			Ok(())
+		}
+
+		/// Pays the vault operator the fee the given amount of a bond has earned since the bond
+		/// started out of the fee on hold. Returns the fee paid.
+		fn pay_earned_bond_fee(
+			vault: &Vault<T::AccountId, T::Balance, BlockNumberFor<T>>,
+			bond: &Bond<T::AccountId, T::Balance, BlockNumberFor<T>>,
+			amount: T::Balance,
+		) -> Result<T::Balance, BondError> {
+			let current_block = frame_system::Pallet::<T>::block_number();
+			let blocks = current_block.saturating_sub(bond.start_block);
+			// the fee is earned at the rate the bond was charged, up to what is still held
+			let amount_on_hold = bond.total_fee.saturating_sub(bond.prepaid_fee);
+			let earned_fee = Self::calculate_block_fees(bond.annual_percent_rate, amount, blocks)
+				.min(amount_on_hold);
+			if earned_fee > 0u128.into() {
+				T::Currency::transfer_on_hold(
+					&HoldReason::BondFee.into(),
+					&bond.bonded_account_id,
+					&vault.operator_account_id,
+					earned_fee,
+					Precision::Exact,
+					Restriction::Free,
+					Fortitude::Force,
+				)
+				.map_err(|_| BondError::UnrecoverableHold)?;
+			}
+			Ok(earned_fee)
+		}
+
 		pub(crate) fn calculate_block_fees(
 			annual_percentage_rate: FixedU128,
 			amount: T::Balance,
@@ -699,41 +1284,23 @@ pub mod pallet {
 			VaultsById::<T>::get(vault_id)
 		}
 
+		fn has_vault_role(vault_id: VaultId, role: VaultRole, account_id: &T::AccountId) -> bool {
+			VaultsById::<T>::get(vault_id)
+				.is_some_and(|vault| Self::has_role(vault_id, &vault, role, account_id))
+		}
+
 		fn bond_funds(
 			vault_id: VaultId,
 			amount: Self::Balance,
 			bond_type: BondType,
 			blocks: Self::BlockNumber,
 			bond_account_id: &Self::AccountId,
-		) -> Result<(Self::Balance, Self::Balance), BondError> {
-			ensure!(amount >= T::MinimumBondAmount::get(), BondError::MinimumBondAmountNotMet);
+		) -> Result<(Self::Balance, Self::Balance, FixedU128), BondError> {
 			let mut vault =
 				VaultsById::<T>::get(vault_id).ok_or::<BondError>(BondError::VaultNotFound)?;
 
-			ensure!(!vault.is_closed, BondError::VaultClosed);
-
-			let vault_argons = match bond_type {
-				BondType::Bitcoin => {
-					ensure!(
-						vault.bitcoin_argons.free_balance() >= amount,
-						BondError::InsufficientVaultFunds
-					);
-					&mut vault.bitcoin_argons
-				},
-				BondType::Mining => {
-					ensure!(
-						vault.amount_eligible_for_mining() >= amount,
-						BondError::InsufficientVaultFunds
-					);
-					&mut vault.mining_argons
-				},
-			};
-
-			let apr = vault_argons.annual_percent_rate;
-			let base_fee = vault_argons.base_fee;
-
-			let fee = Self::calculate_block_fees(apr, amount, blocks).saturating_add(base_fee);
-			ensure!(fee <= amount, BondError::FeeExceedsBondAmount);
+			let (fee, base_fee, apr) =
+				Self::calculate_bond_fees(&vault, amount, &bond_type, blocks)?;
 
 			T::Currency::transfer(
 				bond_account_id,
@@ -750,9 +1317,42 @@ pub mod pallet {
 				Self::hold(bond_account_id, fee - base_fee, HoldReason::BondFee)?;
 			}
 
+			let vault_argons = vault.mut_argons(&bond_type);
 			vault_argons.bonded = vault_argons.bonded.saturating_add(amount);
 			VaultsById::<T>::set(vault_id, Some(vault));
+			VaultReputationById::<T>::mutate(vault_id, |a| {
+				a.bonds_created.saturating_inc();
+				a.total_bonded_argons = a.total_bonded_argons.saturating_add(amount);
+			});
+
+			Ok((fee, base_fee, apr))
+		}
 
+		fn quote_bond_funds(
+			vault_id: VaultId,
+			amount: Self::Balance,
+			bond_type: BondType,
+			blocks: Self::BlockNumber,
+		) -> Result<(Self::Balance, Self::Balance), BondError> {
+			let vault =
+				VaultsById::<T>::get(vault_id).ok_or::<BondError>(BondError::VaultNotFound)?;
+			let (fee, base_fee, _) = Self::calculate_bond_fees(&vault, amount, &bond_type, blocks)?;
+			Ok((fee, base_fee))
+		}
+
+		fn quote_bond_renewal(
+			bond: &Bond<T::AccountId, T::Balance, BlockNumberFor<T>>,
+			vault_id: VaultId,
+			blocks: BlockNumberFor<T>,
+		) -> Result<(Self::Balance, Self::Balance), BondError> {
+			let mut vault =
+				VaultsById::<T>::get(vault_id).ok_or::<BondError>(BondError::VaultNotFound)?;
+			// the current bond funds are released before the renewal is bonded
+			if bond.vault_id == vault_id {
+				vault.mut_argons(&bond.bond_type).reduce_bonded(bond.amount);
+			}
+			let (fee, base_fee, _) =
+				Self::calculate_bond_fees(&vault, bond.amount, &bond.bond_type, blocks)?;
 			Ok((fee, base_fee))
 		}
 
@@ -775,6 +1375,9 @@ pub mod pallet {
 			.map_err(|_| BondError::UnrecoverableHold)?;
 
 			VaultsById::<T>::insert(vault_id, vault);
+			VaultReputationById::<T>::mutate(vault_id, |a| {
+				a.burned_argons = a.burned_argons.saturating_add(amount_to_burn);
+			});
 
 			Ok(())
 		}
@@ -786,7 +1389,7 @@ pub mod pallet {
 		/// 1. From the bonded funds
 		/// 2. From the allocated funds
 		/// 3. From the securitized funds
-		/// 4. TODO: From the ownership tokens
+		/// 4. From the staked ownership tokens (at the `OwnershipTokenArgonPrice`)
 		///
 		/// The funds will be returned to the owed_to_account_id
 		///
@@ -808,11 +1411,15 @@ pub mod pallet {
 				Self::release_hold(bonded_account_id, remaining_fee, HoldReason::BondFee)
 					.map_err(|_| BondError::UnrecoverableHold)?;
 			}
+			let zero = T::Balance::zero();
+			let mut slashed = vec![];
+
 			// 1. take away from the vault first
-			vault.bitcoin_argons.destroy_bond_funds(bonded_amount.min(market_rate))?;
+			let amount_to_pull = bonded_amount.min(market_rate);
+			vault.bitcoin_argons.destroy_bond_funds(amount_to_pull)?;
+			slashed.push((SlashedArgonsSource::Bonded, amount_to_pull));
 
 			let mut still_owed = market_rate.saturating_sub(bonded_amount);
-			let zero = T::Balance::zero();
 
 			// 2: use bitcoin argons
 			if still_owed > zero && vault.bitcoin_argons.free_balance() >= zero {
@@ -820,6 +1427,7 @@ pub mod pallet {
 				vault.bitcoin_argons.destroy_allocated_funds(amount_to_pull)?;
 				still_owed =
 					still_owed.checked_sub(&amount_to_pull).ok_or(BondError::InternalError)?;
+				slashed.push((SlashedArgonsSource::Allocated, amount_to_pull));
 			}
 
 			// 3. Use securitized argons
@@ -831,77 +1439,72 @@ pub mod pallet {
 					.ok_or(BondError::InternalError)?;
 				still_owed =
 					still_owed.checked_sub(&amount_to_pull).ok_or(BondError::InternalError)?;
+				slashed.push((SlashedArgonsSource::Securitized, amount_to_pull));
 			}
 
-			// 3. Use ownership tokens at current value
-			// TODO
-
-			let recouped = market_rate.saturating_sub(still_owed);
+			let argons_recouped = market_rate.saturating_sub(still_owed);
 			T::Currency::transfer_on_hold(
 				&HoldReason::EnterVault.into(),
 				&vault_operator,
 				bonded_account_id,
-				recouped,
+				argons_recouped,
 				Precision::Exact,
 				Restriction::Free,
 				Fortitude::Force,
 			)
 			.map_err(|_| BondError::UnrecoverableHold)?;
 
+			for (source, amount) in slashed {
+				if amount > zero {
+					Self::deposit_event(Event::VaultArgonsSlashed {
+						vault_id,
+						source,
+						amount,
+						compensated_account_id: bonded_account_id.clone(),
+					});
+				}
+			}
+
+			// 4. Use ownership tokens at current value
+			if still_owed > zero {
+				let (ownership_tokens, argon_value) = Self::slash_ownership_tokens(
+					vault_id,
+					&vault_operator,
+					bonded_account_id,
+					still_owed,
+				)?;
+				if ownership_tokens > zero {
+					still_owed = still_owed.saturating_sub(argon_value);
+					Self::deposit_event(Event::VaultOwnershipTokensSlashed {
+						vault_id,
+						ownership_tokens,
+						argon_value,
+						compensated_account_id: bonded_account_id.clone(),
+					});
+				}
+			}
+
 			VaultsById::<T>::insert(vault_id, vault);
 
-			Ok(recouped)
+			let compensated = market_rate.saturating_sub(still_owed);
+			VaultReputationById::<T>::mutate(vault_id, |a| {
+				a.compensated_losses.saturating_inc();
+				a.compensated_argons = a.compensated_argons.saturating_add(compensated);
+			});
+
+			Ok(compensated)
 		}
 
 		fn release_bonded_funds(
 			bond: &Bond<T::AccountId, T::Balance, BlockNumberFor<T>>,
 		) -> Result<T::Balance, BondError> {
 			let vault_id = bond.vault_id;
-			let vault = {
-				let mut vault = VaultsById::<T>::get(vault_id).ok_or(BondError::VaultNotFound)?;
-				vault.mut_argons(&bond.bond_type).reduce_bonded(bond.amount);
-				vault
-			};
-
-			// after reducing the bonded, we can check the minimum securitization needed (can't be
-			// mut)
-			let minimum_securitization = vault.get_minimum_securitization_needed();
-			// working around borrow checker
-			let mut vault = vault;
-			if vault.is_closed {
-				let free_securitization =
-					vault.securitized_argons.saturating_sub(minimum_securitization);
-
-				Self::release_hold(
-					&vault.operator_account_id,
-					bond.amount.saturating_add(free_securitization),
-					HoldReason::EnterVault,
-				)
-				.map_err(|_| BondError::UnrecoverableHold)?;
-
-				vault.securitized_argons = minimum_securitization;
-				vault.mut_argons(&bond.bond_type).reduce_allocated(bond.amount);
-			}
-
-			let apr = vault.argons(&bond.bond_type).annual_percent_rate;
+			let mut vault = VaultsById::<T>::get(vault_id).ok_or(BondError::VaultNotFound)?;
+			Self::remove_bonded_funds(&mut vault, &bond.bond_type, bond.amount)?;
 
-			let current_block = frame_system::Pallet::<T>::block_number();
-			let blocks = current_block.saturating_sub(bond.start_block);
-			let remaining_fee = Self::calculate_block_fees(apr, bond.amount, blocks);
-			if remaining_fee > 0u128.into() {
-				T::Currency::transfer_on_hold(
-					&HoldReason::BondFee.into(),
-					&bond.bonded_account_id,
-					&vault.operator_account_id,
-					remaining_fee,
-					Precision::Exact,
-					Restriction::Free,
-					Fortitude::Force,
-				)
-				.map_err(|_| BondError::UnrecoverableHold)?;
-			}
+			let earned_fee = Self::pay_earned_bond_fee(&vault, bond, bond.amount)?;
 			let amount_on_hold = bond.total_fee.saturating_sub(bond.prepaid_fee);
-			let to_return = amount_on_hold.saturating_sub(remaining_fee);
+			let to_return = amount_on_hold.saturating_sub(earned_fee);
 
 			if to_return > 0u128.into() {
 				Self::release_hold(&bond.bonded_account_id, to_return, HoldReason::BondFee)
@@ -912,14 +1515,100 @@ pub mod pallet {
 			Ok(to_return)
 		}
 
+		fn release_partial_bonded_funds(
+			bond: &Bond<T::AccountId, T::Balance, BlockNumberFor<T>>,
+			amount: T::Balance,
+		) -> Result<T::Balance, BondError> {
+			ensure!(bond.bond_type == BondType::Bitcoin, BondError::NoPermissions);
+			ensure!(amount <= bond.amount, BondError::InsufficientFunds);
+			let vault_id = bond.vault_id;
+			let mut vault = VaultsById::<T>::get(vault_id).ok_or(BondError::VaultNotFound)?;
+			Self::remove_bonded_funds(&mut vault, &bond.bond_type, amount)?;
+			let earned_fee = Self::pay_earned_bond_fee(&vault, bond, amount)?;
+			VaultsById::<T>::insert(vault_id, vault);
+			Ok(earned_fee)
+		}
+
+		fn bond_migration_vault(vault_id: VaultId) -> Option<VaultId> {
+			BondMigrationsByVaultId::<T>::get(vault_id)
+				.filter(|a| a.is_accepted)
+				.map(|a| a.to_vault_id)
+		}
+
+		fn migrate_bonded_funds(
+			bond: &Bond<T::AccountId, T::Balance, BlockNumberFor<T>>,
+			to_vault_id: VaultId,
+		) -> Result<T::Balance, BondError> {
+			ensure!(bond.bond_type == BondType::Bitcoin, BondError::NoPermissions);
+			let vault_id = bond.vault_id;
+			ensure!(
+				Self::bond_migration_vault(vault_id) == Some(to_vault_id),
+				BondError::NoPermissions
+			);
+			let mut vault = VaultsById::<T>::get(vault_id).ok_or(BondError::VaultNotFound)?;
+			let mut to_vault = VaultsById::<T>::get(to_vault_id).ok_or(BondError::VaultNotFound)?;
+			ensure!(!to_vault.is_closed, BondError::VaultClosed);
+			ensure!(
+				to_vault.bitcoin_argons.free_balance() >= bond.amount,
+				BondError::InsufficientVaultFunds
+			);
+
+			Self::remove_bonded_funds(&mut vault, &bond.bond_type, bond.amount)?;
+			let earned_fee = Self::pay_earned_bond_fee(&vault, bond, bond.amount)?;
+
+			// a migrated bond was created with the closed vault, so it doesn't count towards the
+			// reputation of the new vault
+			to_vault.bitcoin_argons.bonded =
+				to_vault.bitcoin_argons.bonded.saturating_add(bond.amount);
+			if vault.bitcoin_argons.bonded == T::Balance::zero() {
+				BondMigrationsByVaultId::<T>::remove(vault_id);
+			}
+			VaultsById::<T>::insert(vault_id, vault);
+			VaultsById::<T>::insert(to_vault_id, to_vault);
+			Ok(earned_fee)
+		}
+
+		fn transfer_bond_fee_hold(
+			bond: &Bond<T::AccountId, T::Balance, BlockNumberFor<T>>,
+			to_account_id: &T::AccountId,
+		) -> Result<(), BondError> {
+			let amount_on_hold = bond.total_fee.saturating_sub(bond.prepaid_fee);
+			if amount_on_hold == T::Balance::zero() {
+				return Ok(());
+			}
+			Self::hold(to_account_id, amount_on_hold, HoldReason::BondFee)?;
+			Self::release_hold(&bond.bonded_account_id, amount_on_hold, HoldReason::BondFee)
+				.map_err(|_| BondError::UnrecoverableHold)?;
+			Ok(())
+		}
+
+		fn record_unlock_cosigned(vault_id: VaultId, latency_blocks: BitcoinHeight) {
+			VaultReputationById::<T>::mutate(vault_id, |a| {
+				a.cosigned_unlocks.saturating_inc();
+				a.total_cosign_latency_blocks =
+					a.total_cosign_latency_blocks.saturating_add(latency_blocks);
+				a.max_cosign_latency_blocks = a.max_cosign_latency_blocks.max(latency_blocks);
+			});
+		}
+
+		fn record_missed_cosign_deadline(vault_id: VaultId) {
+			VaultReputationById::<T>::mutate(vault_id, |a| {
+				a.missed_cosign_deadlines.saturating_inc();
+			});
+		}
+
 		fn create_utxo_script_pubkey(
 			vault_id: VaultId,
 			_utxo_id: UtxoId,
+			script_type: BitcoinCosignScriptType,
 			owner_pubkey: CompressedBitcoinPubkey,
 			vault_claim_height: BitcoinHeight,
 			open_claim_height: BitcoinHeight,
 			current_height: BitcoinHeight,
-		) -> Result<(BitcoinXPub, BitcoinXPub, BitcoinCosignScriptPubkey), BondError> {
+		) -> Result<
+			(BitcoinXPub, BitcoinXPub, BitcoinCosignScriptPubkey, Option<VaultMultisigPubkeys>),
+			BondError,
+		> {
 			let (vault_xpubkey, vault_claim_pubkey) = VaultXPubById::<T>::mutate(vault_id, |a| {
 				let (xpub, counter) =
 					a.as_mut().ok_or(BondError::NoVaultBitcoinPubkeysAvailable)?;
@@ -939,6 +1628,30 @@ pub mod pallet {
 				Ok((pubkey, pubkey2))
 			})?;
 
+			// the cosigners use the same child numbers as the vault xpub
+			let vault_multisig = match VaultMultisigById::<T>::get(vault_id) {
+				Some(multisig) => {
+					let derive_pubkeys = |child_number: u32| {
+						let pubkeys = multisig
+							.cosigner_xpubs
+							.iter()
+							.map(|xpub| {
+								xpub.derive_pubkey(child_number)
+									.map(|a| a.public_key)
+									.map_err(|_| BondError::UnableToGenerateVaultBitcoinPubkey)
+							})
+							.collect::<Result<Vec<_>, _>>()?;
+						Ok::<_, BondError>(BoundedVec::truncate_from(pubkeys))
+					};
+					Some(VaultMultisigPubkeys {
+						threshold: multisig.threshold,
+						cosign_pubkeys: derive_pubkeys(vault_xpubkey.child_number)?,
+						claim_pubkeys: derive_pubkeys(vault_claim_pubkey.child_number)?,
+					})
+				},
+				None => None,
+			};
+
 			let script_args = CosignScriptArgs {
 				vault_pubkey: vault_xpubkey.public_key,
 				vault_claim_pubkey: vault_claim_pubkey.public_key,
@@ -946,20 +1659,22 @@ pub mod pallet {
 				vault_claim_height,
 				open_claim_height,
 				created_at_height: current_height,
+				vault_multisig: vault_multisig.clone(),
 			};
 
 			let network = T::GetBitcoinNetwork::get();
-			let script_pubkey = CosignScript::new(script_args, network.into())
-				.map_err(|_| BondError::InvalidBitcoinScript)?;
+			let cosign_script =
+				CosignScript::with_script_type(script_args, script_type, network.into())
+					.map_err(|_| BondError::InvalidBitcoinScript)?;
 
 			Ok((
 				vault_xpubkey,
 				vault_claim_pubkey,
-				script_pubkey
-					.script
-					.to_p2wsh()
+				cosign_script
+					.get_script_pubkey()
 					.try_into()
 					.map_err(|_| BondError::InvalidBitcoinScript)?,
+				vault_multisig,
 			))
 		}
 	}
@@ -0,0 +1,188 @@
+use crate::{pallet::VaultsById, Config, Pallet};
+use frame_support::{
+	migrations::VersionedMigration, pallet_prelude::*, traits::UncheckedOnRuntimeUpgrade,
+};
+use frame_system::pallet_prelude::BlockNumberFor;
+use log::info;
+
+/// Adds the optional pricing curves to the vault argons and pending vault terms. Existing vaults
+/// keep their flat annual percent rates.
+pub mod v1 {
+	use super::*;
+	use argon_primitives::{
+		bond::{Vault, VaultArgons, VaultTerms},
+		RewardShare,
+	};
+	use codec::Codec;
+	use sp_runtime::{traits::AtLeast32BitUnsigned, FixedU128};
+
+	mod old {
+		use super::*;
+
+		#[derive(Decode, Encode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+		pub struct Vault<
+			AccountId: Codec,
+			Balance: Codec + Copy + MaxEncodedLen + Default + AtLeast32BitUnsigned + TypeInfo,
+			BlockNumber: Codec + MaxEncodedLen + Clone + TypeInfo + PartialEq + Eq,
+		> {
+			pub operator_account_id: AccountId,
+			pub bitcoin_argons: VaultArgons<Balance>,
+			#[codec(compact)]
+			pub securitization_percent: FixedU128,
+			#[codec(compact)]
+			pub securitized_argons: Balance,
+			pub mining_argons: VaultArgons<Balance>,
+			#[codec(compact)]
+			pub mining_reward_sharing_percent_take: RewardShare,
+			pub is_closed: bool,
+			pub pending_terms: Option<(BlockNumber, VaultTerms<Balance>)>,
+		}
+
+		#[derive(Decode, Encode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+		pub struct VaultArgons<Balance: Codec + Copy + MaxEncodedLen + Default> {
+			#[codec(compact)]
+			pub annual_percent_rate: FixedU128,
+			#[codec(compact)]
+			pub allocated: Balance,
+			#[codec(compact)]
+			pub bonded: Balance,
+			#[codec(compact)]
+			pub base_fee: Balance,
+		}
+
+		#[derive(Decode, Encode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+		pub struct VaultTerms<Balance: Codec + MaxEncodedLen + Clone + TypeInfo + PartialEq + Eq> {
+			#[codec(compact)]
+			pub bitcoin_annual_percent_rate: FixedU128,
+			#[codec(compact)]
+			pub bitcoin_base_fee: Balance,
+			#[codec(compact)]
+			pub mining_annual_percent_rate: FixedU128,
+			#[codec(compact)]
+			pub mining_base_fee: Balance,
+			#[codec(compact)]
+			pub mining_reward_sharing_percent_take: FixedU128,
+		}
+	}
+
+	impl<Balance> From<old::VaultArgons<Balance>> for VaultArgons<Balance>
+	where
+		Balance: Codec + Copy + MaxEncodedLen + Default + AtLeast32BitUnsigned,
+	{
+		fn from(old: old::VaultArgons<Balance>) -> Self {
+			Self {
+				annual_percent_rate: old.annual_percent_rate,
+				allocated: old.allocated,
+				bonded: old.bonded,
+				base_fee: old.base_fee,
+				pricing_curve: None,
+			}
+		}
+	}
+
+	impl<Balance> From<old::VaultTerms<Balance>> for VaultTerms<Balance>
+	where
+		Balance: Codec + MaxEncodedLen + Clone + TypeInfo + PartialEq + Eq,
+	{
+		fn from(old: old::VaultTerms<Balance>) -> Self {
+			Self {
+				bitcoin_annual_percent_rate: old.bitcoin_annual_percent_rate,
+				bitcoin_pricing_curve: None,
+				bitcoin_base_fee: old.bitcoin_base_fee,
+				mining_annual_percent_rate: old.mining_annual_percent_rate,
+				mining_pricing_curve: None,
+				mining_base_fee: old.mining_base_fee,
+				mining_reward_sharing_percent_take: old.mining_reward_sharing_percent_take,
+			}
+		}
+	}
+
+	pub struct InnerMigrateV0ToV1<T: Config>(core::marker::PhantomData<T>);
+
+	impl<T: Config> UncheckedOnRuntimeUpgrade for InnerMigrateV0ToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let mut count = 0u64;
+			VaultsById::<T>::translate::<old::Vault<T::AccountId, T::Balance, BlockNumberFor<T>>, _>(
+				|_, old| {
+					count += 1;
+					Some(Vault {
+						operator_account_id: old.operator_account_id,
+						bitcoin_argons: old.bitcoin_argons.into(),
+						securitization_percent: old.securitization_percent,
+						securitized_argons: old.securitized_argons,
+						mining_argons: old.mining_argons.into(),
+						mining_reward_sharing_percent_take: old.mining_reward_sharing_percent_take,
+						is_closed: old.is_closed,
+						pending_terms: old
+							.pending_terms
+							.map(|(block_number, terms)| (block_number, terms.into())),
+					})
+				},
+			);
+			info!("Migrated {} vaults", count);
+
+			T::DbWeight::get().reads_writes(count, count)
+		}
+	}
+
+	pub type MigrateV0ToV1<T> = VersionedMigration<
+		0,
+		1,
+		InnerMigrateV0ToV1<T>,
+		Pallet<T>,
+		<T as frame_system::Config>::DbWeight,
+	>;
+
+	#[cfg(test)]
+	mod test {
+		use super::*;
+		use crate::mock::{new_test_ext, Test};
+		use frame_support::storage::unhashed;
+
+		#[test]
+		fn migrates_vaults_without_pricing_curves() {
+			new_test_ext().execute_with(|| {
+				let argons = old::VaultArgons {
+					annual_percent_rate: FixedU128::from_float(0.1),
+					allocated: 100_000u128,
+					bonded: 50_000,
+					base_fee: 10,
+				};
+				let terms = old::VaultTerms {
+					bitcoin_annual_percent_rate: FixedU128::from_float(0.2),
+					bitcoin_base_fee: 20u128,
+					mining_annual_percent_rate: FixedU128::from_float(0.3),
+					mining_base_fee: 30,
+					mining_reward_sharing_percent_take: FixedU128::from_float(0.4),
+				};
+				let vault = old::Vault {
+					operator_account_id: 1u64,
+					bitcoin_argons: argons.clone(),
+					securitization_percent: FixedU128::from_float(0.5),
+					securitized_argons: 25_000,
+					mining_argons: argons,
+					mining_reward_sharing_percent_take: RewardShare::from_float(0.1),
+					is_closed: false,
+					pending_terms: Some((10u64, terms)),
+				};
+				unhashed::put(&VaultsById::<Test>::hashed_key_for(1), &vault);
+
+				let weight = InnerMigrateV0ToV1::<Test>::on_runtime_upgrade();
+				assert!(weight.ref_time() > 0);
+
+				let migrated = VaultsById::<Test>::get(1).expect("vault");
+				assert_eq!(migrated.operator_account_id, 1);
+				assert_eq!(migrated.bitcoin_argons.bonded, 50_000);
+				assert_eq!(migrated.bitcoin_argons.pricing_curve, None);
+				assert_eq!(migrated.mining_argons.pricing_curve, None);
+				assert_eq!(migrated.securitized_argons, 25_000);
+				let (block_number, terms) = migrated.pending_terms.expect("pending terms");
+				assert_eq!(block_number, 10);
+				assert_eq!(terms.bitcoin_pricing_curve, None);
+				assert_eq!(terms.mining_pricing_curve, None);
+				assert_eq!(terms.mining_base_fee, 30);
+				assert_eq!(terms.mining_reward_sharing_percent_take, FixedU128::from_float(0.4));
+			});
+		}
+	}
+}
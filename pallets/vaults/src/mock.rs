@@ -1,10 +1,13 @@
 use crate as pallet_vaults;
 use argon_primitives::{bitcoin::BitcoinNetwork, MiningSlotProvider};
 use env_logger::{Builder, Env};
-use frame_support::{derive_impl, parameter_types, traits::Currency};
+use frame_support::{
+	derive_impl, parameter_types,
+	traits::{Currency, StorageMapShim},
+};
 use frame_system::pallet_prelude::BlockNumberFor;
 use sp_core::{ConstU32, ConstU64};
-use sp_runtime::BuildStorage;
+use sp_runtime::{BuildStorage, FixedU128};
 
 pub type Balance = u128;
 type Block = frame_system::mocking::MockBlock<Test>;
@@ -15,6 +18,7 @@ frame_support::construct_runtime!(
 	{
 		System: frame_system,
 		Balances: pallet_balances,
+		Ownership: pallet_balances::<Instance2>,
 		Vaults: pallet_vaults,
 	}
 );
@@ -31,6 +35,7 @@ parameter_types! {
 	pub const MinimumBondAmount:u128 = 1_000;
 	pub const BlocksPerYear:u32 = 1440*365;
 	pub static GetBitcoinNetwork: BitcoinNetwork = BitcoinNetwork::Regtest;
+	pub static OwnershipTokenArgonPrice: Option<FixedU128> = Some(FixedU128::from_u32(2));
 }
 
 impl pallet_balances::Config for Test {
@@ -49,11 +54,37 @@ impl pallet_balances::Config for Test {
 	type RuntimeFreezeReason = RuntimeFreezeReason;
 }
 
+pub(crate) type OwnershipToken = pallet_balances::Instance2;
+impl pallet_balances::Config<OwnershipToken> for Test {
+	type MaxLocks = ConstU32<0>;
+	type MaxReserves = ConstU32<0>;
+	type ReserveIdentifier = ();
+	type Balance = Balance;
+	type RuntimeEvent = RuntimeEvent;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = StorageMapShim<
+		pallet_balances::Account<Test, OwnershipToken>,
+		Self::AccountId,
+		pallet_balances::AccountData<Balance>,
+	>;
+	type WeightInfo = ();
+	type FreezeIdentifier = ();
+	type MaxFreezes = ();
+	type RuntimeHoldReason = RuntimeHoldReason;
+	type RuntimeFreezeReason = RuntimeFreezeReason;
+}
+
 pub fn set_argons(account_id: u64, amount: Balance) {
 	let _ = Balances::make_free_balance_be(&account_id, amount);
 	drop(Balances::issue(amount));
 }
 
+pub fn set_ownership(account_id: u64, amount: Balance) {
+	let _ = Ownership::make_free_balance_be(&account_id, amount);
+	drop(Ownership::issue(amount));
+}
+
 parameter_types! {
 	pub static NextSlot: BlockNumberFor<Test> = 100;
 	pub static MiningWindowBlocks: BlockNumberFor<Test> = 100;
@@ -79,8 +110,11 @@ impl pallet_vaults::Config for Test {
 	type MinimumBondAmount = MinimumBondAmount;
 	type MiningSlotProvider = StaticMiningSlotProvider;
 	type MaxPendingTermModificationsPerBlock = ConstU32<100>;
+	type MaxVaultCosigners = ConstU32<5>;
 	type MinTermsModificationBlockDelay = MinTermsModificationBlockDelay;
 	type GetBitcoinNetwork = GetBitcoinNetwork;
+	type OwnershipCurrency = Ownership;
+	type OwnershipTokenArgonPrice = OwnershipTokenArgonPrice;
 }
 
 // Build genesis storage according to the mock runtime.
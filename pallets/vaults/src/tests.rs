@@ -11,16 +11,26 @@ use frame_support::{
 	},
 };
 use k256::elliptic_curve::rand_core::{OsRng, RngCore};
-use sp_runtime::{traits::Zero, FixedU128};
+use sp_runtime::{traits::Zero, BoundedVec, FixedU128};
 
 use crate::{
 	mock::{Vaults, *},
-	pallet::{NextVaultId, PendingTermsModificationsByBlock, VaultXPubById, VaultsById},
-	Error, Event, HoldReason, VaultConfig,
+	pallet::{
+		BondMigrationsByVaultId, NextVaultId, OwnershipTokensStakedByVaultId,
+		PendingTermsModificationsByBlock, VaultMultisigById, VaultRolesById, VaultXPubById,
+		VaultsById,
+	},
+	BondMigration, Error, Event, HoldReason, SlashedArgonsSource, VaultConfig, VaultRoles,
 };
 use argon_primitives::{
-	bitcoin::{CompressedBitcoinPubkey, OpaqueBitcoinXpub},
-	bond::{Bond, BondError, BondExpiration, BondType, VaultProvider, VaultTerms},
+	bitcoin::{
+		BitcoinCosignScriptPubkey, BitcoinCosignScriptType, BitcoinVaultMultisig, BitcoinXPub,
+		CompressedBitcoinPubkey, OpaqueBitcoinXpub,
+	},
+	bond::{
+		Bond, BondError, BondExpiration, BondType, VaultPricingCurve, VaultProvider,
+		VaultReputation, VaultRole, VaultTerms,
+	},
 };
 
 const TEN_PCT: FixedU128 = FixedU128::from_rational(10, 100);
@@ -43,7 +53,9 @@ fn keys() -> OpaqueBitcoinXpub {
 fn default_terms(pct: FixedU128) -> VaultTerms<Balance> {
 	VaultTerms {
 		bitcoin_annual_percent_rate: pct,
+		bitcoin_pricing_curve: None,
 		mining_annual_percent_rate: pct,
+		mining_pricing_curve: None,
 		bitcoin_base_fee: 0,
 		mining_base_fee: 0,
 		mining_reward_sharing_percent_take: FixedU128::zero(),
@@ -54,6 +66,7 @@ fn default_vault() -> VaultConfig<Balance> {
 	VaultConfig {
 		terms: default_terms(TEN_PCT),
 		bitcoin_xpubkey: keys(),
+		bitcoin_multisig: None,
 		bitcoin_amount_allocated: 50_000,
 		mining_amount_allocated: 50_000,
 		securitization_percent: FixedU128::zero(),
@@ -212,6 +225,7 @@ fn it_can_reduce_vault_funds_down_to_bonded() {
 			VaultConfig {
 				terms: default_terms(TEN_PCT),
 				bitcoin_xpubkey: keys(),
+				bitcoin_multisig: None,
 				bitcoin_amount_allocated: 1000,
 				mining_amount_allocated: 1000,
 				securitization_percent: FixedU128::from_float(2.0),
@@ -304,6 +318,7 @@ fn it_can_close_a_vault() {
 			VaultConfig {
 				terms,
 				bitcoin_xpubkey: keys(),
+				bitcoin_multisig: None,
 				bitcoin_amount_allocated: 50_000,
 				mining_amount_allocated: 50_000,
 				securitization_percent: FixedU128::from_float(2.0),
@@ -312,8 +327,9 @@ fn it_can_close_a_vault() {
 		assert_eq!(Balances::free_balance(1), 1000);
 
 		let bond_amount = 50_000;
-		let (fee, paid) = Vaults::bond_funds(1, bond_amount, BondType::Bitcoin, 1440 * 365, &2)
-			.expect("bonding failed");
+		let (fee, paid, apr) =
+			Vaults::bond_funds(1, bond_amount, BondType::Bitcoin, 1440 * 365, &2)
+				.expect("bonding failed");
 		assert_eq!(fee, 501);
 		assert_eq!(paid, 1);
 
@@ -352,6 +368,7 @@ fn it_can_close_a_vault() {
 			expiration: BondExpiration::BitcoinBlock(5000),
 			bond_type: BondType::Bitcoin,
 			start_block: 1,
+			annual_percent_rate: apr,
 			utxo_id: Some(1)
 		},));
 		// should release the 1000 from the bitcoin bond and the 2000 in securitization
@@ -365,6 +382,126 @@ fn it_can_close_a_vault() {
 	});
 }
 
+#[test]
+fn it_can_migrate_bonds_off_a_closed_vault() {
+	new_test_ext().execute_with(|| {
+		// Go past genesis block so events get deposited
+		System::set_block_number(1);
+
+		let vault_owner_balance = 201_000;
+		set_argons(1, vault_owner_balance);
+		set_argons(2, 100_000);
+		set_argons(3, 200_000);
+		let mut terms = default_terms(FixedU128::from_float(0.01));
+		terms.bitcoin_base_fee = 1;
+		assert_ok!(Vaults::create(
+			RuntimeOrigin::signed(1),
+			VaultConfig {
+				terms,
+				bitcoin_xpubkey: keys(),
+				bitcoin_multisig: None,
+				bitcoin_amount_allocated: 50_000,
+				mining_amount_allocated: 50_000,
+				securitization_percent: FixedU128::from_float(2.0),
+			}
+		));
+		assert_ok!(Vaults::create(RuntimeOrigin::signed(3), default_vault()));
+
+		let bond_amount = 50_000;
+		let (fee, paid, apr) =
+			Vaults::bond_funds(1, bond_amount, BondType::Bitcoin, 1440 * 365, &2)
+				.expect("bonding failed");
+		assert_eq!(fee, 501);
+		let bond = Bond {
+			vault_id: 1,
+			bonded_account_id: 2,
+			amount: bond_amount,
+			prepaid_fee: paid,
+			total_fee: fee,
+			expiration: BondExpiration::BitcoinBlock(5000),
+			bond_type: BondType::Bitcoin,
+			start_block: 1,
+			annual_percent_rate: apr,
+			utxo_id: Some(1),
+		};
+
+		assert_err!(
+			Vaults::offer_bond_migration(RuntimeOrigin::signed(1), 1, 2),
+			Error::<Test>::VaultNotClosed
+		);
+		assert_ok!(Vaults::close(RuntimeOrigin::signed(1), 1));
+		assert_err!(
+			Vaults::offer_bond_migration(RuntimeOrigin::signed(3), 1, 2),
+			Error::<Test>::NoPermissions
+		);
+		assert_err!(
+			Vaults::offer_bond_migration(RuntimeOrigin::signed(1), 1, 1),
+			Error::<Test>::InvalidBondMigrationVault
+		);
+		assert_ok!(Vaults::offer_bond_migration(RuntimeOrigin::signed(1), 1, 2));
+		System::assert_last_event(
+			Event::VaultBondMigrationOffered { vault_id: 1, to_vault_id: 2 }.into(),
+		);
+		// the bonds can't move until the other vault accepts them
+		assert_eq!(Vaults::bond_migration_vault(1), None);
+		assert_err!(Vaults::migrate_bonded_funds(&bond, 2), BondError::NoPermissions);
+
+		assert_err!(
+			Vaults::accept_bond_migration(RuntimeOrigin::signed(1), 1, 2),
+			Error::<Test>::NoPermissions
+		);
+		assert_err!(
+			Vaults::accept_bond_migration(RuntimeOrigin::signed(3), 2, 2),
+			Error::<Test>::BondMigrationNotOffered
+		);
+		assert_ok!(Vaults::accept_bond_migration(RuntimeOrigin::signed(3), 1, 2));
+		System::assert_last_event(
+			Event::VaultBondMigrationAccepted { vault_id: 1, to_vault_id: 2 }.into(),
+		);
+		assert_eq!(
+			BondMigrationsByVaultId::<Test>::get(1),
+			Some(BondMigration { to_vault_id: 2, is_accepted: true })
+		);
+		assert_eq!(Vaults::bond_migration_vault(1), Some(2));
+
+		// migrate half way through the bond
+		let migrate_block = 1 + 1440 * 365 / 2;
+		System::set_block_number(migrate_block);
+		let earned_fee = Vaults::migrate_bonded_funds(&bond, 2).expect("migration failed");
+		assert_eq!(earned_fee, 250);
+		// all funds and securitization of the closed vault are released, plus the fees earned
+		assert_eq!(Balances::free_balance(1), vault_owner_balance + paid + earned_fee);
+		assert_eq!(Balances::balance_on_hold(&HoldReason::EnterVault.into(), &1), 0);
+		let vault = VaultsById::<Test>::get(1).unwrap();
+		assert_eq!(vault.bitcoin_argons.bonded, 0);
+		assert_eq!(vault.bitcoin_argons.allocated, 0);
+		assert_eq!(vault.securitized_argons, 0);
+		assert_eq!(BondMigrationsByVaultId::<Test>::get(1), None);
+
+		assert_eq!(VaultsById::<Test>::get(2).unwrap().bitcoin_argons.bonded, bond_amount);
+		// migrated bonds aren't counted as bonds created by the new vault
+		assert_eq!(Vaults::vault_reputation(2).map(|a| a.bonds_created), Some(0));
+		assert_eq!(Vaults::vault_reputation(2).map(|a| a.total_bonded_argons), Some(0));
+		assert_eq!(
+			Balances::balance_on_hold(&HoldReason::BondFee.into(), &2),
+			fee - paid - earned_fee
+		);
+
+		// the new vault earns the rest of the held fee
+		System::set_block_number(1 + 1440 * 365);
+		assert_ok!(Vaults::release_bonded_funds(&Bond {
+			vault_id: 2,
+			prepaid_fee: paid + earned_fee,
+			start_block: migrate_block,
+			..bond
+		}));
+		assert_eq!(VaultsById::<Test>::get(2).unwrap().bitcoin_argons.bonded, 0);
+		assert_eq!(Balances::free_balance(3), 100_000 + fee - paid - earned_fee);
+		assert_eq!(Balances::free_balance(2), 100_000 - fee);
+		assert_eq!(Balances::balance_on_hold(&HoldReason::BondFee.into(), &2), 0);
+	});
+}
+
 #[test]
 fn it_can_bond_funds() {
 	new_test_ext().execute_with(|| {
@@ -381,6 +518,7 @@ fn it_can_bond_funds() {
 			VaultConfig {
 				terms,
 				bitcoin_xpubkey: keys(),
+				bitcoin_multisig: None,
 				bitcoin_amount_allocated: 500_000,
 				mining_amount_allocated: 0,
 				securitization_percent: FixedU128::zero(),
@@ -389,7 +527,7 @@ fn it_can_bond_funds() {
 		assert_eq!(Balances::free_balance(1), 500_000);
 
 		set_argons(2, 2_000);
-		let (total_fee, paid) =
+		let (total_fee, paid, apr) =
 			Vaults::bond_funds(1, 500_000, BondType::Bitcoin, 2440, &2).expect("bonding failed");
 
 		let per_block_fee = 0.01f64 * 500_000f64 / (1440f64 * 365f64);
@@ -411,13 +549,63 @@ fn it_can_bond_funds() {
 			expiration: BondExpiration::BitcoinBlock(2440),
 			bond_type: BondType::Bitcoin,
 			utxo_id: Some(1),
-			start_block: 5
+			start_block: 5,
+			annual_percent_rate: apr
 		},));
 		assert_eq!(Balances::free_balance(1), 500_000 + paid);
 		assert_eq!(Balances::free_balance(2), 2_000 - paid);
 	});
 }
 
+#[test]
+fn it_can_transfer_a_bond_fee_hold() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(5);
+
+		set_argons(1, 1_000_000);
+		assert_ok!(Vaults::create(
+			RuntimeOrigin::signed(1),
+			VaultConfig {
+				terms: default_terms(FixedU128::from_float(0.1)),
+				bitcoin_xpubkey: keys(),
+				bitcoin_multisig: None,
+				bitcoin_amount_allocated: 500_000,
+				mining_amount_allocated: 0,
+				securitization_percent: FixedU128::zero(),
+			}
+		));
+
+		set_argons(2, 2_000);
+		let (total_fee, paid, apr) =
+			Vaults::bond_funds(1, 100_000, BondType::Bitcoin, 14_400, &2).expect("bonding failed");
+		let held = total_fee - paid;
+		assert!(held > 0);
+		let bond = Bond {
+			vault_id: 1,
+			bonded_account_id: 2,
+			amount: 100_000,
+			prepaid_fee: paid,
+			total_fee,
+			expiration: BondExpiration::BitcoinBlock(14_405),
+			bond_type: BondType::Bitcoin,
+			utxo_id: Some(1),
+			start_block: 5,
+			annual_percent_rate: apr,
+		};
+
+		set_argons(3, 100);
+		assert_err!(Vaults::transfer_bond_fee_hold(&bond, &3), BondError::InsufficientFunds);
+		assert_eq!(Balances::balance_on_hold(&HoldReason::BondFee.into(), &2), held);
+
+		set_argons(3, 2_000);
+		assert_ok!(Vaults::transfer_bond_fee_hold(&bond, &3));
+		assert_eq!(Balances::balance_on_hold(&HoldReason::BondFee.into(), &2), 0);
+		assert_eq!(Balances::free_balance(2), 2_000 - paid);
+		assert_eq!(Balances::balance_on_hold(&HoldReason::BondFee.into(), &3), held);
+		assert_eq!(Balances::free_balance(3), 2_000 - held);
+	});
+}
+
 #[test]
 fn it_can_charge_prorated_bond_funds() {
 	new_test_ext().execute_with(|| {
@@ -434,6 +622,7 @@ fn it_can_charge_prorated_bond_funds() {
 			VaultConfig {
 				terms,
 				bitcoin_xpubkey: keys(),
+				bitcoin_multisig: None,
 				bitcoin_amount_allocated: 500_000,
 				mining_amount_allocated: 0,
 				securitization_percent: FixedU128::zero(),
@@ -442,8 +631,10 @@ fn it_can_charge_prorated_bond_funds() {
 		assert_eq!(Balances::free_balance(1), 500_000);
 
 		set_argons(2, 2_000);
-		let (total_fee, paid) =
+		let quote = Vaults::quote_bond_funds(1, 100_000, BondType::Bitcoin, 14_400);
+		let (total_fee, paid, apr) =
 			Vaults::bond_funds(1, 100_000, BondType::Bitcoin, 14_400, &2).expect("bonding failed");
+		assert_eq!(quote, Ok((total_fee, paid)));
 
 		let per_block_fee = 0.1f64 * 100_000f64 / (1440f64 * 365f64);
 		println!("per block fee: {}, total {:?}, paid {:}", per_block_fee, total_fee, paid);
@@ -468,6 +659,7 @@ fn it_can_charge_prorated_bond_funds() {
 			bond_type: BondType::Bitcoin,
 			utxo_id: Some(1),
 			start_block: 5,
+			annual_percent_rate: apr,
 		});
 		assert!(to_return_res.is_ok());
 		let expected_apr_fee = (per_block_fee * 1440f64) as u128;
@@ -479,6 +671,129 @@ fn it_can_charge_prorated_bond_funds() {
 	});
 }
 
+#[test]
+fn it_can_release_part_of_a_bitcoin_bond() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(5);
+
+		set_argons(1, 1_000_000);
+		assert_ok!(Vaults::create(
+			RuntimeOrigin::signed(1),
+			VaultConfig {
+				terms: default_terms(FixedU128::from_float(0.1)),
+				bitcoin_xpubkey: keys(),
+				bitcoin_multisig: None,
+				bitcoin_amount_allocated: 500_000,
+				mining_amount_allocated: 0,
+				securitization_percent: FixedU128::zero(),
+			}
+		));
+
+		set_argons(2, 2_000);
+		let (total_fee, paid, apr) =
+			Vaults::bond_funds(1, 100_000, BondType::Bitcoin, 14_400, &2).expect("bonding failed");
+		let bond = Bond {
+			vault_id: 1,
+			bonded_account_id: 2,
+			amount: 100_000,
+			prepaid_fee: paid,
+			total_fee,
+			expiration: BondExpiration::BitcoinBlock(14_405),
+			bond_type: BondType::Bitcoin,
+			utxo_id: Some(1),
+			start_block: 5,
+			annual_percent_rate: apr,
+		};
+
+		System::set_block_number(5 + 1440);
+		assert_err!(
+			Vaults::release_partial_bonded_funds(&bond, 100_001),
+			BondError::InsufficientFunds
+		);
+		// the vault is paid what the released argons earned so far
+		let earned_fee = Vaults::release_partial_bonded_funds(&bond, 25_000).expect("release");
+		let per_block_fee = 0.1f64 * 25_000f64 / (1440f64 * 365f64);
+		assert_eq!(earned_fee, (per_block_fee * 1440f64) as u128);
+		assert_eq!(VaultsById::<Test>::get(1).unwrap().bitcoin_argons.bonded, 75_000);
+		assert_eq!(
+			Balances::balance_on_hold(&HoldReason::BondFee.into(), &2),
+			total_fee - paid - earned_fee
+		);
+		assert_eq!(Balances::free_balance(1), 500_000 + paid + earned_fee);
+	});
+}
+
+#[test]
+fn it_prices_bonds_on_a_utilization_curve() {
+	new_test_ext().execute_with(|| {
+		// Go past genesis block so events get deposited
+		System::set_block_number(5);
+
+		set_argons(1, 1_000_000);
+		let mut terms = default_terms(FixedU128::from_float(0.5));
+		terms.bitcoin_pricing_curve = Some(VaultPricingCurve {
+			min_annual_percent_rate: FixedU128::from_float(0.2),
+			max_annual_percent_rate: FixedU128::from_float(0.1),
+		});
+		let config = VaultConfig {
+			terms: terms.clone(),
+			bitcoin_xpubkey: keys(),
+			bitcoin_multisig: None,
+			bitcoin_amount_allocated: 400_000,
+			mining_amount_allocated: 0,
+			securitization_percent: FixedU128::zero(),
+		};
+		assert_noop!(
+			Vaults::create(RuntimeOrigin::signed(1), config.clone()),
+			Error::<Test>::InvalidPricingCurve
+		);
+
+		terms.bitcoin_pricing_curve = Some(VaultPricingCurve {
+			min_annual_percent_rate: FixedU128::from_float(0.1),
+			max_annual_percent_rate: FixedU128::from_float(0.3),
+		});
+		assert_ok!(Vaults::create(RuntimeOrigin::signed(1), VaultConfig { terms, ..config }));
+
+		let year = 1440 * 365;
+		set_argons(2, 100_000);
+		// 25% utilized once bonded
+		let (total_fee, _, apr) =
+			Vaults::bond_funds(1, 100_000, BondType::Bitcoin, year, &2).expect("bonding failed");
+		assert_eq!(total_fee, 15_000);
+		assert_eq!(apr, FixedU128::from_float(0.15));
+		let first_bond = Bond {
+			vault_id: 1,
+			bonded_account_id: 2,
+			amount: 100_000,
+			prepaid_fee: 0,
+			total_fee,
+			annual_percent_rate: apr,
+			expiration: BondExpiration::BitcoinBlock(year + 5),
+			bond_type: BondType::Bitcoin,
+			utxo_id: Some(1),
+			start_block: 5,
+		};
+
+		// 75% utilized once bonded
+		let (total_fee, _, apr) =
+			Vaults::bond_funds(1, 200_000, BondType::Bitcoin, year, &2).expect("bonding failed");
+		assert_eq!(total_fee, 50_000);
+		assert_eq!(apr, FixedU128::from_float(0.25));
+
+		let bitcoin_argons = VaultsById::<Test>::get(1).unwrap().bitcoin_argons;
+		assert_eq!(bitcoin_argons.bonded, 300_000);
+		assert_eq!(bitcoin_argons.annual_percent_rate_for(100_000), FixedU128::from_float(0.3));
+		// never above the max rate
+		assert_eq!(bitcoin_argons.annual_percent_rate_for(200_000), FixedU128::from_float(0.3));
+
+		// the first bond earns at the rate it was charged, not the current utilization
+		System::set_block_number(5 + year / 2);
+		let operator_balance = Balances::free_balance(1);
+		assert_eq!(Vaults::release_bonded_funds(&first_bond), Ok(7_500));
+		assert_eq!(Balances::free_balance(1), operator_balance + 7_500);
+	});
+}
+
 #[test]
 fn it_can_burn_a_bond() {
 	new_test_ext().execute_with(|| {
@@ -493,6 +808,7 @@ fn it_can_burn_a_bond() {
 			VaultConfig {
 				terms,
 				bitcoin_xpubkey: keys(),
+				bitcoin_multisig: None,
 				bitcoin_amount_allocated: 100_000,
 				mining_amount_allocated: 100_000,
 				securitization_percent: FixedU128::zero(),
@@ -501,7 +817,7 @@ fn it_can_burn_a_bond() {
 		assert_eq!(Balances::free_balance(1), 800_000);
 
 		set_argons(2, 2_000);
-		let (total_fee, paid) =
+		let (total_fee, paid, apr) =
 			Vaults::bond_funds(1, 100_000, BondType::Bitcoin, 2440, &2).expect("bonding failed");
 
 		assert_eq!(total_fee, 0);
@@ -519,6 +835,7 @@ fn it_can_burn_a_bond() {
 				bond_type: BondType::Bitcoin,
 				utxo_id: Some(1),
 				start_block: 5,
+				annual_percent_rate: apr,
 			},
 			100_000
 		));
@@ -528,6 +845,15 @@ fn it_can_burn_a_bond() {
 		assert_eq!(Balances::free_balance(2), 2_000);
 		assert_eq!(VaultsById::<Test>::get(1).unwrap().bitcoin_argons.bonded, 0);
 		assert_eq!(VaultsById::<Test>::get(1).unwrap().bitcoin_argons.allocated, 0);
+		assert_eq!(
+			Vaults::vault_reputation(1),
+			Some(VaultReputation {
+				bonds_created: 1,
+				total_bonded_argons: 100_000,
+				burned_argons: 100_000,
+				..Default::default()
+			})
+		);
 	});
 }
 
@@ -548,6 +874,7 @@ fn it_can_recoup_reduced_value_bitcoins_from_bond_funds() {
 			VaultConfig {
 				terms,
 				bitcoin_xpubkey: keys(),
+				bitcoin_multisig: None,
 				bitcoin_amount_allocated: 100_000,
 				mining_amount_allocated: 100_000,
 				securitization_percent: FixedU128::zero(),
@@ -557,8 +884,9 @@ fn it_can_recoup_reduced_value_bitcoins_from_bond_funds() {
 		assert_eq!(Balances::free_balance(1), 200);
 		assert_eq!(Balances::balance_on_hold(&HoldReason::EnterVault.into(), &1), 200_000);
 
-		let (total_fee, paid) = Vaults::bond_funds(1, 100_000, BondType::Bitcoin, 1440 * 365, &2)
-			.expect("bonding failed");
+		let (total_fee, paid, apr) =
+			Vaults::bond_funds(1, 100_000, BondType::Bitcoin, 1440 * 365, &2)
+				.expect("bonding failed");
 		assert_eq!(total_fee, 100);
 		assert_eq!(paid, 0);
 
@@ -573,7 +901,8 @@ fn it_can_recoup_reduced_value_bitcoins_from_bond_funds() {
 					expiration: BondExpiration::BitcoinBlock(1440),
 					bond_type: BondType::Bitcoin,
 					utxo_id: Some(1),
-					start_block: 5
+					start_block: 5,
+					annual_percent_rate: apr
 				},
 				50_000
 			)
@@ -587,6 +916,37 @@ fn it_can_recoup_reduced_value_bitcoins_from_bond_funds() {
 		assert_eq!(Balances::free_balance(2), 2_000 + 50_000);
 		assert_eq!(VaultsById::<Test>::get(1).unwrap().bitcoin_argons.bonded, 50_000);
 		assert_eq!(VaultsById::<Test>::get(1).unwrap().bitcoin_argons.allocated, 50_000);
+		let reputation = Vaults::vault_reputation(1).expect("should have a reputation");
+		assert_eq!(reputation.compensated_losses, 1);
+		assert_eq!(reputation.compensated_argons, 50_000);
+	});
+}
+
+#[test]
+fn it_tracks_vault_cosign_performance() {
+	new_test_ext().execute_with(|| {
+		// Go past genesis block so events get deposited
+		System::set_block_number(5);
+
+		set_argons(1, 1_000_000);
+		assert_ok!(Vaults::create(RuntimeOrigin::signed(1), default_vault()));
+		assert_eq!(Vaults::vault_reputation(1), Some(VaultReputation::default()));
+		assert_eq!(Vaults::vault_reputation(2), None);
+
+		Vaults::record_unlock_cosigned(1, 2);
+		Vaults::record_unlock_cosigned(1, 6);
+		Vaults::record_missed_cosign_deadline(1);
+
+		assert_eq!(
+			Vaults::vault_reputation(1),
+			Some(VaultReputation {
+				cosigned_unlocks: 2,
+				total_cosign_latency_blocks: 8,
+				max_cosign_latency_blocks: 6,
+				missed_cosign_deadlines: 1,
+				..Default::default()
+			})
+		);
 	});
 }
 
@@ -607,6 +967,7 @@ fn it_can_recoup_increased_value_bitcoins_from_securitizations() {
 			VaultConfig {
 				terms,
 				bitcoin_xpubkey: keys(),
+				bitcoin_multisig: None,
 				bitcoin_amount_allocated: 100_000,
 				mining_amount_allocated: 50_000,
 				securitization_percent: FixedU128::from_float(2.0),
@@ -616,8 +977,9 @@ fn it_can_recoup_increased_value_bitcoins_from_securitizations() {
 		assert_eq!(Balances::free_balance(1), 200);
 		assert_eq!(Balances::balance_on_hold(&HoldReason::EnterVault.into(), &1), 350_000);
 
-		let (total_fee, paid) = Vaults::bond_funds(1, 50_000, BondType::Bitcoin, 1440 * 365, &2)
-			.expect("bonding failed");
+		let (total_fee, paid, apr) =
+			Vaults::bond_funds(1, 50_000, BondType::Bitcoin, 1440 * 365, &2)
+				.expect("bonding failed");
 		assert_eq!(total_fee, 50);
 		assert_eq!(paid, 0);
 		assert_eq!(Balances::free_balance(2), 2_000 - 50);
@@ -633,7 +995,8 @@ fn it_can_recoup_increased_value_bitcoins_from_securitizations() {
 					expiration: BondExpiration::BitcoinBlock(1440),
 					bond_type: BondType::Bitcoin,
 					utxo_id: Some(1),
-					start_block: 5
+					start_block: 5,
+					annual_percent_rate: apr
 				},
 				200_000
 			)
@@ -654,6 +1017,103 @@ fn it_can_recoup_increased_value_bitcoins_from_securitizations() {
 	});
 }
 
+#[test]
+fn it_can_recoup_lost_bitcoins_from_ownership_tokens() {
+	new_test_ext().execute_with(|| {
+		// Go past genesis block so events get deposited
+		System::set_block_number(5);
+
+		set_argons(2, 2_000);
+		set_argons(1, 100_200);
+		set_ownership(1, 30_010);
+
+		assert_ok!(Vaults::create(
+			RuntimeOrigin::signed(1),
+			VaultConfig {
+				terms: default_terms(FixedU128::from_float(0.001)),
+				bitcoin_xpubkey: keys(),
+				bitcoin_multisig: None,
+				bitcoin_amount_allocated: 100_000,
+				mining_amount_allocated: 0,
+				securitization_percent: FixedU128::zero(),
+			}
+		));
+		assert_noop!(
+			Vaults::stake_ownership_tokens(RuntimeOrigin::signed(2), 1, 30_000),
+			Error::<Test>::NoPermissions
+		);
+		assert_ok!(Vaults::stake_ownership_tokens(RuntimeOrigin::signed(1), 1, 30_000));
+		System::assert_last_event(
+			Event::VaultOwnershipTokensStaked { vault_id: 1, amount: 30_000, total_staked: 30_000 }
+				.into(),
+		);
+		assert_eq!(
+			Ownership::balance_on_hold(&HoldReason::OwnershipSecuritization.into(), &1),
+			30_000
+		);
+
+		let (total_fee, paid, apr) =
+			Vaults::bond_funds(1, 100_000, BondType::Bitcoin, 1440 * 365, &2)
+				.expect("bonding failed");
+		assert_noop!(
+			Vaults::unstake_ownership_tokens(RuntimeOrigin::signed(1), 1, 30_000),
+			Error::<Test>::OwnershipTokensStillSecuritizing
+		);
+
+		assert_eq!(
+			Vaults::compensate_lost_bitcoin(
+				&Bond {
+					vault_id: 1,
+					bonded_account_id: 2,
+					amount: 100_000,
+					prepaid_fee: paid,
+					total_fee,
+					expiration: BondExpiration::BitcoinBlock(1440),
+					bond_type: BondType::Bitcoin,
+					utxo_id: Some(1),
+					start_block: 5,
+					annual_percent_rate: apr
+				},
+				150_000
+			)
+			.expect("compensation failed"),
+			150_000,
+			"gets the rest out of ownership tokens"
+		);
+		System::assert_has_event(
+			Event::VaultArgonsSlashed {
+				vault_id: 1,
+				source: SlashedArgonsSource::Bonded,
+				amount: 100_000,
+				compensated_account_id: 2,
+			}
+			.into(),
+		);
+		// ownership tokens are valued at 2 argons
+		System::assert_last_event(
+			Event::VaultOwnershipTokensSlashed {
+				vault_id: 1,
+				ownership_tokens: 25_000,
+				argon_value: 50_000,
+				compensated_account_id: 2,
+			}
+			.into(),
+		);
+		assert_eq!(Balances::free_balance(2), 2_000 + 100_000);
+		assert_eq!(Ownership::free_balance(2), 25_000);
+		assert_eq!(
+			Ownership::balance_on_hold(&HoldReason::OwnershipSecuritization.into(), &1),
+			5_000
+		);
+		assert_eq!(OwnershipTokensStakedByVaultId::<Test>::get(1), 5_000);
+
+		// no more bitcoins are bonded, so the rest can be withdrawn
+		assert_ok!(Vaults::unstake_ownership_tokens(RuntimeOrigin::signed(1), 1, 5_000));
+		assert_eq!(Ownership::free_balance(1), 5_010);
+		assert_eq!(OwnershipTokensStakedByVaultId::<Test>::get(1), 0);
+	});
+}
+
 #[test]
 fn it_should_allow_vaults_to_rotate_xpubs() {
 	new_test_ext().execute_with(|| {
@@ -667,6 +1127,7 @@ fn it_should_allow_vaults_to_rotate_xpubs() {
 			VaultConfig {
 				terms,
 				bitcoin_xpubkey: keys(),
+				bitcoin_multisig: None,
 				bitcoin_amount_allocated: 100_000,
 				mining_amount_allocated: 100_000,
 				securitization_percent: FixedU128::zero(),
@@ -683,11 +1144,27 @@ fn it_should_allow_vaults_to_rotate_xpubs() {
 		let owner_pubkey = Xpub::from_priv(&Secp256k1::new(), &owner_xpriv);
 		let owner_pubkey: CompressedBitcoinPubkey = owner_pubkey.public_key.serialize().into();
 
-		let key1 = Vaults::create_utxo_script_pubkey(1, 1, owner_pubkey, 100, 120, 80);
+		let key1 = Vaults::create_utxo_script_pubkey(
+			1,
+			1,
+			BitcoinCosignScriptType::P2WSH,
+			owner_pubkey,
+			100,
+			120,
+			80,
+		);
 		assert!(key1.is_ok());
 		let key1 = key1.unwrap();
 
-		let key2 = Vaults::create_utxo_script_pubkey(1, 2, owner_pubkey, 100, 120, 80);
+		let key2 = Vaults::create_utxo_script_pubkey(
+			1,
+			2,
+			BitcoinCosignScriptType::P2WSH,
+			owner_pubkey,
+			100,
+			120,
+			80,
+		);
 		assert!(key2.is_ok());
 		let key2 = key2.unwrap();
 		assert_ne!(key1.0.public_key, key2.0.public_key);
@@ -695,12 +1172,130 @@ fn it_should_allow_vaults_to_rotate_xpubs() {
 		assert_eq!(key2.0.child_number, 3);
 
 		let new_xpub = keys();
-		assert_ok!(Vaults::replace_bitcoin_xpub(RuntimeOrigin::signed(1), 1, new_xpub));
+		assert_ok!(Vaults::replace_bitcoin_xpub(RuntimeOrigin::signed(1), 1, new_xpub, None));
 		let new_keyset = VaultXPubById::<Test>::get(1).unwrap();
 		assert_eq!(new_keyset.1, 0);
 	});
 }
 
+#[test]
+fn it_should_create_taproot_utxo_script_pubkeys() {
+	new_test_ext().execute_with(|| {
+		// Go past genesis block so events get deposited
+		System::set_block_number(5);
+
+		set_argons(1, 1_000_000);
+		assert_ok!(Vaults::create(
+			RuntimeOrigin::signed(1),
+			VaultConfig {
+				terms: default_terms(TEN_PCT),
+				bitcoin_xpubkey: keys(),
+				bitcoin_multisig: None,
+				bitcoin_amount_allocated: 100_000,
+				mining_amount_allocated: 100_000,
+				securitization_percent: FixedU128::zero(),
+			}
+		));
+
+		let mut seed = [0u8; 32];
+		OsRng.fill_bytes(&mut seed);
+		let network = GetBitcoinNetwork::get();
+		let owner_xpriv = Xpriv::new_master(network, &seed).unwrap();
+		let owner_pubkey = Xpub::from_priv(&Secp256k1::new(), &owner_xpriv);
+		let owner_pubkey: CompressedBitcoinPubkey = owner_pubkey.public_key.serialize().into();
+
+		let (_, _, script_pubkey, vault_multisig) = Vaults::create_utxo_script_pubkey(
+			1,
+			1,
+			BitcoinCosignScriptType::P2TR,
+			owner_pubkey,
+			100,
+			120,
+			80,
+		)
+		.expect("should create a taproot script");
+		assert!(matches!(script_pubkey, BitcoinCosignScriptPubkey::P2TR { .. }));
+		assert_eq!(script_pubkey.script_type(), BitcoinCosignScriptType::P2TR);
+		assert_eq!(vault_multisig, None);
+	});
+}
+
+#[test]
+fn it_should_create_multisig_utxo_script_pubkeys() {
+	new_test_ext().execute_with(|| {
+		// Go past genesis block so events get deposited
+		System::set_block_number(5);
+
+		set_argons(1, 1_000_000);
+		let xpubkey = keys();
+		let cosigner_xpubs = vec![keys(), keys()];
+		let multisig = |threshold: u8, cosigner_xpubs: Vec<OpaqueBitcoinXpub>| {
+			Some(BitcoinVaultMultisig {
+				threshold,
+				cosigner_xpubs: BoundedVec::truncate_from(cosigner_xpubs),
+			})
+		};
+		let mut config = default_vault();
+		config.bitcoin_xpubkey = xpubkey.clone();
+
+		config.bitcoin_multisig = multisig(4, cosigner_xpubs.clone());
+		assert_noop!(
+			Vaults::create(RuntimeOrigin::signed(1), config.clone()),
+			Error::<Test>::InvalidVaultMultisig
+		);
+		config.bitcoin_multisig = multisig(2, vec![xpubkey.clone()]);
+		assert_noop!(
+			Vaults::create(RuntimeOrigin::signed(1), config.clone()),
+			Error::<Test>::InvalidVaultMultisig
+		);
+
+		config.bitcoin_multisig = multisig(2, cosigner_xpubs.clone());
+		assert_ok!(Vaults::create(RuntimeOrigin::signed(1), config));
+		assert_eq!(VaultMultisigById::<Test>::get(1).unwrap().threshold, 2);
+
+		let owner_pubkey = CompressedBitcoinPubkey([2; 33]);
+		assert_err!(
+			Vaults::create_utxo_script_pubkey(
+				1,
+				1,
+				BitcoinCosignScriptType::P2TR,
+				owner_pubkey,
+				100,
+				120,
+				80,
+			),
+			BondError::InvalidBitcoinScript
+		);
+		let (vault_xpub, vault_claim_xpub, _, vault_multisig) = Vaults::create_utxo_script_pubkey(
+			1,
+			2,
+			BitcoinCosignScriptType::P2WSH,
+			owner_pubkey,
+			100,
+			120,
+			80,
+		)
+		.expect("should create a multisig script");
+		let vault_multisig = vault_multisig.expect("should be a multisig");
+		assert_eq!(vault_multisig.threshold, 2);
+		for (i, cosigner_xpub) in cosigner_xpubs.into_iter().enumerate() {
+			let cosigner_xpub: BitcoinXPub = cosigner_xpub.try_into().unwrap();
+			assert_eq!(
+				vault_multisig.cosign_pubkeys[i],
+				cosigner_xpub.derive_pubkey(vault_xpub.child_number).unwrap().public_key
+			);
+			assert_eq!(
+				vault_multisig.claim_pubkeys[i],
+				cosigner_xpub.derive_pubkey(vault_claim_xpub.child_number).unwrap().public_key
+			);
+		}
+
+		// replacing the xpub without cosigners makes it a single key vault
+		assert_ok!(Vaults::replace_bitcoin_xpub(RuntimeOrigin::signed(1), 1, keys(), None));
+		assert_eq!(VaultMultisigById::<Test>::get(1), None);
+	});
+}
+
 #[test]
 fn it_should_allow_multiple_vaults_per_account() {
 	new_test_ext().execute_with(|| {
@@ -711,6 +1306,7 @@ fn it_should_allow_multiple_vaults_per_account() {
 		let config = VaultConfig {
 			terms,
 			bitcoin_xpubkey: keys(),
+			bitcoin_multisig: None,
 			bitcoin_amount_allocated: 100_000,
 			mining_amount_allocated: 100_000,
 			securitization_percent: FixedU128::zero(),
@@ -727,6 +1323,70 @@ fn it_should_allow_multiple_vaults_per_account() {
 	});
 }
 
+#[test]
+fn it_can_assign_vault_roles() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+
+		set_argons(1, 1_000_000);
+		assert_ok!(Vaults::create(RuntimeOrigin::signed(1), default_vault()));
+
+		let roles = VaultRoles {
+			funding_manager: Some(2),
+			terms_manager: Some(3),
+			cosigners: BoundedVec::truncate_from(vec![4]),
+			emergency_closer: Some(5),
+		};
+		assert_err!(
+			Vaults::set_vault_roles(RuntimeOrigin::signed(2), 1, roles.clone()),
+			Error::<Test>::NoPermissions
+		);
+		assert_ok!(Vaults::set_vault_roles(RuntimeOrigin::signed(1), 1, roles.clone()));
+		System::assert_last_event(Event::VaultRolesChanged { vault_id: 1 }.into());
+		assert_eq!(VaultRolesById::<Test>::get(1), Some(roles));
+
+		assert!(Vaults::has_vault_role(1, VaultRole::Cosigner, &4));
+		assert!(Vaults::has_vault_role(1, VaultRole::Cosigner, &1));
+		assert!(!Vaults::has_vault_role(1, VaultRole::Cosigner, &2));
+		assert!(!Vaults::has_vault_role(2, VaultRole::Cosigner, &4));
+		// the cosigner can't manage funds or assign roles
+		assert_err!(
+			Vaults::modify_funding(RuntimeOrigin::signed(4), 1, 50_000, 60_000, FixedU128::zero()),
+			Error::<Test>::NoPermissions
+		);
+		assert_err!(
+			Vaults::set_vault_roles(RuntimeOrigin::signed(4), 1, VaultRoles::default()),
+			Error::<Test>::NoPermissions
+		);
+
+		assert_err!(
+			Vaults::modify_terms(RuntimeOrigin::signed(2), 1, default_terms(TEN_PCT)),
+			Error::<Test>::NoPermissions
+		);
+		assert_ok!(Vaults::modify_terms(RuntimeOrigin::signed(3), 1, default_terms(TEN_PCT)));
+
+		// funds are always held from the operator
+		assert_ok!(Vaults::modify_funding(
+			RuntimeOrigin::signed(2),
+			1,
+			50_000,
+			60_000,
+			FixedU128::zero()
+		));
+		assert_eq!(Balances::balance_on_hold(&HoldReason::EnterVault.into(), &1), 110_000);
+		assert_eq!(Balances::balance_on_hold(&HoldReason::EnterVault.into(), &2), 0);
+
+		assert_err!(Vaults::close(RuntimeOrigin::signed(3), 1), Error::<Test>::NoPermissions);
+		assert_ok!(Vaults::close(RuntimeOrigin::signed(5), 1));
+		assert_eq!(Balances::balance_on_hold(&HoldReason::EnterVault.into(), &1), 0);
+		assert_eq!(Balances::free_balance(1), 1_000_000);
+
+		assert_ok!(Vaults::set_vault_roles(RuntimeOrigin::signed(1), 1, VaultRoles::default()));
+		assert_eq!(VaultRolesById::<Test>::get(1), None);
+		assert!(!Vaults::has_vault_role(1, VaultRole::Cosigner, &4));
+	});
+}
+
 #[test]
 fn it_can_schedule_term_changes() {
 	new_test_ext().execute_with(|| {
@@ -736,6 +1396,7 @@ fn it_can_schedule_term_changes() {
 		let config = VaultConfig {
 			terms: terms.clone(),
 			bitcoin_xpubkey: keys(),
+			bitcoin_multisig: None,
 			bitcoin_amount_allocated: 100_000,
 			mining_amount_allocated: 100_000,
 			securitization_percent: FixedU128::zero(),
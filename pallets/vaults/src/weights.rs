@@ -36,6 +36,11 @@ use core::marker::PhantomData;
 pub trait WeightInfo {
 	fn do_something() -> Weight;
 	fn cause_error() -> Weight;
+	fn stake_ownership_tokens() -> Weight;
+	fn unstake_ownership_tokens() -> Weight;
+	fn offer_bond_migration() -> Weight;
+	fn accept_bond_migration() -> Weight;
+	fn set_vault_roles(c: u32, ) -> Weight;
 }
 
 /// Weights for pallet_template using the Substrate node and recommended hardware.
@@ -62,6 +67,50 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(1_u64))
 			.saturating_add(T::DbWeight::get().writes(1_u64))
 	}
+	/// Storage: Vaults VaultsById (r:1 w:0)
+	/// Storage: Ownership Holds (r:1 w:1)
+	/// Storage: System Account (r:1 w:1)
+	/// Storage: Vaults OwnershipTokensStakedByVaultId (r:1 w:1)
+	fn stake_ownership_tokens() -> Weight {
+		Weight::from_parts(40_000_000, 4764)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Storage: Vaults VaultsById (r:1 w:0)
+	/// Storage: Vaults OwnershipTokensStakedByVaultId (r:1 w:1)
+	/// Storage: Ownership Holds (r:1 w:1)
+	/// Storage: System Account (r:1 w:1)
+	fn unstake_ownership_tokens() -> Weight {
+		Weight::from_parts(40_000_000, 4764)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Storage: Vaults VaultsById (r:2 w:0)
+	/// Storage: Vaults VaultRolesById (r:1 w:0)
+	/// Storage: Vaults BondMigrationsByVaultId (r:0 w:1)
+	fn offer_bond_migration() -> Weight {
+		Weight::from_parts(25_000_000, 3721)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: Vaults VaultsById (r:1 w:0)
+	/// Storage: Vaults VaultRolesById (r:1 w:0)
+	/// Storage: Vaults BondMigrationsByVaultId (r:1 w:1)
+	fn accept_bond_migration() -> Weight {
+		Weight::from_parts(25_000_000, 3721)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: Vaults VaultsById (r:1 w:0)
+	/// Storage: Vaults VaultRolesById (r:0 w:1)
+	/// The range of component `c` is `[0, 5]`.
+	fn set_vault_roles(c: u32, ) -> Weight {
+		Weight::from_parts(20_000_000, 3721)
+			// each cosigner account is encoded into the stored roles
+			.saturating_add(Weight::from_parts(500_000, 32).saturating_mul(c.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
 }
 
 // For backwards compatibility and tests
@@ -87,4 +136,48 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(1_u64))
 			.saturating_add(RocksDbWeight::get().writes(1_u64))
 	}
+	/// Storage: Vaults VaultsById (r:1 w:0)
+	/// Storage: Ownership Holds (r:1 w:1)
+	/// Storage: System Account (r:1 w:1)
+	/// Storage: Vaults OwnershipTokensStakedByVaultId (r:1 w:1)
+	fn stake_ownership_tokens() -> Weight {
+		Weight::from_parts(40_000_000, 4764)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	/// Storage: Vaults VaultsById (r:1 w:0)
+	/// Storage: Vaults OwnershipTokensStakedByVaultId (r:1 w:1)
+	/// Storage: Ownership Holds (r:1 w:1)
+	/// Storage: System Account (r:1 w:1)
+	fn unstake_ownership_tokens() -> Weight {
+		Weight::from_parts(40_000_000, 4764)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	/// Storage: Vaults VaultsById (r:2 w:0)
+	/// Storage: Vaults VaultRolesById (r:1 w:0)
+	/// Storage: Vaults BondMigrationsByVaultId (r:0 w:1)
+	fn offer_bond_migration() -> Weight {
+		Weight::from_parts(25_000_000, 3721)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: Vaults VaultsById (r:1 w:0)
+	/// Storage: Vaults VaultRolesById (r:1 w:0)
+	/// Storage: Vaults BondMigrationsByVaultId (r:1 w:1)
+	fn accept_bond_migration() -> Weight {
+		Weight::from_parts(25_000_000, 3721)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: Vaults VaultsById (r:1 w:0)
+	/// Storage: Vaults VaultRolesById (r:0 w:1)
+	/// The range of component `c` is `[0, 5]`.
+	fn set_vault_roles(c: u32, ) -> Weight {
+		Weight::from_parts(20_000_000, 3721)
+			// each cosigner account is encoded into the stored roles
+			.saturating_add(Weight::from_parts(500_000, 32).saturating_mul(c.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
 }
@@ -2,15 +2,19 @@
 #![allow(clippy::too_many_arguments)]
 
 use crate::{
-	bitcoin::{BitcoinNetwork, BitcoinSyncStatus, Satoshis, UtxoRef, UtxoValue},
+	bitcoin::{
+		BitcoinBlock, BitcoinBlockHash, BitcoinHeight, BitcoinNetwork, BitcoinSyncStatus, Satoshis,
+		UtxoRef, UtxoValue,
+	},
 	block_seal::{ComputePuzzle, MiningAuthority},
+	bond::{BondError, BondQuote, VaultReputation},
 	notary::{
 		NotaryId, NotaryNotebookAuditSummary, NotaryNotebookDetails, NotaryNotebookRawVotes,
 		NotaryNotebookVoteDigestDetails,
 	},
 	tick::{Tick, Ticker},
-	BestBlockVoteSeal, BlockSealDigest, BlockVoteDigest, NotebookAuditResult, NotebookNumber,
-	VoteMinimum, VotingKey,
+	BestBlockVoteSeal, BlockNumber, BlockSealDigest, BlockVoteDigest, NotebookAuditResult,
+	NotebookNumber, VaultId, VoteMinimum, VotingKey,
 };
 use alloc::{collections::btree_map::BTreeMap, vec::Vec};
 use codec::Codec;
@@ -92,5 +96,17 @@ sp_api::decl_runtime_apis! {
 		fn redemption_rate(satoshis: Satoshis) -> Option<Balance>;
 		fn market_rate(satoshis: Satoshis) -> Option<Balance>;
 		fn get_bitcoin_network() -> BitcoinNetwork;
+		#[api_version(2)]
+		fn bitcoin_header_tip() -> Option<BitcoinBlock>;
+		#[api_version(2)]
+		fn bitcoin_header_hash(block_height: BitcoinHeight) -> Option<BitcoinBlockHash>;
+	}
+}
+
+sp_api::decl_runtime_apis! {
+	pub trait VaultApis<Balance> where Balance: Codec + codec::MaxEncodedLen + Clone + scale_info::TypeInfo + PartialEq + Eq {
+		fn vault_reputation(vault_id: VaultId) -> Option<VaultReputation<Balance>>;
+		fn quote_bitcoin_bond(vault_id: VaultId, satoshis: Satoshis) -> Result<BondQuote<Balance>, BondError>;
+		fn quote_mining_bond(vault_id: VaultId, amount: Balance, blocks: BlockNumber) -> Result<BondQuote<Balance>, BondError>;
 	}
 }
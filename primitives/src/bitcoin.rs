@@ -4,7 +4,7 @@ use core::convert::{TryFrom, TryInto};
 use codec::{Decode, Encode, MaxEncodedLen};
 use frame_support::pallet_prelude::TypeInfo;
 use serde::{Deserialize, Serialize};
-use sp_core::{ConstU32, H256};
+use sp_core::{ConstU32, H256, U256};
 use sp_debug_derive::RuntimeDebug;
 use sp_runtime::BoundedVec;
 
@@ -28,6 +28,42 @@ impl BitcoinBlock {
 	}
 }
 
+/// A consensus encoded bitcoin block header
+#[derive(Encode, Decode, Clone, PartialEq, Eq, TypeInfo, RuntimeDebug)]
+pub struct OpaqueBitcoinHeader(pub [u8; 80]);
+
+/// A bitcoin block header that has been verified to extend the tracked header chain
+#[derive(Encode, Decode, Clone, PartialEq, Eq, TypeInfo, RuntimeDebug)]
+pub struct BitcoinHeaderEntry {
+	#[codec(compact)]
+	pub block_height: BitcoinHeight,
+	pub previous_block_hash: BitcoinBlockHash,
+	pub merkle_root: H256Le,
+	pub bits: u32,
+	pub time: u32,
+	/// The time of the first block in this difficulty adjustment period
+	pub epoch_start_time: u32,
+	/// The difficulty set at the start of this adjustment period
+	pub epoch_bits: u32,
+	/// The accumulated proof of work of the chain including this block
+	pub chain_work: U256,
+	/// The times of up to the last 11 blocks, ending with this one. Used to find the median time
+	/// past a new block must be after.
+	pub recent_times: Vec<u32>,
+}
+
+/// Proof that a transaction is included in a bitcoin block
+#[derive(Encode, Decode, Clone, PartialEq, Eq, TypeInfo, RuntimeDebug)]
+pub struct BitcoinTxInclusionProof {
+	pub block_hash: BitcoinBlockHash,
+	/// The consensus encoded transaction. Witnesses can be stripped since they aren't part of the
+	/// txid.
+	pub transaction: Vec<u8>,
+	#[codec(compact)]
+	pub tx_index: u32,
+	pub merkle_branch: Vec<H256Le>,
+}
+
 #[derive(RuntimeDebug, Encode, Decode, Clone, PartialEq, Eq, TypeInfo)]
 pub enum BitcoinError {
 	InvalidLockTime,
@@ -46,6 +82,8 @@ impl From<[u8; 33]> for CompressedBitcoinPubkey {
 	}
 }
 
+/// A signature over a bitcoin unlock. For P2WSH scripts this is a DER encoded ECDSA signature with
+/// the sighash type appended. For P2TR scripts this is a 32 byte MuSig2 partial signature.
 #[derive(Clone, PartialEq, Eq, Encode, Decode, TypeInfo, RuntimeDebug, MaxEncodedLen)]
 #[repr(transparent)]
 pub struct BitcoinSignature(pub BoundedVec<u8, ConstU32<73>>);
@@ -57,13 +95,50 @@ impl TryFrom<Vec<u8>> for BitcoinSignature {
 	}
 }
 
+/// A MuSig2 public nonce (two compressed points) used to cooperatively sign a taproot key path
+/// spend.
+#[derive(Clone, Copy, PartialEq, Eq, Encode, Decode, TypeInfo, RuntimeDebug, MaxEncodedLen)]
+#[repr(transparent)]
+pub struct BitcoinMusigNonce(pub [u8; 66]);
+
+impl TryFrom<Vec<u8>> for BitcoinMusigNonce {
+	type Error = Vec<u8>;
+	fn try_from(value: Vec<u8>) -> Result<Self, Self::Error> {
+		Ok(Self(value.try_into()?))
+	}
+}
+
 /// A Script Pubkey for a Bitcoin UTXO. Supported types are:
 /// - P2WSH (Pay to Witness Script Hash)
+/// - P2TR (Pay to Taproot)
 #[derive(Clone, PartialEq, Eq, Encode, Decode, TypeInfo, RuntimeDebug, Copy)]
-#[repr(transparent)]
 pub enum BitcoinCosignScriptPubkey {
 	/// Pay to Witness Script Hash
 	P2WSH { wscript_hash: H256 },
+	/// Pay to Taproot. The output key is the x-only key tweaked with the script tree
+	P2TR { output_key: H256 },
+}
+
+impl BitcoinCosignScriptPubkey {
+	pub fn script_type(&self) -> BitcoinCosignScriptType {
+		match self {
+			BitcoinCosignScriptPubkey::P2WSH { .. } => BitcoinCosignScriptType::P2WSH,
+			BitcoinCosignScriptPubkey::P2TR { .. } => BitcoinCosignScriptType::P2TR,
+		}
+	}
+}
+
+/// The type of script used to lock a bonded bitcoin
+#[derive(
+	Clone, Copy, PartialEq, Eq, Encode, Decode, TypeInfo, RuntimeDebug, MaxEncodedLen, Default,
+)]
+pub enum BitcoinCosignScriptType {
+	/// A witness script where the vault and owner cosign with ecdsa signatures
+	#[default]
+	P2WSH,
+	/// A taproot output where the vault and owner cosign the key path with a MuSig2 aggregate
+	/// key, and the timelocked claims are tapscript leaves
+	P2TR,
 }
 
 #[derive(Clone, PartialEq, Eq, Encode, Decode, TypeInfo, RuntimeDebug)]
@@ -85,6 +160,28 @@ impl TryFrom<Vec<u8>> for OpaqueBitcoinXpub {
 	}
 }
 
+/// The most xpubs that can cosign with a vault's own xpub in a multisig
+pub const MAX_VAULT_COSIGNERS: u32 = 4;
+
+/// The cosigners of a multisig vault (eg, a 2-of-3 across the vault's own HSMs). The vault side
+/// of each cosign script requires `threshold` signatures across the pubkeys derived from the
+/// vault xpub and these cosigner xpubs.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, TypeInfo, RuntimeDebug, MaxEncodedLen)]
+pub struct BitcoinVaultMultisig<Xpub> {
+	pub threshold: u8,
+	pub cosigner_xpubs: BoundedVec<Xpub, ConstU32<MAX_VAULT_COSIGNERS>>,
+}
+
+/// The multisig pubkeys of a vault in a single cosign script. Cosigning requires `threshold`
+/// signatures from the vault pubkey and the `cosign_pubkeys`. Claiming requires `threshold`
+/// signatures from the vault claim pubkey and the `claim_pubkeys`.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, TypeInfo, RuntimeDebug, MaxEncodedLen)]
+pub struct VaultMultisigPubkeys {
+	pub threshold: u8,
+	pub cosign_pubkeys: BoundedVec<CompressedBitcoinPubkey, ConstU32<MAX_VAULT_COSIGNERS>>,
+	pub claim_pubkeys: BoundedVec<CompressedBitcoinPubkey, ConstU32<MAX_VAULT_COSIGNERS>>,
+}
+
 /// A Bitcoin sighash. This is a 32-byte hash that is used to sign a Bitcoin transaction.
 #[derive(Clone, PartialEq, Eq, Encode, Decode, TypeInfo, RuntimeDebug)]
 #[repr(transparent)]
@@ -399,6 +496,20 @@ mod bitcoin_compat {
 		}
 	}
 
+	impl From<&bitcoin::block::Header> for OpaqueBitcoinHeader {
+		fn from(header: &bitcoin::block::Header) -> Self {
+			let mut bytes = [0u8; 80];
+			bytes.copy_from_slice(&bitcoin::consensus::serialize(header));
+			OpaqueBitcoinHeader(bytes)
+		}
+	}
+
+	impl From<UtxoRef> for bitcoin::OutPoint {
+		fn from(utxo_ref: UtxoRef) -> Self {
+			Self { txid: utxo_ref.txid.into(), vout: utxo_ref.output_index }
+		}
+	}
+
 	impl From<bitcoin::bip32::Xpub> for OpaqueBitcoinXpub {
 		fn from(xpub: bitcoin::bip32::Xpub) -> Self {
 			OpaqueBitcoinXpub(xpub.encode())
@@ -463,6 +574,11 @@ mod bitcoin_compat {
 				inner.copy_from_slice(&script.as_bytes()[2..]);
 				return Ok(BitcoinCosignScriptPubkey::P2WSH { wscript_hash: H256(inner) });
 			}
+			if script.is_p2tr() {
+				let mut inner = [0u8; 32];
+				inner.copy_from_slice(&script.as_bytes()[2..]);
+				return Ok(BitcoinCosignScriptPubkey::P2TR { output_key: H256(inner) });
+			}
 			Err(BitcoinScriptPubkeyError::UnsupportedScript)
 		}
 	}
@@ -495,6 +611,10 @@ mod bitcoin_compat {
 					let script_hash = bitcoin::WScriptHash::from_raw_hash(*raw_hash);
 					bitcoin::ScriptBuf::new_p2wsh(&script_hash)
 				},
+				BitcoinCosignScriptPubkey::P2TR { output_key } => bitcoin::script::Builder::new()
+					.push_opcode(bitcoin::opcodes::all::OP_PUSHNUM_1)
+					.push_slice(output_key.to_fixed_bytes())
+					.into_script(),
 			}
 		}
 	}
@@ -1,13 +1,18 @@
 use codec::{Codec, Decode, Encode, MaxEncodedLen};
 use frame_support::PalletError;
 use scale_info::TypeInfo;
-use sp_arithmetic::{traits::UniqueSaturatedInto, FixedPointNumber, FixedU128};
+use serde::{Deserialize, Serialize};
+use sp_arithmetic::{
+	traits::{One, Saturating, UniqueSaturatedInto},
+	FixedPointNumber, FixedU128,
+};
 use sp_debug_derive::RuntimeDebug;
 use sp_runtime::traits::AtLeast32BitUnsigned;
 
 use crate::{
 	bitcoin::{
-		BitcoinCosignScriptPubkey, BitcoinHeight, BitcoinXPub, CompressedBitcoinPubkey, UtxoId,
+		BitcoinCosignScriptPubkey, BitcoinCosignScriptType, BitcoinHeight, BitcoinXPub,
+		CompressedBitcoinPubkey, UtxoId, VaultMultisigPubkeys,
 	},
 	block_seal::RewardSharing,
 	BondId, RewardShare, VaultId,
@@ -38,6 +43,10 @@ pub trait VaultProvider {
 
 	fn get(vault_id: VaultId) -> Option<Vault<Self::AccountId, Self::Balance, Self::BlockNumber>>;
 
+	/// Whether the account can act in the given role for a vault. The vault operator holds every
+	/// role.
+	fn has_vault_role(vault_id: VaultId, role: VaultRole, account_id: &Self::AccountId) -> bool;
+
 	/// Recoup funds from the vault. This will be called if a vault does not move cosigned UTXOs in
 	/// the appropriate timeframe. Steps are taken to repay the bitcoin holder at the market rate.
 	///
@@ -45,7 +54,7 @@ pub trait VaultProvider {
 	/// 1. From the bonded funds
 	/// 2. From the allocated funds
 	/// 3. From the securitized funds
-	/// 4. TODO: From the ownership tokens
+	/// 4. From the ownership tokens staked by the vault operator
 	///
 	/// The funds will be returned to the bond.bonded_account_id
 	///
@@ -64,12 +73,30 @@ pub trait VaultProvider {
 
 	/// Bonds the given amount of funds for the given vault. The fee is calculated based on the
 	/// amount and the duration of the bond.
+	///
+	/// Returns the (total fee, base fee, annual percent rate) that was charged
 	fn bond_funds(
 		vault_id: VaultId,
 		amount: Self::Balance,
 		bond_type: BondType,
 		blocks: Self::BlockNumber,
 		bond_account_id: &Self::AccountId,
+	) -> Result<(Self::Balance, Self::Balance, FixedU128), BondError>;
+
+	/// Calculates the (total fee, base fee) `bond_funds` would charge, without bonding anything
+	fn quote_bond_funds(
+		vault_id: VaultId,
+		amount: Self::Balance,
+		bond_type: BondType,
+		blocks: Self::BlockNumber,
+	) -> Result<(Self::Balance, Self::Balance), BondError>;
+
+	/// Calculates the (total fee, base fee) of renewing a bitcoin bond with the given vault. The
+	/// funds bonded by the bond count as available if it's renewed with its current vault.
+	fn quote_bond_renewal(
+		bond: &Bond<Self::AccountId, Self::Balance, Self::BlockNumber>,
+		vault_id: VaultId,
+		blocks: Self::BlockNumber,
 	) -> Result<(Self::Balance, Self::Balance), BondError>;
 
 	/// Release the bonded funds for the given bond. This will be called when the bond is completed
@@ -78,14 +105,59 @@ pub trait VaultProvider {
 		bond: &Bond<Self::AccountId, Self::Balance, Self::BlockNumber>,
 	) -> Result<Self::Balance, BondError>;
 
+	/// Release the part of a bitcoin bond's funds that backed redeemed satoshis (a partial
+	/// redemption). The vault is paid the fee the released amount has earned so far.
+	///
+	/// Returns the fee paid to the vault
+	fn release_partial_bonded_funds(
+		bond: &Bond<Self::AccountId, Self::Balance, Self::BlockNumber>,
+		amount: Self::Balance,
+	) -> Result<Self::Balance, BondError>;
+
+	/// The vault that accepted the bitcoin bonds of a closed vault, if any
+	fn bond_migration_vault(vault_id: VaultId) -> Option<VaultId>;
+
+	/// Move the bonded funds of a bitcoin bond from a closed vault to the vault that accepted its
+	/// bonds. The closed vault is paid the fee earned by the bond so far, and the funds (and
+	/// securitization) it no longer needs are released to its operator. The rest of the fee stays
+	/// on hold for the new vault.
+	///
+	/// Returns the fee paid to the closed vault
+	fn migrate_bonded_funds(
+		bond: &Bond<Self::AccountId, Self::Balance, Self::BlockNumber>,
+		to_vault_id: VaultId,
+	) -> Result<Self::Balance, BondError>;
+
+	/// Move the remaining bond fee on hold to the new bonded account of a transferred bond. The
+	/// previous account's hold is released.
+	fn transfer_bond_fee_hold(
+		bond: &Bond<Self::AccountId, Self::Balance, Self::BlockNumber>,
+		to_account_id: &Self::AccountId,
+	) -> Result<(), BondError>;
+
+	/// Record that the vault cosigned an unlock the given number of bitcoin blocks after it was
+	/// requested
+	fn record_unlock_cosigned(vault_id: VaultId, latency_blocks: BitcoinHeight);
+
+	/// Record that the vault didn't cosign an unlock request before it was due
+	fn record_missed_cosign_deadline(vault_id: VaultId);
+
+	/// Create the script pubkey a bitcoin must be sent to for a bond. P2TR scripts use a MuSig2
+	/// aggregate of the vault and owner pubkeys for cosigned unlocks. A multisig vault also returns
+	/// the pubkeys derived from its cosigner xpubs.
+	#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 	fn create_utxo_script_pubkey(
 		vault_id: VaultId,
 		utxo_id: UtxoId,
+		script_type: BitcoinCosignScriptType,
 		owner_pubkey: CompressedBitcoinPubkey,
 		vault_claim_height: BitcoinHeight,
 		open_claim_height: BitcoinHeight,
 		current_height: BitcoinHeight,
-	) -> Result<(BitcoinXPub, BitcoinXPub, BitcoinCosignScriptPubkey), BondError>;
+	) -> Result<
+		(BitcoinXPub, BitcoinXPub, BitcoinCosignScriptPubkey, Option<VaultMultisigPubkeys>),
+		BondError,
+	>;
 }
 
 #[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo, PalletError)]
@@ -119,6 +191,35 @@ pub enum BondError {
 	InvalidBitcoinScript,
 	/// An internal processing error occurred that is too technical to be useful to the user
 	InternalError,
+	/// The bitcoin is below the minimum satoshis that can be bonded
+	InsufficientSatoshisBonded,
+	/// No bitcoin prices are available to convert satoshis to argons
+	NoBitcoinPricesAvailable,
+}
+
+/// What a bond would cost against a vault if it were created now
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BondQuote<Balance: Codec + Clone + TypeInfo + PartialEq + Eq> {
+	/// The argons that would be bonded. For bitcoin, this is the market rate of the satoshis.
+	#[codec(compact)]
+	pub amount: Balance,
+	/// The fee paid to the vault operator up front
+	#[codec(compact)]
+	pub base_fee: Balance,
+	/// The annual percent rate fee for the bond duration. It's held, and can be partially returned
+	/// if a mining bond ends early.
+	#[codec(compact)]
+	pub prorated_fee: Balance,
+	/// The base fee plus the prorated fee
+	#[codec(compact)]
+	pub total_fee: Balance,
+	/// The argons needed to unlock the bitcoin at current prices (bitcoin bonds only)
+	pub redemption_price: Option<Balance>,
+	/// The bitcoin height after which the vault can claim the bitcoin (bitcoin bonds only)
+	pub vault_claim_height: Option<BitcoinHeight>,
+	/// The bitcoin height after which either party can claim the bitcoin (bitcoin bonds only)
+	pub open_claim_height: Option<BitcoinHeight>,
 }
 
 #[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
@@ -154,12 +255,18 @@ pub struct VaultTerms<Balance: Codec + MaxEncodedLen + Clone + TypeInfo + Partia
 	/// The annual percent rate per argon vaulted for bitcoin bonds
 	#[codec(compact)]
 	pub bitcoin_annual_percent_rate: FixedU128,
+	/// An optional curve that replaces the bitcoin annual percent rate with one that scales with
+	/// the utilization of the bitcoin argons
+	pub bitcoin_pricing_curve: Option<VaultPricingCurve>,
 	/// The base fee for a bitcoin bond
 	#[codec(compact)]
 	pub bitcoin_base_fee: Balance,
 	/// The annual percent rate per argon vaulted for mining bonds
 	#[codec(compact)]
 	pub mining_annual_percent_rate: FixedU128,
+	/// An optional curve that replaces the mining annual percent rate with one that scales with
+	/// the utilization of the mining argons
+	pub mining_pricing_curve: Option<VaultPricingCurve>,
 	/// A base fee for mining bonds
 	#[codec(compact)]
 	pub mining_base_fee: Balance,
@@ -168,6 +275,78 @@ pub struct VaultTerms<Balance: Codec + MaxEncodedLen + Clone + TypeInfo + Partia
 	pub mining_reward_sharing_percent_take: FixedU128, // max 100, actual percent
 }
 
+/// An annual percent rate that scales linearly with the utilization (bonded / allocated) of vault
+/// argons
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct VaultPricingCurve {
+	/// The annual percent rate when none of the argons are bonded
+	#[codec(compact)]
+	pub min_annual_percent_rate: FixedU128,
+	/// The annual percent rate when all of the argons are bonded
+	#[codec(compact)]
+	pub max_annual_percent_rate: FixedU128,
+}
+
+impl VaultPricingCurve {
+	pub fn is_valid(&self) -> bool {
+		self.min_annual_percent_rate <= self.max_annual_percent_rate
+	}
+
+	/// The annual percent rate at the given utilization (capped at 100%)
+	pub fn annual_percent_rate(&self, utilization: FixedU128) -> FixedU128 {
+		let utilization = utilization.min(FixedU128::one());
+		let spread = self.max_annual_percent_rate.saturating_sub(self.min_annual_percent_rate);
+		self.min_annual_percent_rate.saturating_add(spread.saturating_mul(utilization))
+	}
+}
+
+/// Counters of how reliably a vault has performed its duties, so bonders and miners can compare
+/// vaults on more than their terms
+#[derive(
+	Encode,
+	Decode,
+	Clone,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	TypeInfo,
+	MaxEncodedLen,
+	Default,
+	Serialize,
+	Deserialize,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultReputation<Balance: Codec + MaxEncodedLen + Clone + TypeInfo + PartialEq + Eq> {
+	/// The number of bitcoin unlocks (including moves and fee bumps) the vault has cosigned
+	#[codec(compact)]
+	pub cosigned_unlocks: u32,
+	/// The total bitcoin blocks between each unlock request and the vault's cosign. Divide by
+	/// `cosigned_unlocks` for the average.
+	#[codec(compact)]
+	pub total_cosign_latency_blocks: BitcoinHeight,
+	/// The most bitcoin blocks the vault has taken to cosign an unlock
+	#[codec(compact)]
+	pub max_cosign_latency_blocks: BitcoinHeight,
+	/// The number of unlock requests the vault failed to cosign by the deadline
+	#[codec(compact)]
+	pub missed_cosign_deadlines: u32,
+	/// The number of bonds the vault had to compensate for lost bitcoin
+	#[codec(compact)]
+	pub compensated_losses: u32,
+	/// The argons taken from the vault to compensate for lost bitcoin
+	#[codec(compact)]
+	pub compensated_argons: Balance,
+	/// The argons of the vault that were burned for bitcoin moved outside of Argon
+	#[codec(compact)]
+	pub burned_argons: Balance,
+	/// The number of bonds that have been created with the vault
+	#[codec(compact)]
+	pub bonds_created: u32,
+	/// The total argons that have been bonded with the vault
+	#[codec(compact)]
+	pub total_bonded_argons: Balance,
+}
+
 impl<
 		AccountId: Codec,
 		Balance: Codec
@@ -238,12 +417,30 @@ pub struct VaultArgons<Balance: Codec + Copy + MaxEncodedLen + Default + AtLeast
 	pub bonded: Balance,
 	#[codec(compact)]
 	pub base_fee: Balance,
+	/// If set, the annual percent rate is evaluated from this curve at bond time
+	pub pricing_curve: Option<VaultPricingCurve>,
 }
 
 impl<Balance> VaultArgons<Balance>
 where
 	Balance: Codec + Copy + MaxEncodedLen + Default + AtLeast32BitUnsigned,
 {
+	/// The annual percent rate for a new bond of the given amount. For a pricing curve, this is
+	/// evaluated at the utilization once the amount is bonded.
+	pub fn annual_percent_rate_for(&self, amount: Balance) -> FixedU128 {
+		let Some(curve) = self.pricing_curve else {
+			return self.annual_percent_rate;
+		};
+		let bonded: u128 = self.bonded.saturating_add(amount).unique_saturated_into();
+		let allocated: u128 = self.allocated.unique_saturated_into();
+		let utilization = if allocated == 0 {
+			FixedU128::one()
+		} else {
+			FixedU128::saturating_from_rational(bonded, allocated)
+		};
+		curve.annual_percent_rate(utilization)
+	}
+
 	pub fn destroy_bond_funds(&mut self, amount: Balance) -> Result<(), BondError> {
 		if self.bonded < amount {
 			return Err(BondError::InsufficientFunds);
@@ -286,6 +483,9 @@ pub struct Bond<AccountId: Codec, Balance: Codec, BlockNumber: Codec> {
 	pub total_fee: Balance,
 	#[codec(compact)]
 	pub prepaid_fee: Balance,
+	/// The annual percent rate the bond was charged at. The vault earns the fee at this rate.
+	#[codec(compact)]
+	pub annual_percent_rate: FixedU128,
 	#[codec(compact)]
 	pub amount: Balance,
 	#[codec(compact)]
@@ -301,6 +501,19 @@ pub enum BondExpiration<BlockNumber: Codec> {
 	BitcoinBlock(#[codec(compact)] BitcoinHeight),
 }
 
+/// A permission on a vault that the operator can assign to other accounts
+#[derive(Clone, Copy, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub enum VaultRole {
+	/// Can change the argons allocated to the vault, and hand its bonds to another vault
+	FundingManager,
+	/// Can change the terms of the vault
+	TermsManager,
+	/// Can submit the vault's cosignatures of bitcoin unlocks
+	Cosigner,
+	/// Can close the vault
+	EmergencyCloser,
+}
+
 #[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
 pub enum BondType {
 	Mining,
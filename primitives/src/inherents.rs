@@ -6,7 +6,10 @@ use sp_inherents::{InherentData, InherentIdentifier, IsFatalError};
 use sp_runtime::RuntimeDebug;
 
 use crate::{
-	bitcoin::{BitcoinBlock, BitcoinHeight, BitcoinRejectedReason, UtxoId, UtxoRef},
+	bitcoin::{
+		BitcoinBlock, BitcoinHeight, BitcoinRejectedReason, BitcoinTxInclusionProof,
+		OpaqueBitcoinHeader, UtxoId, UtxoRef,
+	},
 	notary::SignedHeaderBytes,
 	BestBlockVoteSeal, BlockSealDigest, BlockVote, MerkleProof, NotaryId, NotebookNumber,
 	SignedNotebookHeader,
@@ -270,6 +273,10 @@ pub struct BitcoinUtxoSync {
 	pub verified: BTreeMap<UtxoId, UtxoRef>,
 	pub invalid: BTreeMap<UtxoId, BitcoinRejectedReason>,
 	pub sync_to_block: BitcoinBlock,
+	/// Bitcoin headers that extend (or reorg) the runtime's header chain
+	pub headers: Vec<OpaqueBitcoinHeader>,
+	/// Proofs that the funding transactions of the verified utxos are in the header chain
+	pub inclusion_proofs: BTreeMap<UtxoId, BitcoinTxInclusionProof>,
 }
 
 #[cfg(feature = "std")]
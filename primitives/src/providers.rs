@@ -85,6 +85,14 @@ pub trait BitcoinUtxoEvents {
 	fn utxo_spent(utxo_id: UtxoId) -> DispatchResult;
 
 	fn utxo_expired(utxo_id: UtxoId) -> DispatchResult;
+
+	/// Called when the bitcoin block a utxo was verified in is reorged out. The utxo is pending
+	/// confirmation again.
+	fn utxo_verification_reverted(utxo_id: UtxoId) -> DispatchResult;
+
+	/// Called when the bitcoin block a utxo was spent in is reorged out. Returns an error if the
+	/// effects of the spend can't be undone, in which case the utxo stays spent.
+	fn utxo_spend_reverted(utxo_id: UtxoId) -> DispatchResult;
 }
 
 #[impl_trait_for_tuples::impl_for_tuples(5)]
@@ -108,10 +116,25 @@ impl BitcoinUtxoEvents for Tuple {
 		for_tuples!( #( Tuple::utxo_expired(utxo_id)?; )* );
 		Ok(())
 	}
+
+	fn utxo_verification_reverted(utxo_id: UtxoId) -> DispatchResult {
+		for_tuples!( #( Tuple::utxo_verification_reverted(utxo_id)?; )* );
+		Ok(())
+	}
+
+	fn utxo_spend_reverted(utxo_id: UtxoId) -> DispatchResult {
+		for_tuples!( #( Tuple::utxo_spend_reverted(utxo_id)?; )* );
+		Ok(())
+	}
 }
 
 pub trait UtxoBondedEvents<AccountId: Codec, Balance: Codec + Copy> {
+	/// Called when a bonded utxo is verified. If the utxo was verified before, only the argons
+	/// that were still pending mint are queued again.
 	fn utxo_bonded(utxo_id: UtxoId, account_id: &AccountId, amount: Balance) -> DispatchResult;
+	/// Called when the verification of a bonded utxo is reverted by a bitcoin reorg. Argons that
+	/// are still pending mint are held back until the utxo is verified again.
+	fn utxo_verification_reverted(utxo_id: UtxoId) -> DispatchResult;
 	/// Called when a utxo is removed from bond (whether from being spent outside the system, or
 	/// from being unlocked)
 	fn utxo_unlocked(
@@ -126,6 +149,10 @@ impl<AccountId: Codec, Balance: Codec + Copy> UtxoBondedEvents<AccountId, Balanc
 		for_tuples!( #( Tuple::utxo_bonded(utxo_id, account_id, amount)?; )* );
 		Ok(())
 	}
+	fn utxo_verification_reverted(utxo_id: UtxoId) -> DispatchResult {
+		for_tuples!( #( Tuple::utxo_verification_reverted(utxo_id)?; )* );
+		Ok(())
+	}
 	fn utxo_unlocked(
 		utxo_id: UtxoId,
 		remove_pending_mints: bool,
@@ -11,8 +11,10 @@ use super::{
 use crate::SessionKeys;
 use alloc::vec::Vec;
 use argon_primitives::{
-	bitcoin::BitcoinHeight, notary::NotaryRecordWithState, prelude::*, BlockSealAuthorityId,
-	HashOutput, Moment, TickProvider, CHANNEL_HOLD_CLAWBACK_TICKS,
+	bitcoin::{BitcoinHeight, MAX_VAULT_COSIGNERS},
+	notary::NotaryRecordWithState,
+	prelude::*,
+	BlockSealAuthorityId, HashOutput, Moment, TickProvider, CHANNEL_HOLD_CLAWBACK_TICKS,
 };
 pub use frame_support::{
 	construct_runtime, derive_impl,
@@ -285,9 +287,17 @@ parameter_types! {
 
 	pub const MaxUnlockingUtxos: u32 = 1000;
 	pub const MaxPendingTermModificationsPerBlock: u32 = 100;
+	// one cosigner account per key of a multisig vault
+	pub const MaxVaultCosigners: u32 = MAX_VAULT_COSIGNERS + 1;
 	pub const MinTermsModificationBlockDelay: u32 = 1439; // must be at least one slot (day)
 }
 
+parameter_types! {
+	// Staked ownership tokens don't compensate lost bitcoins until governance (sudo) sets a price
+	// with `system.set_storage`.
+	pub storage OwnershipTokenArgonPrice: Option<FixedU128> = None;
+}
+
 impl pallet_vaults::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type WeightInfo = pallet_vaults::weights::SubstrateWeight<Runtime>;
@@ -297,9 +307,12 @@ impl pallet_vaults::Config for Runtime {
 	type MinimumBondAmount = MinimumBondAmount;
 	type BlocksPerDay = BlocksPerDay;
 	type MaxPendingTermModificationsPerBlock = MaxPendingTermModificationsPerBlock;
+	type MaxVaultCosigners = MaxVaultCosigners;
 	type MinTermsModificationBlockDelay = MinTermsModificationBlockDelay;
 	type MiningSlotProvider = MiningSlot;
 	type GetBitcoinNetwork = BitcoinUtxos;
+	type OwnershipCurrency = Ownership;
+	type OwnershipTokenArgonPrice = OwnershipTokenArgonPrice;
 }
 
 pub struct BitcoinSignatureVerifier;
@@ -324,6 +337,7 @@ impl pallet_bond::Config for Runtime {
 	type BitcoinBondDurationBlocks = BitcoinBondDurationBlocks;
 	type BitcoinBondReclamationBlocks = BitcoinBondReclamationBlocks;
 	type UtxoUnlockCosignDeadlineBlocks = UtxoUnlockCosignDeadlineBlocks;
+	type BitcoinSpendRevertBlocks = MaxBitcoinHeaderHistory;
 }
 
 pub struct GrandpaSlotRotation;
@@ -579,11 +593,16 @@ parameter_types! {
 	pub const MaxPriceAgeInTicks: Tick = 24 * 60; // 1 day
 	pub const MaxArgonChangePerTickAwayFromTarget: FixedU128 = FixedU128::from_rational(1, 100); // 1 centagon
 	pub const MaxArgonTargetChangePerTick: FixedU128 = FixedU128::from_rational(1, 100); // 1 centagon
+	pub const MaxPriceIndexOracleOperators: u32 = 10;
+	pub const MaxOracleDeviation: FixedU128 = FixedU128::from_rational(5, 100); // 5%
 
 	pub const MaxPendingConfirmationBlocks: BitcoinHeight = 10 * (6 * 24); // 10 days of bitcoin blocks
 
 	pub const MaxPendingConfirmationUtxos: u32 = 10_000;
 	pub const MaxBitcoinBirthBlocksOld: BitcoinHeight = 10 * (6 * 24); // 10 days of bitcoin blocks
+	pub const MaxBitcoinOracleOperators: u32 = 10;
+	pub const MaxBitcoinHeaderHistory: BitcoinHeight = 20 * (6 * 24); // 20 days of bitcoin blocks
+	pub const MaxBitcoinHeadersPerSync: u32 = 500;
 }
 
 impl pallet_price_index::Config for Runtime {
@@ -596,6 +615,8 @@ impl pallet_price_index::Config for Runtime {
 	type CurrentTick = Ticks;
 	type MaxArgonChangePerTickAwayFromTarget = MaxArgonChangePerTickAwayFromTarget;
 	type MaxArgonTargetChangePerTick = MaxArgonTargetChangePerTick;
+	type MaxOracleOperators = MaxPriceIndexOracleOperators;
+	type MaxOracleDeviation = MaxOracleDeviation;
 }
 
 impl pallet_bitcoin_utxos::Config for Runtime {
@@ -604,6 +625,10 @@ impl pallet_bitcoin_utxos::Config for Runtime {
 	type EventHandler = Bonds;
 	type MaxPendingConfirmationUtxos = MaxPendingConfirmationUtxos;
 	type MaxPendingConfirmationBlocks = MaxPendingConfirmationBlocks;
+	type MaxOracleOperators = MaxBitcoinOracleOperators;
+	type MaxBitcoinHeaderHistory = MaxBitcoinHeaderHistory;
+	type MaxBitcoinHeadersPerSync = MaxBitcoinHeadersPerSync;
+	type UnixTime = Timestamp;
 }
 
 impl pallet_mint::Config for Runtime {
@@ -15,8 +15,12 @@ use crate::configs::ArgonToken;
 
 use alloc::{collections::BTreeMap, vec, vec::Vec};
 use argon_primitives::{
-	bitcoin::{BitcoinNetwork, BitcoinSyncStatus, Satoshis, UtxoRef, UtxoValue},
+	bitcoin::{
+		BitcoinBlock, BitcoinBlockHash, BitcoinHeight, BitcoinNetwork, BitcoinSyncStatus, Satoshis,
+		UtxoRef, UtxoValue,
+	},
 	block_seal::{ComputePuzzle, MiningAuthority},
+	bond::{BondError, BondQuote, VaultReputation},
 	notary::{
 		NotaryNotebookAuditSummary, NotaryNotebookDetails, NotaryNotebookRawVotes,
 		NotaryNotebookVoteDigestDetails, NotaryRecordWithState,
@@ -80,10 +84,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	//   `spec_version`, and `authoring_version` are the same between Wasm and native.
 	// This value is set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
 	//   the compatible custom types.
-	spec_version: 100,
+	spec_version: 101,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	transaction_version: 2,
 	state_version: 1,
 };
 
@@ -196,7 +200,12 @@ pub type SignedExtra = (
 ///
 /// This can be a tuple of types, each implementing `OnRuntimeUpgrade`.
 #[allow(unused_parens)]
-type Migrations = ();
+type Migrations = (
+	pallet_bitcoin_utxos::migrations::v1::MigrateV0ToV1<Runtime>,
+	pallet_bond::migrations::v1::MigrateV0ToV1<Runtime>,
+	pallet_price_index::migrations::v1::MigrateV0ToV1<Runtime>,
+	pallet_vaults::migrations::v1::MigrateV0ToV1<Runtime>,
+);
 
 /// Unchecked extrinsic type as expected by this runtime.
 pub type UncheckedExtrinsic =
@@ -465,6 +474,7 @@ impl_runtime_apis! {
 		}
 	}
 
+	#[api_version(2)]
 	impl argon_primitives::BitcoinApis<Block,Balance> for Runtime {
 		fn get_sync_status() -> Option<BitcoinSyncStatus> {
 			BitcoinUtxos::get_sync_status()
@@ -485,6 +495,28 @@ impl_runtime_apis! {
 		fn get_bitcoin_network() -> BitcoinNetwork {
 			<BitcoinUtxos as Get<BitcoinNetwork>>::get()
 		}
+
+		fn bitcoin_header_tip() -> Option<BitcoinBlock> {
+			BitcoinUtxos::bitcoin_header_tip()
+		}
+
+		fn bitcoin_header_hash(block_height: BitcoinHeight) -> Option<BitcoinBlockHash> {
+			BitcoinUtxos::bitcoin_header_hash(block_height)
+		}
+	}
+
+	impl argon_primitives::VaultApis<Block, Balance> for Runtime {
+		fn vault_reputation(vault_id: VaultId) -> Option<VaultReputation<Balance>> {
+			Vaults::vault_reputation(vault_id)
+		}
+
+		fn quote_bitcoin_bond(vault_id: VaultId, satoshis: Satoshis) -> Result<BondQuote<Balance>, BondError> {
+			Bonds::quote_bitcoin_bond(vault_id, satoshis)
+		}
+
+		fn quote_mining_bond(vault_id: VaultId, amount: Balance, blocks: BlockNumber) -> Result<BondQuote<Balance>, BondError> {
+			Bonds::quote_mining_bond(vault_id, amount, blocks)
+		}
 	}
 
 	impl sp_consensus_grandpa::GrandpaApi<Block> for Runtime {
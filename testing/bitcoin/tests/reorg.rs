@@ -0,0 +1,108 @@
+use argon_bitcoin::{decode_header, UtxoSpendFilter};
+use argon_primitives::bitcoin::{
+	BitcoinBlock, BitcoinCosignScriptPubkey, BitcoinHeight, BitcoinSyncStatus, H256Le, UtxoRef,
+	UtxoValue,
+};
+use argon_testing::{
+	add_blocks, add_wallet_address, fund_script_address, reorg_to_empty_blocks, start_bitcoind,
+};
+use bitcoin::{Address, Amount, BlockHash, ScriptBuf};
+use bitcoincore_rpc::RpcApi;
+use sp_core::H256;
+
+/// Funds a tracked utxo, buries it deeper than the confirmation depth and then replaces the
+/// chain from the funding block. The header chain submitted to the runtime must fork from the
+/// last common block, and the utxo must only verify once it is mined again.
+#[test]
+fn tracks_utxos_through_a_deep_reorg() {
+	let (bitcoind, rpc_url, network) = start_bitcoind().expect("start bitcoind");
+	let block_address = add_wallet_address(&bitcoind);
+	add_blocks(&bitcoind, 101, &block_address);
+
+	let auth = if !rpc_url.username().is_empty() {
+		Some((rpc_url.username().to_string(), rpc_url.password().unwrap_or_default().to_string()))
+	} else {
+		None
+	};
+	let filter = UtxoSpendFilter::new(rpc_url.origin().unicode_serialization(), auth).unwrap();
+
+	let script_pubkey = BitcoinCosignScriptPubkey::P2WSH { wscript_hash: H256([1; 32]) };
+	let script_address = Address::from_script(&ScriptBuf::from(script_pubkey), network).unwrap();
+	let submitted_at_height = bitcoind.client.get_block_count().unwrap();
+	let (txid, vout, tx) =
+		fund_script_address(&bitcoind, &script_address, Amount::ONE_BTC.to_sat(), &block_address);
+	let funding_block = tx.blockhash.unwrap();
+	let funding_height =
+		bitcoind.client.get_block_header_info(&funding_block).unwrap().height as BitcoinHeight;
+	// bury the utxo deeper than the 6 block confirmation depth
+	add_blocks(&bitcoind, 6, &block_address);
+
+	let tracked = UtxoValue {
+		utxo_id: 1,
+		script_pubkey,
+		satoshis: Amount::ONE_BTC.to_sat(),
+		submitted_at_height,
+		watch_for_spent_until_height: submitted_at_height + 100,
+	};
+	let sync = |synched_block: Option<BitcoinBlock>| {
+		let tip = bitcoind.client.get_best_block_hash().unwrap();
+		let sync_status = BitcoinSyncStatus {
+			confirmed_block: BitcoinBlock::new(
+				bitcoind.client.get_block_count().unwrap(),
+				tip.into(),
+			),
+			synched_block,
+			oldest_allowed_block_height: submitted_at_height,
+		};
+		filter.sync_to_block(&sync_status).unwrap();
+		filter.refresh_utxo_status(vec![(None, tracked.clone())]).unwrap()
+	};
+
+	let result = sync(None);
+	assert_eq!(result.verified.get(&1), Some(&UtxoRef { txid: txid.into(), output_index: vout }));
+	assert_eq!(result.inclusion_proofs.get(&1).unwrap().block_hash, H256Le::from(funding_block));
+
+	// the runtime followed the original chain
+	let tip_height = bitcoind.client.get_block_count().unwrap();
+	let runtime_chain = (0..=tip_height)
+		.map(|height| bitcoind.client.get_block_hash(height).unwrap())
+		.collect::<Vec<BlockHash>>();
+
+	reorg_to_empty_blocks(
+		&bitcoind,
+		&funding_block,
+		tip_height - funding_height + 2,
+		&block_address,
+	);
+	let new_tip_height = bitcoind.client.get_block_count().unwrap();
+	assert!(new_tip_height > tip_height);
+
+	let headers = filter
+		.get_headers_to_submit(
+			&BitcoinBlock::new(tip_height, runtime_chain[tip_height as usize].into()),
+			|height| Ok(runtime_chain.get(height as usize).map(|a| H256Le::from(*a))),
+			new_tip_height,
+			100,
+		)
+		.unwrap();
+	// every header from the fork is submitted so the runtime can reorg to the heavier chain
+	assert_eq!(headers.len() as BitcoinHeight, new_tip_height - funding_height + 1);
+	assert_eq!(
+		decode_header(&headers[0]).unwrap().prev_blockhash,
+		runtime_chain[funding_height as usize - 1]
+	);
+
+	// after the reorg, the runtime rescans from the last common block
+	let common_block =
+		BitcoinBlock::new(funding_height - 1, runtime_chain[funding_height as usize - 1].into());
+	let result = sync(Some(common_block.clone()));
+	assert!(result.verified.is_empty());
+	assert!(result.inclusion_proofs.is_empty());
+
+	// once the transaction is mined again, it is verified in the new chain
+	add_blocks(&bitcoind, 7, &block_address);
+	let result = sync(Some(common_block));
+	assert_eq!(result.verified.get(&1), Some(&UtxoRef { txid: txid.into(), output_index: vout }));
+	assert_ne!(result.inclusion_proofs.get(&1).unwrap().block_hash, H256Le::from(funding_block));
+	drop(bitcoind);
+}
@@ -10,7 +10,7 @@ use argon_primitives::bitcoin::Satoshis;
 use bitcoin::{
 	bip32::{DerivationPath, Fingerprint, Xpriv, Xpub},
 	secp256k1::Secp256k1,
-	Address, Amount, CompressedPublicKey, Network, Txid,
+	Address, Amount, BlockHash, CompressedPublicKey, Network, Txid,
 };
 use bitcoincore_rpc::{json::GetRawTransactionResult, RpcApi};
 
@@ -116,6 +116,26 @@ pub fn add_blocks(bitcoind: &BitcoinD, count: u64, grant_to_address: &Address) {
 	bitcoind.client.generate_to_address(count, grant_to_address).unwrap();
 }
 
+/// Replaces the chain from the given block with empty blocks. Transactions in the replaced blocks
+/// return to the mempool, but aren't mined until the next call to `add_blocks`.
+pub fn reorg_to_empty_blocks(
+	bitcoind: &BitcoinD,
+	invalidate_block: &BlockHash,
+	count: u64,
+	grant_to_address: &Address,
+) {
+	bitcoind.client.invalidate_block(invalidate_block).unwrap();
+	for _ in 0..count {
+		bitcoind
+			.client
+			.call::<serde_json::Value>(
+				"generateblock",
+				&[grant_to_address.to_string().into(), serde_json::json!([])],
+			)
+			.unwrap();
+	}
+}
+
 pub fn fund_script_address(
 	bitcoind: &BitcoinD,
 	script_address: &Address,